@@ -0,0 +1,176 @@
+//! Tauri commands para anotações (destaques e notas).
+
+use serde::Deserialize;
+use tauri::AppHandle;
+
+use crate::db;
+use crate::export;
+use crate::models::{
+    Annotation, AnnotationEntry, AnnotationExportFormat, AnnotationQuery, AnnotationTemplates, Quad,
+};
+use crate::repositories;
+
+/// Cor usada quando o frontend não envia uma.
+const DEFAULT_COLOR: &str = "yellow";
+
+fn now_secs() -> i64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs() as i64)
+        .unwrap_or(0)
+}
+
+/// Posição: `cfi_range` (EPUB) ou `page_index` + `quads` (PDF).
+#[derive(Debug, Deserialize)]
+pub struct CreateAnnotationPayload {
+    pub book_id: String,
+    pub volume_id: String,
+    pub chapter_id: Option<String>,
+    pub cfi_range: Option<String>,
+    pub page_index: Option<i32>,
+    #[serde(default)]
+    pub quads: Vec<Quad>,
+    #[serde(default)]
+    pub text: String,
+    pub color: Option<String>,
+    pub note: Option<String>,
+}
+
+#[tauri::command]
+pub fn create_annotation(
+    app: AppHandle,
+    payload: CreateAnnotationPayload,
+) -> crate::Result<Annotation> {
+    let cfi_range = payload
+        .cfi_range
+        .map(|c| c.trim().to_string())
+        .filter(|c| !c.is_empty());
+    if cfi_range.is_none() && payload.page_index.is_none() {
+        return Err(crate::Error::Validation(
+            "annotation needs an EPUB CFI range or a page index".to_string(),
+        ));
+    }
+    let conn = db::open(&app)?;
+    let book = repositories::get_book(&conn, &payload.book_id)?
+        .ok_or_else(|| crate::Error::NotFound(format!("book {}", payload.book_id)))?;
+    let now = now_secs();
+    let annotation = Annotation {
+        id: uuid::Uuid::new_v4().to_string(),
+        book_id: book.id,
+        volume_id: payload.volume_id,
+        chapter_id: payload.chapter_id,
+        book_hash: book.hash,
+        cfi_range,
+        page_index: payload.page_index,
+        quads: payload.quads,
+        text: payload.text,
+        color: payload
+            .color
+            .filter(|c| !c.trim().is_empty())
+            .unwrap_or_else(|| DEFAULT_COLOR.to_string()),
+        note: payload.note.filter(|n| !n.trim().is_empty()),
+        created_at: now,
+        updated_at: now,
+    };
+    repositories::upsert_annotation(&conn, &annotation)?;
+    Ok(annotation)
+}
+
+#[tauri::command]
+pub fn get_annotation(app: AppHandle, id: String) -> crate::Result<Annotation> {
+    let conn = db::open(&app)?;
+    repositories::get_annotation(&conn, &id)?
+        .ok_or_else(|| crate::Error::NotFound(format!("annotation {}", id)))
+}
+
+/// Campos ausentes mantêm o valor atual; `note` vazia remove a nota.
+#[derive(Debug, Deserialize)]
+pub struct UpdateAnnotationPayload {
+    pub id: String,
+    pub color: Option<String>,
+    pub note: Option<String>,
+}
+
+#[tauri::command]
+pub fn update_annotation(
+    app: AppHandle,
+    payload: UpdateAnnotationPayload,
+) -> crate::Result<Annotation> {
+    let conn = db::open(&app)?;
+    let color = payload
+        .color
+        .as_deref()
+        .map(str::trim)
+        .filter(|c| !c.is_empty());
+    let note = payload
+        .note
+        .as_deref()
+        .map(|n| Some(n).filter(|n| !n.trim().is_empty()));
+    if !repositories::update_annotation(&conn, &payload.id, color, note)? {
+        return Err(crate::Error::NotFound(format!("annotation {}", payload.id)));
+    }
+    repositories::get_annotation(&conn, &payload.id)?
+        .ok_or_else(|| crate::Error::NotFound(format!("annotation {}", payload.id)))
+}
+
+#[tauri::command]
+pub fn delete_annotation(app: AppHandle, id: String) -> crate::Result<()> {
+    let conn = db::open(&app)?;
+    repositories::delete_annotation(&conn, &id)
+}
+
+/// Anotações do livro (ou só do volume), na ordem de leitura.
+#[tauri::command]
+pub fn list_annotations(
+    app: AppHandle,
+    book_id: String,
+    volume_id: Option<String>,
+) -> crate::Result<Vec<Annotation>> {
+    let conn = db::open(&app)?;
+    repositories::list_annotations(&conn, &book_id, volume_id.as_deref())
+}
+
+/// Busca na biblioteca toda (ou num livro) com título e capítulo.
+#[tauri::command]
+pub fn query_annotations(
+    app: AppHandle,
+    query: Option<AnnotationQuery>,
+) -> crate::Result<Vec<AnnotationEntry>> {
+    let conn = db::open(&app)?;
+    repositories::query_annotations(&conn, &query.unwrap_or_default())
+}
+
+/// Sem `book_id`, exporta a biblioteca toda.
+#[derive(Debug, Deserialize)]
+pub struct ExportAnnotationsPayload {
+    pub path: String,
+    pub format: AnnotationExportFormat,
+    pub book_id: Option<String>,
+    #[serde(default)]
+    pub templates: AnnotationTemplates,
+}
+
+/// Grava as anotações em `path` e retorna quantas foram exportadas.
+#[tauri::command]
+pub fn export_annotations(
+    app: AppHandle,
+    payload: ExportAnnotationsPayload,
+) -> crate::Result<usize> {
+    let conn = db::open(&app)?;
+    let entries = repositories::query_annotations(
+        &conn,
+        &AnnotationQuery {
+            book_id: payload.book_id,
+            ..AnnotationQuery::default()
+        },
+    )?;
+    let content = export::render_annotations(&entries, payload.format, &payload.templates)?;
+    std::fs::write(&payload.path, content)
+        .map_err(|e| crate::Error::Io(format!("{}: {}", payload.path, e)))?;
+    log::info!(
+        "[annotations] {} anotações exportadas para {}",
+        entries.len(),
+        payload.path
+    );
+    Ok(entries.len())
+}
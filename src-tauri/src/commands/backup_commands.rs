@@ -0,0 +1,33 @@
+//! Tauri commands para backup da biblioteca (arquivo JSON escolhido pelo usuário).
+
+use tauri::AppHandle;
+
+use crate::db;
+use crate::models::{BackupImportReport, LibraryBackup};
+use crate::repositories;
+
+/// Grava o backup em `path`. Os caminhos dos livros são os desta máquina.
+#[tauri::command]
+pub fn export_library(app: AppHandle, path: String) -> crate::Result<()> {
+    let conn = db::open(&app)?;
+    let backup = repositories::export_backup(&conn)?;
+    let json = serde_json::to_vec_pretty(&backup).map_err(|e| crate::Error::Io(e.to_string()))?;
+    std::fs::write(&path, json).map_err(|e| crate::Error::Io(format!("{}: {}", path, e)))?;
+    log::info!(
+        "[backup] Exportado para {} ({} livros, {} marcadores)",
+        path,
+        backup.books.len(),
+        backup.bookmarks.len()
+    );
+    Ok(())
+}
+
+/// Mescla um backup exportado por `export_library`.
+#[tauri::command]
+pub fn import_library(app: AppHandle, path: String) -> crate::Result<BackupImportReport> {
+    let data = std::fs::read(&path).map_err(|e| crate::Error::Io(format!("{}: {}", path, e)))?;
+    let backup: LibraryBackup = serde_json::from_slice(&data)
+        .map_err(|e| crate::Error::Validation(format!("invalid backup file: {}", e)))?;
+    let conn = db::open(&app)?;
+    repositories::import_backup(&conn, &backup)
+}
@@ -1,11 +1,15 @@
 //! Tauri commands para livros (add, list, delete).
 
+use std::collections::HashMap;
+use std::path::Path;
+
 use serde::Deserialize;
 use tauri::AppHandle;
 
 use crate::db;
-use crate::models::{Book, Chapter, Volume};
+use crate::models::{Book, BookMetadata, BookMetadataFlags, BookPage, BookQuery, Chapter, Volume};
 use crate::repositories;
+use crate::sync::koreader;
 
 #[derive(Debug, Deserialize)]
 pub struct VolumeWithChapters {
@@ -44,16 +48,26 @@ pub struct BookPayload {
 #[tauri::command]
 pub fn add_book(app: AppHandle, payload: AddBookPayload) -> crate::Result<()> {
     let conn = db::open(&app)?;
+    // Livros de arquivo ganham o MD5 parcial do conteúdo quando o frontend não
+    // envia hash; é ele que reencontra as anotações após um rescan.
+    let hash = match payload.book.hash {
+        Some(hash) => Some(hash),
+        None if payload.book.book_type == "file" => {
+            koreader::partial_md5(Path::new(&payload.book.path)).ok()
+        }
+        None => None,
+    };
     let book = Book {
         id: payload.book.id.clone(),
         title: payload.book.title,
         path: payload.book.path,
         book_type: payload.book.book_type,
         added_at: payload.book.added_at,
-        hash: payload.book.hash,
+        hash,
         author: None,
         description: None,
         cover_path: None,
+        metadata: Default::default(),
     };
     repositories::insert_book(&conn, &book)?;
     for v in &payload.volumes {
@@ -75,32 +89,82 @@ pub fn add_book(app: AppHandle, payload: AddBookPayload) -> crate::Result<()> {
             repositories::insert_chapter(&conn, &chapter)?;
         }
     }
+    if let Some(hash) = &book.hash {
+        repositories::relink_annotations(&conn, &book.id, hash)?;
+    }
     Ok(())
 }
 
+/// Lista a biblioteca completa com volumes e capítulos.
+/// Três queries no total (livros, volumes, capítulos), agrupadas em memória.
 #[tauri::command]
 pub fn get_books(app: AppHandle) -> crate::Result<Vec<BookWithVolumes>> {
     let conn = db::open(&app)?;
     let books = repositories::list_books(&conn)?;
-    let mut result = Vec::with_capacity(books.len());
-    for book in books {
-        let volumes = repositories::list_volumes(&conn, &book.id)?;
-        let mut volumes_with_chapters = Vec::with_capacity(volumes.len());
-        for vol in volumes {
-            let chapters = repositories::list_chapters(&conn, &vol.id)?;
-            volumes_with_chapters.push(VolumeWithChaptersOut {
+    let mut chapters_by_volume: HashMap<String, Vec<Chapter>> = HashMap::new();
+    for chapter in repositories::list_all_chapters(&conn)? {
+        chapters_by_volume
+            .entry(chapter.volume_id.clone())
+            .or_default()
+            .push(chapter);
+    }
+    let mut volumes_by_book: HashMap<String, Vec<VolumeWithChaptersOut>> = HashMap::new();
+    for vol in repositories::list_all_volumes(&conn)? {
+        let chapters = chapters_by_volume.remove(&vol.id).unwrap_or_default();
+        volumes_by_book
+            .entry(vol.book_id.clone())
+            .or_default()
+            .push(VolumeWithChaptersOut {
                 volume: vol,
                 chapters,
             });
-        }
-        result.push(BookWithVolumes {
-            book,
-            volumes: volumes_with_chapters,
-        });
     }
+    let result = books
+        .into_iter()
+        .map(|book| {
+            let volumes = volumes_by_book.remove(&book.id).unwrap_or_default();
+            BookWithVolumes { book, volumes }
+        })
+        .collect();
     Ok(result)
 }
 
+/// Listagem paginada da biblioteca (resumos, sem árvore de volumes).
+#[tauri::command]
+pub fn query_books(app: AppHandle, query: Option<BookQuery>) -> crate::Result<BookPage> {
+    let conn = db::open(&app)?;
+    repositories::query_books(&conn, &query.unwrap_or_default())
+}
+
+/// Carrega um livro com volumes e capítulos (usado ao abrir o livro).
+#[tauri::command]
+pub fn get_book(app: AppHandle, book_id: String) -> crate::Result<BookWithVolumes> {
+    let conn = db::open(&app)?;
+    load_book_with_volumes(&conn, &book_id)
+}
+
+/// Monta a árvore completa de um único livro.
+pub(crate) fn load_book_with_volumes(
+    conn: &rusqlite::Connection,
+    book_id: &str,
+) -> crate::Result<BookWithVolumes> {
+    let book = repositories::get_book(conn, book_id)?
+        .ok_or_else(|| crate::Error::NotFound("Book not found".to_string()))?;
+    let volumes = repositories::list_volumes(conn, book_id)?;
+    let mut volumes_with_chapters = Vec::with_capacity(volumes.len());
+    for vol in volumes {
+        let chapters = repositories::list_chapters(conn, &vol.id)?;
+        volumes_with_chapters.push(VolumeWithChaptersOut {
+            volume: vol,
+            chapters,
+        });
+    }
+    Ok(BookWithVolumes {
+        book,
+        volumes: volumes_with_chapters,
+    })
+}
+
 #[derive(serde::Serialize)]
 pub struct BookWithVolumes {
     pub book: Book,
@@ -127,19 +191,41 @@ pub struct UpdateBookPayload {
     pub author: Option<String>,
     pub description: Option<String>,
     pub cover_path: Option<String>,
+    /// Metadados estendidos; ausente = não mexer. Só os campos alterados
+    /// ganham flag de edição manual.
+    #[serde(default)]
+    pub metadata: Option<BookMetadata>,
 }
 
 #[tauri::command]
 pub fn update_book(app: AppHandle, payload: UpdateBookPayload) -> crate::Result<()> {
     let conn = db::open(&app)?;
+    let before = repositories::get_book(&conn, &payload.book_id)?
+        .ok_or_else(|| crate::Error::NotFound("Book not found".to_string()))?;
+    let mut flags = BookMetadataFlags {
+        title: true,
+        author: true,
+        description: true,
+        cover: true,
+        ..repositories::get_metadata_flags(&conn, &payload.book_id)?
+    };
+    let tx = conn.unchecked_transaction()?;
+    if let Some(metadata) = &payload.metadata {
+        flags.mark_changed(&before.metadata, metadata);
+        repositories::update_book_metadata(&tx, &payload.book_id, metadata)?;
+    }
     repositories::update_book(
-        &conn,
+        &tx,
         &payload.book_id,
         &payload.title,
         payload.author.as_deref(),
         payload.description.as_deref(),
         payload.cover_path.as_deref(),
     )?;
-    repositories::set_metadata_flags(&conn, &payload.book_id, true, true, true, true)?;
+    repositories::set_metadata_flags(&tx, &payload.book_id, &flags)?;
+    if let Some(after) = repositories::get_book(&tx, &payload.book_id)? {
+        repositories::record_metadata_changes(&tx, &before, &after, "manual")?;
+    }
+    tx.commit()?;
     Ok(())
 }
@@ -0,0 +1,130 @@
+//! Tauri commands para marcadores (bookmarks).
+
+use serde::Deserialize;
+use tauri::AppHandle;
+
+use crate::db;
+use crate::models::{Bookmark, ReadingProgress};
+use crate::repositories;
+
+fn now_secs() -> i64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs() as i64)
+        .unwrap_or(0)
+}
+
+/// Posição: `page_index` (leitores paginados) e/ou `cfi` (EPUB). Sem `label`,
+/// usa "Página N".
+#[derive(Debug, Deserialize)]
+pub struct CreateBookmarkPayload {
+    pub book_id: String,
+    pub volume_id: String,
+    pub chapter_id: Option<String>,
+    pub page_index: Option<i32>,
+    pub cfi: Option<String>,
+    pub label: Option<String>,
+    pub thumbnail: Option<String>,
+}
+
+#[tauri::command]
+pub fn create_bookmark(app: AppHandle, payload: CreateBookmarkPayload) -> crate::Result<Bookmark> {
+    let cfi = payload
+        .cfi
+        .map(|c| c.trim().to_string())
+        .filter(|c| !c.is_empty());
+    if payload.page_index.is_none() && cfi.is_none() {
+        return Err(crate::Error::Validation(
+            "bookmark needs a page index or an EPUB CFI".to_string(),
+        ));
+    }
+    let conn = db::open(&app)?;
+    if repositories::get_book(&conn, &payload.book_id)?.is_none() {
+        return Err(crate::Error::NotFound(format!("book {}", payload.book_id)));
+    }
+    let label = match payload.label.map(|l| l.trim().to_string()) {
+        Some(l) if !l.is_empty() => l,
+        _ => match payload.page_index {
+            Some(page) => format!("Página {}", page),
+            None => "Marcador".to_string(),
+        },
+    };
+    let now = now_secs();
+    let bookmark = Bookmark {
+        id: uuid::Uuid::new_v4().to_string(),
+        book_id: payload.book_id,
+        volume_id: payload.volume_id,
+        chapter_id: payload.chapter_id,
+        page_index: payload.page_index,
+        cfi,
+        label,
+        thumbnail: payload.thumbnail,
+        created_at: now,
+        updated_at: now,
+    };
+    repositories::upsert_bookmark(&conn, &bookmark)?;
+    Ok(bookmark)
+}
+
+/// Marcadores do livro; com `volume_id`, só os do volume.
+#[tauri::command]
+pub fn list_bookmarks(
+    app: AppHandle,
+    book_id: String,
+    volume_id: Option<String>,
+) -> crate::Result<Vec<Bookmark>> {
+    let conn = db::open(&app)?;
+    repositories::list_bookmarks(&conn, &book_id, volume_id.as_deref())
+}
+
+#[tauri::command]
+pub fn rename_bookmark(app: AppHandle, id: String, label: String) -> crate::Result<()> {
+    let label = label.trim();
+    if label.is_empty() {
+        return Err(crate::Error::Validation(
+            "bookmark label cannot be empty".to_string(),
+        ));
+    }
+    let conn = db::open(&app)?;
+    if !repositories::rename_bookmark(&conn, &id, label)? {
+        return Err(crate::Error::NotFound(format!("bookmark {}", id)));
+    }
+    Ok(())
+}
+
+#[tauri::command]
+pub fn delete_bookmark(app: AppHandle, id: String) -> crate::Result<()> {
+    let conn = db::open(&app)?;
+    repositories::delete_bookmark(&conn, &id)
+}
+
+/// Move o progresso do volume para o marcador e o devolve; o frontend abre o
+/// volume e, em EPUB, navega até o `cfi`.
+#[tauri::command]
+pub fn jump_to_bookmark(app: AppHandle, id: String) -> crate::Result<Bookmark> {
+    let conn = db::open(&app)?;
+    let bookmark = repositories::get_bookmark(&conn, &id)?
+        .ok_or_else(|| crate::Error::NotFound(format!("bookmark {}", id)))?;
+    let current = repositories::get_progress(&conn, &bookmark.book_id, &bookmark.volume_id)?;
+    let total_pages = current.as_ref().and_then(|p| p.total_pages);
+    let page_index = bookmark
+        .page_index
+        .or_else(|| current.as_ref().map(|p| p.page_index))
+        .unwrap_or(0);
+    repositories::upsert_progress(
+        &conn,
+        &ReadingProgress {
+            book_id: bookmark.book_id.clone(),
+            volume_id: bookmark.volume_id.clone(),
+            current_chapter_id: bookmark
+                .chapter_id
+                .clone()
+                .or_else(|| current.and_then(|p| p.current_chapter_id)),
+            page_index,
+            scroll_offset: 0.0,
+            updated_at: now_secs(),
+            total_pages,
+        },
+    )?;
+    Ok(bookmark)
+}
@@ -0,0 +1,125 @@
+//! Tauri commands para a sincronização de posição com o KOReader (kosync).
+
+use serde::Deserialize;
+use tauri::AppHandle;
+
+use crate::db;
+use crate::models::{KoreaderConfig, KoreaderSyncReport};
+use crate::repositories;
+use crate::sync::koreader;
+use crate::sync::scheduler;
+
+#[tauri::command]
+pub fn get_koreader_config(app: AppHandle) -> crate::Result<KoreaderConfig> {
+    let conn = db::open(&app)?;
+    repositories::get_koreader_config(&conn)
+}
+
+/// Campos ausentes mantêm o valor atual. `password` vazio remove a senha salva.
+#[derive(Debug, Deserialize)]
+pub struct SaveKoreaderConfigPayload {
+    pub server_url: Option<String>,
+    pub username: Option<String>,
+    pub password: Option<String>,
+    pub match_method: Option<String>,
+    pub interval_secs: Option<i64>,
+    pub server_enabled: Option<bool>,
+    pub server_port: Option<u16>,
+    pub server_lan: Option<bool>,
+    pub server_registration: Option<bool>,
+}
+
+/// Campos que exigem reiniciar o servidor embutido quando mudam.
+fn server_settings(config: &KoreaderConfig) -> (bool, u16, bool, bool) {
+    (
+        config.server_enabled,
+        config.server_port,
+        config.server_lan,
+        config.server_registration,
+    )
+}
+
+#[tauri::command]
+pub fn save_koreader_config(
+    app: AppHandle,
+    payload: SaveKoreaderConfigPayload,
+) -> crate::Result<KoreaderConfig> {
+    let conn = db::open(&app)?;
+    let current = repositories::get_koreader_config(&conn)?;
+    let running = server_settings(&current);
+    let match_method = payload.match_method.unwrap_or(current.match_method);
+    if !matches!(match_method.as_str(), "binary" | "filename") {
+        return Err(crate::Error::Validation(format!(
+            "unknown KOReader match method: {}",
+            match_method
+        )));
+    }
+    let server_url = payload
+        .server_url
+        .map(|u| u.trim().to_string())
+        .filter(|u| !u.is_empty());
+    if let Some(url) = &server_url {
+        if !url.starts_with("http://") && !url.starts_with("https://") {
+            return Err(crate::Error::Validation(
+                "KOReader server must be an http(s) URL".to_string(),
+            ));
+        }
+    }
+    let config = KoreaderConfig {
+        server_url,
+        username: payload
+            .username
+            .map(|u| u.trim().to_string())
+            .filter(|u| !u.is_empty()),
+        // O KOReader envia MD5 da senha; guardamos só o hash
+        userkey: match payload.password {
+            Some(p) if p.is_empty() => None,
+            Some(p) => Some(koreader::md5_hex(p.as_bytes())),
+            None => current.userkey,
+        },
+        match_method,
+        interval_secs: payload.interval_secs.unwrap_or(current.interval_secs),
+        last_sync_at: current.last_sync_at,
+        server_enabled: payload.server_enabled.unwrap_or(current.server_enabled),
+        server_port: payload.server_port.unwrap_or(current.server_port),
+        server_lan: payload.server_lan.unwrap_or(current.server_lan),
+        server_registration: payload
+            .server_registration
+            .unwrap_or(current.server_registration),
+    };
+    repositories::save_koreader_config(&conn, &config)?;
+    if server_settings(&config) != running {
+        scheduler::restart_koreader_server(&app)?;
+    }
+    Ok(config)
+}
+
+fn configured_client(app: &AppHandle) -> crate::Result<koreader::KoreaderClient> {
+    let conn = db::open(app)?;
+    let config = repositories::get_koreader_config(&conn)?;
+    koreader::client_from_config(&config)?
+        .ok_or_else(|| crate::Error::Validation("KOReader sync is not configured".to_string()))
+}
+
+/// Cria a conta configurada no servidor kosync.
+#[tauri::command]
+pub async fn koreader_register(app: AppHandle) -> crate::Result<()> {
+    tauri::async_runtime::spawn_blocking(move || configured_client(&app)?.register())
+        .await
+        .map_err(|e| crate::Error::Io(format!("spawn_blocking: {:?}", e)))?
+}
+
+/// Verifica servidor e credenciais.
+#[tauri::command]
+pub async fn koreader_authorize(app: AppHandle) -> crate::Result<()> {
+    tauri::async_runtime::spawn_blocking(move || configured_client(&app)?.authorize())
+        .await
+        .map_err(|e| crate::Error::Io(format!("spawn_blocking: {:?}", e)))?
+}
+
+#[tauri::command]
+pub async fn koreader_sync_now(app: AppHandle) -> crate::Result<KoreaderSyncReport> {
+    tauri::async_runtime::spawn_blocking(move || scheduler::koreader_sync_now(&app))
+        .await
+        .map_err(|e| crate::Error::Io(format!("spawn_blocking: {:?}", e)))?
+}
@@ -3,13 +3,19 @@
 use serde::{Deserialize, Serialize};
 use tauri::{AppHandle, Manager};
 
-use crate::commands::book_commands::{BookWithVolumes, VolumeWithChaptersOut};
+use crate::commands::book_commands::{load_book_with_volumes, BookWithVolumes};
 use crate::db;
 use crate::metadata;
 use crate::metadata::decision::BookMetadataState;
-use crate::metadata::MetadataCandidate;
+use crate::metadata::{CacheOptions, MetadataCandidate};
+use crate::models::{
+    BookMetadataFlags, BookMetadataPatch, JobItemStatus, MetadataChange, MetadataConfig,
+};
 use crate::repositories;
 
+/// Peso máximo de uma fonte no score.
+const MAX_SOURCE_WEIGHT: f32 = 2.0;
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct MetadataCandidateDto {
     pub source: String,
@@ -30,6 +36,16 @@ pub struct MetadataCandidateDto {
     pub year: Option<i32>,
     #[serde(default)]
     pub language: Option<String>,
+    #[serde(default)]
+    pub publisher: Option<String>,
+    #[serde(default)]
+    pub isbns: Vec<String>,
+    #[serde(default, alias = "pageCount")]
+    pub page_count: Option<i32>,
+    #[serde(default)]
+    pub genres: Vec<String>,
+    #[serde(default, alias = "communityScore")]
+    pub community_score: Option<f32>,
 }
 
 #[derive(Debug, Serialize)]
@@ -75,204 +91,311 @@ fn candidate_to_dto(c: &MetadataCandidate) -> MetadataCandidateDto {
         cover_url: c.cover_url.clone(),
         year: c.year,
         language: c.language.clone(),
+        publisher: c.publisher.clone(),
+        isbns: c.isbns.clone(),
+        page_count: c.page_count,
+        genres: c.genres.clone(),
+        community_score: c.community_score,
+    }
+}
+
+pub(crate) fn candidate_from_dto(dto: MetadataCandidateDto) -> MetadataCandidate {
+    MetadataCandidate {
+        source: dto.source,
+        source_id: dto.source_id,
+        media_type: match dto.media_type.as_str() {
+            "manga" => metadata::MediaType::Manga,
+            "anime" => metadata::MediaType::Anime,
+            _ => metadata::MediaType::Book,
+        },
+        title: dto.title,
+        title_alternatives: dto.title_alternatives,
+        author: dto.author,
+        description: dto.description,
+        cover_url: dto.cover_url,
+        year: dto.year,
+        language: dto.language,
+        publisher: dto.publisher,
+        isbns: dto.isbns,
+        page_count: dto.page_count,
+        genres: dto.genres,
+        community_score: dto.community_score,
     }
 }
 
+/// Metadados estendidos do candidato. Com `edited`, campos editados à mão
+/// ficam como estão; o que o candidato não tem nunca apaga o valor atual.
+fn candidate_patch(c: &MetadataCandidate, edited: Option<&BookMetadataFlags>) -> BookMetadataPatch {
+    let keep = edited.copied().unwrap_or_default();
+    let mut patch = BookMetadataPatch::default();
+    if !keep.publisher && c.publisher.is_some() {
+        patch.publisher = Some(c.publisher.clone());
+    }
+    if !keep.year && c.year.is_some() {
+        patch.year = Some(c.year);
+    }
+    if !keep.language && c.language.is_some() {
+        patch.language = Some(c.language.clone());
+    }
+    if !keep.isbns && !c.isbns.is_empty() {
+        patch.isbns = Some(c.isbns.clone());
+    }
+    let alt_titles: Vec<String> = c
+        .title_alternatives
+        .iter()
+        .filter(|t| **t != c.title)
+        .cloned()
+        .collect();
+    if !keep.alt_titles && !alt_titles.is_empty() {
+        patch.alt_titles = Some(alt_titles);
+    }
+    if !keep.page_count && c.page_count.is_some() {
+        patch.page_count = Some(c.page_count);
+    }
+    if !keep.media_type {
+        patch.media_type = Some(Some(c.media_type));
+    }
+    if !keep.genres && !c.genres.is_empty() {
+        patch.genres = Some(c.genres.clone());
+    }
+    if !keep.external_ids && !c.source_id.is_empty() {
+        patch
+            .external_ids
+            .push((c.source.clone(), c.source_id.clone()));
+    }
+    patch
+}
+
 #[tauri::command]
 pub async fn search_metadata(
     app: AppHandle,
     book_id: String,
+    bypass_cache: Option<bool>,
 ) -> crate::Result<SearchMetadataResult> {
     log::info!("[metadata] Iniciando busca para book_id={}", book_id);
 
     tauri::async_runtime::spawn_blocking(move || {
         let conn = db::open(&app)?;
+        run_metadata_search(&app, &conn, &book_id, bypass_cache.unwrap_or(false))
+    })
+    .await
+    .map_err(|e| crate::Error::Io(format!("spawn_blocking: {:?}", e)))?
+}
 
-        let book = repositories::list_books(&conn)?
-        .into_iter()
-        .find(|b| b.id == book_id)
+/// Busca metadados do livro e aplica o melhor candidato quando a decisão
+/// permitir (um único resultado acima de `SCORE_THRESHOLD`). Também usada
+/// pelo enriquecimento em lote.
+pub(crate) fn run_metadata_search(
+    app: &AppHandle,
+    conn: &rusqlite::Connection,
+    book_id: &str,
+    bypass_cache: bool,
+) -> crate::Result<SearchMetadataResult> {
+    let book = repositories::get_book(conn, book_id)?
         .ok_or_else(|| crate::Error::NotFound("Book not found".to_string()))?;
 
-        let volumes = repositories::list_volumes(&conn, &book_id)?;
-        let mut chapter_names: Vec<String> = Vec::new();
-        let mut chapter_paths: Vec<String> = Vec::new();
-        for vol in &volumes {
-            let chapters = repositories::list_chapters(&conn, &vol.id)?;
-            for ch in chapters {
-                chapter_names.push(ch.name.clone());
-                chapter_paths.push(ch.path.clone());
-            }
+    let volumes = repositories::list_volumes(conn, book_id)?;
+    let mut chapter_names: Vec<String> = Vec::new();
+    let mut chapter_paths: Vec<String> = Vec::new();
+    for vol in &volumes {
+        let chapters = repositories::list_chapters(conn, &vol.id)?;
+        for ch in chapters {
+            chapter_names.push(ch.name.clone());
+            chapter_paths.push(ch.path.clone());
         }
+    }
 
+    log::info!(
+        "[metadata] Livro: title=\"{}\", path=\"{}\", author={:?}, {} capítulos",
+        book.title,
+        book.path,
+        book.author,
+        chapter_names.len()
+    );
+
+    let edited = repositories::get_metadata_flags(conn, book_id)?;
+    let flags = BookMetadataState {
+        author_manually_edited: edited.author,
+        description_manually_edited: edited.description,
+        cover_manually_edited: edited.cover,
+        title_manually_edited: false,
+    };
+
+    let chapter_names_opt = if chapter_names.is_empty() {
+        None
+    } else {
+        Some(chapter_names.as_slice())
+    };
+    let chapter_paths_opt = if chapter_paths.is_empty() {
+        None
+    } else {
+        Some(chapter_paths.as_slice())
+    };
+    let config = repositories::get_metadata_config(conn)?;
+    let cache_options = CacheOptions {
+        ttl_secs: config.cache_ttl_secs,
+        bypass: bypass_cache,
+    };
+    let result = metadata::search_metadata(
+        &book.title,
+        Some(&book.path),
+        book.author.as_deref(),
+        chapter_names_opt,
+        chapter_paths_opt,
+        &flags,
+        conn,
+        cache_options,
+        &config.sources,
+    );
+
+    let Some(search_result) = result else {
+        log::info!("[metadata] Nenhum resultado encontrado");
+        return Ok(SearchMetadataResult {
+            applied: false,
+            confirmed: false,
+            score: 0.0,
+            source: String::new(),
+            title: None,
+            author: None,
+            has_description: false,
+            has_cover: false,
+            candidates: vec![],
+        });
+    };
+
+    let candidates: Vec<RankedCandidateDto> = search_result
+        .ranked_candidates
+        .iter()
+        .map(|rc| RankedCandidateDto {
+            candidate: candidate_to_dto(&rc.candidate),
+            score: rc.score,
+        })
+        .collect();
+
+    let (dec, applied) = if let Some(dec) = &search_result.decision {
         log::info!(
-            "[metadata] Livro: title=\"{}\", path=\"{}\", author={:?}, {} capítulos",
-            book.title,
-            book.path,
-            book.author,
-            chapter_names.len()
+            "[metadata] Aplicando automaticamente: source={}, title=\"{}\", score={:.1}",
+            dec.candidate.source,
+            dec.candidate.title,
+            dec.score
         );
-
-        let (author_me, desc_me, cover_me, _title_me) = repositories::get_metadata_flags(&conn, &book_id)?;
-        let flags = BookMetadataState {
-            author_manually_edited: author_me,
-            description_manually_edited: desc_me,
-            cover_manually_edited: cover_me,
-            title_manually_edited: false,
-        };
-
-        let chapter_names_opt = if chapter_names.is_empty() {
-            None
-        } else {
-            Some(chapter_names.as_slice())
-        };
-        let chapter_paths_opt = if chapter_paths.is_empty() {
-            None
-        } else {
-            Some(chapter_paths.as_slice())
-        };
-        let result = metadata::search_metadata(
-            &book.title,
-            Some(&book.path),
-            book.author.as_deref(),
-            chapter_names_opt,
-            chapter_paths_opt,
-            &flags,
+        (dec.clone(), dec.apply)
+    } else {
+        let best = &search_result.ranked_candidates[0];
+        log::info!(
+            "[metadata] Múltiplos candidatos: source={}, title=\"{}\", score={:.1} - aguardando seleção",
+            best.candidate.source,
+            best.candidate.title,
+            best.score
         );
+        (
+            metadata::decision::MetadataDecision {
+                apply: false,
+                confirmed: best.score >= metadata::decision::SCORE_THRESHOLD,
+                score: best.score,
+                candidate: best.candidate.clone(),
+            },
+            false,
+        )
+    };
 
-        let Some(search_result) = result else {
-            log::info!("[metadata] Nenhum resultado encontrado");
-            return Ok(SearchMetadataResult {
-                applied: false,
-                confirmed: false,
-                score: 0.0,
-                source: String::new(),
-                title: None,
-                author: None,
-                has_description: false,
-                has_cover: false,
-                candidates: vec![],
-            });
-        };
-
-        let candidates: Vec<RankedCandidateDto> = search_result
-            .ranked_candidates
-            .iter()
-            .map(|rc| RankedCandidateDto {
-                candidate: candidate_to_dto(&rc.candidate),
-                score: rc.score,
-            })
-            .collect();
-
-        let (dec, applied) = if let Some(dec) = &search_result.decision {
-            log::info!(
-                "[metadata] Aplicando automaticamente: source={}, title=\"{}\", score={:.1}",
-                dec.candidate.source,
-                dec.candidate.title,
-                dec.score
-            );
-            (dec.clone(), dec.apply)
-        } else {
-            let best = &search_result.ranked_candidates[0];
-            log::info!(
-                "[metadata] Múltiplos candidatos: source={}, title=\"{}\", score={:.1} - aguardando seleção",
-                best.candidate.source,
-                best.candidate.title,
-                best.score
-            );
-            (
-                metadata::decision::MetadataDecision {
-                    apply: false,
-                    confirmed: best.score >= metadata::decision::SCORE_THRESHOLD,
-                    score: best.score,
-                    candidate: best.candidate.clone(),
-                },
-                false,
-            )
-        };
-
-        if applied {
-            let c = &dec.candidate;
-            let mut new_author: Option<Option<&str>> = None;
-            let mut new_desc: Option<Option<&str>> = None;
-            let mut new_cover: Option<Option<&str>> = None;
-
-            // Só atualiza author/description quando o candidato tem valor
-            if !flags.author_manually_edited && c.author.as_ref().map(|s| !s.is_empty()).unwrap_or(false) {
-                new_author = Some(c.author.as_deref());
-            }
-            if !flags.description_manually_edited && c.description.as_ref().map(|s| !s.is_empty()).unwrap_or(false) {
-                new_desc = Some(c.description.as_deref());
-            }
+    if applied {
+        let c = &dec.candidate;
+        let mut new_author: Option<Option<&str>> = None;
+        let mut new_desc: Option<Option<&str>> = None;
+        let mut new_cover: Option<Option<&str>> = None;
+
+        // Só atualiza author/description quando o candidato tem valor
+        if !flags.author_manually_edited
+            && c.author.as_ref().map(|s| !s.is_empty()).unwrap_or(false)
+        {
+            new_author = Some(c.author.as_deref());
+        }
+        if !flags.description_manually_edited
+            && c.description
+                .as_ref()
+                .map(|s| !s.is_empty())
+                .unwrap_or(false)
+        {
+            new_desc = Some(c.description.as_deref());
+        }
 
-            let mut cover_path: Option<String> = None;
-            if !flags.cover_manually_edited {
-                if let Some(ref cover_url) = c.cover_url {
-                    let app_data = app
-                        .path()
-                        .app_data_dir()
-                        .map_err(|e| crate::Error::Path(e.to_string()))?;
-                    let covers_dir = app_data.join("covers");
-                    std::fs::create_dir_all(&covers_dir)
-                        .map_err(|e| crate::Error::Io(e.to_string()))?;
-                    let covers_dir = std::fs::canonicalize(&covers_dir)
-                        .map_err(|e| crate::Error::Io(format!("covers_dir: {}", e)))?;
-                    let ext = if cover_url.contains(".png") { "png" } else { "jpg" };
-                    let safe_name = sanitize_book_id_for_filename(&book_id);
-                    let dest = covers_dir.join(format!("{}.{}", safe_name, ext));
-                    if metadata::cover::download_cover(cover_url, &dest).is_ok() {
-                        log::info!("[metadata] Capa baixada em: {}", dest.display());
-                        cover_path = Some(dest.to_string_lossy().to_string());
-                    } else {
-                        log::warn!("[metadata] Falha ao baixar capa de {}", cover_url);
-                    }
+        let mut cover_path: Option<String> = None;
+        if !flags.cover_manually_edited {
+            if let Some(ref cover_url) = c.cover_url {
+                let app_data = app
+                    .path()
+                    .app_data_dir()
+                    .map_err(|e| crate::Error::Path(e.to_string()))?;
+                let covers_dir = app_data.join("covers");
+                std::fs::create_dir_all(&covers_dir)
+                    .map_err(|e| crate::Error::Io(e.to_string()))?;
+                let covers_dir = std::fs::canonicalize(&covers_dir)
+                    .map_err(|e| crate::Error::Io(format!("covers_dir: {}", e)))?;
+                let ext = if cover_url.contains(".png") {
+                    "png"
+                } else {
+                    "jpg"
+                };
+                let safe_name = sanitize_book_id_for_filename(book_id);
+                let dest = covers_dir.join(format!("{}.{}", safe_name, ext));
+                if metadata::cover::download_cover(cover_url, &dest).is_ok() {
+                    log::info!("[metadata] Capa baixada em: {}", dest.display());
+                    cover_path = Some(dest.to_string_lossy().to_string());
+                } else {
+                    log::warn!("[metadata] Falha ao baixar capa de {}", cover_url);
                 }
-                new_cover = cover_path.as_deref().map(Some);
             }
-
-            repositories::update_book_partial(
-                &conn,
-                &book_id,
-                Some(c.title.as_str()),
-                new_author,
-                new_desc,
-                new_cover,
-            )?;
+            new_cover = cover_path.as_deref().map(Some);
         }
 
-        let result_id = format!(
-            "{}-{}",
+        repositories::update_book_partial(
+            conn,
             book_id,
-            std::time::SystemTime::now()
-                .duration_since(std::time::UNIX_EPOCH)
-                .unwrap_or_default()
-                .as_millis()
-        );
-        repositories::insert_search_result(
-            &conn,
-            &result_id,
-            &book_id,
-            &dec.candidate.source,
-            Some(&dec.candidate.source_id),
-            dec.score,
-            &search_result.search_query_used,
-            dec.apply,
-            dec.confirmed,
+            Some(c.title.as_str()),
+            new_author,
+            new_desc,
+            new_cover,
+            &candidate_patch(c, Some(&edited)),
+            &format!("auto:{}", c.source),
         )?;
+        // Gêneros viram tags automáticas; as manuais ficam intactas
+        if !c.genres.is_empty() {
+            repositories::set_auto_tags(conn, book_id, &c.genres)?;
+        }
+    }
 
-        Ok(SearchMetadataResult {
-            applied,
-            confirmed: dec.confirmed,
-            score: dec.score,
-            source: dec.candidate.source.clone(),
-            title: Some(dec.candidate.title.clone()),
-            author: dec.candidate.author.clone(),
-            has_description: dec.candidate.description.is_some(),
-            has_cover: dec.candidate.cover_url.is_some(),
-            candidates,
-        })
+    let result_id = format!(
+        "{}-{}",
+        book_id,
+        std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_millis()
+    );
+    repositories::insert_search_result(
+        conn,
+        &result_id,
+        book_id,
+        &dec.candidate.source,
+        Some(&dec.candidate.source_id),
+        dec.score,
+        &search_result.search_query_used,
+        dec.apply,
+        dec.confirmed,
+    )?;
+
+    Ok(SearchMetadataResult {
+        applied,
+        confirmed: dec.confirmed,
+        score: dec.score,
+        source: dec.candidate.source.clone(),
+        title: Some(dec.candidate.title.clone()),
+        author: dec.candidate.author.clone(),
+        has_description: dec.candidate.description.is_some(),
+        has_cover: dec.candidate.cover_url.is_some(),
+        candidates,
     })
-    .await
-    .map_err(|e| crate::Error::Io(format!("spawn_blocking: {:?}", e)))?
 }
 
 #[tauri::command]
@@ -293,29 +416,12 @@ pub async fn apply_metadata_candidate(
     tauri::async_runtime::spawn_blocking(move || {
         let conn = db::open(&app)?;
 
-        let _book = repositories::list_books(&conn)?
-            .into_iter()
-            .find(|b| b.id == book_id)
+        let _book = repositories::get_book(&conn, &book_id)?
             .ok_or_else(|| crate::Error::NotFound("Book not found".to_string()))?;
 
         let _ = repositories::get_metadata_flags(&conn, &book_id)?; // ignoramos flags quando usuário seleciona
 
-        let c = MetadataCandidate {
-            source: candidate.source,
-            source_id: candidate.source_id,
-            media_type: match candidate.media_type.as_str() {
-                "manga" => metadata::MediaType::Manga,
-                "anime" => metadata::MediaType::Anime,
-                _ => metadata::MediaType::Book,
-            },
-            title: candidate.title,
-            title_alternatives: candidate.title_alternatives,
-            author: candidate.author,
-            description: candidate.description,
-            cover_url: candidate.cover_url,
-            year: candidate.year,
-            language: candidate.language,
-        };
+        let c = candidate_from_dto(candidate);
 
         let mut new_author: Option<Option<&str>> = None;
         let mut new_desc: Option<Option<&str>> = None;
@@ -374,7 +480,12 @@ pub async fn apply_metadata_candidate(
             new_author,
             new_desc,
             new_cover,
+            &candidate_patch(&c, None),
+            &format!("candidate:{}", c.source),
         )?;
+        if !c.genres.is_empty() {
+            repositories::set_auto_tags(&conn, &book_id, &c.genres)?;
+        }
 
         let result_id = format!(
             "{}-{}",
@@ -395,35 +506,91 @@ pub async fn apply_metadata_candidate(
             true,
             true,
         )?;
+        // Escolha feita: sai da fila de revisão do enriquecimento em lote
+        repositories::resolve_review_items(&conn, &book_id, JobItemStatus::Applied)?;
 
         // Retorna o livro atualizado para o frontend usar diretamente
-        let book = repositories::list_books(&conn)?
-            .into_iter()
-            .find(|b| b.id == book_id)
-            .ok_or_else(|| crate::Error::NotFound("Book not found".to_string()))?;
+        let updated = load_book_with_volumes(&conn, &book_id)?;
+        let book = &updated.book;
         log::info!(
             "[metadata] Após update: author={:?} description={} cover_path={:?}",
             book.author,
             book.description.as_ref().map(|s| s.len()).unwrap_or(0),
             book.cover_path.as_ref().map(|s| s.len()).unwrap_or(0),
         );
-        let volumes = repositories::list_volumes(&conn, &book_id)?;
-        let volumes_with_chapters: Vec<VolumeWithChaptersOut> = volumes
-            .into_iter()
-            .map(|vol| {
-                let chapters = repositories::list_chapters(&conn, &vol.id).unwrap_or_default();
-                VolumeWithChaptersOut {
-                    volume: vol,
-                    chapters,
-                }
-            })
-            .collect();
-
-        Ok(BookWithVolumes {
-            book,
-            volumes: volumes_with_chapters,
-        })
+        Ok(updated)
     })
     .await
     .map_err(|e| crate::Error::Io(format!("spawn_blocking: {:?}", e)))?
 }
+
+/// Histórico de alterações de metadados do livro (mais recentes primeiro).
+#[tauri::command]
+pub fn get_metadata_history(app: AppHandle, book_id: String) -> crate::Result<Vec<MetadataChange>> {
+    let conn = db::open(&app)?;
+    repositories::list_metadata_changes(&conn, &book_id)
+}
+
+/// Desfaz uma alteração: o campo volta ao valor anterior e passa a contar
+/// como editado manualmente, para a busca automática não repetir o erro.
+#[tauri::command]
+pub fn revert_metadata(
+    app: AppHandle,
+    book_id: String,
+    change_id: String,
+) -> crate::Result<BookWithVolumes> {
+    let conn = db::open(&app)?;
+    let field = repositories::revert_metadata(&conn, &book_id, &change_id)?
+        .ok_or_else(|| crate::Error::NotFound(format!("metadata change {}", change_id)))?;
+    let mut flags = repositories::get_metadata_flags(&conn, &book_id)?;
+    flags.mark_field(&field);
+    repositories::set_metadata_flags(&conn, &book_id, &flags)?;
+    log::info!("[metadata] Revertido {} de book_id={}", field, book_id);
+    load_book_with_volumes(&conn, &book_id)
+}
+
+#[tauri::command]
+pub fn get_metadata_config(app: AppHandle) -> crate::Result<MetadataConfig> {
+    let conn = db::open(&app)?;
+    repositories::get_metadata_config(&conn)
+}
+
+#[tauri::command]
+pub fn save_metadata_config(app: AppHandle, config: MetadataConfig) -> crate::Result<()> {
+    if config.cache_ttl_secs < 0 {
+        return Err(crate::Error::Validation(
+            "cache_ttl_secs cannot be negative".to_string(),
+        ));
+    }
+    for source in &config.sources {
+        if !metadata::SOURCE_NAMES.contains(&source.name.as_str()) {
+            return Err(crate::Error::Validation(format!(
+                "unknown metadata source: {}",
+                source.name
+            )));
+        }
+        if !(0.0..=MAX_SOURCE_WEIGHT).contains(&source.weight) {
+            return Err(crate::Error::Validation(format!(
+                "weight for {} must be between 0 and {}",
+                source.name, MAX_SOURCE_WEIGHT
+            )));
+        }
+    }
+    // Mesma unidade de save_global_settings (segundos)
+    let now = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs() as i64)
+        .unwrap_or(0);
+    let conn = db::open(&app)?;
+    repositories::save_metadata_config(&conn, &config, now)
+}
+
+/// Esvazia o cache de buscas de uma fonte, ou de todas. Retorna quantas
+/// entradas foram apagadas.
+#[tauri::command]
+pub fn clear_metadata_cache(app: AppHandle, source: Option<String>) -> crate::Result<usize> {
+    let conn = db::open(&app)?;
+    let n = repositories::clear_metadata_cache(&conn, source.as_deref())?;
+    log::info!("[metadata] Cache limpo: {} entradas", n);
+    Ok(n)
+}
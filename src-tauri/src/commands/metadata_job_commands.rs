@@ -0,0 +1,201 @@
+//! Tauri commands para o enriquecimento de metadados em lote.
+//! O lote roda numa thread em background, um livro por vez, e emite
+//! `metadata-job-progress` a cada livro. Pausar só vale a partir do próximo livro.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+
+use tauri::{AppHandle, Emitter};
+
+use crate::commands::metadata_commands::{candidate_from_dto, run_metadata_search};
+use crate::db;
+use crate::metadata::RankedCandidate;
+use crate::models::{JobItemStatus, JobStatus, MetadataJob, MetadataJobItem};
+use crate::repositories;
+
+/// Pausa entre livros, para não disparar rajadas contra as fontes.
+const BOOK_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Há uma thread processando o lote ativo.
+static WORKER_RUNNING: AtomicBool = AtomicBool::new(false);
+
+fn emit_progress(app: &AppHandle, conn: &rusqlite::Connection, job_id: &str) {
+    if let Ok(Some(job)) = repositories::get_metadata_job(conn, job_id) {
+        let _ = app.emit("metadata-job-progress", &job);
+    }
+}
+
+/// Lote ativo, se estiver em execução.
+fn running_job(conn: &rusqlite::Connection) -> crate::Result<Option<String>> {
+    Ok(repositories::active_metadata_job(conn)?
+        .filter(|job| job.status == JobStatus::Running)
+        .map(|job| job.id))
+}
+
+/// Processa os livros pendentes enquanto houver um lote em execução.
+fn run_jobs(app: &AppHandle) -> crate::Result<()> {
+    let conn = db::open(app)?;
+    loop {
+        let Some(job_id) = running_job(&conn)? else {
+            return Ok(());
+        };
+        let job_id = job_id.as_str();
+        let Some(book_id) = repositories::next_pending_item(&conn, job_id)? else {
+            repositories::set_job_status(&conn, job_id, JobStatus::Completed)?;
+            emit_progress(app, &conn, job_id);
+            log::info!("[metadata:job] Lote {} concluído", job_id);
+            continue;
+        };
+        match run_metadata_search(app, &conn, &book_id, false) {
+            Ok(result) if result.applied => repositories::finish_job_item(
+                &conn,
+                job_id,
+                &book_id,
+                JobItemStatus::Applied,
+                Some(result.score),
+                &[],
+                None,
+            )?,
+            Ok(result) if !result.candidates.is_empty() => {
+                let candidates: Vec<RankedCandidate> = result
+                    .candidates
+                    .into_iter()
+                    .map(|rc| RankedCandidate {
+                        candidate: candidate_from_dto(rc.candidate),
+                        score: rc.score,
+                    })
+                    .collect();
+                repositories::finish_job_item(
+                    &conn,
+                    job_id,
+                    &book_id,
+                    JobItemStatus::Review,
+                    Some(result.score),
+                    &candidates,
+                    None,
+                )?
+            }
+            Ok(_) => repositories::finish_job_item(
+                &conn,
+                job_id,
+                &book_id,
+                JobItemStatus::NoMatch,
+                None,
+                &[],
+                None,
+            )?,
+            Err(e) => {
+                log::warn!("[metadata:job] Falha em book_id={}: {:?}", book_id, e);
+                repositories::finish_job_item(
+                    &conn,
+                    job_id,
+                    &book_id,
+                    JobItemStatus::Failed,
+                    None,
+                    &[],
+                    Some(format!("{:?}", e).as_str()),
+                )?
+            }
+        }
+        emit_progress(app, &conn, job_id);
+        std::thread::sleep(BOOK_INTERVAL);
+    }
+}
+
+/// Inicia a thread do lote, se ainda não houver uma.
+fn spawn_worker(app: AppHandle) {
+    if WORKER_RUNNING.swap(true, Ordering::SeqCst) {
+        return;
+    }
+    std::thread::spawn(move || loop {
+        if let Err(e) = run_jobs(&app) {
+            log::warn!("[metadata:job] Lote interrompido: {:?}", e);
+        }
+        WORKER_RUNNING.store(false, Ordering::SeqCst);
+        // Retomado enquanto a thread saía: continua aqui mesmo
+        let resumed = db::open(&app)
+            .and_then(|conn| running_job(&conn))
+            .map(|job| job.is_some())
+            .unwrap_or(false);
+        if !resumed || WORKER_RUNNING.swap(true, Ordering::SeqCst) {
+            break;
+        }
+    });
+}
+
+/// Retoma, na abertura do app, um lote que estava em execução.
+pub fn resume_metadata_jobs(app: &AppHandle) {
+    let active = db::open(app).and_then(|conn| repositories::active_metadata_job(&conn));
+    match active {
+        Ok(Some(job)) if job.status == JobStatus::Running => {
+            log::info!("[metadata:job] Retomando lote {}", job.id);
+            spawn_worker(app.clone());
+        }
+        Ok(_) => {}
+        Err(e) => log::warn!("[metadata:job] Falha ao verificar lotes: {:?}", e),
+    }
+}
+
+/// Cria um lote com os livros sem metadados confirmados. Se já houver um
+/// lote ativo, ele é retomado.
+#[tauri::command]
+pub fn start_metadata_job(app: AppHandle) -> crate::Result<MetadataJob> {
+    let conn = db::open(&app)?;
+    let job = repositories::create_metadata_job(&conn)?;
+    if job.status == JobStatus::Paused {
+        repositories::set_job_status(&conn, &job.id, JobStatus::Running)?;
+    }
+    let job = repositories::get_metadata_job(&conn, &job.id)?
+        .ok_or_else(|| crate::Error::NotFound(format!("metadata job {}", job.id)))?;
+    if job.status == JobStatus::Running {
+        spawn_worker(app);
+    }
+    Ok(job)
+}
+
+#[tauri::command]
+pub fn pause_metadata_job(app: AppHandle) -> crate::Result<()> {
+    let conn = db::open(&app)?;
+    let job = repositories::active_metadata_job(&conn)?
+        .ok_or_else(|| crate::Error::NotFound("no active metadata job".to_string()))?;
+    repositories::set_job_status(&conn, &job.id, JobStatus::Paused)?;
+    emit_progress(&app, &conn, &job.id);
+    Ok(())
+}
+
+/// Encerra o lote ativo; os livros já processados mantêm o resultado.
+#[tauri::command]
+pub fn cancel_metadata_job(app: AppHandle) -> crate::Result<()> {
+    let conn = db::open(&app)?;
+    let job = repositories::active_metadata_job(&conn)?
+        .ok_or_else(|| crate::Error::NotFound("no active metadata job".to_string()))?;
+    repositories::set_job_status(&conn, &job.id, JobStatus::Cancelled)?;
+    emit_progress(&app, &conn, &job.id);
+    Ok(())
+}
+
+/// Lote mais recente (ativo ou não).
+#[tauri::command]
+pub fn get_metadata_job(app: AppHandle) -> crate::Result<Option<MetadataJob>> {
+    let conn = db::open(&app)?;
+    repositories::latest_metadata_job(&conn)
+}
+
+/// Livros do lote; com `status: "review"`, a fila de revisão.
+#[tauri::command]
+pub fn get_metadata_job_items(
+    app: AppHandle,
+    job_id: String,
+    status: Option<JobItemStatus>,
+) -> crate::Result<Vec<MetadataJobItem>> {
+    let conn = db::open(&app)?;
+    repositories::list_job_items(&conn, &job_id, status)
+}
+
+/// Tira o livro da fila de revisão sem aplicar nenhum candidato.
+#[tauri::command]
+pub fn dismiss_metadata_review(app: AppHandle, book_id: String) -> crate::Result<()> {
+    let conn = db::open(&app)?;
+    repositories::resolve_review_items(&conn, &book_id, JobItemStatus::Dismissed)?;
+    Ok(())
+}
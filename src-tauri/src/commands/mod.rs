@@ -1,29 +1,85 @@
+mod annotation_commands;
+mod backup_commands;
 mod book_commands;
+mod bookmark_commands;
 mod custom_theme_commands;
+mod koreader_commands;
 mod metadata_commands;
+mod metadata_job_commands;
 mod pending_open;
 mod progress_commands;
+mod queue_commands;
+mod read_status_commands;
+mod review_commands;
+mod session_commands;
 mod settings_commands;
 mod shelf_commands;
+mod sync_commands;
+mod tag_commands;
 
+pub use annotation_commands::{
+    create_annotation, delete_annotation, export_annotations, get_annotation, list_annotations,
+    query_annotations, update_annotation, CreateAnnotationPayload, ExportAnnotationsPayload,
+    UpdateAnnotationPayload,
+};
+pub use backup_commands::{export_library, import_library};
 pub use book_commands::{
-    add_book, delete_book, get_books, update_book, AddBookPayload, BookWithVolumes,
-    UpdateBookPayload,
+    add_book, delete_book, get_book, get_books, query_books, update_book, AddBookPayload,
+    BookWithVolumes, UpdateBookPayload,
+};
+pub use bookmark_commands::{
+    create_bookmark, delete_bookmark, jump_to_bookmark, list_bookmarks, rename_bookmark,
+    CreateBookmarkPayload,
 };
 pub use custom_theme_commands::{
     create_custom_theme, delete_custom_theme, get_custom_theme, list_custom_themes,
     update_custom_theme, CreateCustomThemePayload, UpdateCustomThemePayload,
 };
+pub use koreader_commands::{
+    get_koreader_config, koreader_authorize, koreader_register, koreader_sync_now,
+    save_koreader_config, SaveKoreaderConfigPayload,
+};
 pub use metadata_commands::{
-    apply_metadata_candidate, search_metadata, MetadataCandidateDto, SearchMetadataResult,
+    apply_metadata_candidate, clear_metadata_cache, get_metadata_config, get_metadata_history,
+    revert_metadata, save_metadata_config, search_metadata, MetadataCandidateDto,
+    SearchMetadataResult,
+};
+pub use metadata_job_commands::{
+    cancel_metadata_job, dismiss_metadata_review, get_metadata_job, get_metadata_job_items,
+    pause_metadata_job, resume_metadata_jobs, start_metadata_job,
 };
 pub use pending_open::{collect_pending_from_args, get_pending_file_to_open, PendingFileOpen};
-pub use progress_commands::{get_all_progress, get_progress, get_recent_progress, save_progress};
+pub use progress_commands::{
+    delete_history_entries, delete_history_entry, get_all_progress, get_chapter_progress,
+    get_continue_reading, get_history, get_next_unread_chapter, get_progress, get_recent_progress,
+    get_series_completion, mark_previous_chapters_read, save_progress, set_chapters_read,
+};
+pub use queue_commands::{
+    dequeue, enqueue, list_queue, pop_queue, reorder_queue, suggest_next_volume, EnqueuePayload,
+};
+pub use read_status_commands::{
+    get_book_read_state, mark_volumes, set_book_status, set_volume_status,
+};
+pub use review_commands::{get_book_review, save_book_review};
+pub use session_commands::{
+    end_reading_session, get_pages_per_hour, get_reading_stats, get_reading_time_by_book,
+    get_reading_time_by_day, get_volume_eta, reading_session_heartbeat, start_reading_session,
+};
 pub use settings_commands::{
     get_book_settings, get_global_settings, save_book_settings, save_global_settings,
     SaveGlobalSettingsPayload,
 };
 pub use shelf_commands::{
-    add_book_to_shelf, create_shelf, get_book_shelf_ids, get_books_in_shelf, list_shelves,
-    remove_book_from_shelf,
+    add_book_to_shelf, create_shelf, create_smart_shelf, delete_shelf, get_book_shelf_ids,
+    get_books_in_shelf, insert_book_in_shelf, list_shelves, move_book_in_shelf,
+    remove_book_from_shelf, rename_shelf, reorder_shelves, set_shelf_style,
+    update_smart_shelf_rules,
+};
+pub use sync_commands::{
+    get_changes_since, get_device_id, get_sync_config, get_tombstones, save_sync_config, sync_now,
+    SaveSyncConfigPayload,
+};
+pub use tag_commands::{
+    add_tag_to_book, create_tag, delete_tag, get_book_tags, list_tags, merge_tags,
+    remove_tag_from_book, rename_tag,
 };
@@ -4,7 +4,10 @@
 use tauri::AppHandle;
 
 use crate::db;
-use crate::models::ReadingProgress;
+use crate::models::{
+    Chapter, ChapterProgress, ContinueReadingItem, HistoryPage, HistoryQuery, ReadingProgress,
+    SeriesCompletion,
+};
 use crate::repositories;
 
 fn now_secs() -> i64 {
@@ -21,7 +24,16 @@ pub fn save_progress(app: AppHandle, progress: ReadingProgress) -> crate::Result
         updated_at: now_secs(),
         ..progress
     };
-    repositories::upsert_progress(&conn, &p)?;
+    let tx = conn.unchecked_transaction()?;
+    let previous = repositories::get_progress(&tx, &p.book_id, &p.volume_id)?;
+    repositories::upsert_progress(&tx, &p)?;
+    repositories::record_history(&tx, &p, p.updated_at)?;
+    repositories::record_chapter_position(&tx, previous.as_ref(), &p, p.updated_at)?;
+    // Passar da primeira página conta como início da leitura
+    if p.page_index > 1 {
+        repositories::start_volume_if_unread(&tx, &p.volume_id, p.updated_at)?;
+    }
+    tx.commit()?;
     Ok(())
 }
 
@@ -41,8 +53,77 @@ pub fn get_recent_progress(app: AppHandle, limit: i64) -> crate::Result<Vec<Read
     repositories::list_recent_progress(&conn, limit)
 }
 
+/// "Continuar lendo": volumes em andamento com o livro, numa consulta só.
+#[tauri::command]
+pub fn get_continue_reading(app: AppHandle, limit: i64) -> crate::Result<Vec<ContinueReadingItem>> {
+    let conn = db::open(&app)?;
+    repositories::list_continue_reading(&conn, limit)
+}
+
 #[tauri::command]
 pub fn get_all_progress(app: AppHandle) -> crate::Result<Vec<ReadingProgress>> {
     let conn = db::open(&app)?;
     repositories::list_all_progress(&conn)
 }
+
+#[tauri::command]
+pub fn get_chapter_progress(
+    app: AppHandle,
+    book_id: String,
+) -> crate::Result<Vec<ChapterProgress>> {
+    let conn = db::open(&app)?;
+    repositories::list_chapter_progress(&conn, &book_id)
+}
+
+/// Marca capítulos como lidos (ou não lidos). Retorna quantos mudaram.
+#[tauri::command]
+pub fn set_chapters_read(
+    app: AppHandle,
+    chapter_ids: Vec<String>,
+    read: bool,
+) -> crate::Result<usize> {
+    let conn = db::open(&app)?;
+    repositories::set_chapters_read(&conn, &chapter_ids, read, now_secs())
+}
+
+/// Marca como lidos todos os capítulos da série anteriores a `chapter_id`.
+#[tauri::command]
+pub fn mark_previous_chapters_read(app: AppHandle, chapter_id: String) -> crate::Result<usize> {
+    let conn = db::open(&app)?;
+    repositories::mark_previous_chapters_read(&conn, &chapter_id, now_secs())
+}
+
+#[tauri::command]
+pub fn get_next_unread_chapter(app: AppHandle, book_id: String) -> crate::Result<Option<Chapter>> {
+    let conn = db::open(&app)?;
+    repositories::next_unread_chapter(&conn, &book_id)
+}
+
+#[tauri::command]
+pub fn get_series_completion(app: AppHandle, book_id: String) -> crate::Result<SeriesCompletion> {
+    let conn = db::open(&app)?;
+    repositories::series_completion(&conn, &book_id)
+}
+
+/// Linha do tempo de leitura, da mais recente para a mais antiga.
+#[tauri::command]
+pub fn get_history(app: AppHandle, query: HistoryQuery) -> crate::Result<HistoryPage> {
+    let conn = db::open(&app)?;
+    repositories::query_history(&conn, &query)
+}
+
+#[tauri::command]
+pub fn delete_history_entry(app: AppHandle, id: String) -> crate::Result<()> {
+    let conn = db::open(&app)?;
+    if repositories::delete_history_entries(&conn, std::slice::from_ref(&id))? == 0 {
+        return Err(crate::Error::NotFound(format!("history entry {}", id)));
+    }
+    Ok(())
+}
+
+/// Remove várias entradas de uma vez. Retorna quantas foram removidas.
+#[tauri::command]
+pub fn delete_history_entries(app: AppHandle, ids: Vec<String>) -> crate::Result<usize> {
+    let conn = db::open(&app)?;
+    repositories::delete_history_entries(&conn, &ids)
+}
@@ -0,0 +1,76 @@
+//! Tauri commands para a fila de leitura ("ler a seguir").
+
+use serde::Deserialize;
+use tauri::AppHandle;
+
+use crate::db;
+use crate::models::{QueueEntry, QueueItem, Volume};
+use crate::repositories;
+
+fn now_secs() -> i64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs() as i64)
+        .unwrap_or(0)
+}
+
+#[tauri::command]
+pub fn list_queue(app: AppHandle) -> crate::Result<Vec<QueueItem>> {
+    let conn = db::open(&app)?;
+    repositories::list_queue(&conn)
+}
+
+/// Sem `volume_id`, enfileira o livro inteiro. Sem `position`, vai para o fim
+/// (ou fica onde está, se já estiver na fila).
+#[derive(Debug, Deserialize)]
+pub struct EnqueuePayload {
+    pub book_id: String,
+    pub volume_id: Option<String>,
+    pub position: Option<i64>,
+}
+
+#[tauri::command]
+pub fn enqueue(app: AppHandle, payload: EnqueuePayload) -> crate::Result<QueueEntry> {
+    let conn = db::open(&app)?;
+    repositories::enqueue(
+        &conn,
+        &payload.book_id,
+        payload.volume_id.as_deref(),
+        payload.position,
+        now_secs(),
+    )?
+    .ok_or_else(|| match &payload.volume_id {
+        Some(v) => crate::Error::NotFound(format!("volume {} of book {}", v, payload.book_id)),
+        None => crate::Error::NotFound(format!("book {}", payload.book_id)),
+    })
+}
+
+/// Nova ordem da fila; entradas não listadas vão para o fim.
+#[tauri::command]
+pub fn reorder_queue(app: AppHandle, ids: Vec<String>) -> crate::Result<()> {
+    let conn = db::open(&app)?;
+    repositories::reorder_queue(&conn, &ids)
+}
+
+#[tauri::command]
+pub fn dequeue(app: AppHandle, id: String) -> crate::Result<()> {
+    let conn = db::open(&app)?;
+    if !repositories::dequeue(&conn, &id)? {
+        return Err(crate::Error::NotFound(format!("queue entry {}", id)));
+    }
+    Ok(())
+}
+
+/// Remove e retorna o primeiro da fila (None se vazia).
+#[tauri::command]
+pub fn pop_queue(app: AppHandle) -> crate::Result<Option<QueueEntry>> {
+    let conn = db::open(&app)?;
+    repositories::pop_queue(&conn)
+}
+
+/// Próximo volume da série a ler (None se todos concluídos).
+#[tauri::command]
+pub fn suggest_next_volume(app: AppHandle, book_id: String) -> crate::Result<Option<Volume>> {
+    let conn = db::open(&app)?;
+    repositories::suggest_next_volume(&conn, &book_id)
+}
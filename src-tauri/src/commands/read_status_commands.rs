@@ -0,0 +1,66 @@
+//! Tauri commands para o status de leitura (não lido, lendo, concluído,
+//! pausado, abandonado) de livros e volumes.
+
+use tauri::AppHandle;
+
+use crate::db;
+use crate::models::{BookReadState, ReadStatus};
+use crate::repositories;
+
+fn now_secs() -> i64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs() as i64)
+        .unwrap_or(0)
+}
+
+#[tauri::command]
+pub fn get_book_read_state(app: AppHandle, book_id: String) -> crate::Result<BookReadState> {
+    let conn = db::open(&app)?;
+    repositories::get_book_read_state(&conn, &book_id)?
+        .ok_or_else(|| crate::Error::NotFound(format!("book {}", book_id)))
+}
+
+/// completed e unread valem também para todos os volumes do livro.
+#[tauri::command]
+pub fn set_book_status(
+    app: AppHandle,
+    book_id: String,
+    status: ReadStatus,
+) -> crate::Result<BookReadState> {
+    let conn = db::open(&app)?;
+    let tx = conn.unchecked_transaction()?;
+    if !repositories::set_book_status(&tx, &book_id, status, now_secs())? {
+        return Err(crate::Error::NotFound(format!("book {}", book_id)));
+    }
+    tx.commit()?;
+    repositories::get_book_read_state(&conn, &book_id)?
+        .ok_or_else(|| crate::Error::NotFound(format!("book {}", book_id)))
+}
+
+#[tauri::command]
+pub fn set_volume_status(
+    app: AppHandle,
+    volume_id: String,
+    status: ReadStatus,
+) -> crate::Result<()> {
+    let conn = db::open(&app)?;
+    let tx = conn.unchecked_transaction()?;
+    repositories::mark_volumes(&tx, &[volume_id], status, now_secs())?;
+    tx.commit()?;
+    Ok(())
+}
+
+/// Marca vários volumes de uma vez. Retorna quantos mudaram de status.
+#[tauri::command]
+pub fn mark_volumes(
+    app: AppHandle,
+    volume_ids: Vec<String>,
+    status: ReadStatus,
+) -> crate::Result<usize> {
+    let conn = db::open(&app)?;
+    let tx = conn.unchecked_transaction()?;
+    let changed = repositories::mark_volumes(&tx, &volume_ids, status, now_secs())?;
+    tx.commit()?;
+    Ok(changed)
+}
@@ -0,0 +1,58 @@
+//! Tauri commands para avaliação pessoal e resenha dos livros.
+
+use tauri::AppHandle;
+
+use crate::db;
+use crate::models::BookReview;
+use crate::repositories;
+
+fn now_secs() -> i64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs() as i64)
+        .unwrap_or(0)
+}
+
+/// Meias estrelas: 0.5, 1, 1.5 ... 5.
+fn validate_rating(rating: Option<f64>) -> crate::Result<Option<f64>> {
+    match rating {
+        Some(r) if !(0.5..=5.0).contains(&r) || (r * 2.0).fract() != 0.0 => Err(
+            crate::Error::Validation(format!("rating must be 0.5 to 5 in half steps: {}", r)),
+        ),
+        _ => Ok(rating),
+    }
+}
+
+#[tauri::command]
+pub fn get_book_review(app: AppHandle, book_id: String) -> crate::Result<Option<BookReview>> {
+    let conn = db::open(&app)?;
+    repositories::get_review(&conn, &book_id)
+}
+
+/// Substitui nota e resenha; as duas vazias removem a avaliação (retorna None).
+#[tauri::command]
+pub fn save_book_review(
+    app: AppHandle,
+    book_id: String,
+    rating: Option<f64>,
+    review: Option<String>,
+) -> crate::Result<Option<BookReview>> {
+    let rating = validate_rating(rating)?;
+    let review = review
+        .map(|r| r.trim().to_string())
+        .filter(|r| !r.is_empty());
+    let conn = db::open(&app)?;
+    if repositories::get_book(&conn, &book_id)?.is_none() {
+        return Err(crate::Error::NotFound(format!("book {}", book_id)));
+    }
+    repositories::save_review(
+        &conn,
+        &BookReview {
+            book_id: book_id.clone(),
+            rating,
+            review,
+            updated_at: now_secs(),
+        },
+    )?;
+    repositories::get_review(&conn, &book_id)
+}
@@ -0,0 +1,94 @@
+//! Tauri commands para sessões de leitura e seus agregados.
+//! O leitor chama `reading_session_heartbeat` periodicamente enquanto há
+//! atividade (virar página, rolar) e sempre usa o id devolvido.
+
+use tauri::AppHandle;
+
+use crate::db;
+use crate::models::{BookReadingTime, DailyReading, ReadingSession, ReadingStats, VolumeEta};
+use crate::repositories;
+
+fn now_secs() -> i64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs() as i64)
+        .unwrap_or(0)
+}
+
+#[tauri::command]
+pub fn start_reading_session(
+    app: AppHandle,
+    book_id: String,
+    volume_id: String,
+    page_index: i32,
+) -> crate::Result<ReadingSession> {
+    let conn = db::open(&app)?;
+    repositories::start_session(&conn, &book_id, &volume_id, page_index, now_secs())
+}
+
+/// Pode devolver uma sessão nova, se a anterior ficou ociosa.
+#[tauri::command]
+pub fn reading_session_heartbeat(
+    app: AppHandle,
+    session_id: String,
+    page_index: i32,
+) -> crate::Result<ReadingSession> {
+    let conn = db::open(&app)?;
+    repositories::heartbeat_session(&conn, &session_id, page_index, now_secs())
+}
+
+#[tauri::command]
+pub fn end_reading_session(
+    app: AppHandle,
+    session_id: String,
+    page_index: i32,
+) -> crate::Result<()> {
+    let conn = db::open(&app)?;
+    repositories::end_session(&conn, &session_id, page_index, now_secs())
+}
+
+/// `from`/`to` em segundos (início da sessão); ausentes = sem limite.
+#[tauri::command]
+pub fn get_reading_time_by_book(
+    app: AppHandle,
+    from: Option<i64>,
+    to: Option<i64>,
+) -> crate::Result<Vec<BookReadingTime>> {
+    let conn = db::open(&app)?;
+    repositories::reading_time_by_book(&conn, from, to)
+}
+
+#[tauri::command]
+pub fn get_reading_time_by_day(
+    app: AppHandle,
+    from: Option<i64>,
+    to: Option<i64>,
+    book_id: Option<String>,
+) -> crate::Result<Vec<DailyReading>> {
+    let conn = db::open(&app)?;
+    repositories::reading_time_by_day(&conn, from, to, book_id.as_deref())
+}
+
+#[tauri::command]
+pub fn get_pages_per_hour(app: AppHandle, book_id: Option<String>) -> crate::Result<Option<f64>> {
+    let conn = db::open(&app)?;
+    repositories::pages_per_hour(&conn, book_id.as_deref())
+}
+
+#[tauri::command]
+pub fn get_volume_eta(app: AppHandle, volume_id: String) -> crate::Result<VolumeEta> {
+    let conn = db::open(&app)?;
+    repositories::volume_eta(&conn, &volume_id)
+}
+
+/// Painel de estatísticas do intervalo (totais diários/semanais, sequências,
+/// livros concluídos por mês, séries mais lidas, ritmo).
+#[tauri::command]
+pub fn get_reading_stats(
+    app: AppHandle,
+    from: Option<i64>,
+    to: Option<i64>,
+) -> crate::Result<ReadingStats> {
+    let conn = db::open(&app)?;
+    repositories::reading_stats(&conn, from, to, now_secs())
+}
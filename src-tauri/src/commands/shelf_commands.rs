@@ -3,6 +3,7 @@
 use tauri::AppHandle;
 
 use crate::db;
+use crate::models::{SmartRule, SmartShelfRules};
 use crate::repositories;
 
 #[tauri::command]
@@ -17,12 +18,169 @@ pub fn create_shelf(app: AppHandle, id: String, name: String) -> crate::Result<(
     repositories::create_shelf(&conn, &id, &name)
 }
 
+fn shelf_name(name: &str) -> crate::Result<String> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err(crate::Error::Validation(
+            "shelf name cannot be empty".to_string(),
+        ));
+    }
+    Ok(name.to_string())
+}
+
+/// Aceita #rgb ou #rrggbb; vazio = sem cor.
+fn shelf_color(color: Option<String>) -> crate::Result<Option<String>> {
+    let Some(color) = color.map(|c| c.trim().to_ascii_lowercase()) else {
+        return Ok(None);
+    };
+    if color.is_empty() {
+        return Ok(None);
+    }
+    let hex = color.strip_prefix('#').unwrap_or("");
+    if !matches!(hex.len(), 3 | 6) || !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Err(crate::Error::Validation(format!(
+            "invalid shelf color: {}",
+            color
+        )));
+    }
+    Ok(Some(color))
+}
+
+fn validate_rules(rules: &SmartShelfRules) -> crate::Result<()> {
+    for rule in &rules.rules {
+        let error = match rule {
+            SmartRule::Title { value, .. } | SmartRule::Author { value, .. }
+                if value.trim().is_empty() =>
+            {
+                Some("text rule value cannot be empty".to_string())
+            }
+            SmartRule::Tag { tag_id } if tag_id.is_empty() => {
+                Some("tag rule needs a tag".to_string())
+            }
+            SmartRule::AddedWithinDays { days } if *days < 0 => {
+                Some(format!("invalid number of days: {}", days))
+            }
+            SmartRule::BookType { value } if value != "folder" && value != "file" => {
+                Some(format!("invalid book type: {}", value))
+            }
+            SmartRule::Progress { value, .. } if !(0.0..=100.0).contains(value) => {
+                Some(format!("progress must be between 0 and 100: {}", value))
+            }
+            _ => None,
+        };
+        if let Some(error) = error {
+            return Err(crate::Error::Validation(error));
+        }
+    }
+    Ok(())
+}
+
+/// Cria uma estante inteligente; os livros vêm das regras, não de book_shelves.
+#[tauri::command]
+pub fn create_smart_shelf(
+    app: AppHandle,
+    id: String,
+    name: String,
+    rules: SmartShelfRules,
+) -> crate::Result<()> {
+    validate_rules(&rules)?;
+    let conn = db::open(&app)?;
+    repositories::create_smart_shelf(&conn, &id, &shelf_name(&name)?, &rules)
+}
+
+#[tauri::command]
+pub fn update_smart_shelf_rules(
+    app: AppHandle,
+    id: String,
+    rules: SmartShelfRules,
+) -> crate::Result<()> {
+    validate_rules(&rules)?;
+    let conn = db::open(&app)?;
+    if !repositories::set_shelf_rules(&conn, &id, &rules)? {
+        return Err(crate::Error::NotFound(format!("smart shelf {}", id)));
+    }
+    Ok(())
+}
+
+#[tauri::command]
+pub fn rename_shelf(app: AppHandle, id: String, name: String) -> crate::Result<()> {
+    let conn = db::open(&app)?;
+    if !repositories::rename_shelf(&conn, &id, &shelf_name(&name)?)? {
+        return Err(crate::Error::NotFound(format!("shelf {}", id)));
+    }
+    Ok(())
+}
+
+/// Ícone e cor opcionais; None ou vazio remove.
+#[tauri::command]
+pub fn set_shelf_style(
+    app: AppHandle,
+    id: String,
+    icon: Option<String>,
+    color: Option<String>,
+) -> crate::Result<()> {
+    let icon = icon.map(|i| i.trim().to_string()).filter(|i| !i.is_empty());
+    let color = shelf_color(color)?;
+    let conn = db::open(&app)?;
+    if !repositories::set_shelf_style(&conn, &id, icon.as_deref(), color.as_deref())? {
+        return Err(crate::Error::NotFound(format!("shelf {}", id)));
+    }
+    Ok(())
+}
+
+/// Remove a estante; os livros continuam na biblioteca.
+#[tauri::command]
+pub fn delete_shelf(app: AppHandle, id: String) -> crate::Result<()> {
+    let conn = db::open(&app)?;
+    if !repositories::delete_shelf(&conn, &id)? {
+        return Err(crate::Error::NotFound(format!("shelf {}", id)));
+    }
+    Ok(())
+}
+
+/// Nova ordem das estantes; as não listadas vão para o fim.
+#[tauri::command]
+pub fn reorder_shelves(app: AppHandle, ids: Vec<String>) -> crate::Result<()> {
+    let conn = db::open(&app)?;
+    repositories::reorder_shelves(&conn, &ids)
+}
+
 #[tauri::command]
 pub fn add_book_to_shelf(app: AppHandle, book_id: String, shelf_id: String) -> crate::Result<()> {
     let conn = db::open(&app)?;
     repositories::add_book_to_shelf(&conn, &book_id, &shelf_id)
 }
 
+/// Adiciona na posição `index` (0 = início); se já estiver na estante, move.
+#[tauri::command]
+pub fn insert_book_in_shelf(
+    app: AppHandle,
+    book_id: String,
+    shelf_id: String,
+    index: usize,
+) -> crate::Result<()> {
+    let conn = db::open(&app)?;
+    repositories::insert_book_in_shelf(&conn, &book_id, &shelf_id, index)
+}
+
+/// Move o livro para a posição `index` dentro da estante.
+#[tauri::command]
+pub fn move_book_in_shelf(
+    app: AppHandle,
+    book_id: String,
+    shelf_id: String,
+    index: usize,
+) -> crate::Result<()> {
+    let conn = db::open(&app)?;
+    if !repositories::move_book_in_shelf(&conn, &book_id, &shelf_id, index)? {
+        return Err(crate::Error::NotFound(format!(
+            "book {} in shelf {}",
+            book_id, shelf_id
+        )));
+    }
+    Ok(())
+}
+
 #[tauri::command]
 pub fn remove_book_from_shelf(
     app: AppHandle,
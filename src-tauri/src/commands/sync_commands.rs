@@ -0,0 +1,71 @@
+//! Tauri commands para o feed de mudanças e a sincronização entre dispositivos.
+
+use serde::Deserialize;
+use tauri::AppHandle;
+
+use crate::db;
+use crate::models::{ChangeFeed, SyncConfig, Tombstone};
+use crate::repositories;
+use crate::sync::{self, SyncReport};
+
+#[tauri::command]
+pub fn get_device_id(app: AppHandle) -> crate::Result<String> {
+    let conn = db::open(&app)?;
+    repositories::get_device_id(&conn)
+}
+
+#[tauri::command]
+pub fn get_changes_since(
+    app: AppHandle,
+    cursor: i64,
+    limit: Option<i64>,
+) -> crate::Result<ChangeFeed> {
+    let conn = db::open(&app)?;
+    repositories::changes_since(&conn, cursor, limit.unwrap_or(500))
+}
+
+#[tauri::command]
+pub fn get_tombstones(app: AppHandle, since: Option<i64>) -> crate::Result<Vec<Tombstone>> {
+    let conn = db::open(&app)?;
+    repositories::list_tombstones(&conn, since.unwrap_or(0))
+}
+
+#[tauri::command]
+pub fn get_sync_config(app: AppHandle) -> crate::Result<SyncConfig> {
+    let conn = db::open(&app)?;
+    repositories::get_sync_config(&conn)
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SaveSyncConfigPayload {
+    pub backend: Option<String>,
+    pub target: Option<String>,
+    pub username: Option<String>,
+    pub token: Option<String>,
+    pub interval_secs: Option<i64>,
+}
+
+#[tauri::command]
+pub fn save_sync_config(app: AppHandle, payload: SaveSyncConfigPayload) -> crate::Result<()> {
+    let conn = db::open(&app)?;
+    let current = repositories::get_sync_config(&conn)?;
+    let config = SyncConfig {
+        backend: payload.backend,
+        target: payload.target,
+        username: payload.username,
+        token: payload.token,
+        interval_secs: payload.interval_secs.unwrap_or(current.interval_secs),
+        last_sync_at: current.last_sync_at,
+    };
+    // Valida backend/destino antes de salvar
+    sync::backend_from_config(&config)?;
+    repositories::save_sync_config(&conn, &config)
+}
+
+/// Sincroniza agora com o backend configurado.
+#[tauri::command]
+pub async fn sync_now(app: AppHandle) -> crate::Result<SyncReport> {
+    tauri::async_runtime::spawn_blocking(move || sync::scheduler::sync_now(&app))
+        .await
+        .map_err(|e| crate::Error::Io(format!("spawn_blocking: {:?}", e)))?
+}
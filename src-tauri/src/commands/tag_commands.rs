@@ -0,0 +1,78 @@
+//! Tauri commands para tags livres.
+
+use tauri::AppHandle;
+
+use crate::db;
+use crate::models::{BookTag, Tag};
+use crate::repositories;
+
+fn tag_name(name: &str) -> crate::Result<String> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err(crate::Error::Validation(
+            "tag name cannot be empty".to_string(),
+        ));
+    }
+    Ok(name.to_string())
+}
+
+#[tauri::command]
+pub fn list_tags(app: AppHandle) -> crate::Result<Vec<Tag>> {
+    let conn = db::open(&app)?;
+    repositories::list_tags(&conn)
+}
+
+/// Cria a tag, ou retorna a existente com o mesmo nome.
+#[tauri::command]
+pub fn create_tag(app: AppHandle, name: String) -> crate::Result<String> {
+    let conn = db::open(&app)?;
+    repositories::create_tag(&conn, &tag_name(&name)?)
+}
+
+/// Para juntar com uma tag de mesmo nome, use `merge_tags`.
+#[tauri::command]
+pub fn rename_tag(app: AppHandle, id: String, name: String) -> crate::Result<()> {
+    let name = tag_name(&name)?;
+    let conn = db::open(&app)?;
+    if !repositories::rename_tag(&conn, &id, &name)? {
+        return Err(crate::Error::NotFound(format!("tag {}", id)));
+    }
+    Ok(())
+}
+
+/// Junta `source_ids` em `target_id`; as tags de origem são apagadas.
+#[tauri::command]
+pub fn merge_tags(app: AppHandle, source_ids: Vec<String>, target_id: String) -> crate::Result<()> {
+    let conn = db::open(&app)?;
+    if !repositories::merge_tags(&conn, &source_ids, &target_id)? {
+        return Err(crate::Error::NotFound(format!("tag {}", target_id)));
+    }
+    Ok(())
+}
+
+#[tauri::command]
+pub fn delete_tag(app: AppHandle, id: String) -> crate::Result<()> {
+    let conn = db::open(&app)?;
+    if !repositories::delete_tag(&conn, &id)? {
+        return Err(crate::Error::NotFound(format!("tag {}", id)));
+    }
+    Ok(())
+}
+
+#[tauri::command]
+pub fn get_book_tags(app: AppHandle, book_id: String) -> crate::Result<Vec<BookTag>> {
+    let conn = db::open(&app)?;
+    repositories::list_book_tags(&conn, &book_id)
+}
+
+#[tauri::command]
+pub fn add_tag_to_book(app: AppHandle, book_id: String, tag_id: String) -> crate::Result<()> {
+    let conn = db::open(&app)?;
+    repositories::add_tag_to_book(&conn, &book_id, &tag_id)
+}
+
+#[tauri::command]
+pub fn remove_tag_from_book(app: AppHandle, book_id: String, tag_id: String) -> crate::Result<()> {
+    let conn = db::open(&app)?;
+    repositories::remove_tag_from_book(&conn, &book_id, &tag_id)
+}
@@ -3,9 +3,13 @@
 
 use rusqlite::Connection;
 use std::path::PathBuf;
+use std::time::Duration;
 use tauri::{AppHandle, Manager};
 
 const DB_FILENAME: &str = "leitor.db";
+/// Espera por locks de outras conexões (scheduler, servidor KOReader, lote
+/// de metadados) antes de falhar com SQLITE_BUSY.
+pub const BUSY_TIMEOUT: Duration = Duration::from_secs(5);
 
 /// Retorna o caminho do arquivo do banco no app data dir.
 /// Cria o diretório se não existir.
@@ -22,6 +26,7 @@ pub fn app_db_path(app: &AppHandle) -> crate::Result<PathBuf> {
 pub fn open(app: &AppHandle) -> crate::Result<Connection> {
     let path = app_db_path(app)?;
     let conn = Connection::open(&path).map_err(|e| crate::Error::Db(e.to_string()))?;
+    conn.busy_timeout(BUSY_TIMEOUT)?;
     crate::db::schema::run_migrations(&conn).map_err(|e| crate::Error::Db(e.to_string()))?;
     Ok(conn)
 }
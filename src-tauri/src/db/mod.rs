@@ -3,5 +3,5 @@
 mod connection;
 mod schema;
 
-pub use connection::{app_db_path, open};
+pub use connection::{app_db_path, open, BUSY_TIMEOUT};
 pub use schema::{run_migrations, SCHEMA_SQL};
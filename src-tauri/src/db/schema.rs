@@ -1,5 +1,5 @@
 //! Definição das tabelas e migrações do SQLite.
-//! Estrutura preparada para futura sincronização (updated_at para LWW).
+//! Estrutura preparada para sincronização: updated_at para LWW, tombstones e change log.
 
 pub const SCHEMA_SQL: &str = r#"
 -- Livros da biblioteca
@@ -110,6 +110,61 @@ fn migrate_books_metadata(conn: &rusqlite::Connection) -> rusqlite::Result<()> {
     Ok(())
 }
 
+/// Migração: metadados estendidos dos livros (listas e ids externos em JSON)
+/// e as flags de edição manual correspondentes.
+fn migrate_extended_metadata(conn: &rusqlite::Connection) -> rusqlite::Result<()> {
+    let has_col = |table: &str, name: &str| -> bool {
+        conn.query_row(
+            &format!(
+                "SELECT 1 FROM pragma_table_info('{}') WHERE name = ?1 LIMIT 1",
+                table
+            ),
+            [name],
+            |row| row.get::<_, i32>(0),
+        )
+        .map(|v| v == 1)
+        .unwrap_or(false)
+    };
+    let book_columns = [
+        ("publisher", "TEXT"),
+        ("year", "INTEGER"),
+        ("language", "TEXT"),
+        ("isbns", "TEXT"),
+        ("alt_titles", "TEXT"),
+        ("series", "TEXT"),
+        ("series_number", "REAL"),
+        ("page_count", "INTEGER"),
+        ("media_type", "TEXT"),
+        ("genres", "TEXT"),
+        ("external_ids", "TEXT"),
+    ];
+    for (name, sql_type) in book_columns {
+        if !has_col("books", name) {
+            conn.execute(
+                &format!("ALTER TABLE books ADD COLUMN {} {}", name, sql_type),
+                [],
+            )?;
+        }
+    }
+    for (name, _) in book_columns {
+        // series_number segue a flag de series
+        if name == "series_number" {
+            continue;
+        }
+        let flag = format!("{}_manually_edited", name);
+        if !has_col("book_metadata_flags", &flag) {
+            conn.execute(
+                &format!(
+                    "ALTER TABLE book_metadata_flags ADD COLUMN {} INTEGER NOT NULL DEFAULT 0",
+                    flag
+                ),
+                [],
+            )?;
+        }
+    }
+    Ok(())
+}
+
 /// Migração: criar tabelas do sistema de busca de metadados.
 fn migrate_metadata_search(conn: &rusqlite::Connection) -> rusqlite::Result<()> {
     conn.execute_batch(
@@ -140,12 +195,41 @@ fn migrate_metadata_search(conn: &rusqlite::Connection) -> rusqlite::Result<()>
 
         CREATE INDEX IF NOT EXISTS idx_metadata_search_results_book_id ON metadata_search_results(book_id);
 
-        -- Cache de buscas por query normalizada (TTL configurável)
+        -- Cache de buscas por fonte e query normalizada (TTL configurável)
         CREATE TABLE IF NOT EXISTS metadata_search_cache (
-            query_normalized TEXT PRIMARY KEY,
             source TEXT NOT NULL,
+            query_normalized TEXT NOT NULL,
+            results_json TEXT NOT NULL,
+            cached_at INTEGER NOT NULL,
+            PRIMARY KEY (source, query_normalized)
+        );
+        "#,
+    )?;
+    Ok(())
+}
+
+/// Migração: a primeira versão do cache tinha só `query_normalized` como chave,
+/// e as fontes se sobrescreviam. Como é só cache, a tabela antiga é recriada.
+fn migrate_metadata_cache_key(conn: &rusqlite::Connection) -> rusqlite::Result<()> {
+    let source_in_pk: bool = conn
+        .query_row(
+            "SELECT pk > 0 FROM pragma_table_info('metadata_search_cache') WHERE name = 'source'",
+            [],
+            |row| row.get(0),
+        )
+        .unwrap_or(false);
+    if source_in_pk {
+        return Ok(());
+    }
+    conn.execute_batch(
+        r#"
+        DROP TABLE IF EXISTS metadata_search_cache;
+        CREATE TABLE metadata_search_cache (
+            source TEXT NOT NULL,
+            query_normalized TEXT NOT NULL,
             results_json TEXT NOT NULL,
-            cached_at INTEGER NOT NULL
+            cached_at INTEGER NOT NULL,
+            PRIMARY KEY (source, query_normalized)
         );
         "#,
     )?;
@@ -202,8 +286,661 @@ fn migrate_custom_themes(conn: &rusqlite::Connection) -> rusqlite::Result<()> {
         .unwrap_or(false)
     };
     if !has_col("custom_theme_id") {
-        conn.execute("ALTER TABLE global_settings ADD COLUMN custom_theme_id TEXT", [])?;
+        conn.execute(
+            "ALTER TABLE global_settings ADD COLUMN custom_theme_id TEXT",
+            [],
+        )?;
+    }
+    Ok(())
+}
+
+/// Migração: ordem, ícone e cor das estantes. Na primeira execução a ordem
+/// segue o nome, como antes.
+fn migrate_shelf_layout(conn: &rusqlite::Connection) -> rusqlite::Result<()> {
+    let has_position: bool = conn
+        .query_row(
+            "SELECT 1 FROM pragma_table_info('shelves') WHERE name = 'position' LIMIT 1",
+            [],
+            |row| row.get::<_, i32>(0),
+        )
+        .map(|v| v == 1)
+        .unwrap_or(false);
+    if has_position {
+        return Ok(());
+    }
+    conn.execute_batch(
+        r#"
+        ALTER TABLE shelves ADD COLUMN position INTEGER NOT NULL DEFAULT 0;
+        ALTER TABLE shelves ADD COLUMN icon TEXT;
+        ALTER TABLE shelves ADD COLUMN color TEXT;
+        UPDATE shelves SET position = (
+            SELECT COUNT(*) FROM shelves s2
+            WHERE s2.name < shelves.name OR (s2.name = shelves.name AND s2.id < shelves.id)
+        );
+        "#,
+    )?;
+    Ok(())
+}
+
+/// Migração: estantes inteligentes. `kind` distingue manual/smart e `rules`
+/// guarda as regras em JSON.
+fn migrate_smart_shelves(conn: &rusqlite::Connection) -> rusqlite::Result<()> {
+    let has_kind: bool = conn
+        .query_row(
+            "SELECT 1 FROM pragma_table_info('shelves') WHERE name = 'kind' LIMIT 1",
+            [],
+            |row| row.get::<_, i32>(0),
+        )
+        .map(|v| v == 1)
+        .unwrap_or(false);
+    if !has_kind {
+        conn.execute_batch(
+            r#"
+            ALTER TABLE shelves ADD COLUMN kind TEXT NOT NULL DEFAULT 'manual';
+            ALTER TABLE shelves ADD COLUMN rules TEXT;
+            "#,
+        )?;
+    }
+    Ok(())
+}
+
+/// Migração: ordem manual dos livros em cada estante. Os vínculos existentes
+/// ficam na ordem de inserção.
+fn migrate_shelf_book_order(conn: &rusqlite::Connection) -> rusqlite::Result<()> {
+    let has_position: bool = conn
+        .query_row(
+            "SELECT 1 FROM pragma_table_info('book_shelves') WHERE name = 'position' LIMIT 1",
+            [],
+            |row| row.get::<_, i32>(0),
+        )
+        .map(|v| v == 1)
+        .unwrap_or(false);
+    if has_position {
+        return Ok(());
+    }
+    conn.execute_batch(
+        r#"
+        ALTER TABLE book_shelves ADD COLUMN position INTEGER NOT NULL DEFAULT 0;
+        UPDATE book_shelves SET position = (
+            SELECT COUNT(*) FROM book_shelves b2
+            WHERE b2.shelf_id = book_shelves.shelf_id AND b2.rowid < book_shelves.rowid
+        );
+        "#,
+    )?;
+    Ok(())
+}
+
+/// Tabelas sincronizáveis e a expressão da chave de cada linha (`{r}` = NEW/OLD).
+/// Chaves compostas são unidas com `|`.
+pub const SYNC_TABLES: &[(&str, &str)] = &[
+    ("books", "{r}.id"),
+    ("volumes", "{r}.id"),
+    ("chapters", "{r}.id"),
+    ("shelves", "{r}.id"),
+    ("book_shelves", "{r}.book_id || '|' || {r}.shelf_id"),
+    ("reading_progress", "{r}.book_id || '|' || {r}.volume_id"),
+    ("book_settings", "{r}.book_id"),
+    ("global_settings", "CAST({r}.id AS TEXT)"),
+    ("custom_themes", "{r}.id"),
+    ("bookmarks", "{r}.id"),
+    ("annotations", "{r}.id"),
+    ("chapter_progress", "{r}.chapter_id"),
+    ("tags", "{r}.id"),
+    ("book_tags", "{r}.book_id || '|' || {r}.tag_id"),
+    ("book_reviews", "{r}.book_id"),
+];
+
+const NOW: &str = "CAST(strftime('%s', 'now') AS INTEGER)";
+const DEVICE: &str = "(SELECT value FROM sync_meta WHERE key = 'device_id')";
+// Durante a aplicação de mudanças remotas o engine de sync grava
+// `applying_device` com o dispositivo de origem; fora disso vale o local.
+const ORIGIN: &str = "COALESCE((SELECT value FROM sync_meta WHERE key = 'applying_device'), \
+     (SELECT value FROM sync_meta WHERE key = 'device_id'))";
+
+/// Versão da definição dos triggers de sync. Incrementar ao mudar o SQL de
+/// `create_sync_triggers` ou a lista `SYNC_TABLES`.
+const SYNC_TRIGGERS_VERSION: &str = "2";
+
+/// Triggers que alimentam change_log e tombstones para cada tabela de `SYNC_TABLES`.
+/// Cada linha mantém só a entrada mais recente no change_log: quem lê a partir
+/// de um cursor precisa saber que a linha mudou, não de cada versão dela.
+fn create_sync_triggers(conn: &rusqlite::Connection) -> rusqlite::Result<()> {
+    for (table, key) in SYNC_TABLES {
+        let new_key = key.replace("{r}", "NEW");
+        let old_key = key.replace("{r}", "OLD");
+        conn.execute_batch(&format!(
+            r#"
+            DROP TRIGGER IF EXISTS trg_{t}_change_insert;
+            DROP TRIGGER IF EXISTS trg_{t}_change_update;
+            DROP TRIGGER IF EXISTS trg_{t}_change_delete;
+
+            CREATE TRIGGER IF NOT EXISTS trg_{t}_change_insert AFTER INSERT ON {t}
+            BEGIN
+                DELETE FROM tombstones WHERE table_name = '{t}' AND row_key = {nk};
+                DELETE FROM change_log WHERE table_name = '{t}' AND row_key = {nk};
+                INSERT INTO change_log (table_name, row_key, op, changed_at, device_id)
+                VALUES ('{t}', {nk}, 'upsert', {now}, {dev});
+            END;
+
+            CREATE TRIGGER IF NOT EXISTS trg_{t}_change_update AFTER UPDATE ON {t}
+            BEGIN
+                DELETE FROM change_log WHERE table_name = '{t}' AND row_key = {nk};
+                INSERT INTO change_log (table_name, row_key, op, changed_at, device_id)
+                VALUES ('{t}', {nk}, 'upsert', {now}, {dev});
+            END;
+
+            CREATE TRIGGER IF NOT EXISTS trg_{t}_change_delete AFTER DELETE ON {t}
+            BEGIN
+                INSERT OR REPLACE INTO tombstones (table_name, row_key, deleted_at, device_id)
+                VALUES ('{t}', {ok}, {now}, {dev});
+                DELETE FROM change_log WHERE table_name = '{t}' AND row_key = {ok};
+                INSERT INTO change_log (table_name, row_key, op, changed_at, device_id)
+                VALUES ('{t}', {ok}, 'delete', {now}, {dev});
+            END;
+            "#,
+            t = table,
+            nk = new_key,
+            ok = old_key,
+            now = NOW,
+            dev = ORIGIN,
+        ))?;
+    }
+    Ok(())
+}
+
+/// Tabelas que ganharam updated_at nesta migração.
+const UPDATED_AT_TABLES: [&str; 5] = ["books", "volumes", "chapters", "shelves", "book_shelves"];
+
+/// Migração: updated_at nas tabelas que não tinham, id do dispositivo,
+/// tombstones de exclusão e change log alimentado por triggers.
+fn migrate_sync_change_log(conn: &rusqlite::Connection) -> rusqlite::Result<()> {
+    for table in UPDATED_AT_TABLES {
+        let has_updated_at: bool = conn
+            .query_row(
+                &format!(
+                    "SELECT 1 FROM pragma_table_info('{}') WHERE name = 'updated_at' LIMIT 1",
+                    table
+                ),
+                [],
+                |row| row.get::<_, i32>(0),
+            )
+            .map(|v| v == 1)
+            .unwrap_or(false);
+        if !has_updated_at {
+            conn.execute(
+                &format!(
+                    "ALTER TABLE {} ADD COLUMN updated_at INTEGER NOT NULL DEFAULT 0",
+                    table
+                ),
+                [],
+            )?;
+        }
+    }
+
+    let had_change_log: bool = conn
+        .query_row(
+            "SELECT 1 FROM sqlite_master WHERE type = 'table' AND name = 'change_log'",
+            [],
+            |row| row.get::<_, i32>(0),
+        )
+        .map(|v| v == 1)
+        .unwrap_or(false);
+
+    conn.execute_batch(
+        r#"
+        -- Metadados de sincronização (device_id etc.)
+        CREATE TABLE IF NOT EXISTS sync_meta (
+            key TEXT PRIMARY KEY,
+            value TEXT NOT NULL
+        );
+
+        -- Exclusões lógicas: o que foi apagado, quando e em qual dispositivo
+        CREATE TABLE IF NOT EXISTS tombstones (
+            table_name TEXT NOT NULL,
+            row_key TEXT NOT NULL,
+            deleted_at INTEGER NOT NULL,
+            device_id TEXT NOT NULL,
+            PRIMARY KEY (table_name, row_key)
+        );
+
+        -- Feed de mudanças (última por linha); seq é o cursor
+        CREATE TABLE IF NOT EXISTS change_log (
+            seq INTEGER PRIMARY KEY AUTOINCREMENT,
+            table_name TEXT NOT NULL,
+            row_key TEXT NOT NULL,
+            op TEXT NOT NULL CHECK (op IN ('upsert', 'delete')),
+            changed_at INTEGER NOT NULL,
+            device_id TEXT NOT NULL
+        );
+
+        CREATE INDEX IF NOT EXISTS idx_change_log_table_row ON change_log(table_name, row_key);
+        "#,
+    )?;
+    conn.execute(
+        "INSERT OR IGNORE INTO sync_meta (key, value) VALUES ('device_id', ?1)",
+        [uuid::Uuid::new_v4().to_string()],
+    )?;
+
+    // Triggers só são (re)criados quando a versão gravada difere da atual:
+    // mexer no schema a cada `db::open` travaria as outras conexões.
+    let triggers_version: Option<String> = match conn.query_row(
+        "SELECT value FROM sync_meta WHERE key = 'sync_triggers_version'",
+        [],
+        |row| row.get(0),
+    ) {
+        Ok(v) => Some(v),
+        Err(rusqlite::Error::QueryReturnedNoRows) => None,
+        Err(e) => return Err(e),
+    };
+    if triggers_version.as_deref() != Some(SYNC_TRIGGERS_VERSION) {
+        // Linhas anteriores à coluna ficaram sem updated_at e perderiam
+        // qualquer last-write-wins: passam a valer a partir da migração.
+        // As que já têm data mantêm a sua.
+        for table in UPDATED_AT_TABLES {
+            conn.execute(
+                &format!(
+                    "UPDATE {} SET updated_at = {} WHERE updated_at IS NULL OR updated_at = 0",
+                    table, NOW
+                ),
+                [],
+            )?;
+        }
+        create_sync_triggers(conn)?;
+        // Entradas gravadas antes da compactação nos triggers
+        conn.execute(
+            "DELETE FROM change_log WHERE seq NOT IN \
+             (SELECT MAX(seq) FROM change_log GROUP BY table_name, row_key)",
+            [],
+        )?;
+        conn.execute(
+            "INSERT OR REPLACE INTO sync_meta (key, value) VALUES ('sync_triggers_version', ?1)",
+            [SYNC_TRIGGERS_VERSION],
+        )?;
+    }
+
+    // Primeira execução: registra as linhas existentes para que um backend
+    // começando do cursor 0 receba o estado completo.
+    if !had_change_log {
+        for (table, key) in SYNC_TABLES {
+            conn.execute(
+                &format!(
+                    "INSERT INTO change_log (table_name, row_key, op, changed_at, device_id) \
+                     SELECT '{t}', {k}, 'upsert', {now}, {dev} FROM {t} AS r",
+                    t = table,
+                    k = key.replace("{r}", "r"),
+                    now = NOW,
+                    dev = DEVICE,
+                ),
+                [],
+            )?;
+        }
+    }
+    Ok(())
+}
+
+/// Migração: total de páginas no progresso e tabelas do KOReader
+/// (cache de hash dos documentos e armazenamento do servidor embutido).
+fn migrate_koreader_sync(conn: &rusqlite::Connection) -> rusqlite::Result<()> {
+    let has_total_pages: bool = conn
+        .query_row(
+            "SELECT 1 FROM pragma_table_info('reading_progress') WHERE name = 'total_pages' LIMIT 1",
+            [],
+            |row| row.get::<_, i32>(0),
+        )
+        .map(|v| v == 1)
+        .unwrap_or(false);
+    if !has_total_pages {
+        conn.execute(
+            "ALTER TABLE reading_progress ADD COLUMN total_pages INTEGER",
+            [],
+        )?;
+    }
+    conn.execute_batch(
+        r#"
+        -- Chave do documento no KOReader (MD5) por volume; recalculada se o arquivo mudar
+        CREATE TABLE IF NOT EXISTS koreader_documents (
+            volume_id TEXT PRIMARY KEY,
+            book_id TEXT NOT NULL,
+            path TEXT NOT NULL,
+            file_size INTEGER NOT NULL,
+            file_mtime INTEGER NOT NULL,
+            method TEXT NOT NULL,
+            document TEXT NOT NULL
+        );
+        CREATE INDEX IF NOT EXISTS idx_koreader_documents_document ON koreader_documents(document);
+
+        -- Servidor de sincronização embutido (protocolo kosync)
+        CREATE TABLE IF NOT EXISTS koreader_users (
+            username TEXT PRIMARY KEY,
+            userkey TEXT NOT NULL,
+            created_at INTEGER NOT NULL
+        );
+
+        CREATE TABLE IF NOT EXISTS koreader_progress (
+            username TEXT NOT NULL,
+            document TEXT NOT NULL,
+            progress TEXT NOT NULL,
+            percentage REAL NOT NULL,
+            device TEXT NOT NULL,
+            device_id TEXT NOT NULL,
+            timestamp INTEGER NOT NULL,
+            PRIMARY KEY (username, document)
+        );
+        "#,
+    )?;
+    Ok(())
+}
+
+/// Migração: marcadores nomeados por página (ou CFI no EPUB).
+fn migrate_bookmarks(conn: &rusqlite::Connection) -> rusqlite::Result<()> {
+    conn.execute_batch(
+        r#"
+        CREATE TABLE IF NOT EXISTS bookmarks (
+            id TEXT PRIMARY KEY,
+            book_id TEXT NOT NULL,
+            volume_id TEXT NOT NULL,
+            chapter_id TEXT,
+            page_index INTEGER,
+            cfi TEXT,
+            label TEXT NOT NULL,
+            thumbnail TEXT,
+            created_at INTEGER NOT NULL,
+            updated_at INTEGER NOT NULL,
+            FOREIGN KEY (book_id) REFERENCES books(id) ON DELETE CASCADE,
+            FOREIGN KEY (volume_id) REFERENCES volumes(id) ON DELETE CASCADE
+        );
+        CREATE INDEX IF NOT EXISTS idx_bookmarks_book ON bookmarks(book_id, volume_id);
+        "#,
+    )?;
+    Ok(())
+}
+
+/// Migração: destaques e notas. Sem FK para books: a anotação guarda o hash
+/// do livro e é religada quando o mesmo arquivo volta à biblioteca.
+fn migrate_annotations(conn: &rusqlite::Connection) -> rusqlite::Result<()> {
+    conn.execute_batch(
+        r#"
+        CREATE TABLE IF NOT EXISTS annotations (
+            id TEXT PRIMARY KEY,
+            book_id TEXT NOT NULL,
+            volume_id TEXT NOT NULL,
+            chapter_id TEXT,
+            book_hash TEXT,
+            cfi_range TEXT,
+            page_index INTEGER,
+            quads TEXT,
+            text TEXT NOT NULL DEFAULT '',
+            color TEXT NOT NULL,
+            note TEXT,
+            created_at INTEGER NOT NULL,
+            updated_at INTEGER NOT NULL
+        );
+        CREATE INDEX IF NOT EXISTS idx_annotations_book ON annotations(book_id, volume_id);
+        CREATE INDEX IF NOT EXISTS idx_annotations_hash ON annotations(book_hash);
+        "#,
+    )?;
+    Ok(())
+}
+
+/// Migração: sessões de leitura (início/fim, páginas), mantidas por heartbeat.
+fn migrate_reading_sessions(conn: &rusqlite::Connection) -> rusqlite::Result<()> {
+    conn.execute_batch(
+        r#"
+        CREATE TABLE IF NOT EXISTS reading_sessions (
+            id TEXT PRIMARY KEY,
+            book_id TEXT NOT NULL,
+            volume_id TEXT NOT NULL,
+            started_at INTEGER NOT NULL,
+            ended_at INTEGER NOT NULL,
+            start_page INTEGER NOT NULL,
+            end_page INTEGER NOT NULL,
+            pages_turned INTEGER NOT NULL DEFAULT 0,
+            closed INTEGER NOT NULL DEFAULT 0,
+            FOREIGN KEY (book_id) REFERENCES books(id) ON DELETE CASCADE
+        );
+        CREATE INDEX IF NOT EXISTS idx_reading_sessions_started ON reading_sessions(started_at);
+        CREATE INDEX IF NOT EXISTS idx_reading_sessions_book ON reading_sessions(book_id, volume_id);
+        "#,
+    )?;
+    Ok(())
+}
+
+/// Migração: lido/não lido e última página por capítulo.
+fn migrate_chapter_progress(conn: &rusqlite::Connection) -> rusqlite::Result<()> {
+    conn.execute_batch(
+        r#"
+        CREATE TABLE IF NOT EXISTS chapter_progress (
+            chapter_id TEXT PRIMARY KEY,
+            book_id TEXT NOT NULL,
+            volume_id TEXT NOT NULL,
+            read INTEGER NOT NULL DEFAULT 0,
+            last_page INTEGER,
+            read_at INTEGER,
+            updated_at INTEGER NOT NULL,
+            FOREIGN KEY (chapter_id) REFERENCES chapters(id) ON DELETE CASCADE
+        );
+        CREATE INDEX IF NOT EXISTS idx_chapter_progress_book ON chapter_progress(book_id, volume_id);
+        "#,
+    )?;
+    Ok(())
+}
+
+/// Migração: tags livres. `auto` marca vínculos criados a partir dos gêneros
+/// das fontes de metadados; os manuais nunca são removidos por elas.
+fn migrate_tags(conn: &rusqlite::Connection) -> rusqlite::Result<()> {
+    conn.execute_batch(
+        r#"
+        CREATE TABLE IF NOT EXISTS tags (
+            id TEXT PRIMARY KEY,
+            name TEXT NOT NULL,
+            updated_at INTEGER NOT NULL
+        );
+        CREATE INDEX IF NOT EXISTS idx_tags_name ON tags(name COLLATE NOCASE);
+        CREATE TABLE IF NOT EXISTS book_tags (
+            book_id TEXT NOT NULL,
+            tag_id TEXT NOT NULL,
+            auto INTEGER NOT NULL DEFAULT 0,
+            updated_at INTEGER NOT NULL,
+            PRIMARY KEY (book_id, tag_id),
+            FOREIGN KEY (book_id) REFERENCES books(id) ON DELETE CASCADE,
+            FOREIGN KEY (tag_id) REFERENCES tags(id) ON DELETE CASCADE
+        );
+        CREATE INDEX IF NOT EXISTS idx_book_tags_tag_id ON book_tags(tag_id);
+        "#,
+    )?;
+    Ok(())
+}
+
+/// Migração: avaliação (meias estrelas, 0.5–5) e resenha privada por livro.
+fn migrate_book_reviews(conn: &rusqlite::Connection) -> rusqlite::Result<()> {
+    conn.execute_batch(
+        r#"
+        CREATE TABLE IF NOT EXISTS book_reviews (
+            book_id TEXT PRIMARY KEY,
+            rating REAL,
+            review TEXT,
+            updated_at INTEGER NOT NULL,
+            FOREIGN KEY (book_id) REFERENCES books(id) ON DELETE CASCADE
+        );
+        "#,
+    )?;
+    Ok(())
+}
+
+/// Migração: histórico de leitura (linha do tempo de posições).
+fn migrate_reading_history(conn: &rusqlite::Connection) -> rusqlite::Result<()> {
+    conn.execute_batch(
+        r#"
+        CREATE TABLE IF NOT EXISTS reading_history (
+            id TEXT PRIMARY KEY,
+            book_id TEXT NOT NULL,
+            volume_id TEXT NOT NULL,
+            chapter_id TEXT,
+            page_index INTEGER NOT NULL,
+            started_at INTEGER NOT NULL,
+            updated_at INTEGER NOT NULL,
+            FOREIGN KEY (book_id) REFERENCES books(id) ON DELETE CASCADE
+        );
+        CREATE INDEX IF NOT EXISTS idx_reading_history_updated ON reading_history(updated_at);
+        CREATE INDEX IF NOT EXISTS idx_reading_history_book ON reading_history(book_id, volume_id);
+        "#,
+    )?;
+    Ok(())
+}
+
+/// Migração: fila de leitura. Um livro (ou volume) aparece no máximo uma vez.
+fn migrate_reading_queue(conn: &rusqlite::Connection) -> rusqlite::Result<()> {
+    conn.execute_batch(
+        r#"
+        CREATE TABLE IF NOT EXISTS reading_queue (
+            id TEXT PRIMARY KEY,
+            book_id TEXT NOT NULL,
+            volume_id TEXT,
+            position INTEGER NOT NULL,
+            added_at INTEGER NOT NULL,
+            FOREIGN KEY (book_id) REFERENCES books(id) ON DELETE CASCADE
+        );
+        CREATE UNIQUE INDEX IF NOT EXISTS idx_reading_queue_item
+            ON reading_queue(book_id, COALESCE(volume_id, ''));
+        CREATE INDEX IF NOT EXISTS idx_reading_queue_position ON reading_queue(position);
+        "#,
+    )?;
+    Ok(())
+}
+
+/// Migração: histórico de alterações de metadados, campo a campo. Valores em
+/// JSON; alterações feitas juntas (ex.: um candidato aplicado) dividem o `batch_id`.
+fn migrate_metadata_changes(conn: &rusqlite::Connection) -> rusqlite::Result<()> {
+    conn.execute_batch(
+        r#"
+        CREATE TABLE IF NOT EXISTS metadata_changes (
+            id TEXT PRIMARY KEY,
+            book_id TEXT NOT NULL,
+            batch_id TEXT NOT NULL,
+            field TEXT NOT NULL,
+            old_value TEXT NOT NULL,
+            new_value TEXT NOT NULL,
+            origin TEXT NOT NULL,
+            changed_at INTEGER NOT NULL,
+            FOREIGN KEY (book_id) REFERENCES books(id) ON DELETE CASCADE
+        );
+        CREATE INDEX IF NOT EXISTS idx_metadata_changes_book
+            ON metadata_changes(book_id, changed_at);
+        "#,
+    )?;
+    Ok(())
+}
+
+/// Migração: enriquecimento de metadados em lote. O lote sobrevive a
+/// reinícios: os itens `pending` são retomados.
+fn migrate_metadata_jobs(conn: &rusqlite::Connection) -> rusqlite::Result<()> {
+    conn.execute_batch(
+        r#"
+        CREATE TABLE IF NOT EXISTS metadata_jobs (
+            id TEXT PRIMARY KEY,
+            status TEXT NOT NULL
+                CHECK (status IN ('running', 'paused', 'completed', 'cancelled')),
+            created_at INTEGER NOT NULL,
+            updated_at INTEGER NOT NULL
+        );
+        CREATE TABLE IF NOT EXISTS metadata_job_items (
+            job_id TEXT NOT NULL,
+            book_id TEXT NOT NULL,
+            position INTEGER NOT NULL,
+            status TEXT NOT NULL DEFAULT 'pending'
+                CHECK (status IN ('pending', 'applied', 'review', 'no_match', 'failed', 'dismissed')),
+            score REAL,
+            candidates_json TEXT,
+            error TEXT,
+            updated_at INTEGER NOT NULL,
+            PRIMARY KEY (job_id, book_id),
+            FOREIGN KEY (job_id) REFERENCES metadata_jobs(id) ON DELETE CASCADE,
+            FOREIGN KEY (book_id) REFERENCES books(id) ON DELETE CASCADE
+        );
+        CREATE INDEX IF NOT EXISTS idx_metadata_job_items_status
+            ON metadata_job_items(job_id, status, position);
+        "#,
+    )?;
+    Ok(())
+}
+
+/// Migração: configuração da busca de metadados em `global_settings`.
+fn migrate_metadata_settings(conn: &rusqlite::Connection) -> rusqlite::Result<()> {
+    let has_col: bool = conn
+        .query_row(
+            "SELECT 1 FROM pragma_table_info('global_settings') WHERE name = 'metadata_sources' LIMIT 1",
+            [],
+            |row| row.get::<_, i32>(0),
+        )
+        .map(|v| v == 1)
+        .unwrap_or(false);
+    if has_col {
+        return Ok(());
+    }
+    conn.execute_batch(
+        r#"
+        ALTER TABLE global_settings ADD COLUMN metadata_cache_ttl_secs INTEGER;
+        ALTER TABLE global_settings ADD COLUMN metadata_library_kind TEXT;
+        ALTER TABLE global_settings ADD COLUMN metadata_sources TEXT;
+        "#,
+    )?;
+    Ok(())
+}
+
+/// Migração: status de leitura explícito em livros e volumes. Na primeira
+/// execução, volumes com progresso viram "reading" ou, se a página atual
+/// já está no fim, "completed"; o livro segue os volumes.
+fn migrate_read_status(conn: &rusqlite::Connection) -> rusqlite::Result<()> {
+    let has_read_status: bool = conn
+        .query_row(
+            "SELECT 1 FROM pragma_table_info('volumes') WHERE name = 'read_status' LIMIT 1",
+            [],
+            |row| row.get::<_, i32>(0),
+        )
+        .map(|v| v == 1)
+        .unwrap_or(false);
+    if has_read_status {
+        return Ok(());
     }
+    for table in ["books", "volumes"] {
+        conn.execute_batch(&format!(
+            r#"
+            ALTER TABLE {t} ADD COLUMN read_status TEXT NOT NULL DEFAULT 'unread';
+            ALTER TABLE {t} ADD COLUMN started_at INTEGER;
+            ALTER TABLE {t} ADD COLUMN finished_at INTEGER;
+            ALTER TABLE {t} ADD COLUMN reread_count INTEGER NOT NULL DEFAULT 0;
+            CREATE INDEX IF NOT EXISTS idx_{t}_read_status ON {t}(read_status);
+            "#,
+            t = table
+        ))?;
+    }
+    conn.execute_batch(
+        r#"
+        UPDATE volumes SET
+            read_status = CASE
+                WHEN (SELECT rp.page_index >= MAX(COALESCE(rp.total_pages,
+                        (SELECT COUNT(*) FROM chapters c WHERE c.volume_id = volumes.id)), 1)
+                    FROM reading_progress rp
+                    WHERE rp.book_id = volumes.book_id AND rp.volume_id = volumes.id)
+                THEN 'completed' ELSE 'reading' END,
+            started_at = (SELECT rp.updated_at FROM reading_progress rp
+                WHERE rp.book_id = volumes.book_id AND rp.volume_id = volumes.id)
+        WHERE EXISTS (SELECT 1 FROM reading_progress rp
+            WHERE rp.book_id = volumes.book_id AND rp.volume_id = volumes.id AND rp.page_index > 1);
+        UPDATE volumes SET finished_at = started_at WHERE read_status = 'completed';
+
+        UPDATE books SET
+            read_status = CASE
+                WHEN NOT EXISTS (SELECT 1 FROM volumes v
+                    WHERE v.book_id = books.id AND v.read_status <> 'completed')
+                THEN 'completed' ELSE 'reading' END,
+            started_at = (SELECT MIN(v.started_at) FROM volumes v WHERE v.book_id = books.id),
+            finished_at = (SELECT MAX(v.finished_at) FROM volumes v WHERE v.book_id = books.id)
+        WHERE EXISTS (SELECT 1 FROM volumes v
+            WHERE v.book_id = books.id AND v.read_status <> 'unread');
+        UPDATE books SET finished_at = NULL WHERE read_status <> 'completed';
+        "#,
+    )?;
     Ok(())
 }
 
@@ -213,6 +950,26 @@ pub fn run_migrations(conn: &rusqlite::Connection) -> rusqlite::Result<()> {
     migrate_progress_per_volume(conn)?;
     migrate_books_metadata(conn)?;
     migrate_metadata_search(conn)?;
+    migrate_metadata_cache_key(conn)?;
+    migrate_extended_metadata(conn)?;
     migrate_custom_themes(conn)?;
+    migrate_shelf_layout(conn)?;
+    migrate_smart_shelves(conn)?;
+    migrate_shelf_book_order(conn)?;
+    migrate_bookmarks(conn)?;
+    migrate_annotations(conn)?;
+    migrate_chapter_progress(conn)?;
+    migrate_tags(conn)?;
+    migrate_book_reviews(conn)?;
+    migrate_sync_change_log(conn)?;
+    migrate_koreader_sync(conn)?;
+    // Depois do koreader_sync: o backfill usa reading_progress.total_pages
+    migrate_read_status(conn)?;
+    migrate_reading_sessions(conn)?;
+    migrate_reading_history(conn)?;
+    migrate_reading_queue(conn)?;
+    migrate_metadata_changes(conn)?;
+    migrate_metadata_jobs(conn)?;
+    migrate_metadata_settings(conn)?;
     Ok(())
 }
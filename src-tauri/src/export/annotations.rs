@@ -0,0 +1,288 @@
+//! Anotações exportadas em Markdown (compatível com Obsidian: título por livro,
+//! capítulos como subtítulos, citações e localização), JSON ou CSV do Readwise.
+
+use serde_json::json;
+
+use super::template::render;
+use crate::models::{AnnotationEntry, AnnotationExportFormat, AnnotationTemplates};
+
+const DEFAULT_BOOK_TEMPLATE: &str = "# {{title}}
+{{#author}}
+Autor: {{author}}
+{{/author}}
+Anotações: {{count}}
+
+";
+
+const DEFAULT_CHAPTER_TEMPLATE: &str = "## {{chapter}}
+
+";
+
+const DEFAULT_ANNOTATION_TEMPLATE: &str = "{{quote}}
+
+{{#note}}
+{{note}}
+
+{{/note}}
+— {{location}} · {{date}}
+
+";
+
+/// Colunas aceitas pelo importador de CSV do Readwise.
+const READWISE_HEADER: &str = "Highlight,Title,Author,URL,Note,Location,Date";
+
+/// Data UTC (`YYYY-MM-DD`, `HH:MM:SS`) de um timestamp em segundos.
+fn date_time(secs: i64) -> (String, String) {
+    let days = secs.div_euclid(86_400);
+    let rem = secs.rem_euclid(86_400);
+    // Algoritmo civil_from_days (Howard Hinnant)
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    (
+        format!("{:04}-{:02}-{:02}", year, month, day),
+        format!("{:02}:{:02}:{:02}", rem / 3600, rem % 3600 / 60, rem % 60),
+    )
+}
+
+/// "p. N" (página) ou o CFI (EPUB).
+fn location(entry: &AnnotationEntry) -> String {
+    let a = &entry.annotation;
+    match (a.page_index, &a.cfi_range) {
+        (Some(page), _) => format!("p. {}", page),
+        (None, Some(cfi)) => cfi.clone(),
+        (None, None) => String::new(),
+    }
+}
+
+/// Agrupa por livro mantendo a ordem da consulta (título, ordem de leitura).
+fn group_by_book(entries: &[AnnotationEntry]) -> Vec<&[AnnotationEntry]> {
+    let mut groups = Vec::new();
+    let mut start = 0;
+    for i in 1..=entries.len() {
+        if i == entries.len() || entries[i].annotation.book_id != entries[start].annotation.book_id
+        {
+            groups.push(&entries[start..i]);
+            start = i;
+        }
+    }
+    groups
+}
+
+fn markdown(entries: &[AnnotationEntry], templates: &AnnotationTemplates) -> String {
+    let book_tpl = templates.book.as_deref().unwrap_or(DEFAULT_BOOK_TEMPLATE);
+    let chapter_tpl = templates
+        .chapter
+        .as_deref()
+        .unwrap_or(DEFAULT_CHAPTER_TEMPLATE);
+    let annotation_tpl = templates
+        .annotation
+        .as_deref()
+        .unwrap_or(DEFAULT_ANNOTATION_TEMPLATE);
+
+    let mut out = String::new();
+    for group in group_by_book(entries) {
+        let first = &group[0];
+        out.push_str(&render(
+            book_tpl,
+            &[
+                ("title", &first.book_title),
+                ("author", first.book_author.as_deref().unwrap_or("")),
+                ("count", &group.len().to_string()),
+            ],
+        ));
+        let mut chapter: Option<&str> = None;
+        for entry in group {
+            // Livros de arquivo único têm um capítulo com o nome do próprio livro
+            let name = entry
+                .chapter_name
+                .as_deref()
+                .filter(|c| *c != entry.book_title);
+            if name.is_some() && name != chapter {
+                out.push_str(&render(chapter_tpl, &[("chapter", name.unwrap_or(""))]));
+            }
+            chapter = name;
+
+            let a = &entry.annotation;
+            let quote = a
+                .text
+                .lines()
+                .map(|l| format!("> {}", l))
+                .collect::<Vec<_>>()
+                .join("\n");
+            let (date, _) = date_time(a.created_at);
+            out.push_str(&render(
+                annotation_tpl,
+                &[
+                    ("text", &a.text),
+                    ("quote", &quote),
+                    ("note", a.note.as_deref().unwrap_or("")),
+                    ("color", &a.color),
+                    ("location", &location(entry)),
+                    ("date", &date),
+                ],
+            ));
+        }
+    }
+    out
+}
+
+fn json_export(entries: &[AnnotationEntry]) -> crate::Result<String> {
+    let books: Vec<serde_json::Value> = group_by_book(entries)
+        .into_iter()
+        .map(|group| {
+            json!({
+                "book_id": group[0].annotation.book_id,
+                "title": group[0].book_title,
+                "author": group[0].book_author,
+                "annotations": group.iter().map(|e| {
+                    let a = &e.annotation;
+                    json!({
+                        "id": a.id,
+                        "chapter": e.chapter_name,
+                        "location": location(e),
+                        "page_index": a.page_index,
+                        "cfi_range": a.cfi_range,
+                        "text": a.text,
+                        "note": a.note,
+                        "color": a.color,
+                        "created_at": a.created_at,
+                        "updated_at": a.updated_at,
+                    })
+                }).collect::<Vec<_>>(),
+            })
+        })
+        .collect();
+    serde_json::to_string_pretty(&books).map_err(|e| crate::Error::Io(e.to_string()))
+}
+
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+fn readwise_csv(entries: &[AnnotationEntry]) -> String {
+    let mut out = String::from(READWISE_HEADER);
+    out.push('\n');
+    for entry in entries {
+        let a = &entry.annotation;
+        let (date, time) = date_time(a.created_at);
+        // Readwise só aceita localização numérica
+        let location = a.page_index.map(|p| p.to_string()).unwrap_or_default();
+        let row = [
+            csv_field(&a.text),
+            csv_field(&entry.book_title),
+            csv_field(entry.book_author.as_deref().unwrap_or("")),
+            String::new(),
+            csv_field(a.note.as_deref().unwrap_or("")),
+            location,
+            format!("{} {}", date, time),
+        ];
+        out.push_str(&row.join(","));
+        out.push('\n');
+    }
+    out
+}
+
+/// Conteúdo do arquivo exportado. `entries` na ordem de `query_annotations`.
+pub fn render_annotations(
+    entries: &[AnnotationEntry],
+    format: AnnotationExportFormat,
+    templates: &AnnotationTemplates,
+) -> crate::Result<String> {
+    match format {
+        AnnotationExportFormat::Markdown => Ok(markdown(entries, templates)),
+        AnnotationExportFormat::Json => json_export(entries),
+        AnnotationExportFormat::ReadwiseCsv => Ok(readwise_csv(entries)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::Annotation;
+
+    fn entry(chapter: &str, page: i32, text: &str, note: Option<&str>) -> AnnotationEntry {
+        AnnotationEntry {
+            annotation: Annotation {
+                id: format!("a{}", page),
+                book_id: "b1".to_string(),
+                volume_id: "v1".to_string(),
+                chapter_id: None,
+                book_hash: None,
+                cfi_range: None,
+                page_index: Some(page),
+                quads: vec![],
+                text: text.to_string(),
+                color: "yellow".to_string(),
+                note: note.map(str::to_string),
+                // 2024-03-01 12:30:00 UTC
+                created_at: 1_709_296_200,
+                updated_at: 1_709_296_200,
+            },
+            book_title: "SICP".to_string(),
+            book_author: Some("Abelson".to_string()),
+            chapter_name: Some(chapter.to_string()),
+        }
+    }
+
+    #[test]
+    fn renders_markdown_and_readwise_csv() {
+        let entries = vec![
+            entry(
+                "1 Procedures",
+                3,
+                "Programs must be written\nfor people",
+                None,
+            ),
+            entry("1 Procedures", 7, "Lisp", Some("ver \"eval\", apply")),
+            entry("2 Data", 90, "Closure", None),
+        ];
+        let md = render_annotations(
+            &entries,
+            AnnotationExportFormat::Markdown,
+            &AnnotationTemplates::default(),
+        )
+        .unwrap();
+        assert_eq!(
+            md,
+            "# SICP\nAutor: Abelson\nAnotações: 3\n\n\
+             ## 1 Procedures\n\n\
+             > Programs must be written\n> for people\n\n— p. 3 · 2024-03-01\n\n\
+             > Lisp\n\nver \"eval\", apply\n\n— p. 7 · 2024-03-01\n\n\
+             ## 2 Data\n\n\
+             > Closure\n\n— p. 90 · 2024-03-01\n\n"
+        );
+
+        let custom = AnnotationTemplates {
+            annotation: Some("- {{text}}{{#note}} ({{note}}){{/note}}\n".to_string()),
+            book: Some(String::new()),
+            chapter: Some(String::new()),
+        };
+        let md =
+            render_annotations(&entries[1..], AnnotationExportFormat::Markdown, &custom).unwrap();
+        assert_eq!(md, "- Lisp (ver \"eval\", apply)\n- Closure\n");
+
+        let csv = render_annotations(
+            &entries,
+            AnnotationExportFormat::ReadwiseCsv,
+            &AnnotationTemplates::default(),
+        )
+        .unwrap();
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(lines[0], READWISE_HEADER);
+        assert_eq!(
+            lines[3],
+            "Lisp,SICP,Abelson,,\"ver \"\"eval\"\", apply\",7,2024-03-01 12:30:00"
+        );
+    }
+}
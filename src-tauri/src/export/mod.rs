@@ -0,0 +1,6 @@
+//! Exportação de dados do usuário para arquivos (anotações em Markdown, JSON e CSV).
+
+mod annotations;
+mod template;
+
+pub use annotations::render_annotations;
@@ -0,0 +1,46 @@
+//! Modelos de texto mínimos: `{{nome}}` e seções `{{#nome}}...{{/nome}}`,
+//! exibidas só quando a variável não é vazia. Variável desconhecida vira "".
+//! Tags de seção sozinhas na linha não deixam linha em branco na saída.
+
+pub fn render(template: &str, vars: &[(&str, &str)]) -> String {
+    let value = |name: &str| {
+        vars.iter()
+            .find(|(k, _)| *k == name)
+            .map(|(_, v)| *v)
+            .unwrap_or("")
+    };
+    let mut out = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(start) = rest.find("{{") {
+        out.push_str(&rest[..start]);
+        let after = &rest[start + 2..];
+        let Some(end) = after.find("}}") else {
+            out.push_str(&rest[start..]);
+            return out;
+        };
+        let tag = after[..end].trim();
+        rest = &after[end + 2..];
+        if let Some(name) = tag.strip_prefix('#') {
+            let close = format!("{{{{/{}}}}}", name);
+            let (mut inner, mut tail) = match rest.find(&close) {
+                Some(i) => (&rest[..i], &rest[i + close.len()..]),
+                None => (rest, ""),
+            };
+            let at_line_start = out.is_empty() || out.ends_with('\n');
+            if at_line_start && inner.starts_with('\n') {
+                inner = &inner[1..];
+            }
+            if (inner.is_empty() || inner.ends_with('\n')) && tail.starts_with('\n') {
+                tail = &tail[1..];
+            }
+            if !value(name).trim().is_empty() {
+                out.push_str(&render(inner, vars));
+            }
+            rest = tail;
+        } else if !tag.starts_with('/') {
+            out.push_str(value(tag));
+        }
+    }
+    out.push_str(rest);
+    out
+}
@@ -2,17 +2,37 @@ mod archive;
 mod commands;
 mod db;
 mod error;
+mod export;
 mod metadata;
 mod models;
 mod repositories;
+mod sync;
 
 use commands::{
-    add_book, add_book_to_shelf, apply_metadata_candidate, create_custom_theme,
-    create_shelf, delete_book, delete_custom_theme, get_all_progress, get_book_settings,
-    get_book_shelf_ids, get_books, get_books_in_shelf, get_custom_theme, get_global_settings,
-    get_pending_file_to_open, get_progress, get_recent_progress, list_custom_themes, list_shelves,
-    remove_book_from_shelf, save_book_settings, save_global_settings, save_progress,
-    search_metadata, update_book, update_custom_theme,
+    add_book, add_book_to_shelf, add_tag_to_book, apply_metadata_candidate, cancel_metadata_job,
+    clear_metadata_cache, create_annotation, create_bookmark, create_custom_theme, create_shelf,
+    create_smart_shelf, create_tag, delete_annotation, delete_book, delete_bookmark,
+    delete_custom_theme, delete_history_entries, delete_history_entry, delete_shelf, delete_tag,
+    dequeue, dismiss_metadata_review, end_reading_session, enqueue, export_annotations,
+    export_library, get_all_progress, get_annotation, get_book, get_book_read_state,
+    get_book_review, get_book_settings, get_book_shelf_ids, get_book_tags, get_books,
+    get_books_in_shelf, get_changes_since, get_chapter_progress, get_continue_reading,
+    get_custom_theme, get_device_id, get_global_settings, get_history, get_koreader_config,
+    get_metadata_config, get_metadata_history, get_metadata_job, get_metadata_job_items,
+    get_next_unread_chapter, get_pages_per_hour, get_pending_file_to_open, get_progress,
+    get_reading_stats, get_reading_time_by_book, get_reading_time_by_day, get_recent_progress,
+    get_series_completion, get_sync_config, get_tombstones, get_volume_eta, import_library,
+    insert_book_in_shelf, jump_to_bookmark, koreader_authorize, koreader_register,
+    koreader_sync_now, list_annotations, list_bookmarks, list_custom_themes, list_queue,
+    list_shelves, list_tags, mark_previous_chapters_read, mark_volumes, merge_tags,
+    move_book_in_shelf, pause_metadata_job, pop_queue, query_annotations, query_books,
+    reading_session_heartbeat, remove_book_from_shelf, remove_tag_from_book, rename_bookmark,
+    rename_shelf, rename_tag, reorder_queue, reorder_shelves, revert_metadata, save_book_review,
+    save_book_settings, save_global_settings, save_koreader_config, save_metadata_config,
+    save_progress, save_sync_config, search_metadata, set_book_status, set_chapters_read,
+    set_shelf_style, set_volume_status, start_metadata_job, start_reading_session,
+    suggest_next_volume, sync_now, update_annotation, update_book, update_custom_theme,
+    update_smart_shelf_rules,
 };
 use commands::{collect_pending_from_args, PendingFileOpen};
 pub use error::{Error, Result};
@@ -76,12 +96,15 @@ pub fn run() {
             add_book,
             get_pending_file_to_open,
             get_books,
+            query_books,
+            get_book,
             delete_book,
             update_book,
             save_progress,
             get_progress,
             get_all_progress,
             get_recent_progress,
+            get_continue_reading,
             save_book_settings,
             get_book_settings,
             get_global_settings,
@@ -99,6 +122,86 @@ pub fn run() {
             create_custom_theme,
             update_custom_theme,
             delete_custom_theme,
+            get_device_id,
+            get_changes_since,
+            get_tombstones,
+            get_sync_config,
+            save_sync_config,
+            sync_now,
+            get_koreader_config,
+            save_koreader_config,
+            koreader_register,
+            koreader_authorize,
+            koreader_sync_now,
+            create_bookmark,
+            list_bookmarks,
+            rename_bookmark,
+            delete_bookmark,
+            jump_to_bookmark,
+            export_library,
+            import_library,
+            create_annotation,
+            get_annotation,
+            update_annotation,
+            delete_annotation,
+            list_annotations,
+            query_annotations,
+            export_annotations,
+            start_reading_session,
+            reading_session_heartbeat,
+            end_reading_session,
+            get_reading_time_by_book,
+            get_reading_time_by_day,
+            get_pages_per_hour,
+            get_volume_eta,
+            get_reading_stats,
+            get_book_read_state,
+            set_book_status,
+            set_volume_status,
+            mark_volumes,
+            get_history,
+            delete_history_entry,
+            delete_history_entries,
+            get_chapter_progress,
+            set_chapters_read,
+            mark_previous_chapters_read,
+            get_next_unread_chapter,
+            get_series_completion,
+            list_queue,
+            enqueue,
+            reorder_queue,
+            dequeue,
+            pop_queue,
+            suggest_next_volume,
+            rename_shelf,
+            set_shelf_style,
+            delete_shelf,
+            reorder_shelves,
+            create_smart_shelf,
+            update_smart_shelf_rules,
+            insert_book_in_shelf,
+            move_book_in_shelf,
+            list_tags,
+            create_tag,
+            rename_tag,
+            merge_tags,
+            delete_tag,
+            get_book_tags,
+            add_tag_to_book,
+            remove_tag_from_book,
+            get_book_review,
+            save_book_review,
+            get_metadata_history,
+            revert_metadata,
+            get_metadata_config,
+            save_metadata_config,
+            clear_metadata_cache,
+            start_metadata_job,
+            pause_metadata_job,
+            cancel_metadata_job,
+            get_metadata_job,
+            get_metadata_job_items,
+            dismiss_metadata_review,
         ])
         .setup(|app| {
             if cfg!(not(debug_assertions)) {
@@ -108,6 +211,8 @@ pub fn run() {
                 }
                 init_panic_hook();
             }
+            sync::scheduler::start(app.handle().clone());
+            commands::resume_metadata_jobs(app.handle());
             if cfg!(debug_assertions) {
                 use tauri_plugin_log::{Target, TargetKind};
                 app.handle().plugin(
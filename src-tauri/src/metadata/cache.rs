@@ -1,8 +1,10 @@
-//! Cache de buscas por query normalizada.
+//! Cache de buscas por (fonte, query normalizada).
 //! TTL configurável (7-30 dias).
 
 use serde::{Deserialize, Serialize};
 
+use crate::metadata::MetadataCandidate;
+
 /// TTL padrão do cache em segundos (7 dias).
 pub const DEFAULT_CACHE_TTL_SECS: i64 = 7 * 24 * 3600;
 
@@ -21,4 +23,41 @@ impl CachedResult {
             .as_secs() as i64;
         now - self.cached_at > ttl_secs
     }
+
+    /// Candidatos guardados; JSON inválido conta como ausente.
+    pub fn candidates(&self) -> Option<Vec<MetadataCandidate>> {
+        serde_json::from_str(&self.results_json).ok()
+    }
+}
+
+/// Onde o pipeline guarda as respostas das fontes (no app, o SQLite).
+pub trait SearchCache {
+    fn get(&self, source: &str, query: &str) -> Option<CachedResult>;
+    fn put(&self, source: &str, query: &str, candidates: &[MetadataCandidate]);
+}
+
+/// Como a busca usa o cache. `ttl_secs` = 0 desliga o cache.
+#[derive(Debug, Clone, Copy)]
+pub struct CacheOptions {
+    pub ttl_secs: i64,
+    /// Consulta as fontes mesmo com resultado válido (o cache é atualizado).
+    pub bypass: bool,
+}
+
+impl Default for CacheOptions {
+    fn default() -> Self {
+        Self {
+            ttl_secs: DEFAULT_CACHE_TTL_SECS,
+            bypass: false,
+        }
+    }
+}
+
+/// Chave da query no cache: minúsculas, espaços colapsados.
+pub fn cache_key(query: &str) -> String {
+    query
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .to_lowercase()
 }
@@ -11,6 +11,25 @@ pub enum MediaType {
     Manga,
 }
 
+impl MediaType {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            MediaType::Book => "book",
+            MediaType::Anime => "anime",
+            MediaType::Manga => "manga",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "book" => Some(MediaType::Book),
+            "anime" => Some(MediaType::Anime),
+            "manga" => Some(MediaType::Manga),
+            _ => None,
+        }
+    }
+}
+
 /// Resultado candidato de uma fonte de metadados.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MetadataCandidate {
@@ -34,4 +53,19 @@ pub struct MetadataCandidate {
     pub year: Option<i32>,
     /// Código de idioma (eng, por, jpn, etc)
     pub language: Option<String>,
+    /// Editora
+    #[serde(default)]
+    pub publisher: Option<String>,
+    /// ISBN-10/13 conhecidos
+    #[serde(default)]
+    pub isbns: Vec<String>,
+    /// Número de páginas
+    #[serde(default)]
+    pub page_count: Option<i32>,
+    /// Gêneros/assuntos da fonte (viram tags automáticas do livro)
+    #[serde(default)]
+    pub genres: Vec<String>,
+    /// Nota da comunidade na fonte, normalizada para 0–100 (AniList, Jikan)
+    #[serde(default)]
+    pub community_score: Option<f32>,
 }
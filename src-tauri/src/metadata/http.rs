@@ -0,0 +1,272 @@
+//! Client HTTP compartilhado pelas fontes de metadados.
+//! Cada fonte tem um token bucket com o limite publicado da API, há um teto
+//! global de requisições simultâneas, e 429/5xx são repetidos com backoff
+//! exponencial (respeitando `Retry-After`).
+
+use reqwest::blocking::{Client, RequestBuilder, Response};
+use reqwest::header::{HeaderMap, RETRY_AFTER};
+use reqwest::StatusCode;
+use std::collections::HashMap;
+use std::sync::{Condvar, LazyLock, Mutex};
+use std::time::{Duration, Instant};
+
+const TIMEOUT: Duration = Duration::from_secs(8);
+const USER_AGENT: &str = "Readito/1.0";
+
+/// Requisições em andamento somando todas as fontes.
+const MAX_CONCURRENT: usize = 4;
+/// Novas tentativas após a primeira.
+const MAX_RETRIES: u32 = 3;
+const BASE_BACKOFF: Duration = Duration::from_millis(500);
+/// Espera máxima entre tentativas, mesmo que o `Retry-After` peça mais.
+const MAX_BACKOFF: Duration = Duration::from_secs(30);
+
+static CLIENT: LazyLock<Client> = LazyLock::new(|| {
+    Client::builder()
+        .timeout(TIMEOUT)
+        .user_agent(USER_AGENT)
+        .build()
+        .expect("Falha ao criar client HTTP")
+});
+
+static BUCKETS: LazyLock<Mutex<HashMap<String, Bucket>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+static IN_FLIGHT: Mutex<usize> = Mutex::new(0);
+static SLOT_FREED: Condvar = Condvar::new();
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct RateLimit {
+    /// Taxa sustentada (requisições por segundo).
+    per_sec: f64,
+    /// Rajada permitida com o bucket cheio.
+    burst: f64,
+}
+
+/// Limites publicados de cada API.
+fn rate_limit(source: &str) -> RateLimit {
+    match source {
+        // Jikan: 3/s e 60/min
+        "jikan" => RateLimit {
+            per_sec: 1.0,
+            burst: 3.0,
+        },
+        // AniList: 90/min, reduzido para 30/min enquanto a API está degradada
+        "anilist" => RateLimit {
+            per_sec: 0.5,
+            burst: 5.0,
+        },
+        // loc.gov: 20 requisições a cada 10 s nas buscas
+        "loc" => RateLimit {
+            per_sec: 2.0,
+            burst: 10.0,
+        },
+        // Open Library: 1/s sem e-mail de contato no User-Agent.
+        // Kitsu não publica limite: mesmo valor conservador.
+        _ => RateLimit {
+            per_sec: 1.0,
+            burst: 3.0,
+        },
+    }
+}
+
+struct Bucket {
+    limit: RateLimit,
+    tokens: f64,
+    updated: Instant,
+    /// Pausa pedida pela API (429), vale para todas as threads da fonte.
+    blocked_until: Option<Instant>,
+}
+
+impl Bucket {
+    fn new(limit: RateLimit, now: Instant) -> Self {
+        Self {
+            limit,
+            tokens: limit.burst,
+            updated: now,
+            blocked_until: None,
+        }
+    }
+
+    /// Consome um token. Sem token disponível, retorna quanto esperar.
+    fn take(&mut self, now: Instant) -> Option<Duration> {
+        if let Some(until) = self.blocked_until {
+            if now < until {
+                return Some(until - now);
+            }
+            self.blocked_until = None;
+        }
+        let elapsed = now.saturating_duration_since(self.updated).as_secs_f64();
+        self.tokens = (self.tokens + elapsed * self.limit.per_sec).min(self.limit.burst);
+        self.updated = now;
+        if self.tokens >= 1.0 {
+            self.tokens -= 1.0;
+            None
+        } else {
+            Some(Duration::from_secs_f64(
+                (1.0 - self.tokens) / self.limit.per_sec,
+            ))
+        }
+    }
+
+    fn block(&mut self, now: Instant, wait: Duration) {
+        let until = now + wait;
+        self.blocked_until = Some(
+            self.blocked_until
+                .map_or(until, |current| current.max(until)),
+        );
+        self.tokens = 0.0;
+        self.updated = now;
+    }
+}
+
+fn with_bucket<T>(source: &str, f: impl FnOnce(&mut Bucket, Instant) -> T) -> T {
+    let mut buckets = BUCKETS.lock().unwrap_or_else(|e| e.into_inner());
+    let now = Instant::now();
+    let bucket = buckets
+        .entry(source.to_string())
+        .or_insert_with(|| Bucket::new(rate_limit(source), now));
+    f(bucket, now)
+}
+
+/// Bloqueia até a fonte ter um token disponível.
+fn acquire_token(source: &str) {
+    while let Some(wait) = with_bucket(source, |bucket, now| bucket.take(now)) {
+        std::thread::sleep(wait);
+    }
+}
+
+/// Vaga no teto global de requisições; liberada no drop.
+struct Slot;
+
+impl Slot {
+    fn acquire() -> Self {
+        let mut in_flight = IN_FLIGHT.lock().unwrap_or_else(|e| e.into_inner());
+        while *in_flight >= MAX_CONCURRENT {
+            in_flight = SLOT_FREED
+                .wait(in_flight)
+                .unwrap_or_else(|e| e.into_inner());
+        }
+        *in_flight += 1;
+        Slot
+    }
+}
+
+impl Drop for Slot {
+    fn drop(&mut self) {
+        let mut in_flight = IN_FLIGHT.lock().unwrap_or_else(|e| e.into_inner());
+        *in_flight = in_flight.saturating_sub(1);
+        SLOT_FREED.notify_one();
+    }
+}
+
+fn is_retryable(status: StatusCode) -> bool {
+    status == StatusCode::TOO_MANY_REQUESTS || status.is_server_error()
+}
+
+/// `Retry-After` em segundos. A forma com data HTTP é ignorada (cai no backoff).
+fn retry_after(headers: &HeaderMap) -> Option<Duration> {
+    let secs: u64 = headers
+        .get(RETRY_AFTER)?
+        .to_str()
+        .ok()?
+        .trim()
+        .parse()
+        .ok()?;
+    Some(Duration::from_secs(secs).min(MAX_BACKOFF))
+}
+
+fn backoff(attempt: u32) -> Duration {
+    BASE_BACKOFF
+        .saturating_mul(2u32.saturating_pow(attempt))
+        .min(MAX_BACKOFF)
+}
+
+/// Envia a requisição montada por `build` respeitando o limite da fonte.
+/// Respostas 4xx (exceto 429) são devolvidas normalmente; 429/5xx e falhas
+/// de conexão são repetidas até `MAX_RETRIES` vezes.
+pub fn send<F>(source: &str, build: F) -> Result<Response, String>
+where
+    F: Fn(&Client) -> RequestBuilder,
+{
+    let mut attempt = 0;
+    loop {
+        acquire_token(source);
+        let result = {
+            let _slot = Slot::acquire();
+            build(&CLIENT).send()
+        };
+
+        let (wait, reason) = match result {
+            Ok(resp) if is_retryable(resp.status()) => {
+                let status = resp.status();
+                let wait = retry_after(resp.headers()).unwrap_or_else(|| backoff(attempt));
+                if status == StatusCode::TOO_MANY_REQUESTS {
+                    with_bucket(source, |bucket, now| bucket.block(now, wait));
+                }
+                (wait, format!("HTTP {}", status))
+            }
+            Ok(resp) => return Ok(resp),
+            Err(e) if e.is_timeout() || e.is_connect() => (backoff(attempt), e.to_string()),
+            Err(e) => return Err(e.to_string()),
+        };
+
+        if attempt >= MAX_RETRIES {
+            return Err(format!("{} após {} tentativas", reason, attempt + 1));
+        }
+        attempt += 1;
+        log::debug!(
+            "[metadata:{}] {}; nova tentativa {} em {:?}",
+            source,
+            reason,
+            attempt,
+            wait
+        );
+        std::thread::sleep(wait);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bucket_and_backoff() {
+        let start = Instant::now();
+        let limit = RateLimit {
+            per_sec: 1.0,
+            burst: 2.0,
+        };
+        let mut bucket = Bucket::new(limit, start);
+        assert_eq!(bucket.take(start), None);
+        assert_eq!(bucket.take(start), None);
+        let wait = bucket.take(start).unwrap();
+        assert!(wait > Duration::from_millis(900) && wait <= Duration::from_secs(1));
+        // Recarrega com o tempo, sem passar da rajada
+        assert_eq!(bucket.take(start + Duration::from_secs(10)), None);
+        assert_eq!(bucket.take(start + Duration::from_secs(10)), None);
+        assert!(bucket.take(start + Duration::from_secs(10)).is_some());
+
+        let later = start + Duration::from_secs(20);
+        bucket.block(later, Duration::from_secs(5));
+        assert_eq!(
+            bucket.take(later + Duration::from_secs(2)),
+            Some(Duration::from_secs(3))
+        );
+
+        let mut headers = HeaderMap::new();
+        headers.insert(RETRY_AFTER, "7".parse().unwrap());
+        assert_eq!(retry_after(&headers), Some(Duration::from_secs(7)));
+        headers.insert(
+            RETRY_AFTER,
+            "Wed, 21 Oct 2015 07:28:00 GMT".parse().unwrap(),
+        );
+        assert_eq!(retry_after(&headers), None);
+        headers.insert(RETRY_AFTER, "3600".parse().unwrap());
+        assert_eq!(retry_after(&headers), Some(MAX_BACKOFF));
+
+        assert_eq!(backoff(0), Duration::from_millis(500));
+        assert_eq!(backoff(2), Duration::from_secs(2));
+        assert_eq!(backoff(10), MAX_BACKOFF);
+    }
+}
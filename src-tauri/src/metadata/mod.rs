@@ -3,6 +3,7 @@
 mod cache;
 mod candidate;
 pub mod decision;
+mod http;
 mod normalizer;
 mod scorer;
 mod search;
@@ -11,10 +12,11 @@ mod variations;
 pub mod cover;
 pub mod sources;
 
-pub use cache::CachedResult;
+pub use cache::{cache_key, CacheOptions, CachedResult, SearchCache, DEFAULT_CACHE_TTL_SECS};
 pub use candidate::{MediaType, MetadataCandidate};
 pub use decision::apply_metadata_decision;
 pub use normalizer::normalize;
 pub use scorer::{score_candidate, score_candidate_with_context};
 pub use search::{search_metadata, RankedCandidate, SearchResult};
+pub use sources::{SourceSettings, SOURCE_NAMES};
 pub use variations::{generate_variations, is_likely_western_book};
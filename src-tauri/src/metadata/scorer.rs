@@ -1,8 +1,12 @@
 //! Cálculo de score para ranqueamento de candidatos.
 //! Pesos: similaridade textual 40%, autor 25%, ano 15%, idioma 10%, capa 5%, descrição 5%.
 //! Ajustes: boost manga quando conteúdo japonês; penaliza anime quando título parece ocidental.
+//! O peso configurado da fonte só entra na ordenação (`ScoreContext::rank`);
+//! o score em si, comparado com o threshold, não depende dele.
 
-use crate::metadata::{normalizer, MediaType, MetadataCandidate};
+use std::collections::HashMap;
+
+use crate::metadata::{normalizer, MediaType, MetadataCandidate, SourceSettings};
 
 const TITLE_WEIGHT: f32 = 0.40;
 const AUTHOR_WEIGHT: f32 = 0.25;
@@ -17,6 +21,8 @@ pub struct ScoreContext {
     pub search_title: String,
     pub search_path: Option<String>,
     pub is_japanese: bool,
+    /// Peso de cada fonte; fontes ausentes valem 1.0.
+    pub source_weights: HashMap<String, f32>,
 }
 
 impl ScoreContext {
@@ -32,8 +38,24 @@ impl ScoreContext {
             search_title,
             search_path,
             is_japanese,
+            source_weights: HashMap::new(),
         }
     }
+
+    pub fn with_source_weights(mut self, sources: &[SourceSettings]) -> Self {
+        self.source_weights = sources.iter().map(|s| (s.name.clone(), s.weight)).collect();
+        self
+    }
+
+    fn source_weight(&self, source: &str) -> f32 {
+        self.source_weights.get(source).copied().unwrap_or(1.0)
+    }
+
+    /// Chave de ordenação: score ponderado pelo peso da fonte.
+    /// Não serve para decidir se aplica (use o score sem peso).
+    pub fn rank(&self, candidate: &MetadataCandidate, score: f32) -> f32 {
+        score * self.source_weight(&candidate.source)
+    }
 }
 
 /// Score de 0 a 100.
@@ -217,3 +239,52 @@ fn author_score(candidate: &MetadataCandidate, search_author: Option<&str>) -> f
         (None, None) => 50.0,
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn candidate(source: &str, title: &str) -> MetadataCandidate {
+        MetadataCandidate {
+            source: source.to_string(),
+            source_id: "1".to_string(),
+            media_type: MediaType::Book,
+            title: title.to_string(),
+            title_alternatives: vec![],
+            author: None,
+            description: None,
+            cover_url: None,
+            year: None,
+            language: None,
+            publisher: None,
+            isbns: vec![],
+            page_count: None,
+            genres: vec![],
+            community_score: None,
+        }
+    }
+
+    #[test]
+    fn source_weight_only_affects_rank() {
+        let sources = [
+            SourceSettings {
+                name: "loc".to_string(),
+                enabled: true,
+                weight: 2.0,
+            },
+            SourceSettings {
+                name: "open_library".to_string(),
+                enabled: true,
+                weight: 0.5,
+            },
+        ];
+        let ctx = ScoreContext::new("Duna", None).with_source_weights(&sources);
+        let unrelated = candidate("loc", "Fundação");
+        let plain = score_candidate_with_context(&unrelated, "Duna", None, None, None);
+        let weighted = score_candidate_with_context(&unrelated, "Duna", None, None, Some(&ctx));
+        assert_eq!(plain, weighted);
+        assert!(weighted < crate::metadata::decision::SCORE_THRESHOLD);
+        assert_eq!(ctx.rank(&unrelated, weighted), weighted * 2.0);
+        assert_eq!(ctx.rank(&candidate("kitsu", "Duna"), 40.0), 40.0);
+    }
+}
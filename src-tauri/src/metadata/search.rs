@@ -1,16 +1,18 @@
 //! Orquestrador: normalização → variações → fontes → scorer → decisão.
 
+use serde::{Deserialize, Serialize};
 use std::sync::mpsc;
 use std::thread;
 
+use crate::metadata::cache::{cache_key, CacheOptions, SearchCache};
 use crate::metadata::decision::{BookMetadataState, MetadataDecision};
 use crate::metadata::scorer::ScoreContext;
 use crate::metadata::sources::all_sources;
 use crate::metadata::{generate_variations, score_candidate_with_context};
-use crate::metadata::{MediaType, MetadataCandidate};
+use crate::metadata::{MediaType, MetadataCandidate, SourceSettings};
 
 /// Candidato ranqueado para exibição ao usuário.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct RankedCandidate {
     pub candidate: MetadataCandidate,
     pub score: f32,
@@ -30,6 +32,11 @@ pub struct SearchResult {
 /// Executa busca de metadados para um livro.
 /// Consulta fontes em paralelo, ranqueia e retorna a decisão.
 /// Usa título, path, autor, nomes e paths dos capítulos para gerar variações.
+/// Cada (fonte, query) com resultado no cache e dentro do TTL não vai à rede.
+/// Só as fontes habilitadas em `sources` são consultadas. O peso de cada
+/// fonte ordena os candidatos e a ordem da lista desempata; o mínimo e o
+/// threshold de aplicação usam o score sem peso.
+#[allow(clippy::too_many_arguments)]
 pub fn search_metadata(
     title: &str,
     path: Option<&str>,
@@ -37,6 +44,9 @@ pub fn search_metadata(
     chapter_names: Option<&[String]>,
     chapter_paths: Option<&[String]>,
     flags: &BookMetadataState,
+    cache: &dyn SearchCache,
+    cache_options: CacheOptions,
+    sources: &[SourceSettings],
 ) -> Option<SearchResult> {
     let variations = generate_variations(title, path, author, chapter_names, chapter_paths);
     log::info!("[metadata] Variações de busca: {:?}", variations);
@@ -47,31 +57,53 @@ pub fn search_metadata(
 
     let mut all_candidates: Vec<(MetadataCandidate, String)> = Vec::new();
 
+    let use_cache = cache_options.ttl_secs > 0;
     for query in &variations {
         log::debug!("[metadata] Consultando query: \"{}\"", query);
-        let query_clone = query.clone();
-        let sources = all_sources();
+        let key = cache_key(query);
         let (tx, rx) = mpsc::channel();
 
-        for source in sources {
+        for source in all_sources(sources) {
+            let cached = if use_cache && !cache_options.bypass {
+                cache
+                    .get(source.name(), &key)
+                    .filter(|c| !c.is_expired(cache_options.ttl_secs))
+                    .and_then(|c| c.candidates())
+            } else {
+                None
+            };
+            if let Some(results) = cached {
+                log::debug!(
+                    "[metadata] Cache: {} resultados de {} para \"{}\"",
+                    results.len(),
+                    source.name(),
+                    query
+                );
+                let _ = tx.send((source.name(), results, false));
+                continue;
+            }
             let tx = tx.clone();
-            let q = query_clone.clone();
+            let q = query.clone();
             thread::spawn(move || {
                 let results = source.search(&q);
-                for c in results {
-                    let _ = tx.send((c, q.clone()));
-                }
+                let _ = tx.send((source.name(), results, true));
             });
         }
         drop(tx);
 
-        while let Ok((candidate, q)) = rx.recv() {
-            log::debug!(
-                "[metadata] Candidato: source={}, title=\"{}\"",
-                candidate.source,
-                candidate.title
-            );
-            all_candidates.push((candidate, q));
+        while let Ok((source, results, fetched)) = rx.recv() {
+            // Lista vazia também é o retorno de erro/timeout: não vai para o cache
+            if fetched && use_cache && !results.is_empty() {
+                cache.put(source, &key, &results);
+            }
+            for candidate in results {
+                log::debug!(
+                    "[metadata] Candidato: source={}, title=\"{}\"",
+                    candidate.source,
+                    candidate.title
+                );
+                all_candidates.push((candidate, query.clone()));
+            }
         }
     }
 
@@ -81,21 +113,33 @@ pub fn search_metadata(
         return None;
     }
 
-    let score_ctx = ScoreContext::new(title, path);
-    let mut scored: Vec<(MetadataCandidate, f32, String)> = Vec::new();
-    for (candidate, query) in all_candidates {
+    let score_ctx = ScoreContext::new(title, path).with_source_weights(sources);
+    let mut scored: Vec<(MetadataCandidate, f32, f32)> = Vec::new();
+    for (candidate, _query) in all_candidates {
         let score = score_candidate_with_context(&candidate, title, author, None, Some(&score_ctx));
+        let rank = score_ctx.rank(&candidate, score);
         log::debug!(
-            "[metadata] Score {:.1}: source={}, media={:?}, title=\"{}\"",
+            "[metadata] Score {:.1} (ordem {:.1}): source={}, media={:?}, title=\"{}\"",
             score,
+            rank,
             candidate.source,
             candidate.media_type,
             candidate.title
         );
-        scored.push((candidate, score, query));
+        scored.push((candidate, score, rank));
     }
 
-    scored.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
+    let priority = |source: &str| {
+        sources
+            .iter()
+            .position(|s| s.name == source)
+            .unwrap_or(usize::MAX)
+    };
+    scored.sort_by(|a, b| {
+        b.2.partial_cmp(&a.2)
+            .unwrap_or(std::cmp::Ordering::Equal)
+            .then_with(|| priority(&a.0.source).cmp(&priority(&b.0.source)))
+    });
 
     const MIN_SCORE: f32 = 55.0;
     const MAX_CANDIDATES: usize = 5;
@@ -2,13 +2,10 @@
 //! POST https://graphql.anilist.co
 
 use super::source_trait::MetadataSource;
-use crate::metadata::{MediaType, MetadataCandidate};
-use reqwest::blocking::Client;
+use crate::metadata::{http, MediaType, MetadataCandidate};
 use serde::Deserialize;
 use serde_json::json;
-use std::time::Duration;
 
-const TIMEOUT: Duration = Duration::from_secs(8);
 const ENDPOINT: &str = "https://graphql.anilist.co";
 
 #[derive(Deserialize)]
@@ -37,6 +34,10 @@ struct AniListMedia {
     #[serde(rename = "startDate")]
     start_date: Option<AniListFuzzyDate>,
     studios: Option<AniListStudios>,
+    genres: Option<Vec<String>>,
+    /// Média ponderada (0–100)
+    #[serde(rename = "averageScore")]
+    average_score: Option<i32>,
 }
 
 #[derive(Deserialize)]
@@ -76,14 +77,6 @@ impl MetadataSource for AniListSource {
     fn search(&self, query: &str) -> Vec<MetadataCandidate> {
         log::debug!("[metadata:anilist] Buscando \"{}\"", query);
 
-        let client = match Client::builder().timeout(TIMEOUT).build() {
-            Ok(c) => c,
-            Err(e) => {
-                log::warn!("[metadata:anilist] Falha ao criar client: {}", e);
-                return vec![];
-            }
-        };
-
         let mut results = Vec::new();
 
         // Apenas manga (anime excluído - app é para livros/manga/novel)
@@ -99,6 +92,8 @@ impl MetadataSource for AniListSource {
                             description
                             startDate {{ year }}
                             studios(isMain: true) {{ nodes {{ name }} }}
+                            genres
+                            averageScore
                         }}
                     }}
                 }}
@@ -111,12 +106,12 @@ impl MetadataSource for AniListSource {
                 "variables": { "search": query }
             });
 
-            let resp = match client
-                .post(ENDPOINT)
-                .json(&body)
-                .header("Content-Type", "application/json")
-                .send()
-            {
+            let resp = match http::send(self.name(), |client| {
+                client
+                    .post(ENDPOINT)
+                    .json(&body)
+                    .header("Content-Type", "application/json")
+            }) {
                 Ok(r) => r,
                 Err(e) => {
                     log::warn!("[metadata:anilist] Falha na requisição {}: {}", type_str, e);
@@ -193,6 +188,11 @@ impl MetadataSource for AniListSource {
                     cover_url: m.cover_image.as_ref().and_then(|c| c.large.clone()),
                     year: m.start_date.as_ref().and_then(|d| d.year),
                     language: None,
+                    publisher: None,
+                    isbns: vec![],
+                    page_count: None,
+                    genres: m.genres.unwrap_or_default(),
+                    community_score: m.average_score.map(|s| s as f32),
                 });
             }
         }
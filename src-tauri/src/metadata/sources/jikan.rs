@@ -2,12 +2,9 @@
 //! GET https://api.jikan.moe/v4/anime?q=X ou /v4/manga?q=X
 
 use super::source_trait::MetadataSource;
-use crate::metadata::{MediaType, MetadataCandidate};
-use reqwest::blocking::Client;
+use crate::metadata::{http, MediaType, MetadataCandidate};
 use serde::Deserialize;
-use std::time::Duration;
 
-const TIMEOUT: Duration = Duration::from_secs(8);
 const ANIME_URL: &str = "https://api.jikan.moe/v4/anime";
 const MANGA_URL: &str = "https://api.jikan.moe/v4/manga";
 
@@ -28,6 +25,8 @@ struct JikanItem {
     images: Option<JikanImages>,
     #[serde(rename = "published")]
     published: Option<JikanDateRange>,
+    /// Nota do MyAnimeList (0–10)
+    score: Option<f32>,
 }
 
 #[derive(Deserialize)]
@@ -70,21 +69,9 @@ fn search_endpoint(query: &str, url: &str, media_type: MediaType) -> Vec<Metadat
     };
     log::debug!("[metadata:jikan] Buscando \"{}\" em {}", query, endpoint);
 
-    let client = match Client::builder()
-        .timeout(TIMEOUT)
-        .user_agent("Readito/1.0")
-        .build()
-    {
-        Ok(c) => c,
-        Err(e) => {
-            log::warn!("[metadata:jikan] Falha ao criar client: {}", e);
-            return vec![];
-        }
-    };
-
     let full_url = format!("{}?q={}&limit=5", url, urlencoding::encode(query));
 
-    let resp = match client.get(&full_url).send() {
+    let resp = match http::send("jikan", |client| client.get(&full_url)) {
         Ok(r) => r,
         Err(e) => {
             log::warn!("[metadata:jikan] Falha na requisição {}: {}", endpoint, e);
@@ -149,6 +136,11 @@ fn search_endpoint(query: &str, url: &str, media_type: MediaType) -> Vec<Metadat
                 cover_url,
                 year,
                 language: None,
+                publisher: None,
+                isbns: vec![],
+                page_count: None,
+                genres: vec![],
+                community_score: item.score.map(|s| s * 10.0),
             })
         })
         .collect()
@@ -2,12 +2,9 @@
 //! GET https://kitsu.io/api/edge/anime?filter[text]=X
 
 use super::source_trait::MetadataSource;
-use crate::metadata::{MediaType, MetadataCandidate};
-use reqwest::blocking::Client;
+use crate::metadata::{http, MediaType, MetadataCandidate};
 use serde::Deserialize;
-use std::time::Duration;
 
-const TIMEOUT: Duration = Duration::from_secs(8);
 const BASE_URL: &str = "https://kitsu.io/api/edge/anime";
 
 #[derive(Deserialize)]
@@ -52,25 +49,13 @@ impl MetadataSource for KitsuSource {
     fn search(&self, query: &str) -> Vec<MetadataCandidate> {
         log::debug!("[metadata:kitsu] Buscando \"{}\"", query);
 
-        let client = match Client::builder()
-            .timeout(TIMEOUT)
-            .user_agent("Readito/1.0")
-            .build()
-        {
-            Ok(c) => c,
-            Err(e) => {
-                log::warn!("[metadata:kitsu] Falha ao criar client: {}", e);
-                return vec![];
-            }
-        };
-
         let url = format!("{}?filter[text]={}", BASE_URL, urlencoding::encode(query));
 
-        let resp = match client
-            .get(&url)
-            .header("Accept", "application/vnd.api+json")
-            .send()
-        {
+        let resp = match http::send(self.name(), |client| {
+            client
+                .get(&url)
+                .header("Accept", "application/vnd.api+json")
+        }) {
             Ok(r) => r,
             Err(e) => {
                 log::warn!("[metadata:kitsu] Falha na requisição: {}", e);
@@ -131,6 +116,11 @@ impl MetadataSource for KitsuSource {
                     cover_url,
                     year,
                     language: None,
+                    publisher: None,
+                    isbns: vec![],
+                    page_count: None,
+                    genres: vec![],
+                    community_score: None,
                 })
             })
             .collect()
@@ -2,12 +2,9 @@
 //! https://www.loc.gov/books/?q=X&fo=json
 
 use super::source_trait::MetadataSource;
-use crate::metadata::{MediaType, MetadataCandidate};
-use reqwest::blocking::Client;
+use crate::metadata::{http, MediaType, MetadataCandidate};
 use serde::Deserialize;
-use std::time::Duration;
 
-const TIMEOUT: Duration = Duration::from_secs(8);
 const BASE_URL: &str = "https://www.loc.gov/books/";
 
 #[derive(Deserialize)]
@@ -33,16 +30,8 @@ impl MetadataSource for LocSource {
     fn search(&self, query: &str) -> Vec<MetadataCandidate> {
         log::debug!("[metadata:loc] Buscando \"{}\"", query);
 
-        let client = match Client::builder().timeout(TIMEOUT).build() {
-            Ok(c) => c,
-            Err(e) => {
-                log::warn!("[metadata:loc] Falha ao criar client: {}", e);
-                return vec![];
-            }
-        };
-
         let url = format!("{}?q={}&fo=json&c=10", BASE_URL, urlencoding::encode(query));
-        let resp = match client.get(&url).send() {
+        let resp = match http::send(self.name(), |client| client.get(&url)) {
             Ok(r) => r,
             Err(e) => {
                 log::warn!("[metadata:loc] Falha na requisição: {}", e);
@@ -88,6 +77,11 @@ impl MetadataSource for LocSource {
                     cover_url: None,
                     year,
                     language: None,
+                    publisher: None,
+                    isbns: vec![],
+                    page_count: None,
+                    genres: vec![],
+                    community_score: None,
                 })
             })
             .collect()
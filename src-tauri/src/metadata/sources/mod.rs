@@ -14,15 +14,38 @@ pub use loc::LocSource;
 pub use open_library::OpenLibrarySource;
 pub use source_trait::MetadataSource;
 
+use serde::{Deserialize, Serialize};
+
 use crate::metadata::MetadataCandidate;
 
-/// Todas as fontes disponíveis para busca em paralelo.
-/// Apenas livros (Open Library, LOC) e manga (AniList, Jikan). Anime excluído.
-pub fn all_sources() -> Vec<Box<dyn MetadataSource + Send>> {
-    vec![
-        Box::new(OpenLibrarySource),
-        Box::new(LocSource),
-        Box::new(AniListSource),
-        Box::new(JikanSource),
-    ]
+/// Fontes implementadas, na ordem de prioridade padrão.
+pub const SOURCE_NAMES: [&str; 5] = ["open_library", "loc", "anilist", "jikan", "kitsu"];
+
+/// Preferência do usuário para uma fonte. `weight` multiplica o score dos
+/// candidatos dela só na ordenação (1.0 = neutro); não muda o threshold.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SourceSettings {
+    pub name: String,
+    pub enabled: bool,
+    pub weight: f32,
+}
+
+fn source_by_name(name: &str) -> Option<Box<dyn MetadataSource + Send>> {
+    match name {
+        "open_library" => Some(Box::new(OpenLibrarySource)),
+        "loc" => Some(Box::new(LocSource)),
+        "anilist" => Some(Box::new(AniListSource)),
+        "jikan" => Some(Box::new(JikanSource)),
+        "kitsu" => Some(Box::new(KitsuSource)),
+        _ => None,
+    }
+}
+
+/// Fontes habilitadas para busca em paralelo, na ordem da configuração.
+pub fn all_sources(settings: &[SourceSettings]) -> Vec<Box<dyn MetadataSource + Send>> {
+    settings
+        .iter()
+        .filter(|s| s.enabled)
+        .filter_map(|s| source_by_name(&s.name))
+        .collect()
 }
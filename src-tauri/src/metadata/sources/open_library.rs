@@ -2,13 +2,15 @@
 //! https://openlibrary.org/search.json?title=X ou q=X
 
 use super::source_trait::MetadataSource;
-use crate::metadata::{MediaType, MetadataCandidate};
-use reqwest::blocking::Client;
+use crate::metadata::{http, MediaType, MetadataCandidate};
 use serde::Deserialize;
-use std::time::Duration;
 
-const TIMEOUT: Duration = Duration::from_secs(8);
 const BASE_URL: &str = "https://openlibrary.org/search.json";
+/// Os assuntos do Open Library são muitos e cada vez mais específicos; só os
+/// primeiros viram tags.
+const MAX_SUBJECTS: usize = 5;
+/// Uma obra pode listar dezenas de ISBNs (todas as edições).
+const MAX_ISBNS: usize = 10;
 
 #[derive(Deserialize)]
 struct OpenLibraryResponse {
@@ -22,6 +24,10 @@ struct OpenLibraryDoc {
     first_publish_year: Option<i32>,
     cover_i: Option<i64>,
     key: Option<String>,
+    subject: Option<Vec<String>>,
+    publisher: Option<Vec<String>>,
+    isbn: Option<Vec<String>>,
+    number_of_pages_median: Option<i32>,
 }
 
 pub struct OpenLibrarySource;
@@ -32,18 +38,10 @@ impl MetadataSource for OpenLibrarySource {
     }
 
     fn search(&self, query: &str) -> Vec<MetadataCandidate> {
-        let client = match Client::builder().timeout(TIMEOUT).build() {
-            Ok(c) => c,
-            Err(e) => {
-                log::warn!("[metadata:open_library] Falha ao criar client: {}", e);
-                return vec![];
-            }
-        };
-
         let url = format!("{}?q={}", BASE_URL, urlencoding::encode(query));
         log::debug!("[metadata:open_library] GET {}", url);
 
-        let resp = match client.get(&url).send() {
+        let resp = match http::send(self.name(), |client| client.get(&url)) {
             Ok(r) => r,
             Err(e) => {
                 log::warn!("[metadata:open_library] Falha na requisição: {}", e);
@@ -102,6 +100,21 @@ impl MetadataSource for OpenLibrarySource {
                     cover_url,
                     year: d.first_publish_year,
                     language: None,
+                    publisher: d.publisher.and_then(|p| p.into_iter().next()),
+                    isbns: d
+                        .isbn
+                        .unwrap_or_default()
+                        .into_iter()
+                        .take(MAX_ISBNS)
+                        .collect(),
+                    page_count: d.number_of_pages_median,
+                    genres: d
+                        .subject
+                        .unwrap_or_default()
+                        .into_iter()
+                        .take(MAX_SUBJECTS)
+                        .collect(),
+                    community_score: None,
                 })
             })
             .collect()
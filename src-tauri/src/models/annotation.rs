@@ -0,0 +1,72 @@
+//! Modelos de anotação (destaque + nota). EPUB usa intervalo CFI; PDF usa
+//! página e quads da seleção.
+
+use serde::{Deserialize, Serialize};
+
+/// Quadrilátero da seleção em coordenadas da página PDF:
+/// `[x1, y1, x2, y2, x3, y3, x4, y4]` (um por linha selecionada).
+pub type Quad = [f64; 8];
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Annotation {
+    pub id: String,
+    pub book_id: String,
+    pub volume_id: String,
+    pub chapter_id: Option<String>,
+    /// Hash do livro na criação; usado para religar após um rescan.
+    pub book_hash: Option<String>,
+    pub cfi_range: Option<String>,
+    pub page_index: Option<i32>,
+    #[serde(default)]
+    pub quads: Vec<Quad>,
+    pub text: String,
+    pub color: String,
+    pub note: Option<String>,
+    pub created_at: i64,
+    pub updated_at: i64,
+}
+
+/// Anotação com o contexto usado em listagens da biblioteca.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AnnotationEntry {
+    pub annotation: Annotation,
+    pub book_title: String,
+    pub book_author: Option<String>,
+    pub chapter_name: Option<String>,
+}
+
+/// Filtros de `query_annotations`. Sem `book_id`, busca na biblioteca toda.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AnnotationQuery {
+    pub book_id: Option<String>,
+    pub volume_id: Option<String>,
+    pub color: Option<String>,
+    /// Busca no texto destacado e na nota.
+    pub search: Option<String>,
+    pub offset: i64,
+    /// 0 = sem limite.
+    pub limit: i64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AnnotationExportFormat {
+    Markdown,
+    Json,
+    /// CSV no formato de importação do Readwise.
+    ReadwiseCsv,
+}
+
+/// Modelos do Markdown; campos ausentes usam os padrões de `export::annotations`.
+/// Variáveis em `{{nome}}`; `{{#nome}}...{{/nome}}` só aparece se `nome` não for vazio.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AnnotationTemplates {
+    /// Variáveis: title, author, count.
+    pub book: Option<String>,
+    /// Variáveis: chapter.
+    pub chapter: Option<String>,
+    /// Variáveis: text, quote, note, color, location, date.
+    pub annotation: Option<String>,
+}
@@ -0,0 +1,53 @@
+//! Backup da biblioteca em JSON (exportação/importação entre instalações).
+
+use serde::{Deserialize, Serialize};
+
+use super::{
+    Annotation, Book, BookReview, BookSettings, Bookmark, Chapter, CustomTheme, GlobalSettings,
+    ReadingProgress, Shelf, Volume,
+};
+
+/// Versão do formato. Backups de versão maior são recusados na importação.
+pub const BACKUP_VERSION: i64 = 1;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ShelfLink {
+    pub book_id: String,
+    pub shelf_id: String,
+    /// Ordem na estante; backups antigos não têm e seguem a ordem do arquivo.
+    #[serde(default)]
+    pub position: i64,
+}
+
+/// Coleções ausentes no arquivo ficam vazias (backups antigos continuam válidos).
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LibraryBackup {
+    pub version: i64,
+    pub exported_at: i64,
+    pub books: Vec<Book>,
+    pub volumes: Vec<Volume>,
+    pub chapters: Vec<Chapter>,
+    pub shelves: Vec<Shelf>,
+    pub book_shelves: Vec<ShelfLink>,
+    pub progress: Vec<ReadingProgress>,
+    pub book_settings: Vec<BookSettings>,
+    pub reviews: Vec<BookReview>,
+    pub bookmarks: Vec<Bookmark>,
+    pub annotations: Vec<Annotation>,
+    pub custom_themes: Vec<CustomTheme>,
+    pub global_settings: Option<GlobalSettings>,
+}
+
+/// Registros criados ou atualizados por uma importação.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct BackupImportReport {
+    pub books: usize,
+    pub shelves: usize,
+    pub progress: usize,
+    pub settings: usize,
+    pub reviews: usize,
+    pub bookmarks: usize,
+    pub annotations: usize,
+    pub skipped: usize,
+}
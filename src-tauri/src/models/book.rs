@@ -1,7 +1,12 @@
 //! Modelos de livro, volume e capítulo (espelham tabelas books, volumes, chapters).
 
+use std::collections::BTreeMap;
+
 use serde::{Deserialize, Serialize};
 
+use super::ReadStatus;
+use crate::metadata::MediaType;
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Book {
     pub id: String,
@@ -17,6 +22,128 @@ pub struct Book {
     pub description: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub cover_path: Option<String>,
+    /// Metadados estendidos, no mesmo nível do JSON do livro.
+    #[serde(flatten)]
+    pub metadata: BookMetadata,
+}
+
+/// Metadados além de título/autor/descrição/capa. Listas vazias = desconhecido.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct BookMetadata {
+    pub publisher: Option<String>,
+    pub year: Option<i32>,
+    /// Código de idioma (eng, por, jpn...).
+    pub language: Option<String>,
+    pub isbns: Vec<String>,
+    pub alt_titles: Vec<String>,
+    pub series: Option<String>,
+    /// Número na série (pode ser fracionário, ex.: 7.5).
+    pub series_number: Option<f64>,
+    pub page_count: Option<i32>,
+    pub media_type: Option<MediaType>,
+    pub genres: Vec<String>,
+    /// Id do item em cada fonte (open_library, anilist...).
+    pub external_ids: BTreeMap<String, String>,
+}
+
+/// Alterações para `update_book_partial`: None = manter o valor atual.
+/// `external_ids` é mesclado (uma entrada por fonte), não substituído.
+#[derive(Debug, Clone, Default)]
+pub struct BookMetadataPatch {
+    pub publisher: Option<Option<String>>,
+    pub year: Option<Option<i32>>,
+    pub language: Option<Option<String>>,
+    pub isbns: Option<Vec<String>>,
+    pub alt_titles: Option<Vec<String>>,
+    pub series: Option<Option<String>>,
+    pub series_number: Option<Option<f64>>,
+    pub page_count: Option<Option<i32>>,
+    pub media_type: Option<Option<MediaType>>,
+    pub genres: Option<Vec<String>>,
+    pub external_ids: Vec<(String, String)>,
+}
+
+impl BookMetadataPatch {
+    pub fn apply(&self, metadata: &mut BookMetadata) {
+        fn set<T: Clone>(target: &mut T, value: &Option<T>) {
+            if let Some(v) = value {
+                *target = v.clone();
+            }
+        }
+        set(&mut metadata.publisher, &self.publisher);
+        set(&mut metadata.year, &self.year);
+        set(&mut metadata.language, &self.language);
+        set(&mut metadata.isbns, &self.isbns);
+        set(&mut metadata.alt_titles, &self.alt_titles);
+        set(&mut metadata.series, &self.series);
+        set(&mut metadata.series_number, &self.series_number);
+        set(&mut metadata.page_count, &self.page_count);
+        set(&mut metadata.media_type, &self.media_type);
+        set(&mut metadata.genres, &self.genres);
+        for (source, id) in &self.external_ids {
+            metadata.external_ids.insert(source.clone(), id.clone());
+        }
+    }
+}
+
+/// Campos editados manualmente (tabela book_metadata_flags): a busca
+/// automática de metadados não os sobrescreve.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct BookMetadataFlags {
+    pub title: bool,
+    pub author: bool,
+    pub description: bool,
+    pub cover: bool,
+    pub publisher: bool,
+    pub year: bool,
+    pub language: bool,
+    pub isbns: bool,
+    pub alt_titles: bool,
+    /// Série e número.
+    pub series: bool,
+    pub page_count: bool,
+    pub media_type: bool,
+    pub genres: bool,
+    pub external_ids: bool,
+}
+
+impl BookMetadataFlags {
+    /// Marca os campos estendidos que diferem entre `before` e `after`.
+    pub fn mark_changed(&mut self, before: &BookMetadata, after: &BookMetadata) {
+        self.publisher |= before.publisher != after.publisher;
+        self.year |= before.year != after.year;
+        self.language |= before.language != after.language;
+        self.isbns |= before.isbns != after.isbns;
+        self.alt_titles |= before.alt_titles != after.alt_titles;
+        self.series |= before.series != after.series || before.series_number != after.series_number;
+        self.page_count |= before.page_count != after.page_count;
+        self.media_type |= before.media_type != after.media_type;
+        self.genres |= before.genres != after.genres;
+        self.external_ids |= before.external_ids != after.external_ids;
+    }
+
+    /// Liga a flag do campo (nome no JSON do livro, como no histórico).
+    pub fn mark_field(&mut self, field: &str) {
+        match field {
+            "title" => self.title = true,
+            "author" => self.author = true,
+            "description" => self.description = true,
+            "cover_path" => self.cover = true,
+            "publisher" => self.publisher = true,
+            "year" => self.year = true,
+            "language" => self.language = true,
+            "isbns" => self.isbns = true,
+            "alt_titles" => self.alt_titles = true,
+            "series" | "series_number" => self.series = true,
+            "page_count" => self.page_count = true,
+            "media_type" => self.media_type = true,
+            "genres" => self.genres = true,
+            "external_ids" => self.external_ids = true,
+            _ => {}
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -34,3 +161,115 @@ pub struct Chapter {
     pub path: String,
     pub position: i32,
 }
+
+/// Linha resumida da biblioteca (sem árvore de volumes/capítulos).
+/// `first_chapter_path` permite ao frontend derivar a capa de pastas de imagens.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BookSummary {
+    pub book: Book,
+    pub volume_count: i64,
+    pub chapter_count: i64,
+    pub read_pages: i64,
+    pub progress_percent: f64,
+    pub first_chapter_path: Option<String>,
+    pub last_read_at: Option<i64>,
+    pub read_status: ReadStatus,
+    /// Nota pessoal (0.5–5).
+    pub rating: Option<f64>,
+}
+
+/// Campo de ordenação aceito por `query_books` (whitelist para o ORDER BY).
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BookSortField {
+    #[default]
+    AddedAt,
+    Title,
+    Author,
+    LastRead,
+    Progress,
+    Rating,
+}
+
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SortDirection {
+    Asc,
+    #[default]
+    Desc,
+}
+
+/// Status derivado do progresso (página atual x total de capítulos).
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ProgressFilter {
+    NotStarted,
+    Reading,
+    Completed,
+}
+
+/// Formato do livro, pela extensão do primeiro capítulo.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BookFormat {
+    Images,
+    Pdf,
+    Epub,
+    Archive,
+}
+
+impl BookFormat {
+    pub fn extensions(self) -> &'static [&'static str] {
+        match self {
+            BookFormat::Images => &["jpg", "jpeg", "png", "webp", "gif", "bmp", "avif"],
+            BookFormat::Pdf => &["pdf"],
+            BookFormat::Epub => &["epub"],
+            BookFormat::Archive => &["cbz", "zip", "rar"],
+        }
+    }
+}
+
+/// Volume em andamento para "Continuar lendo" (sem a lista de capítulos).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ContinueReadingItem {
+    pub book: Book,
+    pub volume_id: String,
+    pub volume_name: String,
+    pub chapter_count: i64,
+    pub page_index: i64,
+    pub updated_at: i64,
+    /// Primeiro capítulo do livro, para derivar a capa como em `BookSummary`.
+    pub first_chapter_path: Option<String>,
+}
+
+/// Filtros, ordenação e paginação da listagem da biblioteca.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct BookQuery {
+    /// Texto buscado em título e autor (LIKE, case-insensitive).
+    pub search: Option<String>,
+    /// "folder" | "file"
+    pub book_type: Option<String>,
+    pub format: Option<BookFormat>,
+    pub shelf_id: Option<String>,
+    /// O livro precisa ter todas as tags.
+    pub tag_ids: Vec<String>,
+    pub progress: Option<ProgressFilter>,
+    /// Status explícito do livro.
+    pub read_status: Option<ReadStatus>,
+    /// Só livros com nota >= este valor.
+    pub min_rating: Option<f64>,
+    pub sort_by: BookSortField,
+    pub sort_dir: SortDirection,
+    pub offset: i64,
+    /// 0 = sem limite.
+    pub limit: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BookPage {
+    pub items: Vec<BookSummary>,
+    pub total: i64,
+    pub offset: i64,
+    pub limit: i64,
+}
@@ -0,0 +1,19 @@
+//! Modelo de marcador (tabela bookmarks). Vários por volume.
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Bookmark {
+    pub id: String,
+    pub book_id: String,
+    pub volume_id: String,
+    pub chapter_id: Option<String>,
+    /// Página (leitores paginados). EPUB em rolagem usa `cfi`.
+    pub page_index: Option<i32>,
+    pub cfi: Option<String>,
+    pub label: String,
+    /// Miniatura da página (data URL gerada pelo frontend).
+    pub thumbnail: Option<String>,
+    pub created_at: i64,
+    pub updated_at: i64,
+}
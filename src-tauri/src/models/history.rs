@@ -0,0 +1,47 @@
+//! Modelos do histórico de leitura (tabela reading_history): linha do tempo
+//! append-only das posições, agrupando avanços próximos no mesmo volume.
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HistoryEntry {
+    pub id: String,
+    pub book_id: String,
+    pub volume_id: String,
+    pub chapter_id: Option<String>,
+    /// Última página registrada na entrada.
+    pub page_index: i32,
+    pub started_at: i64,
+    pub updated_at: i64,
+}
+
+/// Entrada com os nomes para exibição na linha do tempo.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HistoryItem {
+    pub entry: HistoryEntry,
+    pub book_title: String,
+    pub cover_path: Option<String>,
+    pub volume_name: Option<String>,
+    pub chapter_name: Option<String>,
+}
+
+/// Filtros de `get_history`. `from`/`to` em segundos; entradas que tocam o
+/// intervalo entram no resultado.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct HistoryQuery {
+    pub book_id: Option<String>,
+    pub from: Option<i64>,
+    pub to: Option<i64>,
+    pub offset: i64,
+    /// 0 = sem limite.
+    pub limit: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HistoryPage {
+    pub items: Vec<HistoryItem>,
+    pub total: i64,
+    pub offset: i64,
+    pub limit: i64,
+}
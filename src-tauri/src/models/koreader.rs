@@ -0,0 +1,72 @@
+//! Modelos da sincronização de progresso com o KOReader (protocolo kosync).
+
+use serde::{Deserialize, Serialize};
+
+/// Configuração do KOReader (persistida em sync_meta).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct KoreaderConfig {
+    /// URL do servidor kosync. Vazio com o servidor embutido ligado = usa o embutido.
+    pub server_url: Option<String>,
+    pub username: Option<String>,
+    /// MD5 da senha, enviado em `x-auth-key`. Não é devolvido ao frontend.
+    #[serde(skip_serializing, default)]
+    pub userkey: Option<String>,
+    /// Como identificar o documento: "binary" (MD5 parcial do arquivo, padrão do KOReader)
+    /// ou "filename" (MD5 do nome do arquivo).
+    pub match_method: String,
+    /// Intervalo da sincronização automática; 0 = apenas manual.
+    pub interval_secs: i64,
+    pub last_sync_at: Option<i64>,
+    /// Servidor kosync embutido (para dispositivos na mesma rede).
+    pub server_enabled: bool,
+    pub server_port: u16,
+    /// Servidor embutido visível na rede; desligado, escuta só em 127.0.0.1.
+    pub server_lan: bool,
+    /// Permite criar contas no servidor embutido a partir de outros dispositivos.
+    pub server_registration: bool,
+}
+
+impl Default for KoreaderConfig {
+    fn default() -> Self {
+        Self {
+            server_url: None,
+            username: None,
+            userkey: None,
+            match_method: "binary".to_string(),
+            interval_secs: 0,
+            last_sync_at: None,
+            server_enabled: false,
+            server_port: 7200,
+            server_lan: false,
+            server_registration: false,
+        }
+    }
+}
+
+/// Posição no formato kosync. `progress` é o número da página (PDF/CBZ)
+/// ou um xpointer (EPUB); `timestamp` é preenchido pelo servidor.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct KoreaderProgress {
+    #[serde(default)]
+    pub document: String,
+    pub progress: String,
+    #[serde(default)]
+    pub percentage: f64,
+    #[serde(default)]
+    pub device: String,
+    #[serde(default)]
+    pub device_id: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timestamp: Option<i64>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct KoreaderSyncReport {
+    /// Posições enviadas ao servidor.
+    pub pushed: usize,
+    /// Posições do KOReader aplicadas ao progresso local.
+    pub pulled: usize,
+    /// Volumes sem arquivo local ou sem total de páginas para converter o percentual.
+    pub skipped: usize,
+    pub finished_at: i64,
+}
@@ -0,0 +1,97 @@
+//! Configuração da busca de metadados (colunas `metadata_*` de global_settings).
+
+use serde::{Deserialize, Serialize};
+
+use crate::metadata::{SourceSettings, DEFAULT_CACHE_TTL_SECS, SOURCE_NAMES};
+
+/// Conteúdo predominante da biblioteca; define as fontes padrão.
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LibraryKind {
+    #[default]
+    Mixed,
+    Books,
+    Manga,
+}
+
+impl LibraryKind {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            LibraryKind::Mixed => "mixed",
+            LibraryKind::Books => "books",
+            LibraryKind::Manga => "manga",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "mixed" => Some(LibraryKind::Mixed),
+            "books" => Some(LibraryKind::Books),
+            "manga" => Some(LibraryKind::Manga),
+            _ => None,
+        }
+    }
+
+    /// Fontes padrão, em ordem de prioridade.
+    /// Misto: Open Library, LoC, AniList e Jikan (Kitsu desligado).
+    /// Livros: só Open Library e LoC. Mangá: AniList, Jikan e Kitsu
+    /// primeiro, Open Library para volumes com ISBN, LoC desligado.
+    pub fn default_sources(self) -> Vec<SourceSettings> {
+        let (order, enabled): (&[&str], &[&str]) = match self {
+            LibraryKind::Mixed => (&SOURCE_NAMES, &["open_library", "loc", "anilist", "jikan"]),
+            LibraryKind::Books => (&SOURCE_NAMES, &["open_library", "loc"]),
+            LibraryKind::Manga => (
+                &["anilist", "jikan", "kitsu", "open_library", "loc"],
+                &["anilist", "jikan", "kitsu", "open_library"],
+            ),
+        };
+        order
+            .iter()
+            .map(|name| SourceSettings {
+                name: name.to_string(),
+                enabled: enabled.contains(name),
+                weight: 1.0,
+            })
+            .collect()
+    }
+
+    /// Completa a lista salva com as fontes que faltam (na posição e estado
+    /// padrão do tipo) e descarta nomes desconhecidos ou repetidos.
+    pub fn merge_sources(self, saved: Vec<SourceSettings>) -> Vec<SourceSettings> {
+        let mut sources: Vec<SourceSettings> = Vec::new();
+        for source in saved {
+            if SOURCE_NAMES.contains(&source.name.as_str())
+                && !sources.iter().any(|s| s.name == source.name)
+            {
+                sources.push(source);
+            }
+        }
+        for source in self.default_sources() {
+            if !sources.iter().any(|s| s.name == source.name) {
+                sources.push(source);
+            }
+        }
+        sources
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MetadataConfig {
+    /// Validade das respostas guardadas de cada fonte; 0 = sem cache.
+    pub cache_ttl_secs: i64,
+    pub library_kind: LibraryKind,
+    /// Todas as fontes, na ordem de prioridade. Ao salvar, uma lista vazia
+    /// volta ao padrão de `library_kind`.
+    pub sources: Vec<SourceSettings>,
+}
+
+impl Default for MetadataConfig {
+    fn default() -> Self {
+        Self {
+            cache_ttl_secs: DEFAULT_CACHE_TTL_SECS,
+            library_kind: LibraryKind::default(),
+            sources: LibraryKind::default().default_sources(),
+        }
+    }
+}
@@ -0,0 +1,19 @@
+//! Histórico de alterações de metadados (tabela metadata_changes).
+
+use serde::{Deserialize, Serialize};
+
+/// Uma alteração de um campo do livro. `origin`: "manual", "auto:<fonte>",
+/// "candidate:<fonte>" ou "revert".
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MetadataChange {
+    pub id: String,
+    pub book_id: String,
+    /// Alterações gravadas juntas (ex.: um candidato aplicado).
+    pub batch_id: String,
+    /// Nome do campo no JSON do livro (title, author, cover_path, year...).
+    pub field: String,
+    pub old_value: serde_json::Value,
+    pub new_value: serde_json::Value,
+    pub origin: String,
+    pub changed_at: i64,
+}
@@ -0,0 +1,101 @@
+//! Enriquecimento de metadados em lote (tabelas metadata_jobs e metadata_job_items).
+
+use serde::{Deserialize, Serialize};
+
+use crate::metadata::RankedCandidate;
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum JobStatus {
+    Running,
+    Paused,
+    Completed,
+    Cancelled,
+}
+
+impl JobStatus {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            JobStatus::Running => "running",
+            JobStatus::Paused => "paused",
+            JobStatus::Completed => "completed",
+            JobStatus::Cancelled => "cancelled",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "running" => Some(JobStatus::Running),
+            "paused" => Some(JobStatus::Paused),
+            "completed" => Some(JobStatus::Completed),
+            "cancelled" => Some(JobStatus::Cancelled),
+            _ => None,
+        }
+    }
+}
+
+/// Situação de cada livro do lote. `Review`: candidatos ambíguos aguardando
+/// escolha; `Dismissed`: o usuário descartou a revisão.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum JobItemStatus {
+    Pending,
+    Applied,
+    Review,
+    NoMatch,
+    Failed,
+    Dismissed,
+}
+
+impl JobItemStatus {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            JobItemStatus::Pending => "pending",
+            JobItemStatus::Applied => "applied",
+            JobItemStatus::Review => "review",
+            JobItemStatus::NoMatch => "no_match",
+            JobItemStatus::Failed => "failed",
+            JobItemStatus::Dismissed => "dismissed",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "pending" => Some(JobItemStatus::Pending),
+            "applied" => Some(JobItemStatus::Applied),
+            "review" => Some(JobItemStatus::Review),
+            "no_match" => Some(JobItemStatus::NoMatch),
+            "failed" => Some(JobItemStatus::Failed),
+            "dismissed" => Some(JobItemStatus::Dismissed),
+            _ => None,
+        }
+    }
+}
+
+/// Lote com a contagem de livros por situação (payload de `metadata-job-progress`).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MetadataJob {
+    pub id: String,
+    pub status: JobStatus,
+    pub total: i64,
+    pub pending: i64,
+    pub applied: i64,
+    pub review: i64,
+    pub no_match: i64,
+    pub failed: i64,
+    pub created_at: i64,
+    pub updated_at: i64,
+}
+
+/// Livro do lote; `candidates` só é preenchido em revisão.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MetadataJobItem {
+    pub job_id: String,
+    pub book_id: String,
+    pub title: String,
+    pub status: JobItemStatus,
+    pub score: Option<f32>,
+    pub candidates: Vec<RankedCandidate>,
+    pub error: Option<String>,
+    pub updated_at: i64,
+}
@@ -1,9 +1,46 @@
+mod annotation;
+mod backup;
 mod book;
+mod bookmark;
+mod history;
+mod koreader;
+mod metadata_config;
+mod metadata_history;
+mod metadata_job;
 mod progress;
+mod queue;
+mod read_status;
+mod review;
+mod session;
 mod settings;
 mod shelf;
+mod sync;
+mod tag;
 
-pub use book::{Book, Chapter, Volume};
-pub use progress::ReadingProgress;
+pub use annotation::{
+    Annotation, AnnotationEntry, AnnotationExportFormat, AnnotationQuery, AnnotationTemplates, Quad,
+};
+pub use backup::{BackupImportReport, LibraryBackup, ShelfLink, BACKUP_VERSION};
+pub use book::{
+    Book, BookFormat, BookMetadata, BookMetadataFlags, BookMetadataPatch, BookPage, BookQuery,
+    BookSortField, BookSummary, Chapter, ContinueReadingItem, ProgressFilter, SortDirection,
+    Volume,
+};
+pub use bookmark::Bookmark;
+pub use history::{HistoryEntry, HistoryItem, HistoryPage, HistoryQuery};
+pub use koreader::{KoreaderConfig, KoreaderProgress, KoreaderSyncReport};
+pub use metadata_config::{LibraryKind, MetadataConfig};
+pub use metadata_history::MetadataChange;
+pub use metadata_job::{JobItemStatus, JobStatus, MetadataJob, MetadataJobItem};
+pub use progress::{ChapterProgress, ReadingProgress, SeriesCompletion, VolumeCompletion};
+pub use queue::{QueueEntry, QueueItem};
+pub use read_status::{BookReadState, ReadState, ReadStatus, VolumeReadState};
+pub use review::BookReview;
+pub use session::{
+    BookReadingTime, DailyReading, MonthlyFinished, ReadingSession, ReadingStats,
+    SeriesReadingTime, VolumeEta, WeeklyReading,
+};
 pub use settings::{BookSettings, CustomTheme, GlobalSettings};
-pub use shelf::Shelf;
+pub use shelf::{NumberOp, RuleMatch, Shelf, ShelfKind, SmartRule, SmartShelfRules, TextOp};
+pub use sync::{ChangeEntry, ChangeFeed, SyncConfig, Tombstone};
+pub use tag::{BookTag, Tag};
@@ -10,4 +10,41 @@ pub struct ReadingProgress {
     pub page_index: i32,
     pub scroll_offset: f64,
     pub updated_at: i64,
+    /// Total de páginas do volume quando foi aberto (EPUB/PDF dependem do leitor).
+    /// Usado para converter página <-> percentual (KOReader, estatísticas).
+    #[serde(default)]
+    pub total_pages: Option<i32>,
+}
+
+/// Estado de um capítulo (tabela chapter_progress). Sem linha = não lido.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChapterProgress {
+    pub chapter_id: String,
+    pub book_id: String,
+    pub volume_id: String,
+    pub read: bool,
+    /// Última página vista no capítulo (mesma numeração de `page_index`).
+    pub last_page: Option<i32>,
+    pub read_at: Option<i64>,
+    pub updated_at: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VolumeCompletion {
+    pub volume_id: String,
+    pub chapters_read: i64,
+    pub chapters_total: i64,
+    pub completed: bool,
+}
+
+/// Conclusão da série calculada pelos capítulos lidos.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SeriesCompletion {
+    pub book_id: String,
+    pub chapters_read: i64,
+    pub chapters_total: i64,
+    /// 0–100.
+    pub percent: f64,
+    pub completed: bool,
+    pub volumes: Vec<VolumeCompletion>,
 }
@@ -0,0 +1,24 @@
+//! Modelos da fila de leitura ("ler a seguir"): lista ordenada de livros ou
+//! volumes, separada das estantes.
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QueueEntry {
+    pub id: String,
+    pub book_id: String,
+    /// None = o livro/série inteiro.
+    pub volume_id: Option<String>,
+    /// 0 = início da fila.
+    pub position: i64,
+    pub added_at: i64,
+}
+
+/// Entrada com os dados para exibição.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QueueItem {
+    pub entry: QueueEntry,
+    pub book_title: String,
+    pub cover_path: Option<String>,
+    pub volume_name: Option<String>,
+}
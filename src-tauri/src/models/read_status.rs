@@ -0,0 +1,60 @@
+//! Status de leitura explícito de livros e volumes (colunas read_status,
+//! started_at, finished_at e reread_count).
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ReadStatus {
+    #[default]
+    Unread,
+    Reading,
+    Completed,
+    OnHold,
+    Dropped,
+}
+
+impl ReadStatus {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ReadStatus::Unread => "unread",
+            ReadStatus::Reading => "reading",
+            ReadStatus::Completed => "completed",
+            ReadStatus::OnHold => "on_hold",
+            ReadStatus::Dropped => "dropped",
+        }
+    }
+
+    /// Valores desconhecidos (ex.: gravados por uma versão mais nova) viram Unread.
+    pub fn parse(value: &str) -> Self {
+        match value {
+            "reading" => ReadStatus::Reading,
+            "completed" => ReadStatus::Completed,
+            "on_hold" => ReadStatus::OnHold,
+            "dropped" => ReadStatus::Dropped,
+            _ => ReadStatus::Unread,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ReadState {
+    pub status: ReadStatus,
+    pub started_at: Option<i64>,
+    pub finished_at: Option<i64>,
+    /// Quantas vezes foi retomado depois de concluído.
+    pub reread_count: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VolumeReadState {
+    pub volume_id: String,
+    pub state: ReadState,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BookReadState {
+    pub book_id: String,
+    pub state: ReadState,
+    pub volumes: Vec<VolumeReadState>,
+}
@@ -0,0 +1,12 @@
+//! Avaliação pessoal e resenha privada de um livro (tabela book_reviews).
+
+use serde::{Deserialize, Serialize};
+
+/// Nota de 0.5 a 5, em meias estrelas.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BookReview {
+    pub book_id: String,
+    pub rating: Option<f64>,
+    pub review: Option<String>,
+    pub updated_at: i64,
+}
@@ -0,0 +1,93 @@
+//! Modelos de sessão de leitura e agregados derivados (tabela reading_sessions).
+
+use serde::{Deserialize, Serialize};
+
+/// Sessão contínua de leitura de um volume. `ended_at` é o último heartbeat.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReadingSession {
+    pub id: String,
+    pub book_id: String,
+    pub volume_id: String,
+    pub started_at: i64,
+    pub ended_at: i64,
+    pub start_page: i32,
+    pub end_page: i32,
+    pub pages_turned: i32,
+    pub closed: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BookReadingTime {
+    pub book_id: String,
+    pub title: String,
+    pub seconds: i64,
+    pub sessions: i64,
+    pub pages: i64,
+    pub last_read_at: i64,
+}
+
+/// Totais de um dia (data local, `YYYY-MM-DD`).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DailyReading {
+    pub day: String,
+    pub seconds: i64,
+    pub pages: i64,
+}
+
+/// Estimativa para terminar um volume no ritmo de leitura registrado.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VolumeEta {
+    pub volume_id: String,
+    pub remaining_pages: i64,
+    /// Ritmo do livro; sem sessões suficientes, o ritmo geral.
+    pub pages_per_hour: Option<f64>,
+    pub eta_secs: Option<i64>,
+}
+
+/// Totais de uma semana (segunda-feira local, `YYYY-MM-DD`).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WeeklyReading {
+    pub week_start: String,
+    pub seconds: i64,
+    pub pages: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MonthlyFinished {
+    /// `YYYY-MM`
+    pub month: String,
+    pub books: i64,
+}
+
+/// Tempo de leitura somado por série (todos os volumes e livros de
+/// `books.series`); livros sem série contam sozinhos, pelo título.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SeriesReadingTime {
+    pub series: String,
+    pub books: i64,
+    pub seconds: i64,
+    pub sessions: i64,
+    pub pages: i64,
+    pub last_read_at: i64,
+}
+
+/// Painel de estatísticas de um intervalo (`from`/`to` em segundos; None = tudo).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReadingStats {
+    pub from: Option<i64>,
+    pub to: Option<i64>,
+    pub total_seconds: i64,
+    pub total_pages: i64,
+    pub sessions: i64,
+    pub days_read: i64,
+    pub daily: Vec<DailyReading>,
+    pub weekly: Vec<WeeklyReading>,
+    /// Dias seguidos com leitura até hoje (ou ontem), independente do intervalo.
+    pub current_streak: i64,
+    pub longest_streak: i64,
+    pub finished_per_month: Vec<MonthlyFinished>,
+    pub top_series: Vec<SeriesReadingTime>,
+    pub pages_per_hour: Option<f64>,
+    pub avg_session_secs: Option<f64>,
+    pub avg_daily_secs: Option<f64>,
+}
@@ -1,9 +1,134 @@
-//! Modelo de estante (shelf).
+//! Modelo de estante (shelf): manual (livros escolhidos) ou inteligente
+//! (regras avaliadas em SQL a cada consulta).
 
 use serde::{Deserialize, Serialize};
 
+use super::ReadStatus;
+use crate::metadata::MediaType;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ShelfKind {
+    #[default]
+    Manual,
+    Smart,
+}
+
+impl ShelfKind {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ShelfKind::Manual => "manual",
+            ShelfKind::Smart => "smart",
+        }
+    }
+
+    pub fn parse(value: &str) -> Self {
+        match value {
+            "smart" => ShelfKind::Smart,
+            _ => ShelfKind::Manual,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Shelf {
     pub id: String,
     pub name: String,
+    /// Ordem na barra lateral (0 = primeira).
+    #[serde(default)]
+    pub position: i64,
+    /// Nome do ícone no frontend (ex.: "bookmark", "heart").
+    #[serde(default)]
+    pub icon: Option<String>,
+    /// Cor em hex (#rgb ou #rrggbb).
+    #[serde(default)]
+    pub color: Option<String>,
+    #[serde(default)]
+    pub kind: ShelfKind,
+    /// Regras da estante inteligente (None em estantes manuais).
+    #[serde(default)]
+    pub rules: Option<SmartShelfRules>,
+    /// Calculado na listagem; ignorado na importação.
+    #[serde(default)]
+    pub book_count: i64,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RuleMatch {
+    /// Todas as regras (AND).
+    #[default]
+    All,
+    /// Qualquer regra (OR).
+    Any,
+}
+
+/// Regras de uma estante inteligente, guardadas em JSON na coluna `rules`.
+/// Sem regras, a estante mostra a biblioteca toda.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct SmartShelfRules {
+    #[serde(default, rename = "match")]
+    pub match_mode: RuleMatch,
+    #[serde(default)]
+    pub rules: Vec<SmartRule>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TextOp {
+    Contains,
+    NotContains,
+    Equals,
+    StartsWith,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum NumberOp {
+    Lt,
+    Lte,
+    Gt,
+    Gte,
+    Eq,
+}
+
+/// Uma condição, identificada por `field` no JSON, ex.:
+/// `{"field": "author", "op": "contains", "value": "Urasawa"}`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "field", rename_all = "snake_case")]
+pub enum SmartRule {
+    Title {
+        op: TextOp,
+        value: String,
+    },
+    Author {
+        op: TextOp,
+        value: String,
+    },
+    ReadStatus {
+        value: ReadStatus,
+    },
+    /// Livro com a tag.
+    Tag {
+        tag_id: String,
+    },
+    /// Adicionado nos últimos N dias.
+    AddedWithinDays {
+        days: i64,
+    },
+    /// "folder" | "file"
+    BookType {
+        value: String,
+    },
+    /// Tipo dos metadados; sem ele, pastas de imagens e CBZ/CBR contam como
+    /// mangá e o resto como livro.
+    MediaType {
+        value: MediaType,
+    },
+    /// Progresso em % (0–100).
+    Progress {
+        op: NumberOp,
+        value: f64,
+    },
+    MissingCover,
 }
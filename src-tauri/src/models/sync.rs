@@ -0,0 +1,46 @@
+//! Modelos do change log e tombstones (tabelas change_log, tombstones).
+
+use serde::{Deserialize, Serialize};
+
+/// Entrada do feed de mudanças. `row_key` usa `|` para chaves compostas.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChangeEntry {
+    pub seq: i64,
+    pub table_name: String,
+    pub row_key: String,
+    pub op: String, // "upsert" | "delete"
+    pub changed_at: i64,
+    pub device_id: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Tombstone {
+    pub table_name: String,
+    pub row_key: String,
+    pub deleted_at: i64,
+    pub device_id: String,
+}
+
+/// Página do feed: `cursor` é o último seq retornado (ou o recebido, se vazio).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChangeFeed {
+    pub changes: Vec<ChangeEntry>,
+    pub cursor: i64,
+    pub has_more: bool,
+}
+
+/// Configuração de sincronização (persistida em sync_meta).
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SyncConfig {
+    /// "local_folder" | "http" | "webdav" | None (desativado)
+    pub backend: Option<String>,
+    /// Pasta (local_folder) ou URL base (http, webdav).
+    pub target: Option<String>,
+    /// Usuário da autenticação básica (webdav).
+    pub username: Option<String>,
+    /// Token bearer (http) ou senha (webdav).
+    pub token: Option<String>,
+    /// Intervalo da sincronização automática; 0 = apenas manual.
+    pub interval_secs: i64,
+    pub last_sync_at: Option<i64>,
+}
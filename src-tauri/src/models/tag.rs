@@ -0,0 +1,21 @@
+//! Modelos de tags livres (gênero, humor, "emprestado para X"...).
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Tag {
+    pub id: String,
+    pub name: String,
+    /// Livros com a tag.
+    #[serde(default)]
+    pub book_count: i64,
+}
+
+/// Tag de um livro.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BookTag {
+    pub tag_id: String,
+    pub name: String,
+    /// true = veio dos gêneros da fonte de metadados; false = adicionada pelo usuário.
+    pub auto: bool,
+}
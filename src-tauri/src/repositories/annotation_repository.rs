@@ -0,0 +1,366 @@
+//! Repositório de anotações (destaques e notas).
+
+use rusqlite::params;
+use rusqlite::types::Value;
+
+use super::book_query_repository::like_pattern;
+use crate::models::{Annotation, AnnotationEntry, AnnotationQuery};
+
+fn now_secs() -> i64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs() as i64)
+        .unwrap_or(0)
+}
+
+const ANNOTATION_COLUMNS: &str =
+    "a.id, a.book_id, a.volume_id, a.chapter_id, a.book_hash, a.cfi_range, \
+     a.page_index, a.quads, a.text, a.color, a.note, a.created_at, a.updated_at";
+
+/// Ordem de leitura: volume, capítulo, página/CFI, criação.
+const READING_ORDER: &str =
+    "a.volume_id, COALESCE(c.position, 0), a.page_index, a.cfi_range, a.created_at";
+
+fn annotation_from_row(row: &rusqlite::Row) -> rusqlite::Result<Annotation> {
+    let quads: Option<String> = row.get(7)?;
+    Ok(Annotation {
+        id: row.get(0)?,
+        book_id: row.get(1)?,
+        volume_id: row.get(2)?,
+        chapter_id: row.get(3)?,
+        book_hash: row.get(4)?,
+        cfi_range: row.get(5)?,
+        page_index: row.get(6)?,
+        quads: quads
+            .and_then(|q| serde_json::from_str(&q).ok())
+            .unwrap_or_default(),
+        text: row.get(8)?,
+        color: row.get(9)?,
+        note: row.get(10)?,
+        created_at: row.get(11)?,
+        updated_at: row.get(12)?,
+    })
+}
+
+pub fn upsert_annotation(conn: &rusqlite::Connection, a: &Annotation) -> crate::Result<()> {
+    let quads = if a.quads.is_empty() {
+        None
+    } else {
+        Some(serde_json::to_string(&a.quads).map_err(|e| crate::Error::Io(e.to_string()))?)
+    };
+    conn.execute(
+        r#"
+        INSERT INTO annotations (id, book_id, volume_id, chapter_id, book_hash, cfi_range, page_index, quads, text, color, note, created_at, updated_at)
+        VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13)
+        ON CONFLICT(id) DO UPDATE SET
+            book_id = excluded.book_id,
+            volume_id = excluded.volume_id,
+            chapter_id = excluded.chapter_id,
+            book_hash = excluded.book_hash,
+            cfi_range = excluded.cfi_range,
+            page_index = excluded.page_index,
+            quads = excluded.quads,
+            text = excluded.text,
+            color = excluded.color,
+            note = excluded.note,
+            created_at = excluded.created_at,
+            updated_at = excluded.updated_at
+        "#,
+        params![
+            a.id,
+            a.book_id,
+            a.volume_id,
+            a.chapter_id,
+            a.book_hash,
+            a.cfi_range,
+            a.page_index,
+            quads,
+            a.text,
+            a.color,
+            a.note,
+            a.created_at,
+            a.updated_at,
+        ],
+    )?;
+    Ok(())
+}
+
+pub fn get_annotation(conn: &rusqlite::Connection, id: &str) -> crate::Result<Option<Annotation>> {
+    let mut stmt = conn.prepare(&format!(
+        "SELECT {} FROM annotations a WHERE a.id = ?1",
+        ANNOTATION_COLUMNS
+    ))?;
+    match stmt.query_row([id], annotation_from_row) {
+        Ok(a) => Ok(Some(a)),
+        Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// Atualiza cor e/ou nota. Some(None) em `note` remove a nota.
+/// Retorna false se a anotação não existir.
+pub fn update_annotation(
+    conn: &rusqlite::Connection,
+    id: &str,
+    color: Option<&str>,
+    note: Option<Option<&str>>,
+) -> crate::Result<bool> {
+    let n = conn.execute(
+        r#"
+        UPDATE annotations SET
+            color = COALESCE(?1, color),
+            note = CASE WHEN ?2 THEN ?3 ELSE note END,
+            updated_at = ?4
+        WHERE id = ?5
+        "#,
+        params![color, note.is_some(), note.flatten(), now_secs(), id],
+    )?;
+    Ok(n > 0)
+}
+
+pub fn delete_annotation(conn: &rusqlite::Connection, id: &str) -> crate::Result<()> {
+    conn.execute("DELETE FROM annotations WHERE id = ?1", [id])?;
+    Ok(())
+}
+
+/// Anotações do livro (ou só do volume), na ordem de leitura.
+pub fn list_annotations(
+    conn: &rusqlite::Connection,
+    book_id: &str,
+    volume_id: Option<&str>,
+) -> crate::Result<Vec<Annotation>> {
+    let mut stmt = conn.prepare(&format!(
+        r#"
+        SELECT {} FROM annotations a
+        LEFT JOIN chapters c ON c.id = a.chapter_id
+        WHERE a.book_id = ?1 AND (?2 IS NULL OR a.volume_id = ?2)
+        ORDER BY {}
+        "#,
+        ANNOTATION_COLUMNS, READING_ORDER
+    ))?;
+    let rows = stmt.query_map(params![book_id, volume_id], annotation_from_row)?;
+    let mut out = Vec::new();
+    for row in rows {
+        out.push(row?);
+    }
+    Ok(out)
+}
+
+pub fn list_all_annotations(conn: &rusqlite::Connection) -> crate::Result<Vec<Annotation>> {
+    let mut stmt = conn.prepare(&format!(
+        "SELECT {} FROM annotations a ORDER BY a.book_id, a.created_at",
+        ANNOTATION_COLUMNS
+    ))?;
+    let rows = stmt.query_map([], annotation_from_row)?;
+    let mut out = Vec::new();
+    for row in rows {
+        out.push(row?);
+    }
+    Ok(out)
+}
+
+/// Anotações com título do livro e nome do capítulo, por livro ou da biblioteca
+/// toda. Anotações de livros removidos (aguardando religação) não aparecem.
+pub fn query_annotations(
+    conn: &rusqlite::Connection,
+    query: &AnnotationQuery,
+) -> crate::Result<Vec<AnnotationEntry>> {
+    let mut clauses: Vec<&str> = Vec::new();
+    let mut values: Vec<Value> = Vec::new();
+    if let Some(book_id) = &query.book_id {
+        clauses.push("a.book_id = ?");
+        values.push(Value::Text(book_id.clone()));
+    }
+    if let Some(volume_id) = &query.volume_id {
+        clauses.push("a.volume_id = ?");
+        values.push(Value::Text(volume_id.clone()));
+    }
+    if let Some(color) = &query.color {
+        clauses.push("a.color = ?");
+        values.push(Value::Text(color.clone()));
+    }
+    if let Some(search) = query
+        .search
+        .as_deref()
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+    {
+        let pattern = like_pattern(search);
+        clauses.push(r"(a.text LIKE ? ESCAPE '\' OR a.note LIKE ? ESCAPE '\')");
+        values.push(Value::Text(pattern.clone()));
+        values.push(Value::Text(pattern));
+    }
+    let where_sql = if clauses.is_empty() {
+        String::new()
+    } else {
+        format!("WHERE {}", clauses.join(" AND "))
+    };
+    // LIMIT -1 no SQLite = sem limite
+    values.push(Value::Integer(if query.limit > 0 {
+        query.limit
+    } else {
+        -1
+    }));
+    values.push(Value::Integer(query.offset.max(0)));
+
+    let mut stmt = conn.prepare(&format!(
+        r#"
+        SELECT {}, b.title, b.author, c.name FROM annotations a
+        JOIN books b ON b.id = a.book_id
+        LEFT JOIN chapters c ON c.id = a.chapter_id
+        {}
+        ORDER BY b.title COLLATE NOCASE, a.book_id, {}
+        LIMIT ? OFFSET ?
+        "#,
+        ANNOTATION_COLUMNS, where_sql, READING_ORDER
+    ))?;
+    let rows = stmt.query_map(rusqlite::params_from_iter(values.iter()), |row| {
+        Ok(AnnotationEntry {
+            annotation: annotation_from_row(row)?,
+            book_title: row.get(13)?,
+            book_author: row.get(14)?,
+            chapter_name: row.get(15)?,
+        })
+    })?;
+    let mut out = Vec::new();
+    for row in rows {
+        out.push(row?);
+    }
+    Ok(out)
+}
+
+/// Religa ao livro recém-adicionado as anotações órfãs com o mesmo hash
+/// (livro removido e adicionado de novo num rescan). Livros de arquivo têm um
+/// único volume/capítulo, então a posição (CFI ou página) continua válida.
+pub fn relink_annotations(
+    conn: &rusqlite::Connection,
+    book_id: &str,
+    book_hash: &str,
+) -> crate::Result<usize> {
+    let n = conn.execute(
+        r#"
+        UPDATE annotations SET
+            book_id = ?1,
+            volume_id = (SELECT v.id FROM volumes v WHERE v.book_id = ?1 ORDER BY v.name LIMIT 1),
+            chapter_id = (
+                SELECT c.id FROM volumes v JOIN chapters c ON c.volume_id = v.id
+                WHERE v.book_id = ?1 ORDER BY v.name, c.position LIMIT 1
+            ),
+            updated_at = ?3
+        WHERE book_hash = ?2
+            AND book_id NOT IN (SELECT id FROM books)
+            AND EXISTS (SELECT 1 FROM volumes v WHERE v.book_id = ?1)
+        "#,
+        params![book_id, book_hash, now_secs()],
+    )?;
+    if n > 0 {
+        log::info!(
+            "[annotations] {} anotações religadas ao livro {}",
+            n,
+            book_id
+        );
+    }
+    Ok(n)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{Book, Chapter};
+    use crate::repositories::test_support::{book, memory_db, volume};
+    use crate::repositories::{delete_book, insert_book, insert_chapter, insert_volume};
+
+    fn add_book(conn: &rusqlite::Connection, id: &str, hash: &str) {
+        insert_book(
+            conn,
+            &Book {
+                path: "/books/rust.epub".to_string(),
+                book_type: "file".to_string(),
+                hash: Some(hash.to_string()),
+                ..book(id, "Rust in Action")
+            },
+        )
+        .unwrap();
+        insert_volume(conn, &volume(&format!("{}-v", id), id, "Rust in Action")).unwrap();
+        insert_chapter(
+            conn,
+            &Chapter {
+                id: format!("{}-c", id),
+                volume_id: format!("{}-v", id),
+                name: "Rust in Action".to_string(),
+                path: "/books/rust.epub".to_string(),
+                position: 0,
+            },
+        )
+        .unwrap();
+    }
+
+    /// Livro b1 (hash h1) com um destaque anotado ("borrow checker").
+    fn annotated_book() -> rusqlite::Connection {
+        let conn = memory_db();
+        add_book(&conn, "b1", "h1");
+        upsert_annotation(
+            &conn,
+            &Annotation {
+                id: "a1".to_string(),
+                book_id: "b1".to_string(),
+                volume_id: "b1-v".to_string(),
+                chapter_id: Some("b1-c".to_string()),
+                book_hash: Some("h1".to_string()),
+                cfi_range: Some("epubcfi(/6/4!/4/2,/1:0,/1:12)".to_string()),
+                page_index: None,
+                quads: vec![],
+                text: "Ownership rules".to_string(),
+                color: "yellow".to_string(),
+                note: None,
+                created_at: 1,
+                updated_at: 1,
+            },
+        )
+        .unwrap();
+        assert!(update_annotation(&conn, "a1", None, Some(Some("borrow checker"))).unwrap());
+        conn
+    }
+
+    fn search(text: &str) -> AnnotationQuery {
+        AnnotationQuery {
+            search: Some(text.to_string()),
+            ..AnnotationQuery::default()
+        }
+    }
+
+    #[test]
+    fn search_matches_notes_of_present_books() {
+        let conn = annotated_book();
+        let found = query_annotations(&conn, &search("borrow")).unwrap();
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].book_title, "Rust in Action");
+
+        delete_book(&conn, "b1").unwrap();
+        assert!(query_annotations(&conn, &search("borrow"))
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn annotations_follow_book_hash_across_rescans() {
+        let conn = annotated_book();
+        // Rescan: livro removido e adicionado com outro id, mesmo conteúdo
+        delete_book(&conn, "b1").unwrap();
+        add_book(&conn, "b2", "h1");
+        assert_eq!(relink_annotations(&conn, "b2", "h1").unwrap(), 1);
+        let relinked = list_annotations(&conn, "b2", None).unwrap();
+        assert_eq!(relinked.len(), 1);
+        assert_eq!(relinked[0].volume_id, "b2-v");
+        assert_eq!(relinked[0].chapter_id.as_deref(), Some("b2-c"));
+        assert_eq!(relinked[0].note.as_deref(), Some("borrow checker"));
+    }
+
+    #[test]
+    fn annotations_of_present_books_are_not_relinked() {
+        let conn = annotated_book();
+        add_book(&conn, "b3", "h1");
+        assert_eq!(relink_annotations(&conn, "b3", "h1").unwrap(), 0);
+        assert!(list_annotations(&conn, "b3", None).unwrap().is_empty());
+    }
+}
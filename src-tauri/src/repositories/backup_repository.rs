@@ -0,0 +1,337 @@
+//! Exportação e importação do backup JSON da biblioteca.
+//! A importação mescla: nada local é apagado e, em registros que existem nos
+//! dois lados, vence o `updated_at` mais recente.
+
+use super::{
+    add_book_to_shelf, create_shelf, create_smart_shelf, get_annotation, get_book,
+    get_book_settings, get_bookmark, get_custom_theme, get_global_settings, get_progress,
+    get_review, get_shelf, insert_book, insert_chapter, insert_custom_theme, insert_volume,
+    list_all_annotations, list_all_bookmarks, list_all_chapters, list_all_progress,
+    list_all_reviews, list_all_volumes, list_books, list_custom_themes, list_shelves,
+    save_global_settings, save_review, set_shelf_style, update_custom_theme, upsert_annotation,
+    upsert_book_settings, upsert_bookmark, upsert_progress,
+};
+use crate::models::{
+    BackupImportReport, BookSettings, LibraryBackup, ShelfKind, ShelfLink, BACKUP_VERSION,
+};
+
+fn now_secs() -> i64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs() as i64)
+        .unwrap_or(0)
+}
+
+fn row_exists(conn: &rusqlite::Connection, table: &str, id: &str) -> crate::Result<bool> {
+    let found = conn
+        .query_row(
+            &format!("SELECT 1 FROM {} WHERE id = ?1", table),
+            [id],
+            |_| Ok(()),
+        )
+        .map(|_| true);
+    match found {
+        Ok(v) => Ok(v),
+        Err(rusqlite::Error::QueryReturnedNoRows) => Ok(false),
+        Err(e) => Err(e.into()),
+    }
+}
+
+fn list_shelf_links(conn: &rusqlite::Connection) -> crate::Result<Vec<ShelfLink>> {
+    let mut stmt = conn.prepare(
+        "SELECT book_id, shelf_id, position FROM book_shelves ORDER BY shelf_id, position",
+    )?;
+    let rows = stmt.query_map([], |row| {
+        Ok(ShelfLink {
+            book_id: row.get(0)?,
+            shelf_id: row.get(1)?,
+            position: row.get(2)?,
+        })
+    })?;
+    let mut out = Vec::new();
+    for row in rows {
+        out.push(row?);
+    }
+    Ok(out)
+}
+
+fn list_all_book_settings(conn: &rusqlite::Connection) -> crate::Result<Vec<BookSettings>> {
+    let mut stmt = conn.prepare(
+        "SELECT book_id, layout_mode, reading_direction, zoom, updated_at FROM book_settings",
+    )?;
+    let rows = stmt.query_map([], |row| {
+        Ok(BookSettings {
+            book_id: row.get(0)?,
+            layout_mode: row.get(1)?,
+            reading_direction: row.get(2)?,
+            zoom: row.get(3)?,
+            updated_at: row.get(4)?,
+        })
+    })?;
+    let mut out = Vec::new();
+    for row in rows {
+        out.push(row?);
+    }
+    Ok(out)
+}
+
+pub fn export_backup(conn: &rusqlite::Connection) -> crate::Result<LibraryBackup> {
+    Ok(LibraryBackup {
+        version: BACKUP_VERSION,
+        exported_at: now_secs(),
+        books: list_books(conn)?,
+        volumes: list_all_volumes(conn)?,
+        chapters: list_all_chapters(conn)?,
+        shelves: list_shelves(conn)?,
+        book_shelves: list_shelf_links(conn)?,
+        progress: list_all_progress(conn)?,
+        book_settings: list_all_book_settings(conn)?,
+        reviews: list_all_reviews(conn)?,
+        bookmarks: list_all_bookmarks(conn)?,
+        annotations: list_all_annotations(conn)?,
+        custom_themes: list_custom_themes(conn)?,
+        global_settings: Some(get_global_settings(conn)?),
+    })
+}
+
+/// Mescla o backup no banco, numa única transação.
+pub fn import_backup(
+    conn: &rusqlite::Connection,
+    backup: &LibraryBackup,
+) -> crate::Result<BackupImportReport> {
+    if backup.version > BACKUP_VERSION {
+        return Err(crate::Error::Validation(format!(
+            "backup version {} is newer than supported ({})",
+            backup.version, BACKUP_VERSION
+        )));
+    }
+    let tx = conn.unchecked_transaction()?;
+    let mut report = BackupImportReport::default();
+
+    for book in &backup.books {
+        if get_book(&tx, &book.id)?.is_none() {
+            insert_book(&tx, book)?;
+            report.books += 1;
+        }
+    }
+    for volume in &backup.volumes {
+        if !row_exists(&tx, "volumes", &volume.id)? && row_exists(&tx, "books", &volume.book_id)? {
+            insert_volume(&tx, volume)?;
+        }
+    }
+    for chapter in &backup.chapters {
+        if !row_exists(&tx, "chapters", &chapter.id)?
+            && row_exists(&tx, "volumes", &chapter.volume_id)?
+        {
+            insert_chapter(&tx, chapter)?;
+        }
+    }
+
+    for shelf in &backup.shelves {
+        if !row_exists(&tx, "shelves", &shelf.id)? {
+            match (&shelf.kind, &shelf.rules) {
+                (ShelfKind::Smart, Some(rules)) => {
+                    create_smart_shelf(&tx, &shelf.id, &shelf.name, rules)?
+                }
+                _ => create_shelf(&tx, &shelf.id, &shelf.name)?,
+            }
+            set_shelf_style(
+                &tx,
+                &shelf.id,
+                shelf.icon.as_deref(),
+                shelf.color.as_deref(),
+            )?;
+            report.shelves += 1;
+        }
+    }
+    // Cada vínculo novo entra no fim da estante; ordenar preserva a ordem do arquivo.
+    // Estantes inteligentes não guardam vínculos: os livros vêm das regras.
+    let mut links: Vec<&ShelfLink> = backup.book_shelves.iter().collect();
+    links.sort_by_key(|l| l.position);
+    for link in links {
+        let manual = get_shelf(&tx, &link.shelf_id)?.is_some_and(|s| s.kind == ShelfKind::Manual);
+        if manual && row_exists(&tx, "books", &link.book_id)? {
+            add_book_to_shelf(&tx, &link.book_id, &link.shelf_id)?;
+        }
+    }
+
+    // `None < Some(_)`: registro ausente localmente sempre é importado
+    for p in &backup.progress {
+        if !row_exists(&tx, "volumes", &p.volume_id)? {
+            report.skipped += 1;
+            continue;
+        }
+        let local = get_progress(&tx, &p.book_id, &p.volume_id)?;
+        if local.map(|l| l.updated_at) < Some(p.updated_at) {
+            upsert_progress(&tx, p)?;
+            report.progress += 1;
+        }
+    }
+    for s in &backup.book_settings {
+        if !row_exists(&tx, "books", &s.book_id)? {
+            report.skipped += 1;
+            continue;
+        }
+        let local = get_book_settings(&tx, &s.book_id)?;
+        if local.map(|l| l.updated_at) < Some(s.updated_at) {
+            upsert_book_settings(&tx, s)?;
+            report.settings += 1;
+        }
+    }
+    for r in &backup.reviews {
+        if !row_exists(&tx, "books", &r.book_id)? {
+            report.skipped += 1;
+            continue;
+        }
+        let local = get_review(&tx, &r.book_id)?;
+        if local.map(|l| l.updated_at) < Some(r.updated_at) {
+            save_review(&tx, r)?;
+            report.reviews += 1;
+        }
+    }
+    for b in &backup.bookmarks {
+        if !row_exists(&tx, "books", &b.book_id)? || !row_exists(&tx, "volumes", &b.volume_id)? {
+            report.skipped += 1;
+            continue;
+        }
+        let local = get_bookmark(&tx, &b.id)?;
+        if local.map(|l| l.updated_at) < Some(b.updated_at) {
+            upsert_bookmark(&tx, b)?;
+            report.bookmarks += 1;
+        }
+    }
+    // Sem exigir o livro: anotações órfãs são religadas pelo hash
+    for a in &backup.annotations {
+        let local = get_annotation(&tx, &a.id)?;
+        if local.map(|l| l.updated_at) < Some(a.updated_at) {
+            upsert_annotation(&tx, a)?;
+            report.annotations += 1;
+        }
+    }
+
+    for theme in &backup.custom_themes {
+        match get_custom_theme(&tx, &theme.id)? {
+            None => insert_custom_theme(&tx, theme)?,
+            Some(local) if theme.updated_at > local.updated_at => update_custom_theme(&tx, theme)?,
+            Some(_) => continue,
+        }
+        report.settings += 1;
+    }
+    if let Some(global) = &backup.global_settings {
+        if global.updated_at > get_global_settings(&tx)?.updated_at {
+            save_global_settings(&tx, global)?;
+            report.settings += 1;
+        }
+    }
+
+    tx.commit()?;
+    log::info!(
+        "[backup] Importado: {} livros, {} progressos, {} marcadores ({} ignorados)",
+        report.books,
+        report.progress,
+        report.bookmarks,
+        report.skipped
+    );
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{Bookmark, RuleMatch, SmartRule, SmartShelfRules};
+    use crate::repositories::test_support::{book, memory_db, volume};
+    use crate::repositories::{get_books_in_shelf, list_bookmarks};
+
+    fn library(bookmark_label: &str, updated_at: i64) -> rusqlite::Connection {
+        let conn = memory_db();
+        insert_book(&conn, &book("b1", "Berserk")).unwrap();
+        insert_volume(&conn, &volume("v1", "b1", "Volume 1")).unwrap();
+        upsert_bookmark(
+            &conn,
+            &Bookmark {
+                id: "m1".to_string(),
+                book_id: "b1".to_string(),
+                volume_id: "v1".to_string(),
+                chapter_id: None,
+                page_index: Some(12),
+                cfi: None,
+                label: bookmark_label.to_string(),
+                thumbnail: None,
+                created_at: 10,
+                updated_at,
+            },
+        )
+        .unwrap();
+        conn
+    }
+
+    /// Exporta e relê pelo JSON, como no arquivo de verdade.
+    fn round_trip(backup: &LibraryBackup) -> LibraryBackup {
+        serde_json::from_str(&serde_json::to_string(backup).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn import_into_empty_library_creates_everything() {
+        let source = library("Eclipse", 200);
+        create_shelf(&source, "s1", "Seinen").unwrap();
+        add_book_to_shelf(&source, "b1", "s1").unwrap();
+        let backup = round_trip(&export_backup(&source).unwrap());
+
+        let empty = memory_db();
+        let report = import_backup(&empty, &backup).unwrap();
+        assert_eq!((report.books, report.shelves, report.bookmarks), (1, 1, 1));
+        assert_eq!(
+            list_bookmarks(&empty, "b1", Some("v1")).unwrap()[0].label,
+            "Eclipse"
+        );
+        assert_eq!(list_shelf_links(&empty).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn import_keeps_newer_local_records() {
+        let backup = round_trip(&export_backup(&library("Eclipse", 200)).unwrap());
+
+        let target = library("Local", 300);
+        let report = import_backup(&target, &backup).unwrap();
+        assert_eq!((report.books, report.bookmarks), (0, 0));
+        assert_eq!(get_bookmark(&target, "m1").unwrap().unwrap().label, "Local");
+    }
+
+    #[test]
+    fn import_rejects_newer_backup_versions() {
+        let future = LibraryBackup {
+            version: BACKUP_VERSION + 1,
+            ..LibraryBackup::default()
+        };
+        assert!(matches!(
+            import_backup(&memory_db(), &future),
+            Err(crate::Error::Validation(_))
+        ));
+    }
+
+    #[test]
+    fn import_ignores_links_to_smart_shelves() {
+        let source = library("Eclipse", 200);
+        let rules = SmartShelfRules {
+            match_mode: RuleMatch::All,
+            rules: vec![SmartRule::MissingCover],
+        };
+        create_smart_shelf(&source, "s2", "Sem capa", &rules).unwrap();
+        let mut backup = export_backup(&source).unwrap();
+        // Arquivo antigo (ou editado) com vínculo para a estante inteligente
+        backup.book_shelves.push(ShelfLink {
+            book_id: "b1".to_string(),
+            shelf_id: "s2".to_string(),
+            position: 0,
+        });
+        let backup = round_trip(&backup);
+
+        let empty = memory_db();
+        let report = import_backup(&empty, &backup).unwrap();
+        assert_eq!((report.books, report.shelves), (1, 1));
+        assert!(list_shelf_links(&empty).unwrap().is_empty());
+        let shelf = get_shelf(&empty, "s2").unwrap().unwrap();
+        assert_eq!(shelf.kind, ShelfKind::Smart);
+        assert_eq!(get_books_in_shelf(&empty, "s2").unwrap(), vec!["b1"]);
+    }
+}
@@ -0,0 +1,723 @@
+//! Listagem paginada da biblioteca: um resumo por livro calculado em SQL
+//! (contagem de capítulos, progresso, capa derivada) sem carregar a árvore de volumes.
+
+use super::book_repository::{book_columns, book_from_row, BOOK_COLUMN_COUNT};
+use super::shelf_repository::get_shelf;
+use super::smart_shelf_repository::rules_filter;
+use crate::models::{
+    BookPage, BookQuery, BookSortField, BookSummary, ContinueReadingItem, ProgressFilter,
+    ReadStatus, ShelfKind, SortDirection,
+};
+use rusqlite::types::Value;
+
+fn now_secs() -> i64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs() as i64)
+        .unwrap_or(0)
+}
+
+/// Estatísticas por livro. Páginas lidas = min(page_index, capítulos) somado por volume,
+/// mesma regra usada pelo frontend em `computeBooksWithProgress`.
+const STATS_CTE: &str = r#"
+WITH vol_stats AS (
+    SELECT v.book_id, v.id AS volume_id, COUNT(c.id) AS chapters
+    FROM volumes v
+    LEFT JOIN chapters c ON c.volume_id = v.id
+    GROUP BY v.id
+),
+book_stats AS (
+    SELECT vs.book_id,
+        COUNT(*) AS volume_count,
+        SUM(vs.chapters) AS chapter_count,
+        SUM(MIN(COALESCE(rp.page_index, 0), vs.chapters)) AS read_pages,
+        SUM(CASE WHEN rp.page_index IS NOT NULL AND rp.page_index < vs.chapters THEN 1 ELSE 0 END) AS reading_volumes,
+        SUM(CASE WHEN rp.page_index IS NOT NULL AND rp.page_index >= vs.chapters THEN 1 ELSE 0 END) AS completed_volumes,
+        MAX(rp.updated_at) AS last_read_at
+    FROM vol_stats vs
+    LEFT JOIN reading_progress rp ON rp.book_id = vs.book_id AND rp.volume_id = vs.volume_id
+    GROUP BY vs.book_id
+)
+"#;
+
+const FROM_BOOKS: &str = "FROM books b LEFT JOIN book_stats bs ON bs.book_id = b.id \
+     LEFT JOIN book_reviews br ON br.book_id = b.id";
+
+pub(super) const PROGRESS_RATIO: &str = "(CASE WHEN COALESCE(bs.chapter_count, 0) > 0 \
+     THEN bs.read_pages * 1.0 / bs.chapter_count ELSE 0 END)";
+
+/// Caminho do primeiro capítulo do livro (capa derivada e formato).
+const FIRST_CHAPTER_SQL: &str =
+    "(SELECT c.path FROM volumes v JOIN chapters c ON c.volume_id = v.id \
+     WHERE v.book_id = b.id ORDER BY v.name, c.position LIMIT 1)";
+
+const COMPLETED_SQL: &str = "(COALESCE(bs.volume_count, 0) > 0 \
+     AND COALESCE(bs.completed_volumes, 0) = COALESCE(bs.volume_count, 0))";
+
+const READING_SQL: &str = "(COALESCE(bs.reading_volumes, 0) > 0)";
+
+/// Cláusulas WHERE acumuladas com seus parâmetros posicionais (`?`).
+#[derive(Default)]
+pub(super) struct BookFilter {
+    clauses: Vec<String>,
+    params: Vec<Value>,
+}
+
+impl BookFilter {
+    pub(super) fn push(&mut self, clause: impl Into<String>, params: Vec<Value>) {
+        self.clauses.push(clause.into());
+        self.params.extend(params);
+    }
+
+    pub(super) fn append(&mut self, other: BookFilter) {
+        self.clauses.extend(other.clauses);
+        self.params.extend(other.params);
+    }
+
+    fn where_sql(&self) -> String {
+        if self.clauses.is_empty() {
+            String::new()
+        } else {
+            format!("WHERE {}", self.clauses.join(" AND "))
+        }
+    }
+}
+
+/// Escapa `%`, `_` e `\` para uso em LIKE com `ESCAPE '\'`.
+pub(super) fn like_escape(term: &str) -> String {
+    term.replace('\\', "\\\\")
+        .replace('%', "\\%")
+        .replace('_', "\\_")
+}
+
+/// Padrão "contém" para LIKE com `ESCAPE '\'`.
+pub(super) fn like_pattern(term: &str) -> String {
+    format!("%{}%", like_escape(term))
+}
+
+fn filter_from_query(query: &BookQuery) -> BookFilter {
+    let mut filter = BookFilter::default();
+    if let Some(search) = query
+        .search
+        .as_deref()
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+    {
+        let pattern = like_pattern(search);
+        filter.push(
+            r"(b.title LIKE ? ESCAPE '\' OR b.author LIKE ? ESCAPE '\')",
+            vec![Value::Text(pattern.clone()), Value::Text(pattern)],
+        );
+    }
+    if let Some(book_type) = &query.book_type {
+        filter.push("b.type = ?", vec![Value::Text(book_type.clone())]);
+    }
+    if let Some(format) = query.format {
+        // LIKE já ignora maiúsculas em ASCII
+        let extensions = format.extensions();
+        let clause = vec!["p LIKE ?"; extensions.len()].join(" OR ");
+        filter.push(
+            format!(
+                "EXISTS (SELECT 1 FROM (SELECT {} AS p) WHERE {})",
+                FIRST_CHAPTER_SQL, clause
+            ),
+            extensions
+                .iter()
+                .map(|ext| Value::Text(format!("%.{}", ext)))
+                .collect(),
+        );
+    }
+    if let Some(shelf_id) = &query.shelf_id {
+        filter.push(
+            "b.id IN (SELECT book_id FROM book_shelves WHERE shelf_id = ?)",
+            vec![Value::Text(shelf_id.clone())],
+        );
+    }
+    if let Some(min_rating) = query.min_rating {
+        filter.push("br.rating >= ?", vec![Value::Real(min_rating)]);
+    }
+    for tag_id in &query.tag_ids {
+        filter.push(
+            "b.id IN (SELECT book_id FROM book_tags WHERE tag_id = ?)",
+            vec![Value::Text(tag_id.clone())],
+        );
+    }
+    if let Some(status) = query.read_status {
+        filter.push(
+            "b.read_status = ?",
+            vec![Value::Text(status.as_str().to_string())],
+        );
+    }
+    if let Some(progress) = query.progress {
+        let clause = match progress {
+            ProgressFilter::Completed => COMPLETED_SQL.to_string(),
+            ProgressFilter::Reading => READING_SQL.to_string(),
+            ProgressFilter::NotStarted => format!("NOT {} AND NOT {}", COMPLETED_SQL, READING_SQL),
+        };
+        filter.push(clause, vec![]);
+    }
+    filter
+}
+
+fn order_sql(sort_by: BookSortField, dir: SortDirection) -> String {
+    let dir = match dir {
+        SortDirection::Asc => "ASC",
+        SortDirection::Desc => "DESC",
+    };
+    let expr = match sort_by {
+        BookSortField::AddedAt => "b.added_at",
+        BookSortField::Title => "b.title COLLATE NOCASE",
+        BookSortField::Author => "COALESCE(b.author, '') COLLATE NOCASE",
+        BookSortField::LastRead => "COALESCE(bs.last_read_at, 0)",
+        BookSortField::Progress => PROGRESS_RATIO,
+        // Sem nota fica abaixo de qualquer nota
+        BookSortField::Rating => "COALESCE(br.rating, 0)",
+    };
+    format!("ORDER BY {} {}, b.added_at DESC, b.id", expr, dir)
+}
+
+/// Lista resumos de livros conforme filtros/ordenação, com paginação.
+pub fn query_books(conn: &rusqlite::Connection, query: &BookQuery) -> crate::Result<BookPage> {
+    let mut filter = filter_from_query(query);
+    if let Some(shelf_id) = &query.shelf_id {
+        if let Some(shelf) = get_shelf(conn, shelf_id)?.filter(|s| s.kind == ShelfKind::Smart) {
+            // Estante inteligente: as regras substituem a busca em book_shelves
+            filter = filter_from_query(&BookQuery {
+                shelf_id: None,
+                ..query.clone()
+            });
+            match &shelf.rules {
+                Some(rules) => filter.append(rules_filter(rules, now_secs())),
+                None => filter.push("0", vec![]),
+            }
+        }
+    }
+    query_books_filtered(
+        conn,
+        &filter,
+        query.sort_by,
+        query.sort_dir,
+        query.offset,
+        query.limit,
+    )
+}
+
+pub(super) fn count_books_filtered(
+    conn: &rusqlite::Connection,
+    filter: &BookFilter,
+) -> crate::Result<i64> {
+    Ok(conn.query_row(
+        &format!(
+            "{} SELECT COUNT(*) {} {}",
+            STATS_CTE,
+            FROM_BOOKS,
+            filter.where_sql()
+        ),
+        rusqlite::params_from_iter(filter.params.iter()),
+        |row| row.get(0),
+    )?)
+}
+
+pub(super) fn query_books_filtered(
+    conn: &rusqlite::Connection,
+    filter: &BookFilter,
+    sort_by: BookSortField,
+    sort_dir: SortDirection,
+    offset: i64,
+    limit: i64,
+) -> crate::Result<BookPage> {
+    let where_sql = filter.where_sql();
+    let total = count_books_filtered(conn, filter)?;
+
+    let offset = offset.max(0);
+    // LIMIT -1 no SQLite = sem limite
+    let sql_limit = if limit > 0 { limit } else { -1 };
+    let sql = format!(
+        r#"{}
+        SELECT {},
+            COALESCE(bs.volume_count, 0),
+            COALESCE(bs.chapter_count, 0),
+            COALESCE(bs.read_pages, 0),
+            bs.last_read_at,
+            {},
+            b.read_status,
+            br.rating
+        {} {} {}
+        LIMIT ? OFFSET ?
+        "#,
+        STATS_CTE,
+        book_columns("b"),
+        FIRST_CHAPTER_SQL,
+        FROM_BOOKS,
+        where_sql,
+        order_sql(sort_by, sort_dir)
+    );
+    let mut params = filter.params.clone();
+    params.push(Value::Integer(sql_limit));
+    params.push(Value::Integer(offset));
+
+    let mut stmt = conn.prepare(&sql)?;
+    let rows = stmt.query_map(rusqlite::params_from_iter(params.iter()), |row| {
+        let book = book_from_row(row)?;
+        // Colunas de estatística vêm depois das do livro
+        let col = |i: usize| BOOK_COLUMN_COUNT + i;
+        let chapter_count: i64 = row.get(col(1))?;
+        let read_pages: i64 = row.get(col(2))?;
+        let progress_percent = if chapter_count > 0 {
+            read_pages as f64 / chapter_count as f64 * 100.0
+        } else {
+            0.0
+        };
+        Ok(BookSummary {
+            book,
+            volume_count: row.get(col(0))?,
+            chapter_count,
+            read_pages,
+            progress_percent,
+            last_read_at: row.get(col(3))?,
+            first_chapter_path: row.get(col(4))?,
+            read_status: ReadStatus::parse(&row.get::<_, String>(col(5))?),
+            rating: row.get(col(6))?,
+        })
+    })?;
+    let mut items = Vec::new();
+    for row in rows {
+        items.push(row?);
+    }
+    Ok(BookPage {
+        items,
+        total,
+        offset,
+        limit,
+    })
+}
+
+/// Volumes em andamento (página atual antes do último capítulo), do mais
+/// recente para o mais antigo, numa única consulta.
+pub fn list_continue_reading(
+    conn: &rusqlite::Connection,
+    limit: i64,
+) -> crate::Result<Vec<ContinueReadingItem>> {
+    let sql = format!(
+        r#"
+        SELECT {},
+            rp.volume_id,
+            v.name,
+            (SELECT COUNT(*) FROM chapters c WHERE c.volume_id = v.id) AS chapter_count,
+            rp.page_index,
+            rp.updated_at,
+            {}
+        FROM reading_progress rp
+        JOIN books b ON b.id = rp.book_id
+        JOIN volumes v ON v.id = rp.volume_id
+        WHERE rp.page_index < (SELECT COUNT(*) FROM chapters c WHERE c.volume_id = v.id)
+        ORDER BY rp.updated_at DESC
+        LIMIT ?1
+        "#,
+        book_columns("b"),
+        FIRST_CHAPTER_SQL
+    );
+    let mut stmt = conn.prepare(&sql)?;
+    let rows = stmt.query_map([limit], |row| {
+        let col = |i: usize| BOOK_COLUMN_COUNT + i;
+        Ok(ContinueReadingItem {
+            book: book_from_row(row)?,
+            volume_id: row.get(col(0))?,
+            volume_name: row.get(col(1))?,
+            chapter_count: row.get(col(2))?,
+            page_index: row.get(col(3))?,
+            updated_at: row.get(col(4))?,
+            first_chapter_path: row.get(col(5))?,
+        })
+    })?;
+    let mut items = Vec::new();
+    for row in rows {
+        items.push(row?);
+    }
+    Ok(items)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{Book, BookFormat, BookReview, Chapter, ReadingProgress};
+    use crate::repositories::test_support::{book, memory_db, volume};
+    use crate::repositories::{
+        add_book_to_shelf, add_tag_to_book, create_shelf, create_tag, insert_book, insert_chapter,
+        insert_volume, save_review, set_book_status, upsert_progress,
+    };
+
+    /// Livro com um volume por item de `volumes` (quantidade de capítulos).
+    fn add_book(
+        conn: &rusqlite::Connection,
+        id: &str,
+        title: &str,
+        added_at: i64,
+        ext: &str,
+        volumes: &[i32],
+    ) {
+        insert_book(
+            conn,
+            &Book {
+                added_at,
+                ..book(id, title)
+            },
+        )
+        .unwrap();
+        for (v, chapters) in volumes.iter().enumerate() {
+            let volume_id = format!("{}-v{}", id, v + 1);
+            insert_volume(conn, &volume(&volume_id, id, &format!("Volume {}", v + 1))).unwrap();
+            for position in 0..*chapters {
+                insert_chapter(
+                    conn,
+                    &Chapter {
+                        id: format!("{}-c{}", volume_id, position),
+                        volume_id: volume_id.clone(),
+                        name: format!("{}", position + 1),
+                        path: format!("/manga/{}/{}.{}", volume_id, position + 1, ext),
+                        position,
+                    },
+                )
+                .unwrap();
+            }
+        }
+    }
+
+    fn read(conn: &rusqlite::Connection, volume_id: &str, page_index: i32, updated_at: i64) {
+        upsert_progress(
+            conn,
+            &ReadingProgress {
+                book_id: volume_id.split("-v").next().unwrap().to_string(),
+                volume_id: volume_id.to_string(),
+                current_chapter_id: None,
+                page_index,
+                scroll_offset: 0.0,
+                updated_at,
+                total_pages: None,
+            },
+        )
+        .unwrap();
+    }
+
+    fn set_author(conn: &rusqlite::Connection, id: &str, author: &str) {
+        conn.execute("UPDATE books SET author = ?1 WHERE id = ?2", [author, id])
+            .unwrap();
+    }
+
+    fn rate(conn: &rusqlite::Connection, id: &str, rating: f64) {
+        save_review(
+            conn,
+            &BookReview {
+                book_id: id.to_string(),
+                rating: Some(rating),
+                review: None,
+                updated_at: 1,
+            },
+        )
+        .unwrap();
+    }
+
+    fn ids(conn: &rusqlite::Connection, query: BookQuery) -> Vec<String> {
+        query_books(conn, &query)
+            .unwrap()
+            .items
+            .into_iter()
+            .map(|s| s.book.id)
+            .collect()
+    }
+
+    /// Três livros: alpha (concluído), beta (em andamento) e gamma (não iniciado).
+    fn library() -> rusqlite::Connection {
+        let conn = memory_db();
+        add_book(&conn, "alpha", "Alpha", 1, "png", &[2, 2]);
+        add_book(&conn, "beta", "Beta 100%", 2, "pdf", &[4]);
+        add_book(&conn, "gamma", "Gamma_x", 3, "JPG", &[2]);
+        // Páginas além do total contam como o total do volume
+        read(&conn, "alpha-v1", 2, 10);
+        read(&conn, "alpha-v2", 5, 11);
+        read(&conn, "beta-v1", 1, 12);
+        conn
+    }
+
+    /// `library` mais delta (em andamento), com autores, notas, tags e estante:
+    ///
+    /// | livro | autor | progresso | lido em | nota | tags       | estante |
+    /// |-------|-------|-----------|---------|------|------------|---------|
+    /// | alpha | Miura | 100%      | 11      | 4.5  | dark, epic | s1      |
+    /// | beta  | Inoue | 25%       | 12      | 3    |            | s1      |
+    /// | gamma |       | 0%        |         |      | dark       |         |
+    /// | delta | araki | 50%       | 20      | 4.5  |            |         |
+    ///
+    /// Retorna (dark, epic).
+    fn catalog(conn: &rusqlite::Connection) -> (String, String) {
+        add_book(conn, "delta", "delta", 4, "png", &[4]);
+        read(conn, "delta-v1", 2, 20);
+        for (id, author) in [("alpha", "Miura"), ("beta", "Inoue"), ("delta", "araki")] {
+            set_author(conn, id, author);
+        }
+        for (id, rating) in [("alpha", 4.5), ("beta", 3.0), ("delta", 4.5)] {
+            rate(conn, id, rating);
+        }
+        let dark = create_tag(conn, "Dark").unwrap();
+        let epic = create_tag(conn, "Epic").unwrap();
+        add_tag_to_book(conn, "alpha", &dark).unwrap();
+        add_tag_to_book(conn, "gamma", &dark).unwrap();
+        add_tag_to_book(conn, "alpha", &epic).unwrap();
+        create_shelf(conn, "s1", "Favoritos").unwrap();
+        add_book_to_shelf(conn, "alpha", "s1").unwrap();
+        add_book_to_shelf(conn, "beta", "s1").unwrap();
+        (dark, epic)
+    }
+
+    #[test]
+    fn summarizes_progress_per_book() {
+        let conn = library();
+        let page = query_books(&conn, &BookQuery::default()).unwrap();
+        assert_eq!(page.total, 3);
+        let alpha = &page.items[2];
+        assert_eq!(alpha.book.id, "alpha");
+        assert_eq!((alpha.volume_count, alpha.chapter_count), (2, 4));
+        assert_eq!(alpha.read_pages, 4);
+        assert_eq!(alpha.progress_percent, 100.0);
+        assert_eq!(alpha.last_read_at, Some(11));
+        let beta = &page.items[1];
+        assert_eq!((beta.read_pages, beta.progress_percent), (1, 25.0));
+        assert_eq!(
+            beta.first_chapter_path.as_deref(),
+            Some("/manga/beta-v1/1.pdf")
+        );
+        assert_eq!(page.items[0].read_pages, 0);
+    }
+
+    #[test]
+    fn filters_by_progress() {
+        let conn = library();
+        let by_progress = |progress| {
+            ids(
+                &conn,
+                BookQuery {
+                    progress: Some(progress),
+                    ..Default::default()
+                },
+            )
+        };
+        assert_eq!(by_progress(ProgressFilter::Completed), ["alpha"]);
+        assert_eq!(by_progress(ProgressFilter::Reading), ["beta"]);
+        assert_eq!(by_progress(ProgressFilter::NotStarted), ["gamma"]);
+    }
+
+    #[test]
+    fn search_treats_wildcards_literally() {
+        let conn = library();
+        let search = |term: &str| {
+            ids(
+                &conn,
+                BookQuery {
+                    search: Some(term.to_string()),
+                    ..Default::default()
+                },
+            )
+        };
+        assert_eq!(search("100%"), ["beta"]);
+        assert_eq!(search("%"), ["beta"]);
+        assert_eq!(search("a_"), ["gamma"]);
+        assert!(search("\\").is_empty());
+    }
+
+    #[test]
+    fn filters_by_format_of_the_first_chapter() {
+        let conn = library();
+        let by_format = |format| {
+            ids(
+                &conn,
+                BookQuery {
+                    format: Some(format),
+                    ..Default::default()
+                },
+            )
+        };
+        assert_eq!(by_format(BookFormat::Pdf), ["beta"]);
+        assert_eq!(by_format(BookFormat::Images), ["gamma", "alpha"]);
+        assert!(by_format(BookFormat::Epub).is_empty());
+    }
+
+    #[test]
+    fn combined_filters_must_all_match() {
+        let conn = library();
+        let (dark, epic) = catalog(&conn);
+        set_book_status(&conn, "gamma", ReadStatus::OnHold, 30).unwrap();
+
+        // Busca também no autor ("araki" tem "ra"); só delta é imagem
+        let query = BookQuery {
+            search: Some("ra".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(ids(&conn, query.clone()), ["delta", "alpha"]);
+        let images = BookQuery {
+            format: Some(BookFormat::Images),
+            ..query.clone()
+        };
+        assert_eq!(ids(&conn, images.clone()), ["delta", "alpha"]);
+        assert_eq!(
+            ids(
+                &conn,
+                BookQuery {
+                    progress: Some(ProgressFilter::Reading),
+                    ..images
+                }
+            ),
+            ["delta"]
+        );
+
+        let shelf = BookQuery {
+            shelf_id: Some("s1".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(ids(&conn, shelf.clone()), ["beta", "alpha"]);
+        assert_eq!(
+            ids(
+                &conn,
+                BookQuery {
+                    min_rating: Some(4.0),
+                    ..shelf
+                }
+            ),
+            ["alpha"]
+        );
+
+        let tagged = BookQuery {
+            tag_ids: vec![dark.clone()],
+            ..Default::default()
+        };
+        assert_eq!(ids(&conn, tagged.clone()), ["gamma", "alpha"]);
+        assert_eq!(
+            ids(
+                &conn,
+                BookQuery {
+                    progress: Some(ProgressFilter::NotStarted),
+                    ..tagged.clone()
+                }
+            ),
+            ["gamma"]
+        );
+        assert_eq!(
+            ids(
+                &conn,
+                BookQuery {
+                    read_status: Some(ReadStatus::OnHold),
+                    ..tagged
+                }
+            ),
+            ["gamma"]
+        );
+        // Várias tags: o livro precisa ter todas
+        assert_eq!(
+            ids(
+                &conn,
+                BookQuery {
+                    tag_ids: vec![dark, epic],
+                    ..Default::default()
+                }
+            ),
+            ["alpha"]
+        );
+        assert!(ids(
+            &conn,
+            BookQuery {
+                book_type: Some("file".to_string()),
+                ..Default::default()
+            }
+        )
+        .is_empty());
+    }
+
+    #[test]
+    fn sorts_by_every_field_in_both_directions() {
+        let conn = library();
+        catalog(&conn);
+        let sorted = |sort_by, sort_dir| {
+            ids(
+                &conn,
+                BookQuery {
+                    sort_by,
+                    sort_dir,
+                    ..Default::default()
+                },
+            )
+        };
+        let cases = [
+            (BookSortField::AddedAt, ["alpha", "beta", "gamma", "delta"]),
+            // Sem diferenciar maiúsculas
+            (BookSortField::Title, ["alpha", "beta", "delta", "gamma"]),
+            // Sem autor vem antes de qualquer autor
+            (BookSortField::Author, ["gamma", "delta", "beta", "alpha"]),
+            (BookSortField::LastRead, ["gamma", "alpha", "beta", "delta"]),
+            (BookSortField::Progress, ["gamma", "beta", "delta", "alpha"]),
+        ];
+        for (field, asc) in cases {
+            assert_eq!(sorted(field, SortDirection::Asc), asc, "{:?}", field);
+            let mut desc = asc;
+            desc.reverse();
+            assert_eq!(sorted(field, SortDirection::Desc), desc, "{:?}", field);
+        }
+
+        // Empate na nota: o mais recente primeiro nas duas direções
+        assert_eq!(
+            sorted(BookSortField::Rating, SortDirection::Asc),
+            ["gamma", "beta", "delta", "alpha"]
+        );
+        assert_eq!(
+            sorted(BookSortField::Rating, SortDirection::Desc),
+            ["delta", "alpha", "beta", "gamma"]
+        );
+    }
+
+    #[test]
+    fn pages_through_filtered_and_sorted_results() {
+        let conn = library();
+        catalog(&conn);
+        let page = |offset, limit| {
+            query_books(
+                &conn,
+                &BookQuery {
+                    format: Some(BookFormat::Images),
+                    sort_by: BookSortField::Title,
+                    sort_dir: SortDirection::Asc,
+                    offset,
+                    limit,
+                    ..Default::default()
+                },
+            )
+            .unwrap()
+        };
+        let ids_of = |page: &BookPage| -> Vec<String> {
+            page.items.iter().map(|s| s.book.id.clone()).collect()
+        };
+
+        // O total conta o filtro, não a página
+        let first = page(0, 2);
+        assert_eq!((first.total, first.offset, first.limit), (3, 0, 2));
+        assert_eq!(ids_of(&first), ["alpha", "delta"]);
+        let second = page(2, 2);
+        assert_eq!(second.total, 3);
+        assert_eq!(ids_of(&second), ["gamma"]);
+        let past_end = page(4, 2);
+        assert_eq!(past_end.total, 3);
+        assert!(past_end.items.is_empty());
+
+        // limit 0 = tudo a partir do offset; offset negativo vira 0
+        assert_eq!(ids_of(&page(1, 0)), ["delta", "gamma"]);
+        let clamped = page(-5, 1);
+        assert_eq!(clamped.offset, 0);
+        assert_eq!(ids_of(&clamped), ["alpha"]);
+    }
+
+    #[test]
+    fn continue_reading_lists_only_volumes_in_progress() {
+        let conn = library();
+        let recent = list_continue_reading(&conn, 5).unwrap();
+        assert_eq!(recent.len(), 1);
+        assert_eq!(recent[0].volume_id, "beta-v1");
+        assert_eq!((recent[0].page_index, recent[0].chapter_count), (1, 4));
+    }
+}
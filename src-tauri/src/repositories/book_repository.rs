@@ -1,13 +1,26 @@
 //! Repositório de livros, volumes e capítulos.
 
-use crate::models::{Book, Chapter, Volume};
+use super::record_metadata_changes;
+use crate::metadata::MediaType;
+use crate::models::{Book, BookMetadata, BookMetadataPatch, Chapter, Volume};
 use rusqlite::params;
 
+fn now_secs() -> i64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs() as i64)
+        .unwrap_or(0)
+}
+
 pub fn insert_book(conn: &rusqlite::Connection, book: &Book) -> crate::Result<()> {
+    let m = &book.metadata;
     conn.execute(
         r#"
-        INSERT INTO books (id, title, path, type, added_at, hash, author, description, cover_path)
-        VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)
+        INSERT INTO books (id, title, path, type, added_at, hash, author, description, cover_path,
+            publisher, year, language, isbns, alt_titles, series, series_number, page_count,
+            media_type, genres, external_ids, updated_at)
+        VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17, ?18,
+            ?19, ?20, ?21)
         "#,
         params![
             book.id,
@@ -19,6 +32,18 @@ pub fn insert_book(conn: &rusqlite::Connection, book: &Book) -> crate::Result<()
             book.author,
             book.description,
             book.cover_path,
+            m.publisher,
+            m.year,
+            m.language,
+            json_list(&m.isbns),
+            json_list(&m.alt_titles),
+            m.series,
+            m.series_number,
+            m.page_count,
+            m.media_type.map(MediaType::as_str),
+            json_list(&m.genres),
+            json_map(&m.external_ids),
+            now_secs(),
         ],
     )?;
     Ok(())
@@ -26,8 +51,8 @@ pub fn insert_book(conn: &rusqlite::Connection, book: &Book) -> crate::Result<()
 
 pub fn insert_volume(conn: &rusqlite::Connection, volume: &Volume) -> crate::Result<()> {
     conn.execute(
-        "INSERT INTO volumes (id, book_id, name) VALUES (?1, ?2, ?3)",
-        params![volume.id, volume.book_id, volume.name],
+        "INSERT INTO volumes (id, book_id, name, updated_at) VALUES (?1, ?2, ?3, ?4)",
+        params![volume.id, volume.book_id, volume.name, now_secs()],
     )?;
     Ok(())
 }
@@ -35,8 +60,8 @@ pub fn insert_volume(conn: &rusqlite::Connection, volume: &Volume) -> crate::Res
 pub fn insert_chapter(conn: &rusqlite::Connection, chapter: &Chapter) -> crate::Result<()> {
     conn.execute(
         r#"
-        INSERT INTO chapters (id, volume_id, name, path, position)
-        VALUES (?1, ?2, ?3, ?4, ?5)
+        INSERT INTO chapters (id, volume_id, name, path, position, updated_at)
+        VALUES (?1, ?2, ?3, ?4, ?5, ?6)
         "#,
         params![
             chapter.id,
@@ -44,28 +69,90 @@ pub fn insert_chapter(conn: &rusqlite::Connection, chapter: &Chapter) -> crate::
             chapter.name,
             chapter.path,
             chapter.position,
+            now_secs(),
         ],
     )?;
     Ok(())
 }
 
+/// Colunas de `books` na ordem esperada por `book_from_row`.
+pub(super) const BOOK_COLUMNS: &str =
+    "id, title, path, type, added_at, hash, author, description, \
+     cover_path, publisher, year, language, isbns, alt_titles, series, series_number, page_count, \
+     media_type, genres, external_ids";
+
+/// Quantidade de colunas em `BOOK_COLUMNS` (as seguintes ficam livres para a consulta).
+pub(super) const BOOK_COLUMN_COUNT: usize = 20;
+
+/// `BOOK_COLUMNS` com prefixo de tabela (ex.: `b.id, b.title, ...`).
+pub(super) fn book_columns(alias: &str) -> String {
+    BOOK_COLUMNS
+        .split(", ")
+        .map(|c| format!("{}.{}", alias, c))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Listas em JSON; vazia = NULL.
+fn json_list(values: &[String]) -> Option<String> {
+    if values.is_empty() {
+        None
+    } else {
+        serde_json::to_string(values).ok()
+    }
+}
+
+fn json_map(values: &std::collections::BTreeMap<String, String>) -> Option<String> {
+    if values.is_empty() {
+        None
+    } else {
+        serde_json::to_string(values).ok()
+    }
+}
+
+/// JSON ilegível vira vazio em vez de falhar a listagem.
+fn parse_json<T: serde::de::DeserializeOwned + Default>(value: Option<String>) -> T {
+    value
+        .and_then(|v| serde_json::from_str(&v).ok())
+        .unwrap_or_default()
+}
+
+/// Monta um `Book` a partir de uma linha que começa com `BOOK_COLUMNS`.
+pub(super) fn book_from_row(row: &rusqlite::Row) -> rusqlite::Result<Book> {
+    Ok(Book {
+        id: row.get(0)?,
+        title: row.get(1)?,
+        path: row.get(2)?,
+        book_type: row.get(3)?,
+        added_at: row.get(4)?,
+        hash: row.get(5)?,
+        author: row.get(6).ok(),
+        description: row.get(7).ok(),
+        cover_path: row.get(8).ok(),
+        metadata: BookMetadata {
+            publisher: row.get(9)?,
+            year: row.get(10)?,
+            language: row.get(11)?,
+            isbns: parse_json(row.get(12)?),
+            alt_titles: parse_json(row.get(13)?),
+            series: row.get(14)?,
+            series_number: row.get(15)?,
+            page_count: row.get(16)?,
+            media_type: row
+                .get::<_, Option<String>>(17)?
+                .and_then(|m| MediaType::parse(&m)),
+            genres: parse_json(row.get(18)?),
+            external_ids: parse_json(row.get(19)?),
+        },
+    })
+}
+
 pub fn list_books(conn: &rusqlite::Connection) -> crate::Result<Vec<Book>> {
-    let mut stmt = conn.prepare(
-        "SELECT id, title, path, type, added_at, hash, author, description, cover_path FROM books ORDER BY added_at DESC",
-    )?;
-    let rows = stmt.query_map([], |row| {
-        Ok(Book {
-            id: row.get(0)?,
-            title: row.get(1)?,
-            path: row.get(2)?,
-            book_type: row.get(3)?,
-            added_at: row.get(4)?,
-            hash: row.get(5)?,
-            author: row.get(6).ok(),
-            description: row.get(7).ok(),
-            cover_path: row.get(8).ok(),
-        })
-    })?;
+    let mut stmt = conn.prepare(&format!(
+        "SELECT {} FROM books ORDER BY added_at DESC",
+        BOOK_COLUMNS
+    ))?;
+    let rows = stmt.query_map([], book_from_row)?;
     let mut books = Vec::new();
     for row in rows {
         books.push(row?);
@@ -73,6 +160,16 @@ pub fn list_books(conn: &rusqlite::Connection) -> crate::Result<Vec<Book>> {
     Ok(books)
 }
 
+/// Busca um livro pelo id (sem varrer a tabela inteira).
+pub fn get_book(conn: &rusqlite::Connection, book_id: &str) -> crate::Result<Option<Book>> {
+    let mut stmt = conn.prepare(&format!("SELECT {} FROM books WHERE id = ?1", BOOK_COLUMNS))?;
+    let mut rows = stmt.query([book_id])?;
+    if let Some(row) = rows.next()? {
+        return Ok(Some(book_from_row(row)?));
+    }
+    Ok(None)
+}
+
 pub fn list_volumes(conn: &rusqlite::Connection, book_id: &str) -> crate::Result<Vec<Volume>> {
     let mut stmt =
         conn.prepare("SELECT id, book_id, name FROM volumes WHERE book_id = ?1 ORDER BY name")?;
@@ -110,6 +207,44 @@ pub fn list_chapters(conn: &rusqlite::Connection, volume_id: &str) -> crate::Res
     Ok(chapters)
 }
 
+/// Todos os volumes da biblioteca (uma query), para agrupar em memória por book_id.
+pub fn list_all_volumes(conn: &rusqlite::Connection) -> crate::Result<Vec<Volume>> {
+    let mut stmt = conn.prepare("SELECT id, book_id, name FROM volumes ORDER BY book_id, name")?;
+    let rows = stmt.query_map([], |row| {
+        Ok(Volume {
+            id: row.get(0)?,
+            book_id: row.get(1)?,
+            name: row.get(2)?,
+        })
+    })?;
+    let mut volumes = Vec::new();
+    for row in rows {
+        volumes.push(row?);
+    }
+    Ok(volumes)
+}
+
+/// Todos os capítulos da biblioteca (uma query), para agrupar em memória por volume_id.
+pub fn list_all_chapters(conn: &rusqlite::Connection) -> crate::Result<Vec<Chapter>> {
+    let mut stmt = conn.prepare(
+        "SELECT id, volume_id, name, path, position FROM chapters ORDER BY volume_id, position",
+    )?;
+    let rows = stmt.query_map([], |row| {
+        Ok(Chapter {
+            id: row.get(0)?,
+            volume_id: row.get(1)?,
+            name: row.get(2)?,
+            path: row.get(3)?,
+            position: row.get(4)?,
+        })
+    })?;
+    let mut chapters = Vec::new();
+    for row in rows {
+        chapters.push(row?);
+    }
+    Ok(chapters)
+}
+
 pub fn delete_book(conn: &rusqlite::Connection, book_id: &str) -> crate::Result<()> {
     conn.execute("DELETE FROM books WHERE id = ?1", [book_id])?;
     Ok(())
@@ -124,15 +259,49 @@ pub fn update_book(
     cover_path: Option<&str>,
 ) -> crate::Result<()> {
     conn.execute(
-        "UPDATE books SET title = ?1, author = ?2, description = ?3, cover_path = ?4 WHERE id = ?5",
-        params![title, author, description, cover_path, book_id],
+        "UPDATE books SET title = ?1, author = ?2, description = ?3, cover_path = ?4, updated_at = ?5 WHERE id = ?6",
+        params![title, author, description, cover_path, now_secs(), book_id],
+    )?;
+    Ok(())
+}
+
+/// Grava todos os metadados estendidos (edição manual).
+pub fn update_book_metadata(
+    conn: &rusqlite::Connection,
+    book_id: &str,
+    m: &BookMetadata,
+) -> crate::Result<()> {
+    conn.execute(
+        r#"
+        UPDATE books SET publisher = ?1, year = ?2, language = ?3, isbns = ?4, alt_titles = ?5,
+            series = ?6, series_number = ?7, page_count = ?8, media_type = ?9, genres = ?10,
+            external_ids = ?11, updated_at = ?12
+        WHERE id = ?13
+        "#,
+        params![
+            m.publisher,
+            m.year,
+            m.language,
+            json_list(&m.isbns),
+            json_list(&m.alt_titles),
+            m.series,
+            m.series_number,
+            m.page_count,
+            m.media_type.map(MediaType::as_str),
+            json_list(&m.genres),
+            json_map(&m.external_ids),
+            now_secs(),
+            book_id
+        ],
     )?;
     Ok(())
 }
 
 /// Atualiza apenas os campos fornecidos (usado pelo fluxo de metadados).
 /// Cada Option: Some(val) = atualizar; None = manter atual.
-/// Não altera flags de edição manual.
+/// Não altera flags de edição manual; os campos alterados entram no histórico
+/// com a origem `origin` ("auto:<fonte>", "candidate:<fonte>").
+#[allow(clippy::too_many_arguments)]
 pub fn update_book_partial(
     conn: &rusqlite::Connection,
     book_id: &str,
@@ -140,41 +309,169 @@ pub fn update_book_partial(
     author: Option<Option<&str>>,
     description: Option<Option<&str>>,
     cover_path: Option<Option<&str>>,
+    metadata: &BookMetadataPatch,
+    origin: &str,
 ) -> crate::Result<()> {
-    let current = conn.query_row(
-        "SELECT title, author, description, cover_path FROM books WHERE id = ?1",
-        [book_id],
-        |row| {
-            Ok((
-                row.get::<_, String>(0)?,
-                row.get::<_, Option<String>>(1)?,
-                row.get::<_, Option<String>>(2)?,
-                row.get::<_, Option<String>>(3)?,
-            ))
-        },
-    )?;
-    let new_title = title.unwrap_or(&current.0);
+    let Some(current) = get_book(conn, book_id)? else {
+        log::warn!(
+            "[repo] update_book_partial: nenhuma linha afetada para book_id={} (pode ser id incorreto)",
+            book_id
+        );
+        return Ok(());
+    };
+    let new_title = title.unwrap_or(&current.title);
     let new_author = match author {
         Some(a) => a,
-        None => current.1.as_deref(),
+        None => current.author.as_deref(),
     };
     let new_desc = match description {
         Some(d) => d,
-        None => current.2.as_deref(),
+        None => current.description.as_deref(),
     };
     let new_cover = match cover_path {
         Some(c) => c,
-        None => current.3.as_deref(),
+        None => current.cover_path.as_deref(),
     };
-    let rows = conn.execute(
-        "UPDATE books SET title = ?1, author = ?2, description = ?3, cover_path = ?4 WHERE id = ?5",
-        params![new_title, new_author, new_desc, new_cover, book_id],
-    )?;
-    if rows == 0 {
-        log::warn!(
-            "[repo] update_book_partial: nenhuma linha afetada para book_id={} (pode ser id incorreto)",
+    let mut m = current.metadata.clone();
+    metadata.apply(&mut m);
+    let tx = conn.unchecked_transaction()?;
+    tx.execute(
+        r#"
+        UPDATE books SET title = ?1, author = ?2, description = ?3, cover_path = ?4,
+            publisher = ?5, year = ?6, language = ?7, isbns = ?8, alt_titles = ?9, series = ?10,
+            series_number = ?11, page_count = ?12, media_type = ?13, genres = ?14,
+            external_ids = ?15, updated_at = ?16
+        WHERE id = ?17
+        "#,
+        params![
+            new_title,
+            new_author,
+            new_desc,
+            new_cover,
+            m.publisher,
+            m.year,
+            m.language,
+            json_list(&m.isbns),
+            json_list(&m.alt_titles),
+            m.series,
+            m.series_number,
+            m.page_count,
+            m.media_type.map(MediaType::as_str),
+            json_list(&m.genres),
+            json_map(&m.external_ids),
+            now_secs(),
             book_id
+        ],
+    )?;
+    let updated = Book {
+        title: new_title.to_string(),
+        author: new_author.map(str::to_string),
+        description: new_desc.map(str::to_string),
+        cover_path: new_cover.map(str::to_string),
+        metadata: m,
+        ..current.clone()
+    };
+    record_metadata_changes(&tx, &current, &updated, origin)?;
+    tx.commit()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::BookMetadataFlags;
+    use crate::repositories::test_support::{book, memory_db};
+    use crate::repositories::{get_metadata_flags, set_metadata_flags};
+
+    fn vagabond_metadata() -> BookMetadata {
+        let mut metadata = BookMetadata {
+            publisher: Some("Panini".to_string()),
+            series: Some("Vagabond".to_string()),
+            series_number: Some(3.0),
+            genres: vec!["Seinen".to_string()],
+            ..Default::default()
+        };
+        metadata
+            .external_ids
+            .insert("anilist".to_string(), "30656".to_string());
+        metadata
+    }
+
+    fn vagabond() -> rusqlite::Connection {
+        let conn = memory_db();
+        insert_book(
+            &conn,
+            &Book {
+                metadata: vagabond_metadata(),
+                ..book("b1", "Vagabond")
+            },
+        )
+        .unwrap();
+        conn
+    }
+
+    fn patch() -> BookMetadataPatch {
+        BookMetadataPatch {
+            year: Some(Some(1998)),
+            media_type: Some(Some(MediaType::Manga)),
+            external_ids: vec![("jikan".to_string(), "656".to_string())],
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn extended_metadata_round_trip() {
+        let conn = vagabond();
+        assert_eq!(
+            get_book(&conn, "b1").unwrap().unwrap().metadata,
+            vagabond_metadata()
+        );
+    }
+
+    #[test]
+    fn partial_update_keeps_other_fields() {
+        let conn = vagabond();
+        update_book_partial(&conn, "b1", None, None, None, None, &patch(), "manual").unwrap();
+        let m = get_book(&conn, "b1").unwrap().unwrap().metadata;
+        assert_eq!(m.publisher.as_deref(), Some("Panini"));
+        assert_eq!(m.year, Some(1998));
+        assert_eq!(m.media_type, Some(MediaType::Manga));
+        assert_eq!(m.external_ids.len(), 2);
+    }
+
+    #[test]
+    fn changed_fields_get_manual_edit_flags() {
+        let conn = vagabond();
+        update_book_partial(&conn, "b1", None, None, None, None, &patch(), "manual").unwrap();
+        let m = get_book(&conn, "b1").unwrap().unwrap().metadata;
+
+        let mut flags = BookMetadataFlags::default();
+        flags.mark_changed(&vagabond_metadata(), &m);
+        assert!(flags.year && flags.media_type && flags.external_ids && !flags.publisher);
+        set_metadata_flags(&conn, "b1", &flags).unwrap();
+        assert_eq!(get_metadata_flags(&conn, "b1").unwrap(), flags);
+    }
+
+    #[test]
+    fn manual_edit_flags_can_be_cleared() {
+        let conn = vagabond();
+        let flags = BookMetadataFlags {
+            title: true,
+            year: true,
+            ..Default::default()
+        };
+        set_metadata_flags(&conn, "b1", &flags).unwrap();
+
+        let cleared = BookMetadataFlags {
+            year: false,
+            ..flags
+        };
+        set_metadata_flags(&conn, "b1", &cleared).unwrap();
+        assert_eq!(get_metadata_flags(&conn, "b1").unwrap(), cleared);
+        set_metadata_flags(&conn, "b1", &BookMetadataFlags::default()).unwrap();
+        assert_eq!(
+            get_metadata_flags(&conn, "b1").unwrap(),
+            BookMetadataFlags::default()
         );
     }
-    Ok(())
 }
@@ -0,0 +1,123 @@
+//! Repositório de marcadores (bookmarks).
+
+use crate::models::Bookmark;
+use rusqlite::params;
+
+fn now_secs() -> i64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs() as i64)
+        .unwrap_or(0)
+}
+
+const BOOKMARK_COLUMNS: &str =
+    "id, book_id, volume_id, chapter_id, page_index, cfi, label, thumbnail, created_at, updated_at";
+
+fn bookmark_from_row(row: &rusqlite::Row) -> rusqlite::Result<Bookmark> {
+    Ok(Bookmark {
+        id: row.get(0)?,
+        book_id: row.get(1)?,
+        volume_id: row.get(2)?,
+        chapter_id: row.get(3)?,
+        page_index: row.get(4)?,
+        cfi: row.get(5)?,
+        label: row.get(6)?,
+        thumbnail: row.get(7)?,
+        created_at: row.get(8)?,
+        updated_at: row.get(9)?,
+    })
+}
+
+/// Insere ou substitui o marcador (mesmo id).
+pub fn upsert_bookmark(conn: &rusqlite::Connection, bookmark: &Bookmark) -> crate::Result<()> {
+    conn.execute(
+        r#"
+        INSERT INTO bookmarks (id, book_id, volume_id, chapter_id, page_index, cfi, label, thumbnail, created_at, updated_at)
+        VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)
+        ON CONFLICT(id) DO UPDATE SET
+            book_id = excluded.book_id,
+            volume_id = excluded.volume_id,
+            chapter_id = excluded.chapter_id,
+            page_index = excluded.page_index,
+            cfi = excluded.cfi,
+            label = excluded.label,
+            thumbnail = excluded.thumbnail,
+            created_at = excluded.created_at,
+            updated_at = excluded.updated_at
+        "#,
+        params![
+            bookmark.id,
+            bookmark.book_id,
+            bookmark.volume_id,
+            bookmark.chapter_id,
+            bookmark.page_index,
+            bookmark.cfi,
+            bookmark.label,
+            bookmark.thumbnail,
+            bookmark.created_at,
+            bookmark.updated_at,
+        ],
+    )?;
+    Ok(())
+}
+
+pub fn get_bookmark(conn: &rusqlite::Connection, id: &str) -> crate::Result<Option<Bookmark>> {
+    let mut stmt = conn.prepare(&format!(
+        "SELECT {} FROM bookmarks WHERE id = ?1",
+        BOOKMARK_COLUMNS
+    ))?;
+    match stmt.query_row([id], bookmark_from_row) {
+        Ok(b) => Ok(Some(b)),
+        Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// Marcadores do livro (ou só do volume), na ordem de leitura.
+pub fn list_bookmarks(
+    conn: &rusqlite::Connection,
+    book_id: &str,
+    volume_id: Option<&str>,
+) -> crate::Result<Vec<Bookmark>> {
+    let mut stmt = conn.prepare(&format!(
+        r#"
+        SELECT {} FROM bookmarks
+        WHERE book_id = ?1 AND (?2 IS NULL OR volume_id = ?2)
+        ORDER BY volume_id, page_index, cfi, created_at
+        "#,
+        BOOKMARK_COLUMNS
+    ))?;
+    let rows = stmt.query_map(params![book_id, volume_id], bookmark_from_row)?;
+    let mut out = Vec::new();
+    for row in rows {
+        out.push(row?);
+    }
+    Ok(out)
+}
+
+pub fn list_all_bookmarks(conn: &rusqlite::Connection) -> crate::Result<Vec<Bookmark>> {
+    let mut stmt = conn.prepare(&format!(
+        "SELECT {} FROM bookmarks ORDER BY book_id, volume_id, page_index, created_at",
+        BOOKMARK_COLUMNS
+    ))?;
+    let rows = stmt.query_map([], bookmark_from_row)?;
+    let mut out = Vec::new();
+    for row in rows {
+        out.push(row?);
+    }
+    Ok(out)
+}
+
+/// Retorna false se o marcador não existir.
+pub fn rename_bookmark(conn: &rusqlite::Connection, id: &str, label: &str) -> crate::Result<bool> {
+    let n = conn.execute(
+        "UPDATE bookmarks SET label = ?1, updated_at = ?2 WHERE id = ?3",
+        params![label, now_secs(), id],
+    )?;
+    Ok(n > 0)
+}
+
+pub fn delete_bookmark(conn: &rusqlite::Connection, id: &str) -> crate::Result<()> {
+    conn.execute("DELETE FROM bookmarks WHERE id = ?1", [id])?;
+    Ok(())
+}
@@ -0,0 +1,419 @@
+//! Repositório de progresso por capítulo (lido/não lido e última página).
+//! A ordem de leitura da série é a dos volumes por nome e dos capítulos por
+//! posição, a mesma de `list_volumes` / `list_chapters`.
+
+use rusqlite::params;
+
+use super::{mark_volumes, start_volume_if_unread};
+use crate::models::{
+    Chapter, ChapterProgress, ReadStatus, ReadingProgress, SeriesCompletion, VolumeCompletion,
+};
+
+/// Capítulos da série numerados na ordem de leitura (`?1` = book_id).
+const SERIES_ORDER_CTE: &str = r#"
+WITH series AS (
+    SELECT c.id, c.volume_id, c.name, c.path, c.position, v.book_id,
+        ROW_NUMBER() OVER (ORDER BY v.name, c.position) AS seq
+    FROM chapters c
+    JOIN volumes v ON v.id = c.volume_id
+    WHERE v.book_id = ?1
+)
+"#;
+
+const CHAPTER_PROGRESS_COLUMNS: &str =
+    "chapter_id, book_id, volume_id, read, last_page, read_at, updated_at";
+
+fn chapter_progress_from_row(row: &rusqlite::Row) -> rusqlite::Result<ChapterProgress> {
+    Ok(ChapterProgress {
+        chapter_id: row.get(0)?,
+        book_id: row.get(1)?,
+        volume_id: row.get(2)?,
+        read: row.get::<_, i64>(3)? != 0,
+        last_page: row.get(4)?,
+        read_at: row.get(5)?,
+        updated_at: row.get(6)?,
+    })
+}
+
+pub fn list_chapter_progress(
+    conn: &rusqlite::Connection,
+    book_id: &str,
+) -> crate::Result<Vec<ChapterProgress>> {
+    let mut stmt = conn.prepare(&format!(
+        "SELECT {} FROM chapter_progress WHERE book_id = ?1",
+        CHAPTER_PROGRESS_COLUMNS
+    ))?;
+    let rows = stmt.query_map([book_id], chapter_progress_from_row)?;
+    let mut out = Vec::new();
+    for row in rows {
+        out.push(row?);
+    }
+    Ok(out)
+}
+
+/// Marca (ou desmarca) capítulos como lidos. Retorna quantos mudaram.
+fn set_read(
+    conn: &rusqlite::Connection,
+    chapter_ids: &[String],
+    read: bool,
+    now: i64,
+) -> crate::Result<usize> {
+    let mut changed = 0;
+    for id in chapter_ids {
+        changed += conn.execute(
+            r#"
+            INSERT INTO chapter_progress (chapter_id, book_id, volume_id, read, read_at, updated_at)
+            SELECT c.id, v.book_id, c.volume_id, ?2, CASE WHEN ?2 THEN ?3 END, ?3
+            FROM chapters c JOIN volumes v ON v.id = c.volume_id
+            WHERE c.id = ?1
+            ON CONFLICT(chapter_id) DO UPDATE SET
+                read = excluded.read,
+                read_at = excluded.read_at,
+                updated_at = excluded.updated_at
+            WHERE chapter_progress.read <> excluded.read
+            "#,
+            params![id, read, now],
+        )?;
+    }
+    Ok(changed)
+}
+
+/// Volumes dos capítulos indicados, sem repetição.
+fn volumes_of(conn: &rusqlite::Connection, chapter_ids: &[String]) -> crate::Result<Vec<String>> {
+    let mut volumes: Vec<String> = Vec::new();
+    for id in chapter_ids {
+        match conn.query_row(
+            "SELECT volume_id FROM chapters WHERE id = ?1",
+            [id],
+            |row| row.get::<_, String>(0),
+        ) {
+            Ok(v) if !volumes.contains(&v) => volumes.push(v),
+            Ok(_) | Err(rusqlite::Error::QueryReturnedNoRows) => {}
+            Err(e) => return Err(e.into()),
+        }
+    }
+    Ok(volumes)
+}
+
+/// Leva o status dos volumes ao estado dos capítulos: todos lidos = concluído;
+/// algum lido = em leitura (se ainda não lido). Desmarcar capítulos não
+/// reabre volumes concluídos; isso fica com o status explícito.
+fn refresh_volumes_from_chapters(
+    conn: &rusqlite::Connection,
+    volume_ids: &[String],
+    now: i64,
+) -> crate::Result<()> {
+    let mut completed = Vec::new();
+    for volume_id in volume_ids {
+        let (total, read): (i64, i64) = conn.query_row(
+            r#"
+            SELECT COUNT(c.id), COALESCE(SUM(cp.read), 0)
+            FROM chapters c
+            LEFT JOIN chapter_progress cp ON cp.chapter_id = c.id
+            WHERE c.volume_id = ?1
+            "#,
+            [volume_id],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )?;
+        if total > 0 && read == total {
+            completed.push(volume_id.clone());
+        } else if read > 0 {
+            start_volume_if_unread(conn, volume_id, now)?;
+        }
+    }
+    if !completed.is_empty() {
+        mark_volumes(conn, &completed, ReadStatus::Completed, now)?;
+    }
+    Ok(())
+}
+
+/// `set_chapters_read` sem transação própria, para uso dentro de outra.
+fn update_chapters_read(
+    conn: &rusqlite::Connection,
+    chapter_ids: &[String],
+    read: bool,
+    now: i64,
+) -> crate::Result<usize> {
+    let changed = set_read(conn, chapter_ids, read, now)?;
+    if read {
+        refresh_volumes_from_chapters(conn, &volumes_of(conn, chapter_ids)?, now)?;
+    }
+    Ok(changed)
+}
+
+/// Marca capítulos como lidos ou não lidos e atualiza os volumes afetados,
+/// tudo numa transação.
+pub fn set_chapters_read(
+    conn: &rusqlite::Connection,
+    chapter_ids: &[String],
+    read: bool,
+    now: i64,
+) -> crate::Result<usize> {
+    let tx = conn.unchecked_transaction()?;
+    let changed = update_chapters_read(&tx, chapter_ids, read, now)?;
+    tx.commit()?;
+    Ok(changed)
+}
+
+/// Marca como lidos todos os capítulos da série antes de `chapter_id`
+/// (inclusive em volumes anteriores). Retorna quantos mudaram.
+pub fn mark_previous_chapters_read(
+    conn: &rusqlite::Connection,
+    chapter_id: &str,
+    now: i64,
+) -> crate::Result<usize> {
+    let book_id: String = match conn.query_row(
+        "SELECT v.book_id FROM chapters c JOIN volumes v ON v.id = c.volume_id WHERE c.id = ?1",
+        [chapter_id],
+        |row| row.get(0),
+    ) {
+        Ok(b) => b,
+        Err(rusqlite::Error::QueryReturnedNoRows) => return Ok(0),
+        Err(e) => return Err(e.into()),
+    };
+    let mut stmt = conn.prepare(&format!(
+        r#"{}
+        SELECT id FROM series
+        WHERE seq < (SELECT seq FROM series WHERE id = ?2)
+        "#,
+        SERIES_ORDER_CTE
+    ))?;
+    let rows = stmt.query_map(params![book_id, chapter_id], |row| row.get::<_, String>(0))?;
+    let mut ids = Vec::new();
+    for row in rows {
+        ids.push(row?);
+    }
+    set_chapters_read(conn, &ids, true, now)
+}
+
+/// Próximo capítulo não lido: o primeiro depois do último lido; se não houver,
+/// o primeiro não lido da série (capítulos pulados). None = série toda lida.
+pub fn next_unread_chapter(
+    conn: &rusqlite::Connection,
+    book_id: &str,
+) -> crate::Result<Option<Chapter>> {
+    let r = conn.query_row(
+        &format!(
+            r#"{},
+            state AS (
+                SELECT s.*, COALESCE(cp.read, 0) AS read
+                FROM series s LEFT JOIN chapter_progress cp ON cp.chapter_id = s.id
+            )
+            SELECT id, volume_id, name, path, position FROM state
+            WHERE read = 0
+            ORDER BY seq > COALESCE((SELECT MAX(seq) FROM state WHERE read = 1), 0) DESC, seq
+            LIMIT 1
+            "#,
+            SERIES_ORDER_CTE
+        ),
+        [book_id],
+        |row| {
+            Ok(Chapter {
+                id: row.get(0)?,
+                volume_id: row.get(1)?,
+                name: row.get(2)?,
+                path: row.get(3)?,
+                position: row.get(4)?,
+            })
+        },
+    );
+    match r {
+        Ok(c) => Ok(Some(c)),
+        Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// Conclusão da série e de cada volume pelos capítulos lidos.
+pub fn series_completion(
+    conn: &rusqlite::Connection,
+    book_id: &str,
+) -> crate::Result<SeriesCompletion> {
+    let mut stmt = conn.prepare(
+        r#"
+        SELECT v.id, COUNT(c.id), COALESCE(SUM(cp.read), 0)
+        FROM volumes v
+        LEFT JOIN chapters c ON c.volume_id = v.id
+        LEFT JOIN chapter_progress cp ON cp.chapter_id = c.id
+        WHERE v.book_id = ?1
+        GROUP BY v.id
+        ORDER BY v.name
+        "#,
+    )?;
+    let rows = stmt.query_map([book_id], |row| {
+        let chapters_total: i64 = row.get(1)?;
+        let chapters_read: i64 = row.get(2)?;
+        Ok(VolumeCompletion {
+            volume_id: row.get(0)?,
+            chapters_read,
+            chapters_total,
+            completed: chapters_total > 0 && chapters_read == chapters_total,
+        })
+    })?;
+    let mut volumes = Vec::new();
+    for row in rows {
+        volumes.push(row?);
+    }
+    let chapters_total: i64 = volumes.iter().map(|v| v.chapters_total).sum();
+    let chapters_read: i64 = volumes.iter().map(|v| v.chapters_read).sum();
+    Ok(SeriesCompletion {
+        book_id: book_id.to_string(),
+        chapters_read,
+        chapters_total,
+        percent: if chapters_total > 0 {
+            chapters_read as f64 * 100.0 / chapters_total as f64
+        } else {
+            0.0
+        },
+        completed: chapters_total > 0 && chapters_read == chapters_total,
+        volumes,
+    })
+}
+
+/// Registra a posição salva no capítulo atual. Se o leitor passou para um
+/// capítulo posterior do mesmo volume, o capítulo anterior conta como lido.
+/// Sem transação própria: roda dentro da de quem salva o progresso.
+pub fn record_chapter_position(
+    conn: &rusqlite::Connection,
+    previous: Option<&ReadingProgress>,
+    progress: &ReadingProgress,
+    now: i64,
+) -> crate::Result<()> {
+    let Some(chapter_id) = progress.current_chapter_id.as_deref() else {
+        return Ok(());
+    };
+    conn.execute(
+        r#"
+        INSERT INTO chapter_progress (chapter_id, book_id, volume_id, last_page, updated_at)
+        SELECT c.id, ?2, c.volume_id, ?3, ?4 FROM chapters c WHERE c.id = ?1
+        ON CONFLICT(chapter_id) DO UPDATE SET
+            last_page = excluded.last_page,
+            updated_at = excluded.updated_at
+        "#,
+        params![chapter_id, progress.book_id, progress.page_index, now],
+    )?;
+    let Some(left) = previous
+        .filter(|p| p.volume_id == progress.volume_id)
+        .and_then(|p| p.current_chapter_id.as_deref())
+        .filter(|id| *id != chapter_id)
+    else {
+        return Ok(());
+    };
+    let advanced: bool = conn
+        .query_row(
+            r#"
+            SELECT a.position < b.position FROM chapters a, chapters b
+            WHERE a.id = ?1 AND b.id = ?2 AND a.volume_id = b.volume_id
+            "#,
+            params![left, chapter_id],
+            |row| row.get(0),
+        )
+        .unwrap_or(false);
+    if advanced {
+        update_chapters_read(conn, &[left.to_string()], true, now)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::repositories::test_support::{book, memory_db, volume};
+    use crate::repositories::{get_book_read_state, insert_book, insert_chapter, insert_volume};
+
+    /// v1: c1, c2; v2: c3, c4
+    fn series() -> rusqlite::Connection {
+        let conn = memory_db();
+        insert_book(&conn, &book("b1", "One Piece")).unwrap();
+        for (v, chapters) in [("v1", ["c1", "c2"]), ("v2", ["c3", "c4"])] {
+            insert_volume(&conn, &volume(v, "b1", &format!("Volume {}", &v[1..]))).unwrap();
+            for (i, c) in chapters.iter().enumerate() {
+                insert_chapter(
+                    &conn,
+                    &Chapter {
+                        id: c.to_string(),
+                        volume_id: v.to_string(),
+                        name: format!("Capítulo {}", &c[1..]),
+                        path: format!("/manga/b1/{}/{}", v, c),
+                        position: i as i32,
+                    },
+                )
+                .unwrap();
+            }
+        }
+        conn
+    }
+
+    fn ids(ids: &[&str]) -> Vec<String> {
+        ids.iter().map(|id| id.to_string()).collect()
+    }
+
+    #[test]
+    fn marking_previous_chapters_completes_earlier_volumes() {
+        let conn = series();
+        assert_eq!(next_unread_chapter(&conn, "b1").unwrap().unwrap().id, "c1");
+        assert_eq!(mark_previous_chapters_read(&conn, "c3", 100).unwrap(), 2);
+        assert_eq!(next_unread_chapter(&conn, "b1").unwrap().unwrap().id, "c3");
+        let state = get_book_read_state(&conn, "b1").unwrap().unwrap();
+        assert_eq!(state.volumes[0].state.status, ReadStatus::Completed);
+        assert_eq!(state.state.status, ReadStatus::Reading);
+    }
+
+    #[test]
+    fn moving_to_a_later_chapter_marks_the_previous_one_read() {
+        let conn = series();
+        let at = |chapter: &str, page| ReadingProgress {
+            book_id: "b1".to_string(),
+            volume_id: "v2".to_string(),
+            current_chapter_id: Some(chapter.to_string()),
+            page_index: page,
+            scroll_offset: 0.0,
+            updated_at: 0,
+            total_pages: None,
+        };
+        record_chapter_position(&conn, None, &at("c3", 5), 200).unwrap();
+        record_chapter_position(&conn, Some(&at("c3", 5)), &at("c4", 21), 300).unwrap();
+        let progress = list_chapter_progress(&conn, "b1").unwrap();
+        let get = |id: &str| progress.iter().find(|p| p.chapter_id == id).unwrap();
+        assert!(get("c3").read);
+        assert_eq!((get("c4").read, get("c4").last_page), (false, Some(21)));
+    }
+
+    #[test]
+    fn next_unread_follows_the_last_read_chapter() {
+        let conn = series();
+        set_chapters_read(&conn, &ids(&["c2", "c3"]), true, 100).unwrap();
+        // c1 nunca lido: o próximo continua sendo depois do último lido
+        assert_eq!(next_unread_chapter(&conn, "b1").unwrap().unwrap().id, "c4");
+        let completion = series_completion(&conn, "b1").unwrap();
+        assert_eq!(
+            (completion.chapters_read, completion.chapters_total),
+            (2, 4)
+        );
+        assert_eq!(completion.percent, 50.0);
+        assert!(!completion.volumes[0].completed);
+
+        // Depois do último capítulo, volta ao primeiro que faltou
+        set_chapters_read(&conn, &ids(&["c4"]), true, 200).unwrap();
+        assert_eq!(next_unread_chapter(&conn, "b1").unwrap().unwrap().id, "c1");
+    }
+
+    #[test]
+    fn reading_every_chapter_completes_the_book() {
+        let conn = series();
+        set_chapters_read(&conn, &ids(&["c1", "c2", "c3", "c4"]), true, 100).unwrap();
+        assert!(next_unread_chapter(&conn, "b1").unwrap().is_none());
+        assert!(series_completion(&conn, "b1").unwrap().completed);
+        let state = get_book_read_state(&conn, "b1").unwrap().unwrap();
+        assert_eq!(state.state.status, ReadStatus::Completed);
+    }
+
+    #[test]
+    fn unmarking_chapters_keeps_completed_volumes() {
+        let conn = series();
+        set_chapters_read(&conn, &ids(&["c1", "c2"]), true, 100).unwrap();
+        set_chapters_read(&conn, &ids(&["c1"]), false, 200).unwrap();
+        let state = get_book_read_state(&conn, "b1").unwrap().unwrap();
+        assert_eq!(state.volumes[0].state.status, ReadStatus::Completed);
+    }
+}
@@ -0,0 +1,330 @@
+//! Repositório do histórico de leitura. Cada `record_history` acrescenta uma
+//! entrada, exceto quando continua a leitura da entrada mais recente.
+
+use rusqlite::params;
+use rusqlite::types::Value;
+
+use crate::models::{HistoryEntry, HistoryItem, HistoryPage, HistoryQuery, ReadingProgress};
+
+/// Avanços no mesmo volume dentro deste intervalo atualizam a última entrada
+/// em vez de criar outra.
+pub const HISTORY_COALESCE_SECS: i64 = 30 * 60;
+
+const HISTORY_COLUMNS: &str =
+    "h.id, h.book_id, h.volume_id, h.chapter_id, h.page_index, h.started_at, h.updated_at";
+
+fn entry_from_row(row: &rusqlite::Row) -> rusqlite::Result<HistoryEntry> {
+    Ok(HistoryEntry {
+        id: row.get(0)?,
+        book_id: row.get(1)?,
+        volume_id: row.get(2)?,
+        chapter_id: row.get(3)?,
+        page_index: row.get(4)?,
+        started_at: row.get(5)?,
+        updated_at: row.get(6)?,
+    })
+}
+
+fn latest_entry(
+    conn: &rusqlite::Connection,
+    volume_id: Option<&str>,
+) -> crate::Result<Option<HistoryEntry>> {
+    let r = conn.query_row(
+        &format!(
+            r#"
+            SELECT {} FROM reading_history h
+            WHERE ?1 IS NULL OR h.volume_id = ?1
+            ORDER BY h.updated_at DESC, h.started_at DESC
+            LIMIT 1
+            "#,
+            HISTORY_COLUMNS
+        ),
+        [volume_id],
+        entry_from_row,
+    );
+    match r {
+        Ok(e) => Ok(Some(e)),
+        Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// Registra a posição salva. Continua a entrada mais recente se for do mesmo
+/// volume e recente; ignora a posição se ela não mudou desde a última entrada
+/// do volume (ex.: só abriu e fechou). Retorna a entrada criada ou atualizada.
+pub fn record_history(
+    conn: &rusqlite::Connection,
+    progress: &ReadingProgress,
+    now: i64,
+) -> crate::Result<Option<HistoryEntry>> {
+    let same_position = |e: &HistoryEntry| {
+        e.page_index == progress.page_index && e.chapter_id == progress.current_chapter_id
+    };
+    if let Some(last) = latest_entry(conn, None)? {
+        if last.book_id == progress.book_id
+            && last.volume_id == progress.volume_id
+            && now - last.updated_at <= HISTORY_COALESCE_SECS
+        {
+            if same_position(&last) {
+                return Ok(None);
+            }
+            conn.execute(
+                "UPDATE reading_history SET chapter_id = ?1, page_index = ?2, updated_at = ?3 WHERE id = ?4",
+                params![progress.current_chapter_id, progress.page_index, now, last.id],
+            )?;
+            return Ok(Some(HistoryEntry {
+                chapter_id: progress.current_chapter_id.clone(),
+                page_index: progress.page_index,
+                updated_at: now,
+                ..last
+            }));
+        }
+    }
+    if latest_entry(conn, Some(&progress.volume_id))?
+        .as_ref()
+        .is_some_and(same_position)
+    {
+        return Ok(None);
+    }
+    let entry = HistoryEntry {
+        id: uuid::Uuid::new_v4().to_string(),
+        book_id: progress.book_id.clone(),
+        volume_id: progress.volume_id.clone(),
+        chapter_id: progress.current_chapter_id.clone(),
+        page_index: progress.page_index,
+        started_at: now,
+        updated_at: now,
+    };
+    conn.execute(
+        r#"
+        INSERT INTO reading_history (id, book_id, volume_id, chapter_id, page_index, started_at, updated_at)
+        VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)
+        "#,
+        params![
+            entry.id,
+            entry.book_id,
+            entry.volume_id,
+            entry.chapter_id,
+            entry.page_index,
+            entry.started_at,
+            entry.updated_at,
+        ],
+    )?;
+    Ok(Some(entry))
+}
+
+pub fn get_history_entry(
+    conn: &rusqlite::Connection,
+    id: &str,
+) -> crate::Result<Option<HistoryEntry>> {
+    let r = conn.query_row(
+        &format!(
+            "SELECT {} FROM reading_history h WHERE h.id = ?1",
+            HISTORY_COLUMNS
+        ),
+        [id],
+        entry_from_row,
+    );
+    match r {
+        Ok(e) => Ok(Some(e)),
+        Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// Linha do tempo, da entrada mais recente para a mais antiga. Entradas de
+/// livros removidos não aparecem.
+pub fn query_history(
+    conn: &rusqlite::Connection,
+    query: &HistoryQuery,
+) -> crate::Result<HistoryPage> {
+    let mut clauses: Vec<&str> = Vec::new();
+    let mut values: Vec<Value> = Vec::new();
+    if let Some(book_id) = &query.book_id {
+        clauses.push("h.book_id = ?");
+        values.push(Value::Text(book_id.clone()));
+    }
+    if let Some(from) = query.from {
+        clauses.push("h.updated_at >= ?");
+        values.push(Value::Integer(from));
+    }
+    if let Some(to) = query.to {
+        clauses.push("h.started_at <= ?");
+        values.push(Value::Integer(to));
+    }
+    let where_sql = if clauses.is_empty() {
+        String::new()
+    } else {
+        format!("WHERE {}", clauses.join(" AND "))
+    };
+    let from_sql = "FROM reading_history h JOIN books b ON b.id = h.book_id";
+
+    let total: i64 = conn.query_row(
+        &format!("SELECT COUNT(*) {} {}", from_sql, where_sql),
+        rusqlite::params_from_iter(values.iter()),
+        |row| row.get(0),
+    )?;
+
+    // LIMIT -1 no SQLite = sem limite
+    values.push(Value::Integer(if query.limit > 0 {
+        query.limit
+    } else {
+        -1
+    }));
+    values.push(Value::Integer(query.offset.max(0)));
+    let mut stmt = conn.prepare(&format!(
+        r#"
+        SELECT {}, b.title, b.cover_path, v.name, c.name {}
+        LEFT JOIN volumes v ON v.id = h.volume_id
+        LEFT JOIN chapters c ON c.id = h.chapter_id
+        {}
+        ORDER BY h.updated_at DESC, h.started_at DESC
+        LIMIT ? OFFSET ?
+        "#,
+        HISTORY_COLUMNS, from_sql, where_sql
+    ))?;
+    let rows = stmt.query_map(rusqlite::params_from_iter(values.iter()), |row| {
+        Ok(HistoryItem {
+            entry: entry_from_row(row)?,
+            book_title: row.get(7)?,
+            cover_path: row.get(8)?,
+            volume_name: row.get(9)?,
+            chapter_name: row.get(10)?,
+        })
+    })?;
+    let mut items = Vec::new();
+    for row in rows {
+        items.push(row?);
+    }
+    Ok(HistoryPage {
+        items,
+        total,
+        offset: query.offset.max(0),
+        limit: query.limit,
+    })
+}
+
+/// Remove as entradas indicadas. Retorna quantas existiam.
+pub fn delete_history_entries(conn: &rusqlite::Connection, ids: &[String]) -> crate::Result<usize> {
+    let tx = conn.unchecked_transaction()?;
+    let mut deleted = 0;
+    for id in ids {
+        deleted += tx.execute("DELETE FROM reading_history WHERE id = ?1", [id])?;
+    }
+    tx.commit()?;
+    Ok(deleted)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::repositories::test_support::{book, memory_db, volume};
+    use crate::repositories::{insert_book, insert_volume};
+
+    const T: i64 = 1_000_000;
+    const DAY: i64 = 86_400;
+
+    fn progress(volume_id: &str, page_index: i32) -> ReadingProgress {
+        ReadingProgress {
+            book_id: "b1".to_string(),
+            volume_id: volume_id.to_string(),
+            current_chapter_id: None,
+            page_index,
+            scroll_offset: 0.0,
+            updated_at: 0,
+            total_pages: None,
+        }
+    }
+
+    fn library() -> rusqlite::Connection {
+        let conn = memory_db();
+        insert_book(&conn, &book("b1", "Monster")).unwrap();
+        insert_volume(&conn, &volume("v1", "b1", "Volume 1")).unwrap();
+        insert_volume(&conn, &volume("v2", "b1", "Volume 2")).unwrap();
+        conn
+    }
+
+    /// Quatro entradas: v1 (pág. 9) e v2 (3) no primeiro dia, v1 (12) logo
+    /// depois e v1 (20) dois dias mais tarde.
+    fn timeline(conn: &rusqlite::Connection) {
+        record_history(conn, &progress("v1", 2), T).unwrap();
+        record_history(conn, &progress("v1", 9), T + 600).unwrap();
+        record_history(conn, &progress("v2", 3), T + 700).unwrap();
+        record_history(conn, &progress("v1", 12), T + 800).unwrap();
+        record_history(conn, &progress("v1", 20), T + 2 * DAY).unwrap();
+    }
+
+    #[test]
+    fn nearby_progress_updates_the_same_entry() {
+        let conn = library();
+        let first = record_history(&conn, &progress("v1", 2), T)
+            .unwrap()
+            .unwrap();
+        let same = record_history(&conn, &progress("v1", 9), T + 600)
+            .unwrap()
+            .unwrap();
+        assert_eq!((same.id.as_str(), same.page_index), (first.id.as_str(), 9));
+
+        // Outro volume abre nova entrada; voltar ao v1 depois também
+        let other = record_history(&conn, &progress("v2", 3), T + 700)
+            .unwrap()
+            .unwrap();
+        let back = record_history(&conn, &progress("v1", 12), T + 800)
+            .unwrap()
+            .unwrap();
+        assert_ne!(other.id, first.id);
+        assert_ne!(back.id, first.id);
+    }
+
+    #[test]
+    fn unchanged_position_is_not_recorded_again() {
+        let conn = library();
+        record_history(&conn, &progress("v1", 12), T).unwrap();
+        assert!(record_history(&conn, &progress("v1", 12), T + DAY)
+            .unwrap()
+            .is_none());
+    }
+
+    #[test]
+    fn query_pages_newest_first_within_dates() {
+        let conn = library();
+        timeline(&conn);
+
+        let all = query_history(&conn, &HistoryQuery::default()).unwrap();
+        assert_eq!(all.total, 4);
+        let pages: Vec<i32> = all.items.iter().map(|i| i.entry.page_index).collect();
+        assert_eq!(pages, vec![20, 12, 3, 9]);
+        assert_eq!(all.items[2].volume_name.as_deref(), Some("Volume 2"));
+
+        let first_day = query_history(
+            &conn,
+            &HistoryQuery {
+                to: Some(T + DAY - 1),
+                limit: 2,
+                offset: 1,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        assert_eq!(first_day.total, 3);
+        assert_eq!(first_day.items.len(), 2);
+        assert_eq!(first_day.items[0].entry.page_index, 3);
+    }
+
+    #[test]
+    fn deletes_selected_entries() {
+        let conn = library();
+        timeline(&conn);
+        let all = query_history(&conn, &HistoryQuery::default()).unwrap();
+
+        let ids: Vec<String> = all.items[..2].iter().map(|i| i.entry.id.clone()).collect();
+        assert_eq!(delete_history_entries(&conn, &ids).unwrap(), 2);
+        assert!(get_history_entry(&conn, &ids[0]).unwrap().is_none());
+        assert_eq!(
+            query_history(&conn, &HistoryQuery::default())
+                .unwrap()
+                .total,
+            2
+        );
+    }
+}
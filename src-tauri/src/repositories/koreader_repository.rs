@@ -0,0 +1,261 @@
+//! Repositório do KOReader: configuração, cache de hash dos documentos e
+//! armazenamento do servidor kosync embutido.
+
+use super::sync_repository::{get_sync_meta, set_sync_meta};
+use crate::models::{KoreaderConfig, KoreaderProgress};
+use rusqlite::params;
+
+/// Extensões que o KOReader abre e que o Readito importa como livro de arquivo único.
+const KOREADER_EXTENSIONS: &[&str] = &["epub", "pdf", "cbz", "cbr", "zip"];
+
+fn now_secs() -> i64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs() as i64)
+        .unwrap_or(0)
+}
+
+pub fn get_koreader_config(conn: &rusqlite::Connection) -> crate::Result<KoreaderConfig> {
+    let defaults = KoreaderConfig::default();
+    Ok(KoreaderConfig {
+        server_url: get_sync_meta(conn, "koreader_server_url")?,
+        username: get_sync_meta(conn, "koreader_username")?,
+        userkey: get_sync_meta(conn, "koreader_userkey")?,
+        match_method: get_sync_meta(conn, "koreader_match_method")?
+            .unwrap_or(defaults.match_method),
+        interval_secs: get_sync_meta(conn, "koreader_interval_secs")?
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(defaults.interval_secs),
+        last_sync_at: get_sync_meta(conn, "koreader_last_at")?.and_then(|v| v.parse().ok()),
+        server_enabled: get_sync_meta(conn, "koreader_server_enabled")?.as_deref() == Some("1"),
+        server_port: get_sync_meta(conn, "koreader_server_port")?
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(defaults.server_port),
+        server_lan: get_sync_meta(conn, "koreader_server_lan")?.as_deref() == Some("1"),
+        server_registration: get_sync_meta(conn, "koreader_server_registration")?.as_deref()
+            == Some("1"),
+    })
+}
+
+pub fn save_koreader_config(
+    conn: &rusqlite::Connection,
+    config: &KoreaderConfig,
+) -> crate::Result<()> {
+    set_sync_meta(conn, "koreader_server_url", config.server_url.as_deref())?;
+    set_sync_meta(conn, "koreader_username", config.username.as_deref())?;
+    set_sync_meta(conn, "koreader_userkey", config.userkey.as_deref())?;
+    set_sync_meta(conn, "koreader_match_method", Some(&config.match_method))?;
+    set_sync_meta(
+        conn,
+        "koreader_interval_secs",
+        Some(&config.interval_secs.max(0).to_string()),
+    )?;
+    set_sync_meta(
+        conn,
+        "koreader_server_enabled",
+        Some(if config.server_enabled { "1" } else { "0" }),
+    )?;
+    set_sync_meta(
+        conn,
+        "koreader_server_port",
+        Some(&config.server_port.to_string()),
+    )?;
+    set_sync_meta(
+        conn,
+        "koreader_server_lan",
+        Some(if config.server_lan { "1" } else { "0" }),
+    )?;
+    set_sync_meta(
+        conn,
+        "koreader_server_registration",
+        Some(if config.server_registration { "1" } else { "0" }),
+    )?;
+    Ok(())
+}
+
+pub fn set_koreader_last_sync(conn: &rusqlite::Connection, at: i64) -> crate::Result<()> {
+    set_sync_meta(conn, "koreader_last_at", Some(&at.to_string()))
+}
+
+/// Volume que corresponde a um único arquivo legível pelo KOReader.
+#[derive(Debug, Clone)]
+pub struct KoreaderVolume {
+    pub book_id: String,
+    pub volume_id: String,
+    pub path: String,
+}
+
+/// Volumes de capítulo único cujo arquivo é um documento (EPUB, PDF, CBZ...).
+/// Pastas de imagens não existem do lado do KOReader.
+pub fn list_koreader_volumes(conn: &rusqlite::Connection) -> crate::Result<Vec<KoreaderVolume>> {
+    let mut stmt = conn.prepare(
+        r#"
+        SELECT v.book_id, v.id, MIN(c.path)
+        FROM volumes v
+        JOIN chapters c ON c.volume_id = v.id
+        GROUP BY v.id
+        HAVING COUNT(c.id) = 1
+        "#,
+    )?;
+    let rows = stmt.query_map([], |row| {
+        Ok(KoreaderVolume {
+            book_id: row.get(0)?,
+            volume_id: row.get(1)?,
+            path: row.get(2)?,
+        })
+    })?;
+    let mut out = Vec::new();
+    for row in rows {
+        let volume = row?;
+        let ext = std::path::Path::new(&volume.path)
+            .extension()
+            .and_then(|e| e.to_str())
+            .map(|e| e.to_lowercase())
+            .unwrap_or_default();
+        if KOREADER_EXTENSIONS.contains(&ext.as_str()) {
+            out.push(volume);
+        }
+    }
+    Ok(out)
+}
+
+/// Hash em cache, válido apenas se caminho, tamanho, mtime e método forem os mesmos.
+pub fn get_cached_document(
+    conn: &rusqlite::Connection,
+    volume_id: &str,
+    path: &str,
+    file_size: i64,
+    file_mtime: i64,
+    method: &str,
+) -> crate::Result<Option<String>> {
+    let row = conn.query_row(
+        r#"
+        SELECT document FROM koreader_documents
+        WHERE volume_id = ?1 AND path = ?2 AND file_size = ?3 AND file_mtime = ?4 AND method = ?5
+        "#,
+        params![volume_id, path, file_size, file_mtime, method],
+        |row| row.get(0),
+    );
+    match row {
+        Ok(v) => Ok(Some(v)),
+        Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+        Err(e) => Err(e.into()),
+    }
+}
+
+pub fn save_cached_document(
+    conn: &rusqlite::Connection,
+    volume: &KoreaderVolume,
+    file_size: i64,
+    file_mtime: i64,
+    method: &str,
+    document: &str,
+) -> crate::Result<()> {
+    conn.execute(
+        r#"
+        INSERT OR REPLACE INTO koreader_documents
+            (volume_id, book_id, path, file_size, file_mtime, method, document)
+        VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)
+        "#,
+        params![
+            volume.volume_id,
+            volume.book_id,
+            volume.path,
+            file_size,
+            file_mtime,
+            method,
+            document
+        ],
+    )?;
+    Ok(())
+}
+
+/// Cria um usuário do servidor embutido. false se o nome já existir.
+pub fn create_koreader_user(
+    conn: &rusqlite::Connection,
+    username: &str,
+    userkey: &str,
+) -> crate::Result<bool> {
+    let inserted = conn.execute(
+        "INSERT OR IGNORE INTO koreader_users (username, userkey, created_at) VALUES (?1, ?2, ?3)",
+        params![username, userkey, now_secs()],
+    )?;
+    Ok(inserted > 0)
+}
+
+pub fn get_koreader_userkey(
+    conn: &rusqlite::Connection,
+    username: &str,
+) -> crate::Result<Option<String>> {
+    let row = conn.query_row(
+        "SELECT userkey FROM koreader_users WHERE username = ?1",
+        [username],
+        |row| row.get(0),
+    );
+    match row {
+        Ok(v) => Ok(Some(v)),
+        Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+        Err(e) => Err(e.into()),
+    }
+}
+
+pub fn get_koreader_progress(
+    conn: &rusqlite::Connection,
+    username: &str,
+    document: &str,
+) -> crate::Result<Option<KoreaderProgress>> {
+    let row = conn.query_row(
+        r#"
+        SELECT document, progress, percentage, device, device_id, timestamp
+        FROM koreader_progress WHERE username = ?1 AND document = ?2
+        "#,
+        params![username, document],
+        |row| {
+            Ok(KoreaderProgress {
+                document: row.get(0)?,
+                progress: row.get(1)?,
+                percentage: row.get(2)?,
+                device: row.get(3)?,
+                device_id: row.get(4)?,
+                timestamp: row.get(5)?,
+            })
+        },
+    );
+    match row {
+        Ok(v) => Ok(Some(v)),
+        Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// Grava a posição recebida pelo servidor embutido e devolve o timestamp atribuído.
+pub fn upsert_koreader_progress(
+    conn: &rusqlite::Connection,
+    username: &str,
+    p: &KoreaderProgress,
+) -> crate::Result<i64> {
+    let timestamp = now_secs();
+    conn.execute(
+        r#"
+        INSERT INTO koreader_progress
+            (username, document, progress, percentage, device, device_id, timestamp)
+        VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)
+        ON CONFLICT(username, document) DO UPDATE SET
+            progress = excluded.progress,
+            percentage = excluded.percentage,
+            device = excluded.device,
+            device_id = excluded.device_id,
+            timestamp = excluded.timestamp
+        "#,
+        params![
+            username,
+            p.document,
+            p.progress,
+            p.percentage,
+            p.device,
+            p.device_id,
+            timestamp
+        ],
+    )?;
+    Ok(timestamp)
+}
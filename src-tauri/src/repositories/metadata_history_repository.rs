@@ -0,0 +1,246 @@
+//! Histórico campo a campo das alterações de metadados, e o desfazer.
+//! Os valores são gravados em JSON, com o mesmo nome do campo no JSON do livro.
+
+use super::{get_book, update_book, update_book_metadata};
+use crate::models::{Book, MetadataChange};
+use rusqlite::params;
+use serde_json::Value;
+
+/// Campos acompanhados; o resto do livro (caminho, hash...) não é metadado.
+const TRACKED_FIELDS: [&str; 15] = [
+    "title",
+    "author",
+    "description",
+    "cover_path",
+    "publisher",
+    "year",
+    "language",
+    "isbns",
+    "alt_titles",
+    "series",
+    "series_number",
+    "page_count",
+    "media_type",
+    "genres",
+    "external_ids",
+];
+
+fn now_secs() -> i64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs() as i64)
+        .unwrap_or(0)
+}
+
+fn book_fields(book: &Book) -> crate::Result<serde_json::Map<String, Value>> {
+    match serde_json::to_value(book) {
+        Ok(Value::Object(map)) => Ok(map),
+        Ok(_) => Ok(serde_json::Map::new()),
+        Err(e) => Err(crate::Error::Db(format!("metadata history: {}", e))),
+    }
+}
+
+/// Campo ausente no JSON (Option omitida) conta como null.
+fn field_value(fields: &serde_json::Map<String, Value>, field: &str) -> Value {
+    fields.get(field).cloned().unwrap_or(Value::Null)
+}
+
+fn change_from_row(row: &rusqlite::Row) -> rusqlite::Result<MetadataChange> {
+    let parse = |s: String| serde_json::from_str(&s).unwrap_or(Value::Null);
+    Ok(MetadataChange {
+        id: row.get(0)?,
+        book_id: row.get(1)?,
+        batch_id: row.get(2)?,
+        field: row.get(3)?,
+        old_value: parse(row.get(4)?),
+        new_value: parse(row.get(5)?),
+        origin: row.get(6)?,
+        changed_at: row.get(7)?,
+    })
+}
+
+const CHANGE_COLUMNS: &str =
+    "id, book_id, batch_id, field, old_value, new_value, origin, changed_at";
+
+/// Grava uma linha por campo que difere entre `before` e `after`, todas no
+/// mesmo lote. Retorna quantos campos mudaram.
+pub fn record_metadata_changes(
+    conn: &rusqlite::Connection,
+    before: &Book,
+    after: &Book,
+    origin: &str,
+) -> crate::Result<usize> {
+    let old = book_fields(before)?;
+    let new = book_fields(after)?;
+    let batch_id = uuid::Uuid::new_v4().to_string();
+    let now = now_secs();
+    let mut count = 0;
+    for field in TRACKED_FIELDS {
+        let (old_value, new_value) = (field_value(&old, field), field_value(&new, field));
+        if old_value == new_value {
+            continue;
+        }
+        conn.execute(
+            r#"
+            INSERT INTO metadata_changes (id, book_id, batch_id, field, old_value, new_value,
+                origin, changed_at)
+            VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)
+            "#,
+            params![
+                uuid::Uuid::new_v4().to_string(),
+                after.id,
+                batch_id,
+                field,
+                old_value.to_string(),
+                new_value.to_string(),
+                origin,
+                now
+            ],
+        )?;
+        count += 1;
+    }
+    Ok(count)
+}
+
+/// Histórico do livro, do mais recente ao mais antigo.
+pub fn list_metadata_changes(
+    conn: &rusqlite::Connection,
+    book_id: &str,
+) -> crate::Result<Vec<MetadataChange>> {
+    let mut stmt = conn.prepare(&format!(
+        "SELECT {} FROM metadata_changes WHERE book_id = ?1 ORDER BY changed_at DESC, rowid DESC",
+        CHANGE_COLUMNS
+    ))?;
+    let rows = stmt.query_map([book_id], change_from_row)?;
+    let mut out = Vec::new();
+    for row in rows {
+        out.push(row?);
+    }
+    Ok(out)
+}
+
+pub fn get_metadata_change(
+    conn: &rusqlite::Connection,
+    id: &str,
+) -> crate::Result<Option<MetadataChange>> {
+    let r = conn.query_row(
+        &format!(
+            "SELECT {} FROM metadata_changes WHERE id = ?1",
+            CHANGE_COLUMNS
+        ),
+        [id],
+        change_from_row,
+    );
+    match r {
+        Ok(change) => Ok(Some(change)),
+        Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// Devolve o campo ao valor anterior à alteração `change_id`, mesmo que ele
+/// tenha mudado depois. O desfazer também entra no histórico (origem "revert").
+/// Retorna o campo restaurado, ou None se a alteração não for deste livro.
+pub fn revert_metadata(
+    conn: &rusqlite::Connection,
+    book_id: &str,
+    change_id: &str,
+) -> crate::Result<Option<String>> {
+    let Some(change) = get_metadata_change(conn, change_id)?.filter(|c| c.book_id == book_id)
+    else {
+        return Ok(None);
+    };
+    let Some(before) = get_book(conn, book_id)? else {
+        return Ok(None);
+    };
+    let mut fields = book_fields(&before)?;
+    fields.insert(change.field.clone(), change.old_value.clone());
+    let after: Book = serde_json::from_value(Value::Object(fields))
+        .map_err(|e| crate::Error::Validation(format!("cannot restore {}: {}", change.field, e)))?;
+
+    let tx = conn.unchecked_transaction()?;
+    update_book(
+        &tx,
+        book_id,
+        &after.title,
+        after.author.as_deref(),
+        after.description.as_deref(),
+        after.cover_path.as_deref(),
+    )?;
+    update_book_metadata(&tx, book_id, &after.metadata)?;
+    record_metadata_changes(&tx, &before, &after, "revert")?;
+    tx.commit()?;
+    Ok(Some(change.field))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{BookMetadata, BookMetadataPatch};
+    use crate::repositories::{insert_book, update_book_partial};
+
+    #[test]
+    fn reverts_a_bad_auto_apply_field_by_field() {
+        let conn = rusqlite::Connection::open_in_memory().unwrap();
+        crate::db::run_migrations(&conn).unwrap();
+        insert_book(
+            &conn,
+            &Book {
+                id: "b1".to_string(),
+                title: "Monster".to_string(),
+                path: "/manga/monster".to_string(),
+                book_type: "folder".to_string(),
+                added_at: 0,
+                hash: None,
+                author: Some("Naoki Urasawa".to_string()),
+                description: None,
+                cover_path: None,
+                metadata: BookMetadata {
+                    year: Some(1994),
+                    ..Default::default()
+                },
+            },
+        )
+        .unwrap();
+
+        let patch = BookMetadataPatch {
+            year: Some(Some(2004)),
+            genres: Some(vec!["Horror".to_string()]),
+            ..Default::default()
+        };
+        update_book_partial(
+            &conn,
+            "b1",
+            Some("Monster (Anime)"),
+            Some(Some("Madhouse")),
+            None,
+            None,
+            &patch,
+            "auto:anilist",
+        )
+        .unwrap();
+        let changes = list_metadata_changes(&conn, "b1").unwrap();
+        assert_eq!(changes.len(), 4);
+        assert!(changes.iter().all(|c| c.batch_id == changes[0].batch_id));
+        assert!(changes.iter().all(|c| c.origin == "auto:anilist"));
+
+        for field in ["title", "author", "year", "genres"] {
+            let change = changes.iter().find(|c| c.field == field).unwrap();
+            assert_eq!(
+                revert_metadata(&conn, "b1", &change.id).unwrap().as_deref(),
+                Some(field)
+            );
+        }
+        let book = get_book(&conn, "b1").unwrap().unwrap();
+        assert_eq!(book.title, "Monster");
+        assert_eq!(book.author.as_deref(), Some("Naoki Urasawa"));
+        assert_eq!(book.metadata.year, Some(1994));
+        assert!(book.metadata.genres.is_empty());
+
+        let history = list_metadata_changes(&conn, "b1").unwrap();
+        assert_eq!(history.len(), 8);
+        assert_eq!(history[0].origin, "revert");
+        // Alteração de outro livro não é aplicada
+        assert_eq!(revert_metadata(&conn, "b2", &changes[0].id).unwrap(), None);
+    }
+}
@@ -0,0 +1,345 @@
+//! Repositório do enriquecimento de metadados em lote.
+//! Só existe um lote ativo (em execução ou pausado) por vez.
+
+use crate::metadata::RankedCandidate;
+use crate::models::{JobItemStatus, JobStatus, MetadataJob, MetadataJobItem};
+use rusqlite::params;
+
+fn now_secs() -> i64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs() as i64)
+        .unwrap_or(0)
+}
+
+const JOB_SELECT: &str = r#"
+    SELECT j.id, j.status, j.created_at, j.updated_at,
+        COUNT(i.book_id),
+        COALESCE(SUM(i.status = 'pending'), 0),
+        COALESCE(SUM(i.status = 'applied'), 0),
+        COALESCE(SUM(i.status = 'review'), 0),
+        COALESCE(SUM(i.status = 'no_match'), 0),
+        COALESCE(SUM(i.status = 'failed'), 0)
+    FROM metadata_jobs j
+    LEFT JOIN metadata_job_items i ON i.job_id = j.id
+"#;
+
+fn job_from_row(row: &rusqlite::Row) -> rusqlite::Result<MetadataJob> {
+    let status: String = row.get(1)?;
+    Ok(MetadataJob {
+        id: row.get(0)?,
+        status: JobStatus::parse(&status).unwrap_or(JobStatus::Cancelled),
+        created_at: row.get(2)?,
+        updated_at: row.get(3)?,
+        total: row.get(4)?,
+        pending: row.get(5)?,
+        applied: row.get(6)?,
+        review: row.get(7)?,
+        no_match: row.get(8)?,
+        failed: row.get(9)?,
+    })
+}
+
+fn query_job(
+    conn: &rusqlite::Connection,
+    filter: &str,
+    params: &[&dyn rusqlite::ToSql],
+) -> crate::Result<Option<MetadataJob>> {
+    let sql = format!(
+        "{} {} GROUP BY j.id ORDER BY j.created_at DESC, j.rowid DESC LIMIT 1",
+        JOB_SELECT, filter
+    );
+    match conn.query_row(&sql, params, job_from_row) {
+        Ok(job) => Ok(Some(job)),
+        Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+        Err(e) => Err(e.into()),
+    }
+}
+
+pub fn get_metadata_job(
+    conn: &rusqlite::Connection,
+    id: &str,
+) -> crate::Result<Option<MetadataJob>> {
+    query_job(conn, "WHERE j.id = ?1", &[&id])
+}
+
+/// Lote mais recente, em qualquer situação.
+pub fn latest_metadata_job(conn: &rusqlite::Connection) -> crate::Result<Option<MetadataJob>> {
+    query_job(conn, "", &[])
+}
+
+/// Lote em execução ou pausado, se houver.
+pub fn active_metadata_job(conn: &rusqlite::Connection) -> crate::Result<Option<MetadataJob>> {
+    query_job(conn, "WHERE j.status IN ('running', 'paused')", &[])
+}
+
+/// Cria um lote com todos os livros sem metadados confirmados (nenhuma busca
+/// aplicada e confirmada), na ordem em que entraram na biblioteca. Se já houver
+/// um lote ativo, ele é retornado no lugar.
+pub fn create_metadata_job(conn: &rusqlite::Connection) -> crate::Result<MetadataJob> {
+    if let Some(job) = active_metadata_job(conn)? {
+        return Ok(job);
+    }
+    let id = uuid::Uuid::new_v4().to_string();
+    let now = now_secs();
+    let tx = conn.unchecked_transaction()?;
+    tx.execute(
+        "INSERT INTO metadata_jobs (id, status, created_at, updated_at) VALUES (?1, 'running', ?2, ?2)",
+        params![id, now],
+    )?;
+    let added = tx.execute(
+        r#"
+        INSERT INTO metadata_job_items (job_id, book_id, position, updated_at)
+        SELECT ?1, b.id, ROW_NUMBER() OVER (ORDER BY b.added_at, b.rowid), ?2
+        FROM books b
+        WHERE NOT EXISTS (
+            SELECT 1 FROM metadata_search_results r
+            WHERE r.book_id = b.id AND r.applied = 1 AND r.confirmed = 1
+        )
+        "#,
+        params![id, now],
+    )?;
+    if added == 0 {
+        tx.execute(
+            "UPDATE metadata_jobs SET status = 'completed' WHERE id = ?1",
+            [&id],
+        )?;
+    }
+    tx.commit()?;
+    get_metadata_job(conn, &id)?
+        .ok_or_else(|| crate::Error::NotFound(format!("metadata job {}", id)))
+}
+
+/// Retorna false se o lote não existir.
+pub fn set_job_status(
+    conn: &rusqlite::Connection,
+    id: &str,
+    status: JobStatus,
+) -> crate::Result<bool> {
+    let n = conn.execute(
+        "UPDATE metadata_jobs SET status = ?1, updated_at = ?2 WHERE id = ?3",
+        params![status.as_str(), now_secs(), id],
+    )?;
+    Ok(n > 0)
+}
+
+/// Próximo livro a processar, na ordem do lote.
+pub fn next_pending_item(
+    conn: &rusqlite::Connection,
+    job_id: &str,
+) -> crate::Result<Option<String>> {
+    let r = conn.query_row(
+        r#"
+        SELECT book_id FROM metadata_job_items
+        WHERE job_id = ?1 AND status = 'pending'
+        ORDER BY position LIMIT 1
+        "#,
+        [job_id],
+        |row| row.get(0),
+    );
+    match r {
+        Ok(id) => Ok(Some(id)),
+        Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// Grava o resultado de um livro. Os candidatos só são guardados em revisão.
+pub fn finish_job_item(
+    conn: &rusqlite::Connection,
+    job_id: &str,
+    book_id: &str,
+    status: JobItemStatus,
+    score: Option<f32>,
+    candidates: &[RankedCandidate],
+    error: Option<&str>,
+) -> crate::Result<()> {
+    let candidates_json = if status == JobItemStatus::Review {
+        Some(serde_json::to_string(candidates).map_err(|e| crate::Error::Db(e.to_string()))?)
+    } else {
+        None
+    };
+    let now = now_secs();
+    conn.execute(
+        r#"
+        UPDATE metadata_job_items
+        SET status = ?1, score = ?2, candidates_json = ?3, error = ?4, updated_at = ?5
+        WHERE job_id = ?6 AND book_id = ?7
+        "#,
+        params![
+            status.as_str(),
+            score,
+            candidates_json,
+            error,
+            now,
+            job_id,
+            book_id
+        ],
+    )?;
+    conn.execute(
+        "UPDATE metadata_jobs SET updated_at = ?1 WHERE id = ?2",
+        params![now, job_id],
+    )?;
+    Ok(())
+}
+
+/// Itens do lote na ordem de processamento, opcionalmente só de uma situação.
+pub fn list_job_items(
+    conn: &rusqlite::Connection,
+    job_id: &str,
+    status: Option<JobItemStatus>,
+) -> crate::Result<Vec<MetadataJobItem>> {
+    let mut stmt = conn.prepare(
+        r#"
+        SELECT i.job_id, i.book_id, b.title, i.status, i.score, i.candidates_json, i.error,
+            i.updated_at
+        FROM metadata_job_items i
+        JOIN books b ON b.id = i.book_id
+        WHERE i.job_id = ?1 AND (?2 IS NULL OR i.status = ?2)
+        ORDER BY i.position
+        "#,
+    )?;
+    let rows = stmt.query_map(params![job_id, status.map(JobItemStatus::as_str)], |row| {
+        let status: String = row.get(3)?;
+        let candidates: Option<String> = row.get(5)?;
+        Ok(MetadataJobItem {
+            job_id: row.get(0)?,
+            book_id: row.get(1)?,
+            title: row.get(2)?,
+            status: JobItemStatus::parse(&status).unwrap_or(JobItemStatus::Failed),
+            score: row.get(4)?,
+            candidates: candidates
+                .and_then(|json| serde_json::from_str(&json).ok())
+                .unwrap_or_default(),
+            error: row.get(6)?,
+            updated_at: row.get(7)?,
+        })
+    })?;
+    let mut out = Vec::new();
+    for row in rows {
+        out.push(row?);
+    }
+    Ok(out)
+}
+
+/// Fecha as revisões pendentes do livro (em qualquer lote) com `status`.
+/// Retorna quantas foram fechadas.
+pub fn resolve_review_items(
+    conn: &rusqlite::Connection,
+    book_id: &str,
+    status: JobItemStatus,
+) -> crate::Result<usize> {
+    let n = conn.execute(
+        r#"
+        UPDATE metadata_job_items SET status = ?1, candidates_json = NULL, updated_at = ?2
+        WHERE book_id = ?3 AND status = 'review'
+        "#,
+        params![status.as_str(), now_secs(), book_id],
+    )?;
+    Ok(n)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::metadata::{MediaType, MetadataCandidate};
+    use crate::models::Book;
+    use crate::repositories::{insert_book, insert_search_result};
+
+    fn add_book(conn: &rusqlite::Connection, id: &str, added_at: i64) {
+        insert_book(
+            conn,
+            &Book {
+                id: id.to_string(),
+                title: id.to_uppercase(),
+                path: format!("/manga/{}", id),
+                book_type: "folder".to_string(),
+                added_at,
+                hash: None,
+                author: None,
+                description: None,
+                cover_path: None,
+                metadata: Default::default(),
+            },
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn job_covers_unconfirmed_books_and_tracks_reviews() {
+        let conn = rusqlite::Connection::open_in_memory().unwrap();
+        crate::db::run_migrations(&conn).unwrap();
+        add_book(&conn, "b2", 20);
+        add_book(&conn, "b1", 10);
+        add_book(&conn, "done", 5);
+        insert_search_result(&conn, "r1", "done", "anilist", None, 90.0, "", true, true).unwrap();
+
+        let job = create_metadata_job(&conn).unwrap();
+        assert_eq!(
+            (job.status, job.total, job.pending),
+            (JobStatus::Running, 2, 2)
+        );
+        // Com um lote ativo, não se cria outro
+        assert_eq!(create_metadata_job(&conn).unwrap().id, job.id);
+
+        assert_eq!(
+            next_pending_item(&conn, &job.id).unwrap().as_deref(),
+            Some("b1")
+        );
+        finish_job_item(
+            &conn,
+            &job.id,
+            "b1",
+            JobItemStatus::Applied,
+            Some(88.0),
+            &[],
+            None,
+        )
+        .unwrap();
+        let ranked = RankedCandidate {
+            candidate: MetadataCandidate {
+                source: "jikan".to_string(),
+                source_id: "2".to_string(),
+                media_type: MediaType::Manga,
+                title: "B2".to_string(),
+                title_alternatives: vec![],
+                author: None,
+                description: None,
+                cover_url: None,
+                year: None,
+                language: None,
+                publisher: None,
+                isbns: vec![],
+                page_count: None,
+                genres: vec![],
+                community_score: None,
+            },
+            score: 60.0,
+        };
+        finish_job_item(
+            &conn,
+            &job.id,
+            "b2",
+            JobItemStatus::Review,
+            Some(60.0),
+            &[ranked],
+            None,
+        )
+        .unwrap();
+        assert_eq!(next_pending_item(&conn, &job.id).unwrap(), None);
+
+        let reviews = list_job_items(&conn, &job.id, Some(JobItemStatus::Review)).unwrap();
+        assert_eq!(reviews.len(), 1);
+        assert_eq!(reviews[0].candidates[0].candidate.source, "jikan");
+        assert_eq!(
+            resolve_review_items(&conn, "b2", JobItemStatus::Applied).unwrap(),
+            1
+        );
+
+        assert!(set_job_status(&conn, &job.id, JobStatus::Completed).unwrap());
+        let job = get_metadata_job(&conn, &job.id).unwrap().unwrap();
+        assert_eq!((job.applied, job.review, job.pending), (2, 0, 0));
+        assert!(active_metadata_job(&conn).unwrap().is_none());
+        assert_eq!(latest_metadata_job(&conn).unwrap().unwrap().id, job.id);
+    }
+}
@@ -1,50 +1,115 @@
-//! Repositório para flags de edição manual e histórico de buscas.
+//! Repositório para flags de edição manual, histórico e cache de buscas.
 
+use crate::metadata::{CachedResult, MetadataCandidate, SearchCache};
+use crate::models::BookMetadataFlags;
 use rusqlite::params;
 
+fn now_secs() -> i64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs() as i64)
+        .unwrap_or(0)
+}
+
+/// Colunas de flags na ordem dos campos de `BookMetadataFlags`.
+const FLAG_COLUMNS: [&str; 14] = [
+    "title_manually_edited",
+    "author_manually_edited",
+    "description_manually_edited",
+    "cover_manually_edited",
+    "publisher_manually_edited",
+    "year_manually_edited",
+    "language_manually_edited",
+    "isbns_manually_edited",
+    "alt_titles_manually_edited",
+    "series_manually_edited",
+    "page_count_manually_edited",
+    "media_type_manually_edited",
+    "genres_manually_edited",
+    "external_ids_manually_edited",
+];
+
+fn flags_to_array(f: &BookMetadataFlags) -> [bool; 14] {
+    [
+        f.title,
+        f.author,
+        f.description,
+        f.cover,
+        f.publisher,
+        f.year,
+        f.language,
+        f.isbns,
+        f.alt_titles,
+        f.series,
+        f.page_count,
+        f.media_type,
+        f.genres,
+        f.external_ids,
+    ]
+}
+
 pub fn get_metadata_flags(
     conn: &rusqlite::Connection,
     book_id: &str,
-) -> crate::Result<(bool, bool, bool, bool)> {
+) -> crate::Result<BookMetadataFlags> {
     let row = conn.query_row(
-        "SELECT author_manually_edited, description_manually_edited, cover_manually_edited, title_manually_edited FROM book_metadata_flags WHERE book_id = ?1",
+        &format!(
+            "SELECT {} FROM book_metadata_flags WHERE book_id = ?1",
+            FLAG_COLUMNS.join(", ")
+        ),
         [book_id],
         |row| {
-            Ok((
-                row.get::<_, i32>(0)? != 0,
-                row.get::<_, i32>(1)? != 0,
-                row.get::<_, i32>(2)? != 0,
-                row.get::<_, i32>(3)? != 0,
-            ))
+            let flag = |i: usize| row.get::<_, i32>(i).map(|v| v != 0);
+            Ok(BookMetadataFlags {
+                title: flag(0)?,
+                author: flag(1)?,
+                description: flag(2)?,
+                cover: flag(3)?,
+                publisher: flag(4)?,
+                year: flag(5)?,
+                language: flag(6)?,
+                isbns: flag(7)?,
+                alt_titles: flag(8)?,
+                series: flag(9)?,
+                page_count: flag(10)?,
+                media_type: flag(11)?,
+                genres: flag(12)?,
+                external_ids: flag(13)?,
+            })
         },
     );
     match row {
         Ok(r) => Ok(r),
-        Err(rusqlite::Error::QueryReturnedNoRows) => Ok((false, false, false, false)),
+        Err(rusqlite::Error::QueryReturnedNoRows) => Ok(BookMetadataFlags::default()),
         Err(e) => Err(e.into()),
     }
 }
 
+/// Grava as flags exatamente como passadas (false desliga). Para só ligar
+/// campos, parta de `get_metadata_flags`.
 pub fn set_metadata_flags(
     conn: &rusqlite::Connection,
     book_id: &str,
-    author: bool,
-    description: bool,
-    cover: bool,
-    title: bool,
+    flags: &BookMetadataFlags,
 ) -> crate::Result<()> {
-    conn.execute(
-        r#"
-        INSERT INTO book_metadata_flags (book_id, author_manually_edited, description_manually_edited, cover_manually_edited, title_manually_edited)
-        VALUES (?1, ?2, ?3, ?4, ?5)
-        ON CONFLICT(book_id) DO UPDATE SET
-            author_manually_edited = MAX(author_manually_edited, ?2),
-            description_manually_edited = MAX(description_manually_edited, ?3),
-            cover_manually_edited = MAX(cover_manually_edited, ?4),
-            title_manually_edited = MAX(title_manually_edited, ?5)
-        "#,
-        params![book_id, author as i32, description as i32, cover as i32, title as i32],
-    )?;
+    let placeholders: Vec<String> = (0..FLAG_COLUMNS.len())
+        .map(|i| format!("?{}", i + 2))
+        .collect();
+    let updates: Vec<String> = FLAG_COLUMNS
+        .iter()
+        .zip(&placeholders)
+        .map(|(c, p)| format!("{} = {}", c, p))
+        .collect();
+    let sql = format!(
+        "INSERT INTO book_metadata_flags (book_id, {}) VALUES (?1, {}) \
+         ON CONFLICT(book_id) DO UPDATE SET {}",
+        FLAG_COLUMNS.join(", "),
+        placeholders.join(", "),
+        updates.join(", ")
+    );
+    let mut values: Vec<rusqlite::types::Value> = vec![book_id.to_string().into()];
+    values.extend(flags_to_array(flags).iter().map(|&f| (f as i64).into()));
+    conn.execute(&sql, rusqlite::params_from_iter(values))?;
     Ok(())
 }
 
@@ -82,3 +147,126 @@ pub fn insert_search_result(
     )?;
     Ok(())
 }
+
+pub fn get_cached_search(
+    conn: &rusqlite::Connection,
+    source: &str,
+    query_normalized: &str,
+) -> crate::Result<Option<CachedResult>> {
+    let r = conn.query_row(
+        r#"
+        SELECT source, results_json, cached_at FROM metadata_search_cache
+        WHERE source = ?1 AND query_normalized = ?2
+        "#,
+        params![source, query_normalized],
+        |row| {
+            Ok(CachedResult {
+                source: row.get(0)?,
+                results_json: row.get(1)?,
+                cached_at: row.get(2)?,
+            })
+        },
+    );
+    match r {
+        Ok(cached) => Ok(Some(cached)),
+        Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+        Err(e) => Err(e.into()),
+    }
+}
+
+pub fn save_cached_search(
+    conn: &rusqlite::Connection,
+    source: &str,
+    query_normalized: &str,
+    candidates: &[MetadataCandidate],
+) -> crate::Result<()> {
+    let json = serde_json::to_string(candidates).map_err(|e| crate::Error::Db(e.to_string()))?;
+    conn.execute(
+        r#"
+        INSERT INTO metadata_search_cache (source, query_normalized, results_json, cached_at)
+        VALUES (?1, ?2, ?3, ?4)
+        ON CONFLICT(source, query_normalized) DO UPDATE SET
+            results_json = excluded.results_json,
+            cached_at = excluded.cached_at
+        "#,
+        params![source, query_normalized, json, now_secs()],
+    )?;
+    Ok(())
+}
+
+/// Apaga o cache de uma fonte (ou de todas). Retorna quantas entradas saíram.
+pub fn clear_metadata_cache(
+    conn: &rusqlite::Connection,
+    source: Option<&str>,
+) -> crate::Result<usize> {
+    let n = match source {
+        Some(s) => conn.execute("DELETE FROM metadata_search_cache WHERE source = ?1", [s])?,
+        None => conn.execute("DELETE FROM metadata_search_cache", [])?,
+    };
+    Ok(n)
+}
+
+/// Falhas do cache só são registradas: a busca segue indo às fontes.
+impl SearchCache for rusqlite::Connection {
+    fn get(&self, source: &str, query: &str) -> Option<CachedResult> {
+        get_cached_search(self, source, query).unwrap_or_else(|e| {
+            log::warn!("[metadata] Falha ao ler cache de {}: {:?}", source, e);
+            None
+        })
+    }
+
+    fn put(&self, source: &str, query: &str, candidates: &[MetadataCandidate]) {
+        if let Err(e) = save_cached_search(self, source, query, candidates) {
+            log::warn!("[metadata] Falha ao gravar cache de {}: {:?}", source, e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::metadata::{cache_key, MediaType};
+
+    fn candidate(source: &str, title: &str) -> MetadataCandidate {
+        MetadataCandidate {
+            source: source.to_string(),
+            source_id: "1".to_string(),
+            media_type: MediaType::Manga,
+            title: title.to_string(),
+            title_alternatives: vec![],
+            author: None,
+            description: None,
+            cover_url: None,
+            year: None,
+            language: None,
+            publisher: None,
+            isbns: vec![],
+            page_count: None,
+            genres: vec![],
+            community_score: None,
+        }
+    }
+
+    #[test]
+    fn cache_is_keyed_by_source_and_query() {
+        let conn = rusqlite::Connection::open_in_memory().unwrap();
+        crate::db::run_migrations(&conn).unwrap();
+        let key = cache_key("  Vinland   SAGA ");
+        assert_eq!(key, "vinland saga");
+
+        conn.put("anilist", &key, &[candidate("anilist", "Vinland Saga")]);
+        conn.put("jikan", &key, &[candidate("jikan", "Vinland Saga (MAL)")]);
+        let anilist = conn.get("anilist", &key).unwrap();
+        assert!(!anilist.is_expired(60));
+        assert_eq!(anilist.candidates().unwrap()[0].title, "Vinland Saga");
+        assert_eq!(
+            conn.get("jikan", &key).unwrap().candidates().unwrap()[0].title,
+            "Vinland Saga (MAL)"
+        );
+
+        assert_eq!(clear_metadata_cache(&conn, Some("jikan")).unwrap(), 1);
+        assert!(conn.get("jikan", &key).is_none());
+        assert!(conn.get("anilist", &key).is_some());
+        assert_eq!(clear_metadata_cache(&conn, None).unwrap(), 1);
+    }
+}
@@ -1,26 +1,103 @@
+mod annotation_repository;
+mod backup_repository;
+mod book_query_repository;
 mod book_repository;
+mod bookmark_repository;
+mod chapter_progress_repository;
 mod custom_theme_repository;
+mod history_repository;
+mod koreader_repository;
+mod metadata_history_repository;
+mod metadata_job_repository;
 mod metadata_repository;
 mod progress_repository;
+mod queue_repository;
+mod read_status_repository;
+mod review_repository;
+mod session_repository;
 mod settings_repository;
 mod shelf_repository;
+mod smart_shelf_repository;
+mod stats_repository;
+mod sync_repository;
+mod tag_repository;
+#[cfg(test)]
+pub(crate) mod test_support;
 
+pub use annotation_repository::{
+    delete_annotation, get_annotation, list_all_annotations, list_annotations, query_annotations,
+    relink_annotations, update_annotation, upsert_annotation,
+};
+pub use backup_repository::{export_backup, import_backup};
+pub use book_query_repository::{list_continue_reading, query_books};
 pub use book_repository::{
-    delete_book, insert_book, insert_chapter, insert_volume, list_books, list_chapters,
-    list_volumes, update_book, update_book_partial,
+    delete_book, get_book, insert_book, insert_chapter, insert_volume, list_all_chapters,
+    list_all_volumes, list_books, list_chapters, list_volumes, update_book, update_book_metadata,
+    update_book_partial,
+};
+pub use bookmark_repository::{
+    delete_bookmark, get_bookmark, list_all_bookmarks, list_bookmarks, rename_bookmark,
+    upsert_bookmark,
+};
+pub use chapter_progress_repository::{
+    list_chapter_progress, mark_previous_chapters_read, next_unread_chapter,
+    record_chapter_position, series_completion, set_chapters_read,
 };
 pub use custom_theme_repository::{
     delete_custom_theme, get_custom_theme, insert_custom_theme, list_custom_themes,
     update_custom_theme,
 };
-pub use metadata_repository::{get_metadata_flags, insert_search_result, set_metadata_flags};
+pub use history_repository::{
+    delete_history_entries, get_history_entry, query_history, record_history,
+};
+pub use koreader_repository::{
+    create_koreader_user, get_cached_document, get_koreader_config, get_koreader_progress,
+    get_koreader_userkey, list_koreader_volumes, save_cached_document, save_koreader_config,
+    set_koreader_last_sync, upsert_koreader_progress, KoreaderVolume,
+};
+pub use metadata_history_repository::{
+    get_metadata_change, list_metadata_changes, record_metadata_changes, revert_metadata,
+};
+pub use metadata_job_repository::{
+    active_metadata_job, create_metadata_job, finish_job_item, get_metadata_job,
+    latest_metadata_job, list_job_items, next_pending_item, resolve_review_items, set_job_status,
+};
+pub use metadata_repository::{
+    clear_metadata_cache, get_cached_search, get_metadata_flags, insert_search_result,
+    save_cached_search, set_metadata_flags,
+};
 pub use progress_repository::{
     get_progress, list_all_progress, list_recent_progress, upsert_progress,
 };
+pub use queue_repository::{
+    advance_queue, dequeue, enqueue, get_queue_entry, list_queue, pop_queue, reorder_queue,
+    suggest_next_volume,
+};
+pub use read_status_repository::{
+    get_book_read_state, mark_volumes, refresh_book_status, set_book_status, start_volume_if_unread,
+};
+pub use review_repository::{get_review, list_all_reviews, save_review};
+pub use session_repository::{
+    end_session, get_session, heartbeat_session, pages_per_hour, reading_time_by_book,
+    reading_time_by_day, start_session, volume_eta, IDLE_TIMEOUT_SECS,
+};
 pub use settings_repository::{
-    get_book_settings, get_global_settings, save_global_settings, upsert_book_settings,
+    get_book_settings, get_global_settings, get_metadata_config, save_global_settings,
+    save_metadata_config, upsert_book_settings,
 };
 pub use shelf_repository::{
-    add_book_to_shelf, create_shelf, get_book_shelf_ids, get_books_in_shelf, list_shelves,
-    remove_book_from_shelf,
+    add_book_to_shelf, create_shelf, create_smart_shelf, delete_shelf, get_book_shelf_ids,
+    get_books_in_shelf, get_shelf, insert_book_in_shelf, list_shelves, move_book_in_shelf,
+    remove_book_from_shelf, rename_shelf, reorder_shelves, set_shelf_rules, set_shelf_style,
+};
+pub use stats_repository::reading_stats;
+pub use sync_repository::{
+    apply_sync_delete, apply_sync_upsert, changed_keys_since, changes_since, get_device_id,
+    get_sync_config, get_sync_meta, get_tombstone, latest_change_seq, list_tombstones,
+    read_sync_row, row_origin_device, save_sync_config, set_sync_meta, sync_table_spec, SyncTable,
+    SYNC_TABLE_SPECS,
+};
+pub use tag_repository::{
+    add_tag_to_book, create_tag, delete_tag, find_tag_by_name, list_book_tags, list_tags,
+    merge_tags, remove_tag_from_book, rename_tag, set_auto_tags,
 };
@@ -4,16 +4,32 @@
 use crate::models::ReadingProgress;
 use rusqlite::params;
 
+const PROGRESS_COLUMNS: &str =
+    "book_id, volume_id, current_chapter_id, page_index, scroll_offset, updated_at, total_pages";
+
+fn progress_from_row(row: &rusqlite::Row) -> rusqlite::Result<ReadingProgress> {
+    Ok(ReadingProgress {
+        book_id: row.get(0)?,
+        volume_id: row.get(1)?,
+        current_chapter_id: row.get(2)?,
+        page_index: row.get(3)?,
+        scroll_offset: row.get(4)?,
+        updated_at: row.get(5)?,
+        total_pages: row.get(6)?,
+    })
+}
+
 pub fn upsert_progress(conn: &rusqlite::Connection, p: &ReadingProgress) -> crate::Result<()> {
     conn.execute(
         r#"
-        INSERT INTO reading_progress (book_id, volume_id, current_chapter_id, page_index, scroll_offset, updated_at)
-        VALUES (?1, ?2, ?3, ?4, ?5, ?6)
+        INSERT INTO reading_progress (book_id, volume_id, current_chapter_id, page_index, scroll_offset, updated_at, total_pages)
+        VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)
         ON CONFLICT(book_id, volume_id) DO UPDATE SET
             current_chapter_id = excluded.current_chapter_id,
             page_index = excluded.page_index,
             scroll_offset = excluded.scroll_offset,
-            updated_at = excluded.updated_at
+            updated_at = excluded.updated_at,
+            total_pages = COALESCE(excluded.total_pages, reading_progress.total_pages)
         "#,
         params![
             p.book_id,
@@ -22,6 +38,7 @@ pub fn upsert_progress(conn: &rusqlite::Connection, p: &ReadingProgress) -> crat
             p.page_index,
             p.scroll_offset,
             p.updated_at,
+            p.total_pages,
         ],
     )?;
     Ok(())
@@ -32,23 +49,13 @@ pub fn get_progress(
     book_id: &str,
     volume_id: &str,
 ) -> crate::Result<Option<ReadingProgress>> {
-    let mut stmt = conn.prepare(
-        r#"
-        SELECT book_id, volume_id, current_chapter_id, page_index, scroll_offset, updated_at
-        FROM reading_progress WHERE book_id = ?1 AND volume_id = ?2
-        "#,
-    )?;
+    let mut stmt = conn.prepare(&format!(
+        "SELECT {} FROM reading_progress WHERE book_id = ?1 AND volume_id = ?2",
+        PROGRESS_COLUMNS
+    ))?;
     let mut rows = stmt.query(params![book_id, volume_id])?;
     if let Some(row) = rows.next()? {
-        let r = row;
-        return Ok(Some(ReadingProgress {
-            book_id: r.get(0)?,
-            volume_id: r.get(1)?,
-            current_chapter_id: r.get(2)?,
-            page_index: r.get(3)?,
-            scroll_offset: r.get(4)?,
-            updated_at: r.get(5)?,
-        }));
+        return Ok(Some(progress_from_row(row)?));
     }
     Ok(None)
 }
@@ -58,24 +65,11 @@ pub fn list_recent_progress(
     conn: &rusqlite::Connection,
     limit: i64,
 ) -> crate::Result<Vec<ReadingProgress>> {
-    let mut stmt = conn.prepare(
-        r#"
-        SELECT book_id, volume_id, current_chapter_id, page_index, scroll_offset, updated_at
-        FROM reading_progress
-        ORDER BY updated_at DESC
-        LIMIT ?1
-        "#,
-    )?;
-    let rows = stmt.query_map(params![limit], |row| {
-        Ok(ReadingProgress {
-            book_id: row.get(0)?,
-            volume_id: row.get(1)?,
-            current_chapter_id: row.get(2)?,
-            page_index: row.get(3)?,
-            scroll_offset: row.get(4)?,
-            updated_at: row.get(5)?,
-        })
-    })?;
+    let mut stmt = conn.prepare(&format!(
+        "SELECT {} FROM reading_progress ORDER BY updated_at DESC LIMIT ?1",
+        PROGRESS_COLUMNS
+    ))?;
+    let rows = stmt.query_map(params![limit], progress_from_row)?;
     let mut out = Vec::new();
     for row in rows {
         out.push(row?);
@@ -85,22 +79,11 @@ pub fn list_recent_progress(
 
 /// Lista todos os progressos (para ordenação/filtros por status).
 pub fn list_all_progress(conn: &rusqlite::Connection) -> crate::Result<Vec<ReadingProgress>> {
-    let mut stmt = conn.prepare(
-        r#"
-        SELECT book_id, volume_id, current_chapter_id, page_index, scroll_offset, updated_at
-        FROM reading_progress
-        "#,
-    )?;
-    let rows = stmt.query_map([], |row| {
-        Ok(ReadingProgress {
-            book_id: row.get(0)?,
-            volume_id: row.get(1)?,
-            current_chapter_id: row.get(2)?,
-            page_index: row.get(3)?,
-            scroll_offset: row.get(4)?,
-            updated_at: row.get(5)?,
-        })
-    })?;
+    let mut stmt = conn.prepare(&format!(
+        "SELECT {} FROM reading_progress",
+        PROGRESS_COLUMNS
+    ))?;
+    let rows = stmt.query_map([], progress_from_row)?;
     let mut out = Vec::new();
     for row in rows {
         out.push(row?);
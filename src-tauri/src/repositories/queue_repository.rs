@@ -0,0 +1,357 @@
+//! Repositório da fila de leitura. As posições são mantidas contíguas
+//! (0..n) a cada alteração.
+
+use rusqlite::params;
+
+use crate::models::{QueueEntry, QueueItem, Volume};
+
+const QUEUE_COLUMNS: &str = "q.id, q.book_id, q.volume_id, q.position, q.added_at";
+
+fn entry_from_row(row: &rusqlite::Row) -> rusqlite::Result<QueueEntry> {
+    Ok(QueueEntry {
+        id: row.get(0)?,
+        book_id: row.get(1)?,
+        volume_id: row.get(2)?,
+        position: row.get(3)?,
+        added_at: row.get(4)?,
+    })
+}
+
+fn queue_ids(conn: &rusqlite::Connection) -> crate::Result<Vec<String>> {
+    let mut stmt = conn.prepare("SELECT id FROM reading_queue ORDER BY position, added_at")?;
+    let rows = stmt.query_map([], |row| row.get::<_, String>(0))?;
+    let mut ids = Vec::new();
+    for row in rows {
+        ids.push(row?);
+    }
+    Ok(ids)
+}
+
+fn write_order(conn: &rusqlite::Connection, ids: &[String]) -> crate::Result<()> {
+    let mut stmt = conn.prepare("UPDATE reading_queue SET position = ?1 WHERE id = ?2")?;
+    for (i, id) in ids.iter().enumerate() {
+        stmt.execute(params![i as i64, id])?;
+    }
+    Ok(())
+}
+
+fn find_entry(
+    conn: &rusqlite::Connection,
+    sql_where: &str,
+    params: impl rusqlite::Params,
+) -> crate::Result<Option<QueueEntry>> {
+    let r = conn.query_row(
+        &format!(
+            "SELECT {} FROM reading_queue q WHERE {}",
+            QUEUE_COLUMNS, sql_where
+        ),
+        params,
+        entry_from_row,
+    );
+    match r {
+        Ok(e) => Ok(Some(e)),
+        Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+        Err(e) => Err(e.into()),
+    }
+}
+
+pub fn get_queue_entry(conn: &rusqlite::Connection, id: &str) -> crate::Result<Option<QueueEntry>> {
+    find_entry(conn, "q.id = ?1", [id])
+}
+
+pub fn list_queue(conn: &rusqlite::Connection) -> crate::Result<Vec<QueueItem>> {
+    let mut stmt = conn.prepare(&format!(
+        r#"
+        SELECT {}, b.title, b.cover_path, v.name
+        FROM reading_queue q
+        JOIN books b ON b.id = q.book_id
+        LEFT JOIN volumes v ON v.id = q.volume_id
+        ORDER BY q.position, q.added_at
+        "#,
+        QUEUE_COLUMNS
+    ))?;
+    let rows = stmt.query_map([], |row| {
+        Ok(QueueItem {
+            entry: entry_from_row(row)?,
+            book_title: row.get(5)?,
+            cover_path: row.get(6)?,
+            volume_name: row.get(7)?,
+        })
+    })?;
+    let mut out = Vec::new();
+    for row in rows {
+        out.push(row?);
+    }
+    Ok(out)
+}
+
+/// Move a entrada para `position` (além do fim = último).
+fn move_entry(conn: &rusqlite::Connection, id: &str, position: i64) -> crate::Result<()> {
+    let mut ids = queue_ids(conn)?;
+    ids.retain(|i| i != id);
+    let at = (position.max(0) as usize).min(ids.len());
+    ids.insert(at, id.to_string());
+    write_order(conn, &ids)
+}
+
+/// Coloca o livro (ou um volume dele) na fila, em `position` ou no fim. Se já
+/// estiver na fila, só muda de posição quando `position` é informada.
+/// None se o livro ou o volume não existir.
+pub fn enqueue(
+    conn: &rusqlite::Connection,
+    book_id: &str,
+    volume_id: Option<&str>,
+    position: Option<i64>,
+    now: i64,
+) -> crate::Result<Option<QueueEntry>> {
+    let exists = match volume_id {
+        Some(v) => conn.query_row(
+            "SELECT 1 FROM volumes WHERE id = ?1 AND book_id = ?2",
+            params![v, book_id],
+            |_| Ok(()),
+        ),
+        None => conn.query_row("SELECT 1 FROM books WHERE id = ?1", [book_id], |_| Ok(())),
+    };
+    match exists {
+        Ok(()) => {}
+        Err(rusqlite::Error::QueryReturnedNoRows) => return Ok(None),
+        Err(e) => return Err(e.into()),
+    }
+
+    let tx = conn.unchecked_transaction()?;
+    let id = match find_entry(
+        &tx,
+        "q.book_id = ?1 AND q.volume_id IS ?2",
+        params![book_id, volume_id],
+    )? {
+        Some(existing) => {
+            if position.is_none() {
+                return Ok(Some(existing));
+            }
+            existing.id
+        }
+        None => {
+            let id = uuid::Uuid::new_v4().to_string();
+            tx.execute(
+                r#"
+                INSERT INTO reading_queue (id, book_id, volume_id, position, added_at)
+                VALUES (?1, ?2, ?3, (SELECT COUNT(*) FROM reading_queue), ?4)
+                "#,
+                params![id, book_id, volume_id, now],
+            )?;
+            id
+        }
+    };
+    if let Some(position) = position {
+        move_entry(&tx, &id, position)?;
+    }
+    tx.commit()?;
+    get_queue_entry(conn, &id)
+}
+
+/// Reordena a fila pela lista de ids. Entradas fora da lista vão para o fim,
+/// na ordem atual; ids desconhecidos são ignorados.
+pub fn reorder_queue(conn: &rusqlite::Connection, ids: &[String]) -> crate::Result<()> {
+    let tx = conn.unchecked_transaction()?;
+    let current = queue_ids(&tx)?;
+    let mut order: Vec<String> = Vec::with_capacity(current.len());
+    for id in ids {
+        if current.contains(id) && !order.contains(id) {
+            order.push(id.clone());
+        }
+    }
+    for id in current {
+        if !order.contains(&id) {
+            order.push(id);
+        }
+    }
+    write_order(&tx, &order)?;
+    tx.commit()?;
+    Ok(())
+}
+
+/// Retorna false se a entrada não existir.
+pub fn dequeue(conn: &rusqlite::Connection, id: &str) -> crate::Result<bool> {
+    let tx = conn.unchecked_transaction()?;
+    let n = tx.execute("DELETE FROM reading_queue WHERE id = ?1", [id])?;
+    write_order(&tx, &queue_ids(&tx)?)?;
+    tx.commit()?;
+    Ok(n > 0)
+}
+
+/// Remove e retorna a primeira entrada.
+pub fn pop_queue(conn: &rusqlite::Connection) -> crate::Result<Option<QueueEntry>> {
+    let Some(first) = find_entry(conn, "1 ORDER BY q.position, q.added_at LIMIT 1", [])? else {
+        return Ok(None);
+    };
+    dequeue(conn, &first.id)?;
+    Ok(Some(first))
+}
+
+/// Próximo volume a ler na série: o primeiro não concluído depois do último
+/// concluído (ordem por nome, como em `list_volumes`); sem nenhum depois, o
+/// primeiro não concluído. None = todos concluídos.
+pub fn suggest_next_volume(
+    conn: &rusqlite::Connection,
+    book_id: &str,
+) -> crate::Result<Option<Volume>> {
+    let r = conn.query_row(
+        r#"
+        WITH series AS (
+            SELECT id, book_id, name, read_status,
+                ROW_NUMBER() OVER (ORDER BY name) AS seq
+            FROM volumes WHERE book_id = ?1
+        )
+        SELECT id, book_id, name FROM series
+        WHERE read_status <> 'completed'
+        ORDER BY seq > COALESCE(
+            (SELECT MAX(seq) FROM series WHERE read_status = 'completed'), 0) DESC, seq
+        LIMIT 1
+        "#,
+        [book_id],
+        |row| {
+            Ok(Volume {
+                id: row.get(0)?,
+                book_id: row.get(1)?,
+                name: row.get(2)?,
+            })
+        },
+    );
+    match r {
+        Ok(v) => Ok(Some(v)),
+        Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// Avança a fila quando um volume é concluído: a entrada do volume dá lugar
+/// ao próximo volume da série (mesma posição) ou sai da fila; a entrada do
+/// livro inteiro sai quando não sobra volume a ler. Não abre transação: roda
+/// dentro da de quem marcou o volume.
+pub fn advance_queue(conn: &rusqlite::Connection, volume_id: &str, now: i64) -> crate::Result<()> {
+    let book_id: String = match conn.query_row(
+        "SELECT book_id FROM volumes WHERE id = ?1",
+        [volume_id],
+        |row| row.get(0),
+    ) {
+        Ok(b) => b,
+        Err(rusqlite::Error::QueryReturnedNoRows) => return Ok(()),
+        Err(e) => return Err(e.into()),
+    };
+    let next = suggest_next_volume(conn, &book_id)?;
+    if let Some(entry) = find_entry(conn, "q.volume_id = ?1", [volume_id])? {
+        let next_queued = match &next {
+            Some(v) => find_entry(conn, "q.volume_id = ?1", [&v.id])?.is_some(),
+            None => true,
+        };
+        match next.as_ref().filter(|_| !next_queued) {
+            Some(v) => {
+                conn.execute(
+                    "UPDATE reading_queue SET volume_id = ?1, added_at = ?2 WHERE id = ?3",
+                    params![v.id, now, entry.id],
+                )?;
+            }
+            None => {
+                conn.execute("DELETE FROM reading_queue WHERE id = ?1", [&entry.id])?;
+            }
+        }
+    }
+    if next.is_none() {
+        conn.execute(
+            "DELETE FROM reading_queue WHERE book_id = ?1 AND volume_id IS NULL",
+            [&book_id],
+        )?;
+    }
+    write_order(conn, &queue_ids(conn)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::ReadStatus;
+    use crate::repositories::test_support::{book, memory_db, volume};
+    use crate::repositories::{insert_book, insert_volume, mark_volumes};
+
+    /// "dune" (d1) e "akira" (a1, a2, a3).
+    fn library() -> rusqlite::Connection {
+        let conn = memory_db();
+        for (id, volumes) in [("dune", &["d1"][..]), ("akira", &["a1", "a2", "a3"][..])] {
+            insert_book(&conn, &book(id, id)).unwrap();
+            for v in volumes {
+                insert_volume(&conn, &volume(v, id, &format!("Volume {}", v))).unwrap();
+            }
+        }
+        conn
+    }
+
+    fn order(conn: &rusqlite::Connection) -> Vec<(String, Option<String>)> {
+        list_queue(conn)
+            .unwrap()
+            .into_iter()
+            .map(|i| (i.entry.book_id, i.entry.volume_id))
+            .collect()
+    }
+
+    fn some(v: &str) -> Option<String> {
+        Some(v.to_string())
+    }
+
+    #[test]
+    fn enqueue_places_entries_without_duplicates() {
+        let conn = library();
+        let dune = enqueue(&conn, "dune", None, None, 1).unwrap().unwrap();
+        enqueue(&conn, "akira", Some("a1"), Some(0), 2).unwrap();
+        // Repetido sem posição: mesma entrada, sem mover
+        let again = enqueue(&conn, "dune", None, None, 3).unwrap().unwrap();
+        assert_eq!((again.id.as_str(), again.position), (dune.id.as_str(), 1));
+        // Volume de outro livro
+        assert!(enqueue(&conn, "akira", Some("d1"), None, 4)
+            .unwrap()
+            .is_none());
+        assert_eq!(
+            order(&conn),
+            vec![
+                ("akira".to_string(), some("a1")),
+                ("dune".to_string(), None)
+            ]
+        );
+    }
+
+    #[test]
+    fn reorders_entries() {
+        let conn = library();
+        enqueue(&conn, "akira", Some("a1"), None, 1).unwrap();
+        let dune = enqueue(&conn, "dune", None, None, 2).unwrap().unwrap();
+        reorder_queue(&conn, std::slice::from_ref(&dune.id)).unwrap();
+        assert_eq!(order(&conn)[0].0, "dune");
+    }
+
+    #[test]
+    fn completing_volumes_advances_through_the_series() {
+        let conn = library();
+        enqueue(&conn, "dune", None, None, 1).unwrap();
+        enqueue(&conn, "akira", Some("a1"), None, 2).unwrap();
+
+        // Concluir a1 põe a2 no lugar; concluir a série a tira da fila
+        mark_volumes(&conn, &["a1".to_string()], ReadStatus::Completed, 5).unwrap();
+        assert_eq!(order(&conn)[1], ("akira".to_string(), some("a2")));
+        assert_eq!(
+            suggest_next_volume(&conn, "akira").unwrap().unwrap().id,
+            "a2"
+        );
+        let rest = vec!["a2".to_string(), "a3".to_string()];
+        mark_volumes(&conn, &rest, ReadStatus::Completed, 6).unwrap();
+        assert_eq!(order(&conn), vec![("dune".to_string(), None)]);
+        assert!(suggest_next_volume(&conn, "akira").unwrap().is_none());
+    }
+
+    #[test]
+    fn pop_takes_the_first_entry() {
+        let conn = library();
+        let dune = enqueue(&conn, "dune", None, None, 1).unwrap().unwrap();
+        let popped = pop_queue(&conn).unwrap().unwrap();
+        assert_eq!(popped.id, dune.id);
+        assert!(pop_queue(&conn).unwrap().is_none());
+        assert!(!dequeue(&conn, &dune.id).unwrap());
+    }
+}
@@ -0,0 +1,297 @@
+//! Status de leitura de livros e volumes e suas transições.
+
+use rusqlite::params;
+
+use super::advance_queue;
+use crate::models::{BookReadState, ReadState, ReadStatus, VolumeReadState};
+
+/// Transição aplicada por `set_status`. O SET do SQLite lê os valores antigos:
+/// - reading após completed conta uma releitura e reinicia as datas;
+/// - reading/completed preenchem `started_at` se ainda não houver;
+/// - completed grava `finished_at` (mantém o original se já estava concluído);
+/// - unread limpa as datas; on_hold/dropped as mantêm.
+const TRANSITION_SQL: &str = r#"
+UPDATE {t} SET
+    reread_count = reread_count
+        + CASE WHEN read_status = 'completed' AND ?1 = 'reading' THEN 1 ELSE 0 END,
+    started_at = CASE
+        WHEN ?1 = 'unread' THEN NULL
+        WHEN ?1 = 'reading' AND read_status = 'completed' THEN ?2
+        WHEN ?1 IN ('reading', 'completed') THEN COALESCE(started_at, ?2)
+        ELSE started_at END,
+    finished_at = CASE
+        WHEN ?1 = 'completed' AND read_status = 'completed' THEN finished_at
+        WHEN ?1 = 'completed' THEN ?2
+        WHEN ?1 IN ('unread', 'reading') THEN NULL
+        ELSE finished_at END,
+    read_status = ?1,
+    updated_at = ?2
+WHERE id = ?3 AND read_status <> ?1
+"#;
+
+fn state_from_row(row: &rusqlite::Row, offset: usize) -> rusqlite::Result<ReadState> {
+    Ok(ReadState {
+        status: ReadStatus::parse(&row.get::<_, String>(offset)?),
+        started_at: row.get(offset + 1)?,
+        finished_at: row.get(offset + 2)?,
+        reread_count: row.get(offset + 3)?,
+    })
+}
+
+fn set_status(
+    conn: &rusqlite::Connection,
+    table: &str,
+    id: &str,
+    status: ReadStatus,
+    now: i64,
+) -> crate::Result<bool> {
+    let n = conn.execute(
+        &TRANSITION_SQL.replace("{t}", table),
+        params![status.as_str(), now, id],
+    )?;
+    Ok(n > 0)
+}
+
+pub fn get_book_read_state(
+    conn: &rusqlite::Connection,
+    book_id: &str,
+) -> crate::Result<Option<BookReadState>> {
+    let state = conn.query_row(
+        "SELECT read_status, started_at, finished_at, reread_count FROM books WHERE id = ?1",
+        [book_id],
+        |row| state_from_row(row, 0),
+    );
+    let state = match state {
+        Ok(s) => s,
+        Err(rusqlite::Error::QueryReturnedNoRows) => return Ok(None),
+        Err(e) => return Err(e.into()),
+    };
+    let mut stmt = conn.prepare(
+        r#"
+        SELECT id, read_status, started_at, finished_at, reread_count
+        FROM volumes WHERE book_id = ?1 ORDER BY name
+        "#,
+    )?;
+    let rows = stmt.query_map([book_id], |row| {
+        Ok(VolumeReadState {
+            volume_id: row.get(0)?,
+            state: state_from_row(row, 1)?,
+        })
+    })?;
+    let mut volumes = Vec::new();
+    for row in rows {
+        volumes.push(row?);
+    }
+    Ok(Some(BookReadState {
+        book_id: book_id.to_string(),
+        state,
+        volumes,
+    }))
+}
+
+/// Recalcula o status do livro a partir dos volumes: todos concluídos =
+/// completed; algum iniciado = reading; senão unread. on_hold e dropped são
+/// escolhas do usuário e não mudam.
+pub fn refresh_book_status(
+    conn: &rusqlite::Connection,
+    book_id: &str,
+    now: i64,
+) -> crate::Result<()> {
+    let (current, total, completed, started): (String, i64, i64, i64) = conn.query_row(
+        r#"
+        SELECT b.read_status, COUNT(v.id),
+            COALESCE(SUM(v.read_status = 'completed'), 0),
+            COALESCE(SUM(v.read_status <> 'unread'), 0)
+        FROM books b
+        LEFT JOIN volumes v ON v.book_id = b.id
+        WHERE b.id = ?1
+        GROUP BY b.id
+        "#,
+        [book_id],
+        |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?)),
+    )?;
+    if matches!(
+        ReadStatus::parse(&current),
+        ReadStatus::OnHold | ReadStatus::Dropped
+    ) {
+        return Ok(());
+    }
+    let target = if total > 0 && completed == total {
+        ReadStatus::Completed
+    } else if started > 0 {
+        ReadStatus::Reading
+    } else {
+        ReadStatus::Unread
+    };
+    set_status(conn, "books", book_id, target, now)?;
+    Ok(())
+}
+
+/// Status do livro. completed e unread valem também para todos os volumes.
+/// Retorna false se o livro não existir.
+pub fn set_book_status(
+    conn: &rusqlite::Connection,
+    book_id: &str,
+    status: ReadStatus,
+    now: i64,
+) -> crate::Result<bool> {
+    let exists = conn
+        .query_row("SELECT 1 FROM books WHERE id = ?1", [book_id], |_| Ok(()))
+        .map(|_| true);
+    match exists {
+        Ok(_) => {}
+        Err(rusqlite::Error::QueryReturnedNoRows) => return Ok(false),
+        Err(e) => return Err(e.into()),
+    }
+    if matches!(status, ReadStatus::Completed | ReadStatus::Unread) {
+        let mut stmt = conn.prepare("SELECT id FROM volumes WHERE book_id = ?1")?;
+        let rows = stmt.query_map([book_id], |row| row.get::<_, String>(0))?;
+        let mut ids = Vec::new();
+        for row in rows {
+            ids.push(row?);
+        }
+        for id in ids {
+            if set_status(conn, "volumes", &id, status, now)? && status == ReadStatus::Completed {
+                advance_queue(conn, &id, now)?;
+            }
+        }
+    }
+    set_status(conn, "books", book_id, status, now)?;
+    Ok(true)
+}
+
+/// Marca vários volumes (de um ou mais livros) e atualiza os livros afetados.
+/// Retorna quantos volumes mudaram de status. Não abre transação própria:
+/// quem chama decide o escopo (e pode estar dentro de uma maior).
+pub fn mark_volumes(
+    conn: &rusqlite::Connection,
+    volume_ids: &[String],
+    status: ReadStatus,
+    now: i64,
+) -> crate::Result<usize> {
+    let mut changed = 0;
+    let mut books: Vec<String> = Vec::new();
+    for id in volume_ids {
+        let book_id =
+            match conn.query_row("SELECT book_id FROM volumes WHERE id = ?1", [id], |row| {
+                row.get::<_, String>(0)
+            }) {
+                Ok(b) => b,
+                Err(rusqlite::Error::QueryReturnedNoRows) => continue,
+                Err(e) => return Err(e.into()),
+            };
+        if set_status(conn, "volumes", id, status, now)? {
+            changed += 1;
+            if status == ReadStatus::Completed {
+                advance_queue(conn, id, now)?;
+            }
+        }
+        if !books.contains(&book_id) {
+            books.push(book_id);
+        }
+    }
+    for book_id in &books {
+        refresh_book_status(conn, book_id, now)?;
+    }
+    Ok(changed)
+}
+
+/// Abrir um volume ainda não lido o coloca (e o livro) em "reading".
+pub fn start_volume_if_unread(
+    conn: &rusqlite::Connection,
+    volume_id: &str,
+    now: i64,
+) -> crate::Result<()> {
+    let status: Option<String> = match conn.query_row(
+        "SELECT read_status FROM volumes WHERE id = ?1",
+        [volume_id],
+        |row| row.get(0),
+    ) {
+        Ok(s) => Some(s),
+        Err(rusqlite::Error::QueryReturnedNoRows) => None,
+        Err(e) => return Err(e.into()),
+    };
+    if status.as_deref() == Some("unread") {
+        mark_volumes(conn, &[volume_id.to_string()], ReadStatus::Reading, now)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::repositories::test_support::{book, memory_db, volume};
+    use crate::repositories::{insert_book, insert_volume};
+
+    fn two_volumes() -> (rusqlite::Connection, Vec<String>) {
+        let conn = memory_db();
+        insert_book(&conn, &book("b1", "Berserk")).unwrap();
+        insert_volume(&conn, &volume("v1", "b1", "Volume 1")).unwrap();
+        insert_volume(&conn, &volume("v2", "b1", "Volume 2")).unwrap();
+        (conn, vec!["v1".to_string(), "v2".to_string()])
+    }
+
+    fn book_state(conn: &rusqlite::Connection) -> BookReadState {
+        get_book_read_state(conn, "b1").unwrap().unwrap()
+    }
+
+    #[test]
+    fn opening_a_volume_starts_the_book() {
+        let (conn, _) = two_volumes();
+        start_volume_if_unread(&conn, "v1", 100).unwrap();
+        let state = book_state(&conn);
+        assert_eq!(state.state.status, ReadStatus::Reading);
+        assert_eq!(state.state.started_at, Some(100));
+    }
+
+    #[test]
+    fn completing_every_volume_completes_the_book() {
+        let (conn, ids) = two_volumes();
+        start_volume_if_unread(&conn, "v1", 100).unwrap();
+        assert_eq!(
+            mark_volumes(&conn, &ids, ReadStatus::Completed, 200).unwrap(),
+            2
+        );
+        let state = book_state(&conn);
+        assert_eq!(state.state.status, ReadStatus::Completed);
+        assert_eq!(
+            (state.state.started_at, state.state.finished_at),
+            (Some(100), Some(200))
+        );
+        assert_eq!(state.volumes[0].state.started_at, Some(100));
+    }
+
+    #[test]
+    fn reading_a_completed_volume_again_counts_a_reread() {
+        let (conn, ids) = two_volumes();
+        mark_volumes(&conn, &ids, ReadStatus::Completed, 200).unwrap();
+        // Voltar a ler um volume concluído conta como releitura do volume e do livro
+        mark_volumes(&conn, &ids[..1], ReadStatus::Reading, 300).unwrap();
+        let state = book_state(&conn);
+        assert_eq!(state.state.status, ReadStatus::Reading);
+        assert_eq!(state.state.reread_count, 1);
+        assert_eq!(state.state.finished_at, None);
+        assert_eq!(state.volumes[0].state.reread_count, 1);
+    }
+
+    #[test]
+    fn on_hold_is_not_overridden_by_volumes() {
+        let (conn, ids) = two_volumes();
+        // on_hold é escolha do usuário: volumes não alteram
+        assert!(set_book_status(&conn, "b1", ReadStatus::OnHold, 400).unwrap());
+        mark_volumes(&conn, &ids, ReadStatus::Completed, 500).unwrap();
+        assert_eq!(book_state(&conn).state.status, ReadStatus::OnHold);
+    }
+
+    #[test]
+    fn unread_book_resets_every_volume() {
+        let (conn, ids) = two_volumes();
+        mark_volumes(&conn, &ids, ReadStatus::Reading, 100).unwrap();
+        assert!(set_book_status(&conn, "b1", ReadStatus::Unread, 600).unwrap());
+        assert!(book_state(&conn)
+            .volumes
+            .iter()
+            .all(|v| v.state.status == ReadStatus::Unread && v.state.started_at.is_none()));
+        assert!(!set_book_status(&conn, "missing", ReadStatus::Reading, 700).unwrap());
+    }
+}
@@ -0,0 +1,136 @@
+//! Repositório de avaliações e resenhas (uma por livro).
+
+use crate::models::BookReview;
+use rusqlite::params;
+
+fn review_from_row(row: &rusqlite::Row) -> rusqlite::Result<BookReview> {
+    Ok(BookReview {
+        book_id: row.get(0)?,
+        rating: row.get(1)?,
+        review: row.get(2)?,
+        updated_at: row.get(3)?,
+    })
+}
+
+pub fn get_review(conn: &rusqlite::Connection, book_id: &str) -> crate::Result<Option<BookReview>> {
+    let r = conn.query_row(
+        "SELECT book_id, rating, review, updated_at FROM book_reviews WHERE book_id = ?1",
+        [book_id],
+        review_from_row,
+    );
+    match r {
+        Ok(review) => Ok(Some(review)),
+        Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+        Err(e) => Err(e.into()),
+    }
+}
+
+pub fn list_all_reviews(conn: &rusqlite::Connection) -> crate::Result<Vec<BookReview>> {
+    let mut stmt = conn.prepare("SELECT book_id, rating, review, updated_at FROM book_reviews")?;
+    let rows = stmt.query_map([], review_from_row)?;
+    let mut out = Vec::new();
+    for row in rows {
+        out.push(row?);
+    }
+    Ok(out)
+}
+
+/// Grava nota e resenha. Sem nenhuma das duas, a linha é removida.
+pub fn save_review(conn: &rusqlite::Connection, review: &BookReview) -> crate::Result<()> {
+    if review.rating.is_none() && review.review.is_none() {
+        conn.execute(
+            "DELETE FROM book_reviews WHERE book_id = ?1",
+            [&review.book_id],
+        )?;
+        return Ok(());
+    }
+    conn.execute(
+        r#"
+        INSERT INTO book_reviews (book_id, rating, review, updated_at)
+        VALUES (?1, ?2, ?3, ?4)
+        ON CONFLICT(book_id) DO UPDATE SET
+            rating = excluded.rating,
+            review = excluded.review,
+            updated_at = excluded.updated_at
+        "#,
+        params![
+            review.book_id,
+            review.rating,
+            review.review,
+            review.updated_at
+        ],
+    )?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{BookQuery, BookSortField, SortDirection};
+    use crate::repositories::test_support::{book, memory_db};
+    use crate::repositories::{insert_book, query_books};
+
+    fn review(book_id: &str, rating: Option<f64>, review: Option<&str>) -> BookReview {
+        BookReview {
+            book_id: book_id.to_string(),
+            rating,
+            review: review.map(str::to_string),
+            updated_at: 1,
+        }
+    }
+
+    /// b1 (3.5), b2 (5.0, com resenha) e b3 sem avaliação.
+    fn rated_library() -> rusqlite::Connection {
+        let conn = memory_db();
+        for id in ["b1", "b2", "b3"] {
+            insert_book(&conn, &book(id, id)).unwrap();
+        }
+        save_review(&conn, &review("b1", Some(3.5), None)).unwrap();
+        save_review(
+            &conn,
+            &review("b2", Some(5.0), Some("Releitura obrigatória")),
+        )
+        .unwrap();
+        conn
+    }
+
+    #[test]
+    fn clearing_rating_and_review_removes_the_row() {
+        let conn = rated_library();
+        save_review(&conn, &review("b3", Some(1.0), None)).unwrap();
+        // Sem nota nem resenha, a avaliação some
+        save_review(&conn, &review("b3", None, None)).unwrap();
+        assert!(get_review(&conn, "b3").unwrap().is_none());
+    }
+
+    #[test]
+    fn library_sorts_by_rating() {
+        let conn = rated_library();
+        let page = query_books(
+            &conn,
+            &BookQuery {
+                sort_by: BookSortField::Rating,
+                sort_dir: SortDirection::Desc,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        let ids: Vec<_> = page.items.iter().map(|s| s.book.id.as_str()).collect();
+        assert_eq!(ids, vec!["b2", "b1", "b3"]);
+        assert_eq!(page.items[0].rating, Some(5.0));
+    }
+
+    #[test]
+    fn library_filters_by_minimum_rating() {
+        let conn = rated_library();
+        let page = query_books(
+            &conn,
+            &BookQuery {
+                min_rating: Some(3.5),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        assert_eq!(page.total, 2);
+    }
+}
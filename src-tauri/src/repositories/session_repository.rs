@@ -0,0 +1,393 @@
+//! Repositório de sessões de leitura. O frontend envia heartbeats enquanto o
+//! leitor está em uso; um intervalo maior que `IDLE_TIMEOUT_SECS` encerra a
+//! sessão no último heartbeat (o tempo parado não conta).
+
+use rusqlite::params;
+
+use crate::models::{BookReadingTime, DailyReading, ReadingSession, VolumeEta};
+
+/// Sem heartbeat por mais que isso, a sessão é considerada ociosa.
+pub const IDLE_TIMEOUT_SECS: i64 = 5 * 60;
+/// Sessões mais curtas e sem páginas viradas são descartadas ao fechar.
+const MIN_SESSION_SECS: i64 = 10;
+/// Saltos maiores contam como navegação, não leitura.
+const MAX_PAGE_JUMP: i32 = 20;
+/// Mínimo de tempo lido no livro para usar o ritmo dele na estimativa.
+const MIN_BOOK_PACE_SECS: i64 = 10 * 60;
+
+const SESSION_COLUMNS: &str =
+    "id, book_id, volume_id, started_at, ended_at, start_page, end_page, pages_turned, closed";
+
+fn session_from_row(row: &rusqlite::Row) -> rusqlite::Result<ReadingSession> {
+    Ok(ReadingSession {
+        id: row.get(0)?,
+        book_id: row.get(1)?,
+        volume_id: row.get(2)?,
+        started_at: row.get(3)?,
+        ended_at: row.get(4)?,
+        start_page: row.get(5)?,
+        end_page: row.get(6)?,
+        pages_turned: row.get(7)?,
+        closed: row.get::<_, i64>(8)? != 0,
+    })
+}
+
+fn pages_between(from: i32, to: i32) -> i32 {
+    let delta = (to - from).abs();
+    if delta > MAX_PAGE_JUMP {
+        0
+    } else {
+        delta
+    }
+}
+
+pub fn get_session(conn: &rusqlite::Connection, id: &str) -> crate::Result<Option<ReadingSession>> {
+    let mut stmt = conn.prepare(&format!(
+        "SELECT {} FROM reading_sessions WHERE id = ?1",
+        SESSION_COLUMNS
+    ))?;
+    match stmt.query_row([id], session_from_row) {
+        Ok(s) => Ok(Some(s)),
+        Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// Fecha a sessão no último heartbeat, descartando-a se for curta demais.
+fn close_session(conn: &rusqlite::Connection, id: &str) -> crate::Result<()> {
+    conn.execute("UPDATE reading_sessions SET closed = 1 WHERE id = ?1", [id])?;
+    conn.execute(
+        "DELETE FROM reading_sessions WHERE id = ?1 AND pages_turned = 0 AND ended_at - started_at < ?2",
+        params![id, MIN_SESSION_SECS],
+    )?;
+    Ok(())
+}
+
+/// Abre uma sessão, fechando as que ficaram abertas (ex.: app encerrado).
+pub fn start_session(
+    conn: &rusqlite::Connection,
+    book_id: &str,
+    volume_id: &str,
+    page: i32,
+    now: i64,
+) -> crate::Result<ReadingSession> {
+    let open: Vec<String> = {
+        let mut stmt = conn.prepare("SELECT id FROM reading_sessions WHERE closed = 0")?;
+        let rows = stmt.query_map([], |row| row.get(0))?;
+        let mut out = Vec::new();
+        for row in rows {
+            out.push(row?);
+        }
+        out
+    };
+    for id in open {
+        close_session(conn, &id)?;
+    }
+    let session = ReadingSession {
+        id: uuid::Uuid::new_v4().to_string(),
+        book_id: book_id.to_string(),
+        volume_id: volume_id.to_string(),
+        started_at: now,
+        ended_at: now,
+        start_page: page,
+        end_page: page,
+        pages_turned: 0,
+        closed: false,
+    };
+    conn.execute(
+        r#"
+        INSERT INTO reading_sessions (id, book_id, volume_id, started_at, ended_at, start_page, end_page, pages_turned, closed)
+        VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, 0, 0)
+        "#,
+        params![
+            session.id,
+            session.book_id,
+            session.volume_id,
+            session.started_at,
+            session.ended_at,
+            session.start_page,
+            session.end_page,
+        ],
+    )?;
+    Ok(session)
+}
+
+/// Registra atividade na sessão. Se ela estava ociosa (ou já fechada), é
+/// encerrada no último heartbeat e uma nova começa: use o id retornado.
+pub fn heartbeat_session(
+    conn: &rusqlite::Connection,
+    id: &str,
+    page: i32,
+    now: i64,
+) -> crate::Result<ReadingSession> {
+    let session = get_session(conn, id)?
+        .ok_or_else(|| crate::Error::NotFound(format!("reading session {}", id)))?;
+    if session.closed || now - session.ended_at > IDLE_TIMEOUT_SECS {
+        close_session(conn, id)?;
+        return start_session(conn, &session.book_id, &session.volume_id, page, now);
+    }
+    let pages_turned = session.pages_turned + pages_between(session.end_page, page);
+    conn.execute(
+        "UPDATE reading_sessions SET ended_at = ?1, end_page = ?2, pages_turned = ?3 WHERE id = ?4",
+        params![now, page, pages_turned, id],
+    )?;
+    Ok(ReadingSession {
+        ended_at: now,
+        end_page: page,
+        pages_turned,
+        ..session
+    })
+}
+
+/// Encerra a sessão (leitor fechado). Tempo ocioso no fim não é contado.
+pub fn end_session(
+    conn: &rusqlite::Connection,
+    id: &str,
+    page: i32,
+    now: i64,
+) -> crate::Result<()> {
+    let Some(session) = get_session(conn, id)? else {
+        return Ok(());
+    };
+    if !session.closed && now - session.ended_at <= IDLE_TIMEOUT_SECS {
+        conn.execute(
+            "UPDATE reading_sessions SET ended_at = ?1, end_page = ?2, pages_turned = ?3 WHERE id = ?4",
+            params![
+                now,
+                page,
+                session.pages_turned + pages_between(session.end_page, page),
+                id
+            ],
+        )?;
+    }
+    close_session(conn, id)
+}
+
+/// Tempo de leitura por livro no intervalo `[from, to]` (segundos; None = sem limite).
+pub fn reading_time_by_book(
+    conn: &rusqlite::Connection,
+    from: Option<i64>,
+    to: Option<i64>,
+) -> crate::Result<Vec<BookReadingTime>> {
+    let mut stmt = conn.prepare(
+        r#"
+        SELECT s.book_id, b.title, SUM(s.ended_at - s.started_at), COUNT(*), SUM(s.pages_turned), MAX(s.ended_at)
+        FROM reading_sessions s
+        JOIN books b ON b.id = s.book_id
+        WHERE (?1 IS NULL OR s.started_at >= ?1) AND (?2 IS NULL OR s.started_at <= ?2)
+        GROUP BY s.book_id
+        ORDER BY 3 DESC
+        "#,
+    )?;
+    let rows = stmt.query_map(params![from, to], |row| {
+        Ok(BookReadingTime {
+            book_id: row.get(0)?,
+            title: row.get(1)?,
+            seconds: row.get(2)?,
+            sessions: row.get(3)?,
+            pages: row.get(4)?,
+            last_read_at: row.get(5)?,
+        })
+    })?;
+    let mut out = Vec::new();
+    for row in rows {
+        out.push(row?);
+    }
+    Ok(out)
+}
+
+/// Totais por dia (data local) no intervalo, opcionalmente de um livro.
+pub fn reading_time_by_day(
+    conn: &rusqlite::Connection,
+    from: Option<i64>,
+    to: Option<i64>,
+    book_id: Option<&str>,
+) -> crate::Result<Vec<DailyReading>> {
+    let mut stmt = conn.prepare(
+        r#"
+        SELECT date(started_at, 'unixepoch', 'localtime') AS day,
+            SUM(ended_at - started_at), SUM(pages_turned)
+        FROM reading_sessions
+        WHERE (?1 IS NULL OR started_at >= ?1) AND (?2 IS NULL OR started_at <= ?2)
+            AND (?3 IS NULL OR book_id = ?3)
+        GROUP BY day
+        ORDER BY day
+        "#,
+    )?;
+    let rows = stmt.query_map(params![from, to, book_id], |row| {
+        Ok(DailyReading {
+            day: row.get(0)?,
+            seconds: row.get(1)?,
+            pages: row.get(2)?,
+        })
+    })?;
+    let mut out = Vec::new();
+    for row in rows {
+        out.push(row?);
+    }
+    Ok(out)
+}
+
+/// Páginas por hora (do livro ou geral) e o tempo lido que a sustenta.
+fn pace(conn: &rusqlite::Connection, book_id: Option<&str>) -> crate::Result<(Option<f64>, i64)> {
+    let (pages, secs): (i64, i64) = conn.query_row(
+        r#"
+        SELECT COALESCE(SUM(pages_turned), 0), COALESCE(SUM(ended_at - started_at), 0)
+        FROM reading_sessions
+        WHERE ?1 IS NULL OR book_id = ?1
+        "#,
+        params![book_id],
+        |row| Ok((row.get(0)?, row.get(1)?)),
+    )?;
+    if secs <= 0 || pages <= 0 {
+        return Ok((None, secs));
+    }
+    Ok((Some(pages as f64 * 3600.0 / secs as f64), secs))
+}
+
+pub fn pages_per_hour(
+    conn: &rusqlite::Connection,
+    book_id: Option<&str>,
+) -> crate::Result<Option<f64>> {
+    Ok(pace(conn, book_id)?.0)
+}
+
+/// Páginas restantes (total do leitor ou, sem ele, número de capítulos) e
+/// tempo estimado para terminar o volume.
+pub fn volume_eta(conn: &rusqlite::Connection, volume_id: &str) -> crate::Result<VolumeEta> {
+    let row = conn.query_row(
+        r#"
+        SELECT v.book_id,
+            COALESCE(rp.total_pages, (SELECT COUNT(*) FROM chapters c WHERE c.volume_id = v.id)),
+            COALESCE(rp.page_index, 0)
+        FROM volumes v
+        LEFT JOIN reading_progress rp ON rp.volume_id = v.id AND rp.book_id = v.book_id
+        WHERE v.id = ?1
+        "#,
+        [volume_id],
+        |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                row.get::<_, i64>(1)?,
+                row.get::<_, i64>(2)?,
+            ))
+        },
+    );
+    let (book_id, total, page) = match row {
+        Ok(r) => r,
+        Err(rusqlite::Error::QueryReturnedNoRows) => {
+            return Err(crate::Error::NotFound(format!("volume {}", volume_id)))
+        }
+        Err(e) => return Err(e.into()),
+    };
+    let remaining_pages = (total - page).max(0);
+    let (book_pace, book_secs) = pace(conn, Some(&book_id))?;
+    let pages_per_hour = match book_pace {
+        Some(p) if book_secs >= MIN_BOOK_PACE_SECS => Some(p),
+        _ => pace(conn, None)?.0.or(book_pace),
+    };
+    Ok(VolumeEta {
+        volume_id: volume_id.to_string(),
+        remaining_pages,
+        pages_per_hour,
+        eta_secs: pages_per_hour.map(|pph| (remaining_pages as f64 / pph * 3600.0).round() as i64),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::ReadingProgress;
+    use crate::repositories::test_support::{book, memory_db, volume};
+    use crate::repositories::{insert_book, insert_volume, upsert_progress};
+
+    const T0: i64 = 1_700_000_000;
+
+    fn library() -> rusqlite::Connection {
+        let conn = memory_db();
+        insert_book(&conn, &book("b1", "Dune")).unwrap();
+        insert_volume(&conn, &volume("v1", "b1", "Dune")).unwrap();
+        conn
+    }
+
+    /// Duas sessões separadas por uma pausa longa: 480s + 240s, 15 páginas.
+    fn read_with_a_break(conn: &rusqlite::Connection) {
+        let s = start_session(conn, "b1", "v1", 10, T0).unwrap();
+        let s = heartbeat_session(conn, &s.id, 15, T0 + 240).unwrap();
+        let s = heartbeat_session(conn, &s.id, 200, T0 + 480).unwrap();
+        let s = heartbeat_session(conn, &s.id, 201, T0 + 4500).unwrap();
+        let s = heartbeat_session(conn, &s.id, 211, T0 + 4740).unwrap();
+        end_session(conn, &s.id, 211, T0 + 4740).unwrap();
+    }
+
+    #[test]
+    fn page_jumps_do_not_count_as_pages_read() {
+        let conn = library();
+        let s = start_session(&conn, "b1", "v1", 10, T0).unwrap();
+        let s = heartbeat_session(&conn, &s.id, 15, T0 + 240).unwrap();
+        // Salto grande = navegação, não conta como páginas lidas
+        let s = heartbeat_session(&conn, &s.id, 200, T0 + 480).unwrap();
+        assert_eq!(s.pages_turned, 5);
+    }
+
+    #[test]
+    fn idle_gap_closes_session_at_last_heartbeat() {
+        let conn = library();
+        let s = start_session(&conn, "b1", "v1", 10, T0).unwrap();
+        let s = heartbeat_session(&conn, &s.id, 15, T0 + 240).unwrap();
+        let first = s.id.clone();
+
+        // Volta depois de 1h: a sessão anterior fecha no último heartbeat
+        let s = heartbeat_session(&conn, &s.id, 16, T0 + 4000).unwrap();
+        assert_ne!(s.id, first);
+        let closed = get_session(&conn, &first).unwrap().unwrap();
+        assert!(closed.closed);
+        assert_eq!(closed.ended_at, T0 + 240);
+    }
+
+    #[test]
+    fn very_short_sessions_are_discarded() {
+        let conn = library();
+        let noise = start_session(&conn, "b1", "v1", 211, T0).unwrap();
+        end_session(&conn, &noise.id, 211, T0 + 2).unwrap();
+        assert!(get_session(&conn, &noise.id).unwrap().is_none());
+    }
+
+    #[test]
+    fn aggregates_sum_sessions_by_book_and_day() {
+        let conn = library();
+        read_with_a_break(&conn);
+
+        let by_book = reading_time_by_book(&conn, None, None).unwrap();
+        assert_eq!(by_book.len(), 1);
+        assert_eq!(
+            (by_book[0].seconds, by_book[0].sessions, by_book[0].pages),
+            (720, 2, 15)
+        );
+        let days = reading_time_by_day(&conn, Some(T0), None, Some("b1")).unwrap();
+        assert_eq!(days.iter().map(|d| d.seconds).sum::<i64>(), 720);
+        assert_eq!(pages_per_hour(&conn, Some("b1")).unwrap(), Some(75.0));
+    }
+
+    #[test]
+    fn eta_uses_the_reading_pace() {
+        let conn = library();
+        read_with_a_break(&conn);
+        upsert_progress(
+            &conn,
+            &ReadingProgress {
+                book_id: "b1".to_string(),
+                volume_id: "v1".to_string(),
+                current_chapter_id: None,
+                page_index: 211,
+                scroll_offset: 0.0,
+                updated_at: T0,
+                total_pages: Some(271),
+            },
+        )
+        .unwrap();
+        let eta = volume_eta(&conn, "v1").unwrap();
+        assert_eq!(eta.remaining_pages, 60);
+        assert_eq!(eta.eta_secs, Some(2880));
+    }
+}
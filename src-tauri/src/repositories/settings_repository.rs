@@ -1,6 +1,7 @@
 //! Repositório de configurações por livro e globais.
 
-use crate::models::{BookSettings, GlobalSettings};
+use crate::metadata::SourceSettings;
+use crate::models::{BookSettings, GlobalSettings, LibraryKind, MetadataConfig};
 use rusqlite::params;
 
 pub fn upsert_book_settings(conn: &rusqlite::Connection, s: &BookSettings) -> crate::Result<()> {
@@ -85,3 +86,114 @@ pub fn save_global_settings(conn: &rusqlite::Connection, s: &GlobalSettings) ->
     )?;
     Ok(())
 }
+
+/// Configuração da busca de metadados (colunas `metadata_*` de
+/// global_settings). `sources` sempre traz todas as fontes: as que não
+/// foram salvas entram com o padrão de `library_kind`.
+pub fn get_metadata_config(conn: &rusqlite::Connection) -> crate::Result<MetadataConfig> {
+    let defaults = MetadataConfig::default();
+    let (ttl, kind, sources): (Option<i64>, Option<String>, Option<String>) = conn.query_row(
+        "SELECT metadata_cache_ttl_secs, metadata_library_kind, metadata_sources FROM global_settings WHERE id = 1",
+        [],
+        |r| Ok((r.get(0)?, r.get(1)?, r.get(2)?)),
+    )?;
+    let library_kind = kind
+        .and_then(|v| LibraryKind::parse(&v))
+        .unwrap_or(defaults.library_kind);
+    let saved: Vec<SourceSettings> = sources
+        .and_then(|v| serde_json::from_str(&v).ok())
+        .unwrap_or_default();
+    Ok(MetadataConfig {
+        cache_ttl_secs: ttl.unwrap_or(defaults.cache_ttl_secs),
+        library_kind,
+        sources: library_kind.merge_sources(saved),
+    })
+}
+
+/// Lista de fontes vazia volta ao padrão de `library_kind`.
+pub fn save_metadata_config(
+    conn: &rusqlite::Connection,
+    config: &MetadataConfig,
+    updated_at: i64,
+) -> crate::Result<()> {
+    let sources = if config.sources.is_empty() {
+        None
+    } else {
+        Some(serde_json::to_string(&config.sources).map_err(|e| crate::Error::Db(e.to_string()))?)
+    };
+    conn.execute(
+        r#"
+        UPDATE global_settings SET
+            metadata_cache_ttl_secs = ?1,
+            metadata_library_kind = ?2,
+            metadata_sources = ?3,
+            updated_at = ?4
+        WHERE id = 1
+        "#,
+        params![
+            config.cache_ttl_secs.max(0),
+            config.library_kind.as_str(),
+            sources,
+            updated_at,
+        ],
+    )?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sources_follow_library_kind_defaults() {
+        let conn = rusqlite::Connection::open_in_memory().unwrap();
+        crate::db::run_migrations(&conn).unwrap();
+        let enabled = |config: &MetadataConfig| -> Vec<String> {
+            config
+                .sources
+                .iter()
+                .filter(|s| s.enabled)
+                .map(|s| s.name.clone())
+                .collect()
+        };
+
+        let config = get_metadata_config(&conn).unwrap();
+        assert_eq!(config.sources.len(), 5);
+        assert_eq!(
+            enabled(&config),
+            ["open_library", "loc", "anilist", "jikan"]
+        );
+
+        let mut config = MetadataConfig {
+            library_kind: LibraryKind::Manga,
+            sources: vec![],
+            ..config
+        };
+        save_metadata_config(&conn, &config, 1).unwrap();
+        let loaded = get_metadata_config(&conn).unwrap();
+        assert_eq!(
+            enabled(&loaded),
+            ["anilist", "jikan", "kitsu", "open_library"]
+        );
+
+        // Lista parcial: o resto vem do padrão; nomes desconhecidos somem
+        config.sources = vec![
+            SourceSettings {
+                name: "jikan".to_string(),
+                enabled: true,
+                weight: 1.5,
+            },
+            SourceSettings {
+                name: "goodreads".to_string(),
+                enabled: true,
+                weight: 1.0,
+            },
+        ];
+        save_metadata_config(&conn, &config, 1).unwrap();
+        let loaded = get_metadata_config(&conn).unwrap();
+        assert_eq!(loaded.sources[0].name, "jikan");
+        assert_eq!(loaded.sources[0].weight, 1.5);
+        assert_eq!(loaded.sources.len(), 5);
+        assert!(!loaded.sources.iter().any(|s| s.name == "loc" && s.enabled));
+    }
+}
@@ -1,43 +1,257 @@
 //! Repositório de estantes (shelves) e associações book_shelves.
 
-use crate::models::Shelf;
+use super::book_query_repository::{count_books_filtered, query_books_filtered};
+use super::smart_shelf_repository::rules_filter;
+use crate::models::{BookSortField, Shelf, ShelfKind, SmartShelfRules, SortDirection};
 use rusqlite::params;
 
+fn now_secs() -> i64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs() as i64)
+        .unwrap_or(0)
+}
+
+/// Contagem de estantes manuais; a das inteligentes é calculada pelas regras.
+const SHELF_COLUMNS: &str = "s.id, s.name, s.position, s.icon, s.color, s.kind, s.rules, \
+     (SELECT COUNT(*) FROM book_shelves bsh JOIN books b ON b.id = bsh.book_id \
+      WHERE bsh.shelf_id = s.id)";
+
+fn shelf_from_row(row: &rusqlite::Row) -> rusqlite::Result<Shelf> {
+    let rules: Option<String> = row.get(6)?;
+    Ok(Shelf {
+        id: row.get(0)?,
+        name: row.get(1)?,
+        position: row.get(2)?,
+        icon: row.get(3)?,
+        color: row.get(4)?,
+        kind: ShelfKind::parse(&row.get::<_, String>(5)?),
+        // Regras ilegíveis (ex.: de uma versão mais nova) deixam a estante vazia
+        rules: rules.and_then(|r| serde_json::from_str(&r).ok()),
+        book_count: row.get(7)?,
+    })
+}
+
+/// Número de livros de uma estante inteligente (0 sem regras válidas).
+fn smart_book_count(conn: &rusqlite::Connection, shelf: &Shelf) -> crate::Result<i64> {
+    match &shelf.rules {
+        Some(rules) => count_books_filtered(conn, &rules_filter(rules, now_secs())),
+        None => Ok(0),
+    }
+}
+
 pub fn list_shelves(conn: &rusqlite::Connection) -> crate::Result<Vec<Shelf>> {
-    let mut stmt = conn.prepare("SELECT id, name FROM shelves ORDER BY name")?;
-    let rows = stmt.query_map([], |row| {
-        Ok(Shelf {
-            id: row.get(0)?,
-            name: row.get(1)?,
-        })
-    })?;
+    let mut stmt = conn.prepare(&format!(
+        "SELECT {} FROM shelves s ORDER BY s.position, s.name",
+        SHELF_COLUMNS
+    ))?;
+    let rows = stmt.query_map([], shelf_from_row)?;
     let mut out = Vec::new();
     for row in rows {
-        out.push(row?);
+        let mut shelf = row?;
+        if shelf.kind == ShelfKind::Smart {
+            shelf.book_count = smart_book_count(conn, &shelf)?;
+        }
+        out.push(shelf);
     }
     Ok(out)
 }
 
+pub fn get_shelf(conn: &rusqlite::Connection, id: &str) -> crate::Result<Option<Shelf>> {
+    let r = conn.query_row(
+        &format!("SELECT {} FROM shelves s WHERE s.id = ?1", SHELF_COLUMNS),
+        [id],
+        shelf_from_row,
+    );
+    let mut shelf = match r {
+        Ok(s) => s,
+        Err(rusqlite::Error::QueryReturnedNoRows) => return Ok(None),
+        Err(e) => return Err(e.into()),
+    };
+    if shelf.kind == ShelfKind::Smart {
+        shelf.book_count = smart_book_count(conn, &shelf)?;
+    }
+    Ok(Some(shelf))
+}
+
+/// Cria a estante no fim da lista.
 pub fn create_shelf(conn: &rusqlite::Connection, id: &str, name: &str) -> crate::Result<()> {
     conn.execute(
-        "INSERT INTO shelves (id, name) VALUES (?1, ?2)",
-        params![id, name],
+        r#"
+        INSERT INTO shelves (id, name, position, updated_at)
+        VALUES (?1, ?2, (SELECT COALESCE(MAX(position) + 1, 0) FROM shelves), ?3)
+        "#,
+        params![id, name, now_secs()],
+    )?;
+    Ok(())
+}
+
+/// Cria uma estante inteligente no fim da lista.
+pub fn create_smart_shelf(
+    conn: &rusqlite::Connection,
+    id: &str,
+    name: &str,
+    rules: &SmartShelfRules,
+) -> crate::Result<()> {
+    let rules = serde_json::to_string(rules).map_err(|e| crate::Error::Io(e.to_string()))?;
+    conn.execute(
+        r#"
+        INSERT INTO shelves (id, name, position, kind, rules, updated_at)
+        VALUES (?1, ?2, (SELECT COALESCE(MAX(position) + 1, 0) FROM shelves), ?3, ?4, ?5)
+        "#,
+        params![id, name, ShelfKind::Smart.as_str(), rules, now_secs()],
+    )?;
+    Ok(())
+}
+
+/// Substitui as regras. Retorna false se não houver estante inteligente com o id.
+pub fn set_shelf_rules(
+    conn: &rusqlite::Connection,
+    id: &str,
+    rules: &SmartShelfRules,
+) -> crate::Result<bool> {
+    let rules = serde_json::to_string(rules).map_err(|e| crate::Error::Io(e.to_string()))?;
+    let n = conn.execute(
+        "UPDATE shelves SET rules = ?1, updated_at = ?2 WHERE id = ?3 AND kind = ?4",
+        params![rules, now_secs(), id, ShelfKind::Smart.as_str()],
+    )?;
+    Ok(n > 0)
+}
+
+/// Retorna false se a estante não existir.
+pub fn rename_shelf(conn: &rusqlite::Connection, id: &str, name: &str) -> crate::Result<bool> {
+    let n = conn.execute(
+        "UPDATE shelves SET name = ?1, updated_at = ?2 WHERE id = ?3",
+        params![name, now_secs(), id],
     )?;
+    Ok(n > 0)
+}
+
+/// Ícone e cor (None remove). Retorna false se a estante não existir.
+pub fn set_shelf_style(
+    conn: &rusqlite::Connection,
+    id: &str,
+    icon: Option<&str>,
+    color: Option<&str>,
+) -> crate::Result<bool> {
+    let n = conn.execute(
+        "UPDATE shelves SET icon = ?1, color = ?2, updated_at = ?3 WHERE id = ?4",
+        params![icon, color, now_secs(), id],
+    )?;
+    Ok(n > 0)
+}
+
+/// Remove a estante e suas associações com livros; o cascade é explícito para
+/// não depender de `PRAGMA foreign_keys`. Retorna false se não existir.
+pub fn delete_shelf(conn: &rusqlite::Connection, id: &str) -> crate::Result<bool> {
+    let tx = conn.unchecked_transaction()?;
+    tx.execute("DELETE FROM book_shelves WHERE shelf_id = ?1", [id])?;
+    let n = tx.execute("DELETE FROM shelves WHERE id = ?1", [id])?;
+    tx.commit()?;
+    Ok(n > 0)
+}
+
+/// Reordena pela lista de ids. Estantes fora da lista vão para o fim, na
+/// ordem atual; ids desconhecidos são ignorados.
+pub fn reorder_shelves(conn: &rusqlite::Connection, ids: &[String]) -> crate::Result<()> {
+    let current: Vec<String> = list_shelves(conn)?.into_iter().map(|s| s.id).collect();
+    let mut order: Vec<&String> = Vec::with_capacity(current.len());
+    for id in ids.iter().chain(current.iter()) {
+        if current.contains(id) && !order.contains(&id) {
+            order.push(id);
+        }
+    }
+    let tx = conn.unchecked_transaction()?;
+    let now = now_secs();
+    for (i, id) in order.iter().enumerate() {
+        // Só grava o que mudou, para não gerar mudanças de sync à toa
+        tx.execute(
+            "UPDATE shelves SET position = ?1, updated_at = ?2 WHERE id = ?3 AND position <> ?1",
+            params![i as i64, now, id],
+        )?;
+    }
+    tx.commit()?;
     Ok(())
 }
 
+/// Adiciona no fim da estante; se o livro já estiver nela, nada muda.
+/// Estantes inteligentes são recusadas: o conteúdo delas vem das regras.
 pub fn add_book_to_shelf(
     conn: &rusqlite::Connection,
     book_id: &str,
     shelf_id: &str,
 ) -> crate::Result<()> {
+    if get_shelf(conn, shelf_id)?.is_some_and(|s| s.kind == ShelfKind::Smart) {
+        return Err(crate::Error::Validation(format!(
+            "cannot add books to smart shelf {}",
+            shelf_id
+        )));
+    }
     conn.execute(
-        "INSERT OR IGNORE INTO book_shelves (book_id, shelf_id) VALUES (?1, ?2)",
-        params![book_id, shelf_id],
+        r#"
+        INSERT OR IGNORE INTO book_shelves (book_id, shelf_id, position, updated_at)
+        VALUES (?1, ?2,
+            (SELECT COALESCE(MAX(position) + 1, 0) FROM book_shelves WHERE shelf_id = ?2), ?3)
+        "#,
+        params![book_id, shelf_id, now_secs()],
     )?;
     Ok(())
 }
 
+/// `move_book_in_shelf` sem transação própria.
+fn place_book_in_shelf(
+    conn: &rusqlite::Connection,
+    book_id: &str,
+    shelf_id: &str,
+    index: usize,
+) -> crate::Result<bool> {
+    let mut order = get_manual_shelf_order(conn, shelf_id)?;
+    let Some(from) = order.iter().position(|id| id == book_id) else {
+        return Ok(false);
+    };
+    let id = order.remove(from);
+    order.insert(index.min(order.len()), id);
+    let now = now_secs();
+    for (i, id) in order.iter().enumerate() {
+        // Só grava o que mudou, para não gerar mudanças de sync à toa
+        conn.execute(
+            "UPDATE book_shelves SET position = ?1, updated_at = ?2 \
+             WHERE book_id = ?3 AND shelf_id = ?4 AND position <> ?1",
+            params![i as i64, now, id, shelf_id],
+        )?;
+    }
+    Ok(true)
+}
+
+/// Coloca o livro na posição `index` (limitada ao fim) e renumera o resto.
+/// Retorna false se o livro não estiver na estante.
+pub fn move_book_in_shelf(
+    conn: &rusqlite::Connection,
+    book_id: &str,
+    shelf_id: &str,
+    index: usize,
+) -> crate::Result<bool> {
+    let tx = conn.unchecked_transaction()?;
+    let moved = place_book_in_shelf(&tx, book_id, shelf_id, index)?;
+    tx.commit()?;
+    Ok(moved)
+}
+
+/// Adiciona (ou move, se já estiver na estante) o livro na posição `index`.
+/// Como `add_book_to_shelf`, recusa estantes inteligentes.
+pub fn insert_book_in_shelf(
+    conn: &rusqlite::Connection,
+    book_id: &str,
+    shelf_id: &str,
+    index: usize,
+) -> crate::Result<()> {
+    let tx = conn.unchecked_transaction()?;
+    add_book_to_shelf(&tx, book_id, shelf_id)?;
+    place_book_in_shelf(&tx, book_id, shelf_id, index)?;
+    tx.commit()?;
+    Ok(())
+}
+
 pub fn remove_book_from_shelf(
     conn: &rusqlite::Connection,
     book_id: &str,
@@ -63,11 +277,36 @@ pub fn get_book_shelf_ids(
     Ok(out)
 }
 
+/// Ids dos livros da estante, na ordem manual. Estantes inteligentes avaliam
+/// as regras na hora (ordem por título).
 pub fn get_books_in_shelf(
     conn: &rusqlite::Connection,
     shelf_id: &str,
 ) -> crate::Result<Vec<String>> {
-    let mut stmt = conn.prepare("SELECT book_id FROM book_shelves WHERE shelf_id = ?1")?;
+    if let Some(shelf) = get_shelf(conn, shelf_id)?.filter(|s| s.kind == ShelfKind::Smart) {
+        let Some(rules) = &shelf.rules else {
+            return Ok(Vec::new());
+        };
+        let page = query_books_filtered(
+            conn,
+            &rules_filter(rules, now_secs()),
+            BookSortField::Title,
+            SortDirection::Asc,
+            0,
+            0,
+        )?;
+        return Ok(page.items.into_iter().map(|s| s.book.id).collect());
+    }
+    get_manual_shelf_order(conn, shelf_id)
+}
+
+/// Ids dos vínculos em book_shelves, na ordem manual (empate: ordem de inserção).
+fn get_manual_shelf_order(
+    conn: &rusqlite::Connection,
+    shelf_id: &str,
+) -> crate::Result<Vec<String>> {
+    let mut stmt = conn
+        .prepare("SELECT book_id FROM book_shelves WHERE shelf_id = ?1 ORDER BY position, rowid")?;
     let rows = stmt.query_map([shelf_id], |row| row.get::<_, String>(0))?;
     let mut out = Vec::new();
     for row in rows {
@@ -75,3 +314,136 @@ pub fn get_books_in_shelf(
     }
     Ok(out)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{RuleMatch, SmartRule};
+    use crate::repositories::insert_book;
+    use crate::repositories::test_support::{book, memory_db};
+
+    fn names(conn: &rusqlite::Connection) -> Vec<String> {
+        list_shelves(conn)
+            .unwrap()
+            .into_iter()
+            .map(|s| s.name)
+            .collect()
+    }
+
+    fn three_shelves() -> rusqlite::Connection {
+        let conn = memory_db();
+        create_shelf(&conn, "s1", "Lendo").unwrap();
+        create_shelf(&conn, "s2", "Favoritos").unwrap();
+        create_shelf(&conn, "s3", "Quero ler").unwrap();
+        conn
+    }
+
+    #[test]
+    fn new_shelves_go_last_and_can_be_reordered() {
+        let conn = three_shelves();
+        // Nova estante entra no fim, não pela ordem alfabética
+        assert_eq!(names(&conn), vec!["Lendo", "Favoritos", "Quero ler"]);
+
+        reorder_shelves(&conn, &["s3".to_string(), "missing".to_string()]).unwrap();
+        assert_eq!(names(&conn), vec!["Quero ler", "Lendo", "Favoritos"]);
+    }
+
+    #[test]
+    fn renames_and_styles_shelves() {
+        let conn = three_shelves();
+        assert!(rename_shelf(&conn, "s2", "Preferidos").unwrap());
+        assert!(set_shelf_style(&conn, "s2", Some("heart"), Some("#e11d48")).unwrap());
+        let s2 = get_shelf(&conn, "s2").unwrap().unwrap();
+        assert_eq!(
+            (s2.name.as_str(), s2.icon.as_deref(), s2.color.as_deref()),
+            ("Preferidos", Some("heart"), Some("#e11d48"))
+        );
+    }
+
+    #[test]
+    fn deleting_a_shelf_drops_its_links() {
+        let conn = three_shelves();
+        insert_book(&conn, &book("b1", "Berserk")).unwrap();
+        add_book_to_shelf(&conn, "b1", "s2").unwrap();
+        assert!(delete_shelf(&conn, "s2").unwrap());
+        assert!(get_books_in_shelf(&conn, "s2").unwrap().is_empty());
+        assert!(get_book_shelf_ids(&conn, "b1").unwrap().is_empty());
+        assert!(!delete_shelf(&conn, "s2").unwrap());
+    }
+
+    /// Estante "s1" com b1, b2 e b3 (nessa ordem) e b4 fora dela.
+    fn ordered_shelf() -> rusqlite::Connection {
+        let conn = memory_db();
+        create_shelf(&conn, "s1", "Lista").unwrap();
+        for id in ["b1", "b2", "b3", "b4"] {
+            insert_book(&conn, &book(id, id)).unwrap();
+        }
+        for id in ["b1", "b2", "b3"] {
+            add_book_to_shelf(&conn, id, "s1").unwrap();
+        }
+        conn
+    }
+
+    #[test]
+    fn books_keep_the_order_they_were_added() {
+        let conn = ordered_shelf();
+        assert_eq!(
+            get_books_in_shelf(&conn, "s1").unwrap(),
+            vec!["b1", "b2", "b3"]
+        );
+        // Adicionar de novo não muda a posição
+        add_book_to_shelf(&conn, "b1", "s1").unwrap();
+        assert_eq!(get_books_in_shelf(&conn, "s1").unwrap()[0], "b1");
+    }
+
+    #[test]
+    fn moves_books_within_the_shelf() {
+        let conn = ordered_shelf();
+        assert!(move_book_in_shelf(&conn, "b3", "s1", 0).unwrap());
+        // Índice além do fim vai para o fim
+        assert!(move_book_in_shelf(&conn, "b1", "s1", 99).unwrap());
+        assert_eq!(
+            get_books_in_shelf(&conn, "s1").unwrap(),
+            vec!["b3", "b2", "b1"]
+        );
+        assert!(!move_book_in_shelf(&conn, "b4", "s1", 0).unwrap());
+        assert!(!move_book_in_shelf(&conn, "missing", "s1", 0).unwrap());
+    }
+
+    #[test]
+    fn inserts_books_at_a_position() {
+        let conn = ordered_shelf();
+        insert_book_in_shelf(&conn, "b4", "s1", 1).unwrap();
+        assert_eq!(
+            get_books_in_shelf(&conn, "s1").unwrap(),
+            vec!["b1", "b4", "b2", "b3"]
+        );
+        // Já na estante: só move
+        insert_book_in_shelf(&conn, "b3", "s1", 0).unwrap();
+        assert_eq!(
+            get_books_in_shelf(&conn, "s1").unwrap(),
+            vec!["b3", "b1", "b4", "b2"]
+        );
+    }
+
+    #[test]
+    fn smart_shelves_refuse_manual_links() {
+        let conn = memory_db();
+        insert_book(&conn, &book("b1", "Berserk")).unwrap();
+        let rules = SmartShelfRules {
+            match_mode: RuleMatch::All,
+            rules: vec![SmartRule::MissingCover],
+        };
+        create_smart_shelf(&conn, "s1", "Sem capa", &rules).unwrap();
+
+        assert!(matches!(
+            add_book_to_shelf(&conn, "b1", "s1"),
+            Err(crate::Error::Validation(_))
+        ));
+        assert!(matches!(
+            insert_book_in_shelf(&conn, "b1", "s1", 0),
+            Err(crate::Error::Validation(_))
+        ));
+        assert!(get_book_shelf_ids(&conn, "b1").unwrap().is_empty());
+    }
+}
@@ -0,0 +1,352 @@
+//! Estantes inteligentes: as regras JSON viram cláusulas WHERE sobre a mesma
+//! consulta de `query_books` (livro `b` + estatísticas `bs`).
+
+use rusqlite::types::Value;
+
+use super::book_query_repository::{like_escape, like_pattern, BookFilter, PROGRESS_RATIO};
+use crate::models::{NumberOp, RuleMatch, SmartRule, SmartShelfRules, TextOp};
+
+/// Livros de imagens: pastas e arquivos de quadrinhos.
+const IMAGE_BOOK_SQL: &str = "(b.type = 'folder' OR lower(b.path) LIKE '%.cbz' \
+     OR lower(b.path) LIKE '%.cbr' OR lower(b.path) LIKE '%.cb7')";
+
+fn text_rule(column: &str, op: TextOp, value: &str) -> (String, Vec<Value>) {
+    let (sql, param) = match op {
+        TextOp::Contains => (
+            format!(r"{} LIKE ? ESCAPE '\'", column),
+            like_pattern(value),
+        ),
+        TextOp::NotContains => (
+            format!(r"COALESCE({}, '') NOT LIKE ? ESCAPE '\'", column),
+            like_pattern(value),
+        ),
+        TextOp::Equals => (format!("{} = ? COLLATE NOCASE", column), value.to_string()),
+        TextOp::StartsWith => (
+            format!(r"{} LIKE ? ESCAPE '\'", column),
+            format!("{}%", like_escape(value)),
+        ),
+    };
+    (sql, vec![Value::Text(param)])
+}
+
+fn rule_sql(rule: &SmartRule, now: i64) -> (String, Vec<Value>) {
+    match rule {
+        SmartRule::Title { op, value } => text_rule("b.title", *op, value),
+        SmartRule::Author { op, value } => text_rule("b.author", *op, value),
+        SmartRule::ReadStatus { value } => (
+            "b.read_status = ?".to_string(),
+            vec![Value::Text(value.as_str().to_string())],
+        ),
+        SmartRule::Tag { tag_id } => (
+            "b.id IN (SELECT book_id FROM book_tags WHERE tag_id = ?)".to_string(),
+            vec![Value::Text(tag_id.clone())],
+        ),
+        // added_at vem do frontend em milissegundos; `days` vem do JSON das
+        // regras, então satura em vez de estourar
+        SmartRule::AddedWithinDays { days } => (
+            "b.added_at >= ?".to_string(),
+            vec![Value::Integer(
+                now.saturating_sub(days.saturating_mul(86_400))
+                    .saturating_mul(1000),
+            )],
+        ),
+        SmartRule::BookType { value } => {
+            ("b.type = ?".to_string(), vec![Value::Text(value.clone())])
+        }
+        // Tipo vindo dos metadados; sem ele, deduzido do formato
+        SmartRule::MediaType { value } => (
+            format!(
+                "COALESCE(b.media_type, CASE WHEN {} THEN 'manga' ELSE 'book' END) = ?",
+                IMAGE_BOOK_SQL
+            ),
+            vec![Value::Text(value.as_str().to_string())],
+        ),
+        SmartRule::Progress { op, value } => {
+            let op = match op {
+                NumberOp::Lt => "<",
+                NumberOp::Lte => "<=",
+                NumberOp::Gt => ">",
+                NumberOp::Gte => ">=",
+                NumberOp::Eq => "=",
+            };
+            (
+                format!("{} * 100 {} ?", PROGRESS_RATIO, op),
+                vec![Value::Real(*value)],
+            )
+        }
+        SmartRule::MissingCover => (
+            "(b.cover_path IS NULL OR b.cover_path = '')".to_string(),
+            vec![],
+        ),
+    }
+}
+
+/// Filtro equivalente às regras. Sem regras, não restringe nada.
+pub(super) fn rules_filter(rules: &SmartShelfRules, now: i64) -> BookFilter {
+    let mut filter = BookFilter::default();
+    if rules.rules.is_empty() {
+        return filter;
+    }
+    let mut parts = Vec::with_capacity(rules.rules.len());
+    let mut params = Vec::new();
+    for rule in &rules.rules {
+        let (sql, values) = rule_sql(rule, now);
+        parts.push(format!("({})", sql));
+        params.extend(values);
+    }
+    let joiner = match rules.match_mode {
+        RuleMatch::All => " AND ",
+        RuleMatch::Any => " OR ",
+    };
+    filter.push(format!("({})", parts.join(joiner)), params);
+    filter
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::metadata::MediaType;
+    use crate::models::{Book, BookMetadata, BookSortField, ReadStatus, SortDirection};
+    use crate::repositories::book_query_repository::query_books_filtered;
+    use crate::repositories::test_support::{book, memory_db};
+    use crate::repositories::{
+        add_tag_to_book, create_smart_shelf, create_tag, get_books_in_shelf, insert_book,
+        list_shelves, set_book_status, set_shelf_rules,
+    };
+
+    const NOW: i64 = 1_718_452_800;
+
+    fn file(id: &str, title: &str, author: &str, path: &str) -> Book {
+        Book {
+            path: path.to_string(),
+            book_type: "file".to_string(),
+            author: Some(author.to_string()),
+            ..book(id, title)
+        }
+    }
+
+    /// Monster e Pluto (Urasawa, mangás) e Duna (Herbert, livro).
+    fn library() -> rusqlite::Connection {
+        let conn = memory_db();
+        for b in [
+            file("b1", "Monster", "Naoki Urasawa", "/m/monster.cbz"),
+            file("b2", "Pluto", "Naoki Urasawa", "/m/pluto.cbz"),
+            file("b3", "Duna", "Frank Herbert", "/l/duna.epub"),
+        ] {
+            insert_book(&conn, &b).unwrap();
+        }
+        conn
+    }
+
+    /// Ids que passam nas regras, em ordem de título.
+    fn matching(
+        conn: &rusqlite::Connection,
+        match_mode: RuleMatch,
+        rules: Vec<SmartRule>,
+    ) -> Vec<String> {
+        let rules = SmartShelfRules { match_mode, rules };
+        query_books_filtered(
+            conn,
+            &rules_filter(&rules, NOW),
+            BookSortField::Title,
+            SortDirection::Asc,
+            0,
+            0,
+        )
+        .unwrap()
+        .items
+        .into_iter()
+        .map(|s| s.book.id)
+        .collect()
+    }
+
+    #[test]
+    fn smart_shelf_lists_books_from_its_rules() {
+        let conn = library();
+        let rules = SmartShelfRules {
+            match_mode: RuleMatch::All,
+            rules: vec![SmartRule::Author {
+                op: TextOp::Contains,
+                value: "urasawa".to_string(),
+            }],
+        };
+        create_smart_shelf(&conn, "s1", "Urasawa", &rules).unwrap();
+        assert_eq!(get_books_in_shelf(&conn, "s1").unwrap(), vec!["b1", "b2"]);
+        assert_eq!(list_shelves(&conn).unwrap()[0].book_count, 2);
+
+        let rules = SmartShelfRules {
+            match_mode: RuleMatch::All,
+            rules: vec![SmartRule::BookType {
+                value: "folder".to_string(),
+            }],
+        };
+        assert!(set_shelf_rules(&conn, "s1", &rules).unwrap());
+        assert!(get_books_in_shelf(&conn, "s1").unwrap().is_empty());
+    }
+
+    #[test]
+    fn all_rules_must_match() {
+        let conn = library();
+        let rules = vec![
+            SmartRule::Author {
+                op: TextOp::Contains,
+                value: "urasawa".to_string(),
+            },
+            SmartRule::Progress {
+                op: NumberOp::Lt,
+                value: 100.0,
+            },
+        ];
+        assert_eq!(matching(&conn, RuleMatch::All, rules), vec!["b1", "b2"]);
+    }
+
+    #[test]
+    fn any_rule_may_match() {
+        let conn = library();
+        insert_book(
+            &conn,
+            &Book {
+                cover_path: Some("/covers/b4.jpg".to_string()),
+                ..file("b4", "Akira", "Katsuhiro Otomo", "/m/akira.cbz")
+            },
+        )
+        .unwrap();
+        let rules = vec![
+            SmartRule::Title {
+                op: TextOp::StartsWith,
+                value: "aki".to_string(),
+            },
+            SmartRule::MissingCover,
+        ];
+        assert_eq!(
+            matching(&conn, RuleMatch::Any, rules),
+            vec!["b4", "b3", "b1", "b2"]
+        );
+        assert_eq!(
+            matching(&conn, RuleMatch::All, vec![SmartRule::MissingCover]),
+            vec!["b3", "b1", "b2"]
+        );
+    }
+
+    #[test]
+    fn huge_day_counts_do_not_overflow() {
+        let conn = library();
+        let rules = vec![SmartRule::AddedWithinDays { days: i64::MAX }];
+        assert_eq!(matching(&conn, RuleMatch::All, rules).len(), 3);
+    }
+
+    #[test]
+    fn text_rules_not_contains_and_equals() {
+        let conn = library();
+        // Sem autor também conta como "não contém"
+        insert_book(&conn, &book("b4", "Akira")).unwrap();
+        let not_contains = vec![SmartRule::Author {
+            op: TextOp::NotContains,
+            value: "urasawa".to_string(),
+        }];
+        assert_eq!(
+            matching(&conn, RuleMatch::All, not_contains),
+            vec!["b4", "b3"]
+        );
+
+        let equals = |value: &str| {
+            vec![SmartRule::Title {
+                op: TextOp::Equals,
+                value: value.to_string(),
+            }]
+        };
+        assert_eq!(
+            matching(&conn, RuleMatch::All, equals("monster")),
+            vec!["b1"]
+        );
+        assert!(matching(&conn, RuleMatch::All, equals("Mon")).is_empty());
+    }
+
+    #[test]
+    fn read_status_rule() {
+        let conn = library();
+        set_book_status(&conn, "b2", ReadStatus::Completed, NOW).unwrap();
+        let status = |value| vec![SmartRule::ReadStatus { value }];
+        assert_eq!(
+            matching(&conn, RuleMatch::All, status(ReadStatus::Completed)),
+            vec!["b2"]
+        );
+        assert_eq!(
+            matching(&conn, RuleMatch::All, status(ReadStatus::Unread)),
+            vec!["b3", "b1"]
+        );
+    }
+
+    #[test]
+    fn tag_rule() {
+        let conn = library();
+        let seinen = create_tag(&conn, "Seinen").unwrap();
+        add_tag_to_book(&conn, "b1", &seinen).unwrap();
+        let rules = vec![SmartRule::Tag { tag_id: seinen }];
+        assert_eq!(matching(&conn, RuleMatch::All, rules), vec!["b1"]);
+    }
+
+    #[test]
+    fn book_type_rule() {
+        let conn = library();
+        insert_book(&conn, &book("b4", "Akira")).unwrap();
+        let book_type = |value: &str| {
+            vec![SmartRule::BookType {
+                value: value.to_string(),
+            }]
+        };
+        assert_eq!(
+            matching(&conn, RuleMatch::All, book_type("folder")),
+            vec!["b4"]
+        );
+        assert_eq!(matching(&conn, RuleMatch::All, book_type("file")).len(), 3);
+    }
+
+    #[test]
+    fn media_type_rule_prefers_metadata_over_format() {
+        let conn = library();
+        insert_book(
+            &conn,
+            &Book {
+                metadata: BookMetadata {
+                    media_type: Some(MediaType::Book),
+                    ..Default::default()
+                },
+                ..file("b4", "Akira", "Katsuhiro Otomo", "/m/akira.cbz")
+            },
+        )
+        .unwrap();
+        let media = |value| vec![SmartRule::MediaType { value }];
+        assert_eq!(
+            matching(&conn, RuleMatch::All, media(MediaType::Manga)),
+            vec!["b1", "b2"]
+        );
+        assert_eq!(
+            matching(&conn, RuleMatch::All, media(MediaType::Book)),
+            vec!["b4", "b3"]
+        );
+    }
+
+    #[test]
+    fn added_within_days_compares_milliseconds() {
+        let conn = memory_db();
+        let day = 86_400;
+        // added_at em milissegundos, como o frontend grava
+        for (id, title, ago) in [
+            ("b1", "Recente", 2),
+            ("b2", "Antigo", 10),
+            ("b3", "Limite", 7),
+        ] {
+            insert_book(
+                &conn,
+                &Book {
+                    added_at: (NOW - ago * day) * 1000,
+                    ..book(id, title)
+                },
+            )
+            .unwrap();
+        }
+        let rules = vec![SmartRule::AddedWithinDays { days: 7 }];
+        assert_eq!(matching(&conn, RuleMatch::All, rules), vec!["b3", "b1"]);
+    }
+}
@@ -0,0 +1,323 @@
+//! Estatísticas de leitura calculadas em SQL sobre reading_sessions, para o
+//! painel do frontend não precisar carregar sessões individuais.
+
+use rusqlite::params;
+
+use super::reading_time_by_day;
+use crate::models::{MonthlyFinished, ReadingStats, SeriesReadingTime, WeeklyReading};
+
+/// Quantas séries entram em `top_series`.
+const TOP_SERIES_LIMIT: i64 = 5;
+
+const RANGE_SQL: &str = "(?1 IS NULL OR started_at >= ?1) AND (?2 IS NULL OR started_at <= ?2)";
+
+/// Sequências de dias consecutivos com leitura (gaps and islands).
+fn streaks_cte() -> String {
+    format!(
+        r#"
+WITH days AS (
+    SELECT DISTINCT date(started_at, 'unixepoch', 'localtime') AS day
+    FROM reading_sessions
+    WHERE {}
+),
+islands AS (
+    SELECT day, julianday(day) - ROW_NUMBER() OVER (ORDER BY day) AS grp FROM days
+),
+streaks AS (
+    SELECT MAX(day) AS last_day, COUNT(*) AS len FROM islands GROUP BY grp
+)
+"#,
+        RANGE_SQL
+    )
+}
+
+/// Livros concluídos pelo status explícito; `finished_at` marca o mês.
+const FINISHED_BOOKS_CTE: &str = r#"
+WITH finished AS (
+    SELECT id AS book_id, finished_at
+    FROM books
+    WHERE read_status = 'completed' AND finished_at IS NOT NULL
+)
+"#;
+
+fn weekly(
+    conn: &rusqlite::Connection,
+    from: Option<i64>,
+    to: Option<i64>,
+) -> crate::Result<Vec<WeeklyReading>> {
+    // 'weekday 0' avança até domingo; -6 dias = segunda-feira da semana
+    let mut stmt = conn.prepare(&format!(
+        r#"
+        SELECT date(started_at, 'unixepoch', 'localtime', 'weekday 0', '-6 days') AS week,
+            SUM(ended_at - started_at), SUM(pages_turned)
+        FROM reading_sessions
+        WHERE {}
+        GROUP BY week
+        ORDER BY week
+        "#,
+        RANGE_SQL
+    ))?;
+    let rows = stmt.query_map(params![from, to], |row| {
+        Ok(WeeklyReading {
+            week_start: row.get(0)?,
+            seconds: row.get(1)?,
+            pages: row.get(2)?,
+        })
+    })?;
+    let mut out = Vec::new();
+    for row in rows {
+        out.push(row?);
+    }
+    Ok(out)
+}
+
+fn finished_per_month(
+    conn: &rusqlite::Connection,
+    from: Option<i64>,
+    to: Option<i64>,
+) -> crate::Result<Vec<MonthlyFinished>> {
+    let mut stmt = conn.prepare(&format!(
+        r#"{}
+        SELECT strftime('%Y-%m', finished_at, 'unixepoch', 'localtime') AS month, COUNT(*)
+        FROM finished
+        WHERE (?1 IS NULL OR finished_at >= ?1) AND (?2 IS NULL OR finished_at <= ?2)
+        GROUP BY month
+        ORDER BY month
+        "#,
+        FINISHED_BOOKS_CTE
+    ))?;
+    let rows = stmt.query_map(params![from, to], |row| {
+        Ok(MonthlyFinished {
+            month: row.get(0)?,
+            books: row.get(1)?,
+        })
+    })?;
+    let mut out = Vec::new();
+    for row in rows {
+        out.push(row?);
+    }
+    Ok(out)
+}
+
+/// Séries com mais tempo de leitura no intervalo. Livros sem série formam
+/// um grupo próprio (pelo id), exibido pelo título.
+fn top_series(
+    conn: &rusqlite::Connection,
+    from: Option<i64>,
+    to: Option<i64>,
+) -> crate::Result<Vec<SeriesReadingTime>> {
+    let mut stmt = conn.prepare(&format!(
+        r#"
+        SELECT MAX(COALESCE(NULLIF(b.series, ''), b.title)), COUNT(DISTINCT s.book_id),
+            SUM(s.ended_at - s.started_at), COUNT(*), SUM(s.pages_turned), MAX(s.ended_at)
+        FROM (SELECT * FROM reading_sessions WHERE {}) s
+        JOIN books b ON b.id = s.book_id
+        GROUP BY COALESCE(NULLIF(b.series, ''), 'book:' || b.id)
+        ORDER BY 3 DESC
+        LIMIT ?3
+        "#,
+        RANGE_SQL
+    ))?;
+    let rows = stmt.query_map(params![from, to, TOP_SERIES_LIMIT], |row| {
+        Ok(SeriesReadingTime {
+            series: row.get(0)?,
+            books: row.get(1)?,
+            seconds: row.get(2)?,
+            sessions: row.get(3)?,
+            pages: row.get(4)?,
+            last_read_at: row.get(5)?,
+        })
+    })?;
+    let mut out = Vec::new();
+    for row in rows {
+        out.push(row?);
+    }
+    Ok(out)
+}
+
+/// (sequência atual, maior sequência no intervalo). A atual considera todas
+/// as sessões e vale se o último dia lido foi hoje ou ontem (`now`, local).
+fn streaks(
+    conn: &rusqlite::Connection,
+    from: Option<i64>,
+    to: Option<i64>,
+    now: i64,
+) -> crate::Result<(i64, i64)> {
+    let longest: i64 = conn.query_row(
+        &format!(
+            "{} SELECT COALESCE(MAX(len), 0) FROM streaks",
+            streaks_cte()
+        ),
+        params![from, to],
+        |row| row.get(0),
+    )?;
+    let current: i64 = conn.query_row(
+        &format!(
+            r#"{}
+            SELECT COALESCE(MAX(len), 0) FROM streaks
+            WHERE last_day >= date(?3, 'unixepoch', 'localtime', '-1 day')
+            "#,
+            streaks_cte()
+        ),
+        params![None::<i64>, None::<i64>, now],
+        |row| row.get(0),
+    )?;
+    Ok((current, longest))
+}
+
+pub fn reading_stats(
+    conn: &rusqlite::Connection,
+    from: Option<i64>,
+    to: Option<i64>,
+    now: i64,
+) -> crate::Result<ReadingStats> {
+    let (total_seconds, total_pages, sessions, days_read): (i64, i64, i64, i64) = conn.query_row(
+        &format!(
+            r#"
+            SELECT COALESCE(SUM(ended_at - started_at), 0), COALESCE(SUM(pages_turned), 0), COUNT(*),
+                COUNT(DISTINCT date(started_at, 'unixepoch', 'localtime'))
+            FROM reading_sessions
+            WHERE {}
+            "#,
+            RANGE_SQL
+        ),
+        params![from, to],
+        |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?)),
+    )?;
+    let (current_streak, longest_streak) = streaks(conn, from, to, now)?;
+
+    let ratio = |num: i64, den: i64| (den > 0).then(|| num as f64 / den as f64);
+    Ok(ReadingStats {
+        from,
+        to,
+        total_seconds,
+        total_pages,
+        sessions,
+        days_read,
+        daily: reading_time_by_day(conn, from, to, None)?,
+        weekly: weekly(conn, from, to)?,
+        current_streak,
+        longest_streak,
+        finished_per_month: finished_per_month(conn, from, to)?,
+        top_series: top_series(conn, from, to)?,
+        pages_per_hour: ratio(total_pages * 3600, total_seconds).filter(|_| total_pages > 0),
+        avg_session_secs: ratio(total_seconds, sessions),
+        avg_daily_secs: ratio(total_seconds, days_read),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{Book, BookMetadata, ReadStatus};
+    use crate::repositories::test_support::{book, memory_db, volume};
+    use crate::repositories::{
+        end_session, heartbeat_session, insert_book, insert_volume, mark_volumes, start_session,
+    };
+
+    // Meio-dia, para as datas locais não dependerem do fuso
+    const NOW: i64 = 1_718_452_800 + 12 * 3600;
+    const DAY: i64 = 86_400;
+
+    fn add_book(conn: &rusqlite::Connection, id: &str, title: &str, series: Option<&str>) {
+        insert_book(
+            conn,
+            &Book {
+                metadata: BookMetadata {
+                    series: series.map(str::to_string),
+                    ..Default::default()
+                },
+                ..book(id, title)
+            },
+        )
+        .unwrap();
+        insert_volume(conn, &volume(&format!("v{}", id), id, "Volume 1")).unwrap();
+    }
+
+    fn read(conn: &rusqlite::Connection, book_id: &str, at: i64) {
+        let s = start_session(conn, book_id, &format!("v{}", book_id), 1, at).unwrap();
+        let s = heartbeat_session(conn, &s.id, 11, at + 240).unwrap();
+        end_session(conn, &s.id, 11, at + 300).unwrap();
+    }
+
+    /// Leitura 5, 4 e 3 dias atrás (sequência de 3), ontem e hoje (atual: 2).
+    fn five_days() -> rusqlite::Connection {
+        let conn = memory_db();
+        add_book(&conn, "b1", "Vagabond", None);
+        for ago in [5, 4, 3, 1, 0] {
+            read(&conn, "b1", NOW - ago * DAY);
+        }
+        conn
+    }
+
+    #[test]
+    fn sums_totals_and_rates() {
+        let conn = five_days();
+        let stats = reading_stats(&conn, None, None, NOW).unwrap();
+        assert_eq!((stats.sessions, stats.days_read), (5, 5));
+        assert_eq!((stats.total_seconds, stats.total_pages), (1500, 50));
+        assert_eq!(stats.daily.len(), 5);
+        assert_eq!(
+            stats.weekly.iter().map(|w| w.seconds).sum::<i64>(),
+            stats.total_seconds
+        );
+        assert_eq!(stats.pages_per_hour, Some(120.0));
+        assert_eq!(stats.avg_session_secs, Some(300.0));
+        assert_eq!(stats.top_series[0].series, "Vagabond");
+    }
+
+    #[test]
+    fn computes_current_and_longest_streaks() {
+        let conn = five_days();
+        let stats = reading_stats(&conn, None, None, NOW).unwrap();
+        assert_eq!((stats.current_streak, stats.longest_streak), (2, 3));
+
+        // Intervalo só com os dois últimos dias: a maior sequência é a atual
+        let recent = reading_stats(&conn, Some(NOW - 2 * DAY), Some(NOW + DAY), NOW).unwrap();
+        assert_eq!((recent.sessions, recent.longest_streak), (2, 2));
+        assert_eq!(recent.current_streak, 2);
+    }
+
+    #[test]
+    fn counts_finished_books_per_month() {
+        let conn = five_days();
+        assert!(reading_stats(&conn, None, None, NOW)
+            .unwrap()
+            .finished_per_month
+            .is_empty());
+        mark_volumes(&conn, &["vb1".to_string()], ReadStatus::Completed, NOW).unwrap();
+        let stats = reading_stats(&conn, None, None, NOW).unwrap();
+        assert_eq!(stats.finished_per_month.len(), 1);
+        assert_eq!(stats.finished_per_month[0].books, 1);
+    }
+
+    #[test]
+    fn top_series_combines_books_of_the_same_series() {
+        let conn = memory_db();
+        add_book(&conn, "b1", "Berserk Vol. 1", Some("Berserk"));
+        add_book(&conn, "b2", "Berserk Vol. 2", Some("Berserk"));
+        add_book(&conn, "b3", "Vagabond", None);
+        add_book(&conn, "b4", "Vagabond", None);
+        for (book, at) in [
+            ("b1", 1_000),
+            ("b1", 1_500),
+            ("b2", 2_000),
+            ("b3", 3_000),
+            ("b3", 4_000),
+            ("b4", 5_000),
+        ] {
+            read(&conn, book, at);
+        }
+
+        let top = reading_stats(&conn, None, None, 10_000).unwrap().top_series;
+        let summary: Vec<_> = top
+            .iter()
+            .map(|s| (s.series.as_str(), s.books, s.sessions))
+            .collect();
+        // Livros sem série não se juntam só por terem o mesmo título
+        assert_eq!(
+            summary,
+            [("Berserk", 2, 3), ("Vagabond", 1, 2), ("Vagabond", 1, 1)]
+        );
+    }
+}
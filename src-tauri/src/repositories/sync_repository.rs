@@ -0,0 +1,737 @@
+//! Repositório do change log (última mudança de cada linha) e tombstones.
+//! As entradas são gravadas por triggers (ver `db::schema::SYNC_TABLES`).
+
+use crate::models::{ChangeEntry, ChangeFeed, SyncConfig, Tombstone};
+use rusqlite::params;
+
+pub fn get_device_id(conn: &rusqlite::Connection) -> crate::Result<String> {
+    let id = conn.query_row(
+        "SELECT value FROM sync_meta WHERE key = 'device_id'",
+        [],
+        |row| row.get(0),
+    )?;
+    Ok(id)
+}
+
+/// Mudanças com seq > cursor, em ordem. `limit` <= 0 retorna todas.
+pub fn changes_since(
+    conn: &rusqlite::Connection,
+    cursor: i64,
+    limit: i64,
+) -> crate::Result<ChangeFeed> {
+    // Busca uma a mais para saber se há próxima página
+    let fetch = if limit > 0 { limit + 1 } else { -1 };
+    let mut stmt = conn.prepare(
+        r#"
+        SELECT seq, table_name, row_key, op, changed_at, device_id
+        FROM change_log
+        WHERE seq > ?1
+        ORDER BY seq
+        LIMIT ?2
+        "#,
+    )?;
+    let rows = stmt.query_map(params![cursor, fetch], |row| {
+        Ok(ChangeEntry {
+            seq: row.get(0)?,
+            table_name: row.get(1)?,
+            row_key: row.get(2)?,
+            op: row.get(3)?,
+            changed_at: row.get(4)?,
+            device_id: row.get(5)?,
+        })
+    })?;
+    let mut changes = Vec::new();
+    for row in rows {
+        changes.push(row?);
+    }
+    let has_more = limit > 0 && changes.len() as i64 > limit;
+    if has_more {
+        changes.truncate(limit as usize);
+    }
+    let cursor = changes.last().map(|c| c.seq).unwrap_or(cursor);
+    Ok(ChangeFeed {
+        changes,
+        cursor,
+        has_more,
+    })
+}
+
+/// Último seq do change log (0 se vazio).
+pub fn latest_change_seq(conn: &rusqlite::Connection) -> crate::Result<i64> {
+    let seq = conn.query_row("SELECT COALESCE(MAX(seq), 0) FROM change_log", [], |row| {
+        row.get(0)
+    })?;
+    Ok(seq)
+}
+
+/// Tombstones com deleted_at >= since.
+pub fn list_tombstones(conn: &rusqlite::Connection, since: i64) -> crate::Result<Vec<Tombstone>> {
+    let mut stmt = conn.prepare(
+        r#"
+        SELECT table_name, row_key, deleted_at, device_id
+        FROM tombstones
+        WHERE deleted_at >= ?1
+        ORDER BY deleted_at
+        "#,
+    )?;
+    let rows = stmt.query_map([since], |row| {
+        Ok(Tombstone {
+            table_name: row.get(0)?,
+            row_key: row.get(1)?,
+            deleted_at: row.get(2)?,
+            device_id: row.get(3)?,
+        })
+    })?;
+    let mut out = Vec::new();
+    for row in rows {
+        out.push(row?);
+    }
+    Ok(out)
+}
+
+pub fn get_sync_meta(conn: &rusqlite::Connection, key: &str) -> crate::Result<Option<String>> {
+    let row = conn.query_row("SELECT value FROM sync_meta WHERE key = ?1", [key], |row| {
+        row.get(0)
+    });
+    match row {
+        Ok(v) => Ok(Some(v)),
+        Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// Grava (ou remove, se `value` = None) uma chave de sync_meta.
+pub fn set_sync_meta(
+    conn: &rusqlite::Connection,
+    key: &str,
+    value: Option<&str>,
+) -> crate::Result<()> {
+    match value {
+        Some(v) => conn.execute(
+            r#"
+            INSERT INTO sync_meta (key, value) VALUES (?1, ?2)
+            ON CONFLICT(key) DO UPDATE SET value = excluded.value
+            "#,
+            params![key, v],
+        )?,
+        None => conn.execute("DELETE FROM sync_meta WHERE key = ?1", [key])?,
+    };
+    Ok(())
+}
+
+/// Descrição de uma tabela sincronizada pelo engine de sync.
+pub struct SyncTable {
+    pub name: &'static str,
+    pub keys: &'static [&'static str],
+    /// Colunas de dados trocadas (sem as chaves e sem updated_at).
+    pub columns: &'static [&'static str],
+    /// false = só atualiza linhas já existentes localmente (ex.: livros, cujo path é local).
+    pub insert: bool,
+    /// Aceita exclusões remotas.
+    pub delete: bool,
+    /// (coluna, tabela referenciada): o registro é ignorado se a referência não existir.
+    pub requires: &'static [(&'static str, &'static str)],
+}
+
+/// Tabelas sincronizadas, em ordem de aplicação (referenciadas antes das dependentes).
+pub const SYNC_TABLE_SPECS: &[SyncTable] = &[
+    SyncTable {
+        name: "books",
+        keys: &["id"],
+        columns: &[
+            "title",
+            "author",
+            "description",
+            "read_status",
+            "started_at",
+            "finished_at",
+            "reread_count",
+            "publisher",
+            "year",
+            "language",
+            "isbns",
+            "alt_titles",
+            "series",
+            "series_number",
+            "page_count",
+            "media_type",
+            "genres",
+            "external_ids",
+        ],
+        insert: false,
+        delete: false,
+        requires: &[],
+    },
+    SyncTable {
+        name: "volumes",
+        keys: &["id"],
+        columns: &["read_status", "started_at", "finished_at", "reread_count"],
+        insert: false,
+        delete: false,
+        requires: &[],
+    },
+    SyncTable {
+        name: "global_settings",
+        keys: &["id"],
+        columns: &[
+            "theme",
+            "custom_theme_id",
+            "default_layout_mode",
+            "default_reading_direction",
+            "metadata_cache_ttl_secs",
+            "metadata_library_kind",
+            "metadata_sources",
+        ],
+        insert: false,
+        delete: false,
+        requires: &[],
+    },
+    SyncTable {
+        name: "custom_themes",
+        keys: &["id"],
+        columns: &["name", "css"],
+        insert: true,
+        delete: true,
+        requires: &[],
+    },
+    SyncTable {
+        name: "shelves",
+        keys: &["id"],
+        columns: &["name", "position", "icon", "color", "kind", "rules"],
+        insert: true,
+        delete: true,
+        requires: &[],
+    },
+    SyncTable {
+        name: "book_shelves",
+        keys: &["book_id", "shelf_id"],
+        columns: &["position"],
+        insert: true,
+        delete: true,
+        requires: &[("book_id", "books"), ("shelf_id", "shelves")],
+    },
+    SyncTable {
+        name: "book_settings",
+        keys: &["book_id"],
+        columns: &["layout_mode", "reading_direction", "zoom"],
+        insert: true,
+        delete: true,
+        requires: &[("book_id", "books")],
+    },
+    SyncTable {
+        name: "reading_progress",
+        keys: &["book_id", "volume_id"],
+        columns: &[
+            "current_chapter_id",
+            "page_index",
+            "scroll_offset",
+            "total_pages",
+        ],
+        insert: true,
+        delete: true,
+        requires: &[("book_id", "books"), ("volume_id", "volumes")],
+    },
+    SyncTable {
+        name: "bookmarks",
+        keys: &["id"],
+        columns: &[
+            "book_id",
+            "volume_id",
+            "chapter_id",
+            "page_index",
+            "cfi",
+            "label",
+            "thumbnail",
+            "created_at",
+        ],
+        insert: true,
+        delete: true,
+        requires: &[("book_id", "books"), ("volume_id", "volumes")],
+    },
+    SyncTable {
+        name: "annotations",
+        keys: &["id"],
+        columns: &[
+            "book_id",
+            "volume_id",
+            "chapter_id",
+            "book_hash",
+            "cfi_range",
+            "page_index",
+            "quads",
+            "text",
+            "color",
+            "note",
+            "created_at",
+        ],
+        insert: true,
+        delete: true,
+        requires: &[("book_id", "books"), ("volume_id", "volumes")],
+    },
+    SyncTable {
+        name: "chapter_progress",
+        keys: &["chapter_id"],
+        columns: &["book_id", "volume_id", "read", "last_page", "read_at"],
+        insert: true,
+        delete: true,
+        requires: &[("chapter_id", "chapters"), ("book_id", "books")],
+    },
+    SyncTable {
+        name: "tags",
+        keys: &["id"],
+        columns: &["name"],
+        insert: true,
+        delete: true,
+        requires: &[],
+    },
+    SyncTable {
+        name: "book_tags",
+        keys: &["book_id", "tag_id"],
+        columns: &["auto"],
+        insert: true,
+        delete: true,
+        requires: &[("book_id", "books"), ("tag_id", "tags")],
+    },
+    SyncTable {
+        name: "book_reviews",
+        keys: &["book_id"],
+        columns: &["rating", "review"],
+        insert: true,
+        delete: true,
+        requires: &[("book_id", "books")],
+    },
+];
+
+pub fn sync_table_spec(name: &str) -> Option<&'static SyncTable> {
+    SYNC_TABLE_SPECS.iter().find(|t| t.name == name)
+}
+
+/// Divide `row_key` (partes unidas com `|`) nos valores das colunas-chave.
+fn split_key<'a>(spec: &SyncTable, key: &'a str) -> crate::Result<Vec<&'a str>> {
+    let parts: Vec<&str> = key.splitn(spec.keys.len(), '|').collect();
+    if parts.len() != spec.keys.len() {
+        return Err(crate::Error::Validation(format!(
+            "invalid key '{}' for {}",
+            key, spec.name
+        )));
+    }
+    Ok(parts)
+}
+
+fn key_where(spec: &SyncTable, first_param: usize) -> String {
+    spec.keys
+        .iter()
+        .enumerate()
+        .map(|(i, k)| format!("{} = ?{}", k, first_param + i))
+        .collect::<Vec<_>>()
+        .join(" AND ")
+}
+
+fn sql_to_json(v: rusqlite::types::ValueRef) -> serde_json::Value {
+    use rusqlite::types::ValueRef;
+    match v {
+        ValueRef::Null => serde_json::Value::Null,
+        ValueRef::Integer(i) => serde_json::Value::from(i),
+        ValueRef::Real(f) => serde_json::Value::from(f),
+        ValueRef::Text(t) => serde_json::Value::from(String::from_utf8_lossy(t).to_string()),
+        ValueRef::Blob(_) => serde_json::Value::Null,
+    }
+}
+
+fn json_to_sql(v: Option<&serde_json::Value>) -> rusqlite::types::Value {
+    use rusqlite::types::Value;
+    match v {
+        None | Some(serde_json::Value::Null) => Value::Null,
+        Some(serde_json::Value::Bool(b)) => Value::Integer(*b as i64),
+        Some(serde_json::Value::Number(n)) => match n.as_i64() {
+            Some(i) => Value::Integer(i),
+            None => Value::Real(n.as_f64().unwrap_or(0.0)),
+        },
+        Some(serde_json::Value::String(s)) => Value::Text(s.clone()),
+        Some(other) => Value::Text(other.to_string()),
+    }
+}
+
+/// Estado atual de uma linha sincronizável: (updated_at, colunas de dados).
+pub fn read_sync_row(
+    conn: &rusqlite::Connection,
+    spec: &SyncTable,
+    key: &str,
+) -> crate::Result<Option<(i64, serde_json::Map<String, serde_json::Value>)>> {
+    let parts = split_key(spec, key)?;
+    let mut cols = vec!["updated_at"];
+    cols.extend_from_slice(spec.columns);
+    let sql = format!(
+        "SELECT {} FROM {} WHERE {}",
+        cols.join(", "),
+        spec.name,
+        key_where(spec, 1)
+    );
+    let mut stmt = conn.prepare(&sql)?;
+    let mut rows = stmt.query(rusqlite::params_from_iter(parts.iter()))?;
+    if let Some(row) = rows.next()? {
+        let updated_at: i64 = row.get(0)?;
+        let mut data = serde_json::Map::new();
+        for (i, col) in spec.columns.iter().enumerate() {
+            data.insert(col.to_string(), sql_to_json(row.get_ref(i + 1)?));
+        }
+        return Ok(Some((updated_at, data)));
+    }
+    Ok(None)
+}
+
+pub fn get_tombstone(
+    conn: &rusqlite::Connection,
+    table_name: &str,
+    row_key: &str,
+) -> crate::Result<Option<Tombstone>> {
+    let row = conn.query_row(
+        r#"
+        SELECT table_name, row_key, deleted_at, device_id
+        FROM tombstones WHERE table_name = ?1 AND row_key = ?2
+        "#,
+        params![table_name, row_key],
+        |row| {
+            Ok(Tombstone {
+                table_name: row.get(0)?,
+                row_key: row.get(1)?,
+                deleted_at: row.get(2)?,
+                device_id: row.get(3)?,
+            })
+        },
+    );
+    match row {
+        Ok(t) => Ok(Some(t)),
+        Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// Dispositivo que fez a última mudança registrada para a linha.
+pub fn row_origin_device(
+    conn: &rusqlite::Connection,
+    table_name: &str,
+    row_key: &str,
+) -> crate::Result<Option<String>> {
+    let row = conn.query_row(
+        r#"
+        SELECT device_id FROM change_log
+        WHERE table_name = ?1 AND row_key = ?2
+        ORDER BY seq DESC LIMIT 1
+        "#,
+        params![table_name, row_key],
+        |row| row.get(0),
+    );
+    match row {
+        Ok(d) => Ok(Some(d)),
+        Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// Chaves alteradas por `device_id` após o cursor, sem repetição, na ordem da última mudança.
+/// Retorna também o novo cursor (último seq considerado).
+pub fn changed_keys_since(
+    conn: &rusqlite::Connection,
+    cursor: i64,
+    device_id: &str,
+) -> crate::Result<(Vec<(String, String)>, i64)> {
+    // Fixa o limite antes da leitura: mudanças gravadas depois ficam para a próxima rodada
+    let upto = latest_change_seq(conn)?.max(cursor);
+    let mut stmt = conn.prepare(
+        r#"
+        SELECT table_name, row_key, MAX(seq) AS last_seq
+        FROM change_log
+        WHERE seq > ?1 AND seq <= ?2 AND device_id = ?3
+        GROUP BY table_name, row_key
+        ORDER BY last_seq
+        "#,
+    )?;
+    let rows = stmt.query_map(params![cursor, upto, device_id], |row| {
+        Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))
+    })?;
+    let mut out = Vec::new();
+    for row in rows {
+        out.push(row?);
+    }
+    Ok((out, upto))
+}
+
+/// Executa `f` com `applying_device` definido, para que os triggers registrem
+/// a mudança em nome do dispositivo de origem.
+fn with_origin<T>(
+    conn: &rusqlite::Connection,
+    origin_device: &str,
+    f: impl FnOnce() -> crate::Result<T>,
+) -> crate::Result<T> {
+    set_sync_meta(conn, "applying_device", Some(origin_device))?;
+    let result = f();
+    set_sync_meta(conn, "applying_device", None)?;
+    result
+}
+
+/// Aplica uma linha remota. Retorna false se foi ignorada (referência ausente
+/// ou tabela que só atualiza linhas existentes).
+pub fn apply_sync_upsert(
+    conn: &rusqlite::Connection,
+    spec: &SyncTable,
+    key: &str,
+    updated_at: i64,
+    data: &serde_json::Map<String, serde_json::Value>,
+    origin_device: &str,
+) -> crate::Result<bool> {
+    let parts = split_key(spec, key)?;
+    for (col, table) in spec.requires {
+        let idx = spec.keys.iter().position(|k| k == col);
+        let value = match idx {
+            Some(i) => rusqlite::types::Value::Text(parts[i].to_string()),
+            None => json_to_sql(data.get(*col)),
+        };
+        let exists: bool = conn
+            .query_row(
+                &format!("SELECT 1 FROM {} WHERE id = ?1", table),
+                [value],
+                |_| Ok(true),
+            )
+            .unwrap_or(false);
+        if !exists {
+            return Ok(false);
+        }
+    }
+
+    // Colunas ausentes (registro de uma versão anterior) mantêm o valor local
+    let columns: Vec<&str> = spec
+        .columns
+        .iter()
+        .copied()
+        .filter(|c| data.contains_key(*c))
+        .collect();
+    let mut values: Vec<rusqlite::types::Value> = Vec::new();
+    with_origin(conn, origin_device, || {
+        if spec.insert {
+            let mut cols: Vec<&str> = spec.keys.to_vec();
+            cols.extend_from_slice(&columns);
+            cols.push("updated_at");
+            for p in &parts {
+                values.push(rusqlite::types::Value::Text(p.to_string()));
+            }
+            for col in &columns {
+                values.push(json_to_sql(data.get(*col)));
+            }
+            values.push(rusqlite::types::Value::Integer(updated_at));
+            let placeholders = (1..=cols.len())
+                .map(|i| format!("?{}", i))
+                .collect::<Vec<_>>()
+                .join(", ");
+            let mut sets: Vec<String> = columns
+                .iter()
+                .map(|c| format!("{} = excluded.{}", c, c))
+                .collect();
+            sets.push("updated_at = excluded.updated_at".to_string());
+            let sql = format!(
+                "INSERT INTO {t} ({cols}) VALUES ({ph}) ON CONFLICT({keys}) DO UPDATE SET {sets}",
+                t = spec.name,
+                cols = cols.join(", "),
+                ph = placeholders,
+                keys = spec.keys.join(", "),
+                sets = sets.join(", "),
+            );
+            conn.execute(&sql, rusqlite::params_from_iter(values.iter()))?;
+            Ok(true)
+        } else {
+            let mut sets: Vec<String> = columns
+                .iter()
+                .enumerate()
+                .map(|(i, c)| format!("{} = ?{}", c, i + 1))
+                .collect();
+            sets.push(format!("updated_at = ?{}", columns.len() + 1));
+            for col in &columns {
+                values.push(json_to_sql(data.get(*col)));
+            }
+            values.push(rusqlite::types::Value::Integer(updated_at));
+            for p in &parts {
+                values.push(rusqlite::types::Value::Text(p.to_string()));
+            }
+            let sql = format!(
+                "UPDATE {} SET {} WHERE {}",
+                spec.name,
+                sets.join(", "),
+                key_where(spec, columns.len() + 2)
+            );
+            let rows = conn.execute(&sql, rusqlite::params_from_iter(values.iter()))?;
+            Ok(rows > 0)
+        }
+    })
+}
+
+/// Aplica uma exclusão remota. Retorna false se a tabela não aceita exclusões.
+pub fn apply_sync_delete(
+    conn: &rusqlite::Connection,
+    spec: &SyncTable,
+    key: &str,
+    origin_device: &str,
+) -> crate::Result<bool> {
+    if !spec.delete {
+        return Ok(false);
+    }
+    let parts = split_key(spec, key)?;
+    with_origin(conn, origin_device, || {
+        conn.execute(
+            &format!("DELETE FROM {} WHERE {}", spec.name, key_where(spec, 1)),
+            rusqlite::params_from_iter(parts.iter()),
+        )?;
+        Ok(true)
+    })
+}
+
+pub fn get_sync_config(conn: &rusqlite::Connection) -> crate::Result<SyncConfig> {
+    Ok(SyncConfig {
+        backend: get_sync_meta(conn, "sync_backend")?,
+        target: get_sync_meta(conn, "sync_target")?,
+        username: get_sync_meta(conn, "sync_username")?,
+        token: get_sync_meta(conn, "sync_token")?,
+        interval_secs: get_sync_meta(conn, "sync_interval_secs")?
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(0),
+        last_sync_at: get_sync_meta(conn, "sync_last_at")?.and_then(|v| v.parse().ok()),
+    })
+}
+
+/// Salva a configuração. Trocar backend ou destino zera os cursores de push/pull.
+pub fn save_sync_config(conn: &rusqlite::Connection, config: &SyncConfig) -> crate::Result<()> {
+    let current = get_sync_config(conn)?;
+    if current.backend != config.backend || current.target != config.target {
+        set_sync_meta(conn, "sync_push_cursor", None)?;
+        set_sync_meta(conn, "sync_pull_cursor", None)?;
+    }
+    set_sync_meta(conn, "sync_backend", config.backend.as_deref())?;
+    set_sync_meta(conn, "sync_target", config.target.as_deref())?;
+    set_sync_meta(conn, "sync_username", config.username.as_deref())?;
+    set_sync_meta(conn, "sync_token", config.token.as_deref())?;
+    set_sync_meta(
+        conn,
+        "sync_interval_secs",
+        Some(&config.interval_secs.max(0).to_string()),
+    )?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn conn() -> rusqlite::Connection {
+        let conn = rusqlite::Connection::open_in_memory().unwrap();
+        crate::db::run_migrations(&conn).unwrap();
+        conn
+    }
+
+    #[test]
+    fn migrations_leave_schema_alone_when_up_to_date() {
+        let conn = conn();
+        let schema_version = || -> i64 {
+            conn.query_row("PRAGMA schema_version", [], |row| row.get(0))
+                .unwrap()
+        };
+        let before = schema_version();
+        crate::db::run_migrations(&conn).unwrap();
+        assert_eq!(schema_version(), before);
+
+        // Versão antiga dos triggers: recriados na próxima abertura
+        set_sync_meta(&conn, "sync_triggers_version", Some("0")).unwrap();
+        crate::db::run_migrations(&conn).unwrap();
+        assert!(schema_version() > before);
+        assert_ne!(
+            get_sync_meta(&conn, "sync_triggers_version")
+                .unwrap()
+                .as_deref(),
+            Some("0")
+        );
+    }
+
+    fn feed(conn: &rusqlite::Connection, cursor: i64) -> Vec<(String, String, String)> {
+        changes_since(conn, cursor, 0)
+            .unwrap()
+            .changes
+            .into_iter()
+            .map(|c| (c.table_name, c.row_key, c.op))
+            .collect()
+    }
+
+    #[test]
+    fn triggers_log_changes_and_tombstones() {
+        let conn = conn();
+        let base = latest_change_seq(&conn).unwrap();
+        let entry = |key: &str, op: &str| ("shelves".to_string(), key.to_string(), op.to_string());
+
+        crate::repositories::create_shelf(&conn, "s1", "Mangá").unwrap();
+        crate::repositories::create_shelf(&conn, "s2", "Livros").unwrap();
+        assert_eq!(
+            feed(&conn, base),
+            [entry("s1", "upsert"), entry("s2", "upsert")]
+        );
+
+        // Update: a linha volta para o fim do feed, sem entrada duplicada
+        crate::repositories::rename_shelf(&conn, "s1", "Quadrinhos").unwrap();
+        assert_eq!(
+            feed(&conn, base),
+            [entry("s2", "upsert"), entry("s1", "upsert")]
+        );
+
+        crate::repositories::delete_shelf(&conn, "s2").unwrap();
+        assert_eq!(
+            feed(&conn, base),
+            [entry("s1", "upsert"), entry("s2", "delete")]
+        );
+        let tombstone = get_tombstone(&conn, "shelves", "s2").unwrap().unwrap();
+        assert_eq!(tombstone.device_id, get_device_id(&conn).unwrap());
+        assert_eq!(list_tombstones(&conn, 0).unwrap().len(), 1);
+
+        // Recriar a linha apaga o tombstone
+        crate::repositories::create_shelf(&conn, "s2", "Livros").unwrap();
+        assert!(get_tombstone(&conn, "shelves", "s2").unwrap().is_none());
+
+        // Paginação pelo cursor
+        let page = changes_since(&conn, base, 1).unwrap();
+        assert!(page.has_more);
+        assert_eq!(page.changes[0].row_key, "s1");
+        let page = changes_since(&conn, page.cursor, 1).unwrap();
+        assert!(!page.has_more);
+        assert_eq!(page.changes[0].row_key, "s2");
+        let page = changes_since(&conn, page.cursor, 1).unwrap();
+        assert!(page.changes.is_empty());
+        assert_eq!(page.cursor, latest_change_seq(&conn).unwrap());
+    }
+
+    #[test]
+    fn rows_without_updated_at_are_backfilled() {
+        let conn = conn();
+        crate::repositories::create_shelf(&conn, "s1", "Mangá").unwrap();
+        crate::repositories::create_shelf(&conn, "s2", "Livros").unwrap();
+        conn.execute("UPDATE shelves SET updated_at = 0 WHERE id = 's1'", [])
+            .unwrap();
+        conn.execute("UPDATE shelves SET updated_at = 1000 WHERE id = 's2'", [])
+            .unwrap();
+        set_sync_meta(&conn, "sync_triggers_version", Some("1")).unwrap();
+        crate::db::run_migrations(&conn).unwrap();
+        let updated_at = |id: &str| -> i64 {
+            conn.query_row(
+                "SELECT updated_at FROM shelves WHERE id = ?1",
+                [id],
+                |row| row.get(0),
+            )
+            .unwrap()
+        };
+        assert!(updated_at("s1") > 1000);
+        // Quem já tinha data não ganha uma nova (e não venceria conflitos à toa)
+        assert_eq!(updated_at("s2"), 1000);
+        let entries: i64 = conn
+            .query_row(
+                "SELECT COUNT(*) FROM change_log WHERE table_name = 'shelves' AND row_key = 's1'",
+                [],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert_eq!(entries, 1);
+    }
+}
@@ -0,0 +1,316 @@
+//! Repositório de tags livres e do vínculo livro–tag.
+//! Nomes são únicos sem diferenciar maiúsculas; a unicidade é garantida aqui
+//! (e não por índice) para que a sincronização nunca falhe por conflito.
+
+use crate::models::{BookTag, Tag};
+use rusqlite::params;
+
+fn now_secs() -> i64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs() as i64)
+        .unwrap_or(0)
+}
+
+/// Tags em ordem alfabética, com o número de livros de cada uma.
+pub fn list_tags(conn: &rusqlite::Connection) -> crate::Result<Vec<Tag>> {
+    let mut stmt = conn.prepare(
+        r#"
+        SELECT t.id, t.name,
+            (SELECT COUNT(*) FROM book_tags bt JOIN books b ON b.id = bt.book_id
+             WHERE bt.tag_id = t.id)
+        FROM tags t
+        ORDER BY t.name COLLATE NOCASE
+        "#,
+    )?;
+    let rows = stmt.query_map([], |row| {
+        Ok(Tag {
+            id: row.get(0)?,
+            name: row.get(1)?,
+            book_count: row.get(2)?,
+        })
+    })?;
+    let mut out = Vec::new();
+    for row in rows {
+        out.push(row?);
+    }
+    Ok(out)
+}
+
+/// Id da tag com o nome (sem diferenciar maiúsculas).
+pub fn find_tag_by_name(conn: &rusqlite::Connection, name: &str) -> crate::Result<Option<String>> {
+    let r = conn.query_row(
+        "SELECT id FROM tags WHERE name = ?1 COLLATE NOCASE ORDER BY id LIMIT 1",
+        [name],
+        |row| row.get(0),
+    );
+    match r {
+        Ok(id) => Ok(Some(id)),
+        Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// Cria a tag; se já existir uma com o mesmo nome, retorna o id dela.
+pub fn create_tag(conn: &rusqlite::Connection, name: &str) -> crate::Result<String> {
+    if let Some(id) = find_tag_by_name(conn, name)? {
+        return Ok(id);
+    }
+    let id = uuid::Uuid::new_v4().to_string();
+    conn.execute(
+        "INSERT INTO tags (id, name, updated_at) VALUES (?1, ?2, ?3)",
+        params![id, name, now_secs()],
+    )?;
+    Ok(id)
+}
+
+/// Retorna false se a tag não existir. Recusa um nome já usado por outra tag
+/// (para juntar as duas, use `merge_tags`); mudar só maiúsculas é permitido.
+pub fn rename_tag(conn: &rusqlite::Connection, id: &str, name: &str) -> crate::Result<bool> {
+    let taken: bool = conn.query_row(
+        "SELECT EXISTS(SELECT 1 FROM tags WHERE name = ?1 COLLATE NOCASE AND id <> ?2)",
+        params![name, id],
+        |row| row.get(0),
+    )?;
+    if taken {
+        return Err(crate::Error::Validation(format!(
+            "tag already exists: {}",
+            name
+        )));
+    }
+    let n = conn.execute(
+        "UPDATE tags SET name = ?1, updated_at = ?2 WHERE id = ?3",
+        params![name, now_secs(), id],
+    )?;
+    Ok(n > 0)
+}
+
+/// Move os livros das tags `source_ids` para `target_id` e remove as de origem.
+/// Um vínculo manual prevalece sobre um automático. Retorna false se o destino
+/// não existir.
+pub fn merge_tags(
+    conn: &rusqlite::Connection,
+    source_ids: &[String],
+    target_id: &str,
+) -> crate::Result<bool> {
+    let exists = conn
+        .query_row("SELECT 1 FROM tags WHERE id = ?1", [target_id], |_| Ok(()))
+        .map(|_| true);
+    match exists {
+        Ok(_) => {}
+        Err(rusqlite::Error::QueryReturnedNoRows) => return Ok(false),
+        Err(e) => return Err(e.into()),
+    }
+    let tx = conn.unchecked_transaction()?;
+    let now = now_secs();
+    for source in source_ids.iter().filter(|s| s.as_str() != target_id) {
+        tx.execute(
+            r#"
+            INSERT INTO book_tags (book_id, tag_id, auto, updated_at)
+            SELECT book_id, ?1, auto, ?3 FROM book_tags WHERE tag_id = ?2
+            ON CONFLICT(book_id, tag_id) DO UPDATE SET
+                auto = MIN(auto, excluded.auto),
+                updated_at = excluded.updated_at
+            WHERE auto <> MIN(auto, excluded.auto)
+            "#,
+            params![target_id, source, now],
+        )?;
+        tx.execute("DELETE FROM book_tags WHERE tag_id = ?1", [source])?;
+        tx.execute("DELETE FROM tags WHERE id = ?1", [source])?;
+    }
+    tx.commit()?;
+    Ok(true)
+}
+
+/// Remove a tag dos livros e a apaga. Retorna false se não existir.
+pub fn delete_tag(conn: &rusqlite::Connection, id: &str) -> crate::Result<bool> {
+    let tx = conn.unchecked_transaction()?;
+    tx.execute("DELETE FROM book_tags WHERE tag_id = ?1", [id])?;
+    let n = tx.execute("DELETE FROM tags WHERE id = ?1", [id])?;
+    tx.commit()?;
+    Ok(n > 0)
+}
+
+pub fn list_book_tags(conn: &rusqlite::Connection, book_id: &str) -> crate::Result<Vec<BookTag>> {
+    let mut stmt = conn.prepare(
+        r#"
+        SELECT bt.tag_id, t.name, bt.auto
+        FROM book_tags bt
+        JOIN tags t ON t.id = bt.tag_id
+        WHERE bt.book_id = ?1
+        ORDER BY t.name COLLATE NOCASE
+        "#,
+    )?;
+    let rows = stmt.query_map([book_id], |row| {
+        Ok(BookTag {
+            tag_id: row.get(0)?,
+            name: row.get(1)?,
+            auto: row.get::<_, i64>(2)? != 0,
+        })
+    })?;
+    let mut out = Vec::new();
+    for row in rows {
+        out.push(row?);
+    }
+    Ok(out)
+}
+
+/// Vínculo manual. Se a tag já veio dos metadados, passa a ser manual (e não
+/// sai mais numa nova busca).
+pub fn add_tag_to_book(
+    conn: &rusqlite::Connection,
+    book_id: &str,
+    tag_id: &str,
+) -> crate::Result<()> {
+    conn.execute(
+        r#"
+        INSERT INTO book_tags (book_id, tag_id, auto, updated_at) VALUES (?1, ?2, 0, ?3)
+        ON CONFLICT(book_id, tag_id) DO UPDATE SET auto = 0, updated_at = excluded.updated_at
+        WHERE auto <> 0
+        "#,
+        params![book_id, tag_id, now_secs()],
+    )?;
+    Ok(())
+}
+
+pub fn remove_tag_from_book(
+    conn: &rusqlite::Connection,
+    book_id: &str,
+    tag_id: &str,
+) -> crate::Result<()> {
+    conn.execute(
+        "DELETE FROM book_tags WHERE book_id = ?1 AND tag_id = ?2",
+        params![book_id, tag_id],
+    )?;
+    Ok(())
+}
+
+/// Substitui as tags automáticas do livro pelos gêneros da fonte de metadados.
+/// Tags manuais não são tocadas; um gênero que já é tag manual continua manual.
+pub fn set_auto_tags(
+    conn: &rusqlite::Connection,
+    book_id: &str,
+    genres: &[String],
+) -> crate::Result<()> {
+    let tx = conn.unchecked_transaction()?;
+    let now = now_secs();
+    let mut tag_ids = Vec::new();
+    for genre in genres.iter().map(|g| g.trim()).filter(|g| !g.is_empty()) {
+        let id = create_tag(&tx, genre)?;
+        if !tag_ids.contains(&id) {
+            tag_ids.push(id);
+        }
+    }
+    let mut stmt = tx.prepare("SELECT tag_id FROM book_tags WHERE book_id = ?1 AND auto = 1")?;
+    let rows = stmt.query_map([book_id], |row| row.get::<_, String>(0))?;
+    let mut stale = Vec::new();
+    for row in rows {
+        let id = row?;
+        if !tag_ids.contains(&id) {
+            stale.push(id);
+        }
+    }
+    drop(stmt);
+    for id in &stale {
+        tx.execute(
+            "DELETE FROM book_tags WHERE book_id = ?1 AND tag_id = ?2",
+            params![book_id, id],
+        )?;
+    }
+    for id in &tag_ids {
+        tx.execute(
+            "INSERT OR IGNORE INTO book_tags (book_id, tag_id, auto, updated_at) VALUES (?1, ?2, 1, ?3)",
+            params![book_id, id, now],
+        )?;
+    }
+    tx.commit()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::repositories::insert_book;
+    use crate::repositories::test_support::{book, memory_db};
+
+    fn tag_names(conn: &rusqlite::Connection, book_id: &str) -> Vec<(String, bool)> {
+        list_book_tags(conn, book_id)
+            .unwrap()
+            .into_iter()
+            .map(|t| (t.name, t.auto))
+            .collect()
+    }
+
+    fn genres(names: &[&str]) -> Vec<String> {
+        names.iter().map(|n| n.to_string()).collect()
+    }
+
+    /// Livro "b1" com a tag manual "Dark"; retorna o id da tag.
+    fn tagged_book(conn: &rusqlite::Connection) -> String {
+        insert_book(conn, &book("b1", "Berserk")).unwrap();
+        let dark = create_tag(conn, "Dark").unwrap();
+        add_tag_to_book(conn, "b1", &dark).unwrap();
+        dark
+    }
+
+    #[test]
+    fn auto_tags_never_replace_manual_ones() {
+        let conn = memory_db();
+        tagged_book(&conn);
+        set_auto_tags(&conn, "b1", &genres(&["Action", "dark", "Horror"])).unwrap();
+        assert_eq!(
+            tag_names(&conn, "b1"),
+            vec![
+                ("Action".to_string(), true),
+                ("Dark".to_string(), false),
+                ("Horror".to_string(), true)
+            ]
+        );
+    }
+
+    #[test]
+    fn new_genres_replace_old_auto_tags() {
+        let conn = memory_db();
+        tagged_book(&conn);
+        set_auto_tags(&conn, "b1", &genres(&["Action", "Horror"])).unwrap();
+        // Nova busca: gêneros antigos saem, a tag manual fica
+        set_auto_tags(&conn, "b1", &genres(&["Drama"])).unwrap();
+        assert_eq!(
+            tag_names(&conn, "b1"),
+            vec![("Dark".to_string(), false), ("Drama".to_string(), true)]
+        );
+    }
+
+    #[test]
+    fn merge_keeps_manual_links_and_counts() {
+        let conn = memory_db();
+        let dark = tagged_book(&conn);
+        set_auto_tags(&conn, "b1", &genres(&["Drama"])).unwrap();
+
+        let drama = find_tag_by_name(&conn, "DRAMA").unwrap().unwrap();
+        assert!(merge_tags(&conn, &[drama], &dark).unwrap());
+        assert_eq!(tag_names(&conn, "b1"), vec![("Dark".to_string(), false)]);
+        let dark_count = list_tags(&conn)
+            .unwrap()
+            .into_iter()
+            .find(|t| t.id == dark)
+            .map(|t| t.book_count);
+        assert_eq!(dark_count, Some(1));
+        assert!(!merge_tags(&conn, &[], "missing").unwrap());
+    }
+
+    #[test]
+    fn rename_keeps_names_unique() {
+        let conn = memory_db();
+        let dark = create_tag(&conn, "Dark").unwrap();
+        let drama = create_tag(&conn, "Drama").unwrap();
+
+        assert!(matches!(
+            rename_tag(&conn, &drama, "DARK"),
+            Err(crate::Error::Validation(_))
+        ));
+        assert!(rename_tag(&conn, &dark, "dark").unwrap());
+        assert_eq!(find_tag_by_name(&conn, "Dark").unwrap(), Some(dark));
+        assert!(!rename_tag(&conn, "missing", "Other").unwrap());
+    }
+}
@@ -0,0 +1,34 @@
+//! Fixtures compartilhadas pelos testes dos repositórios (e do sync).
+
+use crate::models::{Book, Volume};
+
+/// Banco em memória já migrado.
+pub fn memory_db() -> rusqlite::Connection {
+    let conn = rusqlite::Connection::open_in_memory().unwrap();
+    crate::db::run_migrations(&conn).unwrap();
+    conn
+}
+
+/// Livro mínimo (pasta em `/manga/<id>`); o resto vem com `..book(id, title)`.
+pub fn book(id: &str, title: &str) -> Book {
+    Book {
+        id: id.to_string(),
+        title: title.to_string(),
+        path: format!("/manga/{}", id),
+        book_type: "folder".to_string(),
+        added_at: 0,
+        hash: None,
+        author: None,
+        description: None,
+        cover_path: None,
+        metadata: Default::default(),
+    }
+}
+
+pub fn volume(id: &str, book_id: &str, name: &str) -> Volume {
+    Volume {
+        id: id.to_string(),
+        book_id: book_id.to_string(),
+        name: name.to_string(),
+    }
+}
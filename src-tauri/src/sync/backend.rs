@@ -0,0 +1,52 @@
+//! Trait de backend de sincronização e construção a partir da configuração.
+
+use super::http::HttpBackend;
+use super::local_folder::LocalFolderBackend;
+use super::record::SyncRecord;
+use super::webdav::WebDavBackend;
+use crate::models::SyncConfig;
+
+/// Registros recebidos e o cursor opaco para a próxima chamada.
+#[derive(Debug, Default)]
+pub struct PullResult {
+    pub records: Vec<SyncRecord>,
+    pub cursor: Option<String>,
+}
+
+/// Transporte de registros entre dispositivos. O merge (LWW) é feito pelo engine,
+/// então o backend só precisa armazenar e devolver registros.
+pub trait SyncBackend: Send {
+    /// Nome do backend (local_folder, http, webdav).
+    fn name(&self) -> &'static str;
+
+    /// Envia registros alterados neste dispositivo.
+    fn push(&self, device_id: &str, records: &[SyncRecord]) -> crate::Result<()>;
+
+    /// Recebe registros dos outros dispositivos desde `cursor` (None = tudo).
+    fn pull(&self, device_id: &str, cursor: Option<&str>) -> crate::Result<PullResult>;
+}
+
+/// Cria o backend configurado. None se a sincronização estiver desativada.
+pub fn backend_from_config(config: &SyncConfig) -> crate::Result<Option<Box<dyn SyncBackend>>> {
+    let target = config.target.as_deref().map(str::trim).unwrap_or("");
+    match config.backend.as_deref() {
+        None | Some("") | Some("none") => Ok(None),
+        Some(_) if target.is_empty() => Err(crate::Error::Validation(
+            "sync target is required".to_string(),
+        )),
+        Some("local_folder") => Ok(Some(Box::new(LocalFolderBackend::new(target)))),
+        Some("http") => Ok(Some(Box::new(HttpBackend::new(
+            target,
+            config.token.clone(),
+        )?))),
+        Some("webdav") => Ok(Some(Box::new(WebDavBackend::new(
+            target,
+            config.username.clone(),
+            config.token.clone(),
+        )?))),
+        Some(other) => Err(crate::Error::Validation(format!(
+            "unknown sync backend: {}",
+            other
+        ))),
+    }
+}
@@ -0,0 +1,269 @@
+//! Engine de sincronização: push das mudanças locais, pull das remotas e merge LWW.
+
+use serde::Serialize;
+
+use super::backend::SyncBackend;
+use super::record::{merge_latest, SyncRecord};
+use crate::repositories::{self, SYNC_TABLE_SPECS};
+
+const PUSH_CURSOR_KEY: &str = "sync_push_cursor";
+const PULL_CURSOR_KEY: &str = "sync_pull_cursor";
+const LAST_SYNC_KEY: &str = "sync_last_at";
+
+#[derive(Debug, Default, Clone, Serialize)]
+pub struct SyncReport {
+    pub backend: String,
+    pub pushed: usize,
+    pub pulled: usize,
+    pub applied: usize,
+    /// Registros remotos descartados (versão local mais nova ou referência ausente).
+    pub skipped: usize,
+    pub finished_at: i64,
+}
+
+fn now_secs() -> i64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs() as i64)
+        .unwrap_or(0)
+}
+
+/// Registros das linhas alteradas neste dispositivo desde o último push.
+fn collect_outgoing(
+    conn: &rusqlite::Connection,
+    device_id: &str,
+    cursor: i64,
+) -> crate::Result<(Vec<SyncRecord>, i64)> {
+    let (keys, new_cursor) = repositories::changed_keys_since(conn, cursor, device_id)?;
+    let mut out = Vec::with_capacity(keys.len());
+    for (table, key) in keys {
+        let Some(spec) = repositories::sync_table_spec(&table) else {
+            continue;
+        };
+        if let Some((updated_at, data)) = repositories::read_sync_row(conn, spec, &key)? {
+            out.push(SyncRecord {
+                table,
+                key,
+                updated_at,
+                device_id: device_id.to_string(),
+                deleted: false,
+                data,
+            });
+        } else if let Some(t) = repositories::get_tombstone(conn, &table, &key)? {
+            out.push(SyncRecord {
+                table,
+                key,
+                updated_at: t.deleted_at,
+                device_id: device_id.to_string(),
+                deleted: true,
+                data: serde_json::Map::new(),
+            });
+        }
+    }
+    Ok((out, new_cursor))
+}
+
+/// Versão local de uma linha: (updated_at, dispositivo de origem).
+fn local_version(
+    conn: &rusqlite::Connection,
+    spec: &repositories::SyncTable,
+    key: &str,
+    device_id: &str,
+) -> crate::Result<Option<(i64, String)>> {
+    if let Some((updated_at, _)) = repositories::read_sync_row(conn, spec, key)? {
+        let origin = repositories::row_origin_device(conn, spec.name, key)?
+            .unwrap_or_else(|| device_id.to_string());
+        return Ok(Some((updated_at, origin)));
+    }
+    if let Some(t) = repositories::get_tombstone(conn, spec.name, key)? {
+        return Ok(Some((t.deleted_at, t.device_id)));
+    }
+    Ok(None)
+}
+
+/// Executa uma rodada completa de sincronização com o backend.
+pub fn run_sync(
+    conn: &rusqlite::Connection,
+    backend: &dyn SyncBackend,
+) -> crate::Result<SyncReport> {
+    let device_id = repositories::get_device_id(conn)?;
+    let mut report = SyncReport {
+        backend: backend.name().to_string(),
+        ..Default::default()
+    };
+
+    let push_cursor = repositories::get_sync_meta(conn, PUSH_CURSOR_KEY)?
+        .and_then(|c| c.parse::<i64>().ok())
+        .unwrap_or(0);
+    let (outgoing, new_push_cursor) = collect_outgoing(conn, &device_id, push_cursor)?;
+    if !outgoing.is_empty() {
+        backend.push(&device_id, &outgoing)?;
+    }
+    report.pushed = outgoing.len();
+    repositories::set_sync_meta(conn, PUSH_CURSOR_KEY, Some(&new_push_cursor.to_string()))?;
+
+    let pull_cursor = repositories::get_sync_meta(conn, PULL_CURSOR_KEY)?;
+    let pulled = backend.pull(&device_id, pull_cursor.as_deref())?;
+    report.pulled = pulled.records.len();
+    let mut incoming = merge_latest(
+        pulled
+            .records
+            .into_iter()
+            .filter(|r| r.device_id != device_id),
+    );
+    // Tabelas referenciadas primeiro (estantes antes de book_shelves etc.)
+    incoming.sort_by_key(|r| {
+        SYNC_TABLE_SPECS
+            .iter()
+            .position(|t| t.name == r.table)
+            .unwrap_or(usize::MAX)
+    });
+
+    let tx = conn.unchecked_transaction()?;
+    for record in &incoming {
+        let Some(spec) = repositories::sync_table_spec(&record.table) else {
+            report.skipped += 1;
+            continue;
+        };
+        if let Some((updated_at, origin)) = local_version(conn, spec, &record.key, &device_id)? {
+            if !record.wins_over(updated_at, &origin) {
+                report.skipped += 1;
+                continue;
+            }
+        }
+        let applied = if record.deleted {
+            repositories::apply_sync_delete(conn, spec, &record.key, &record.device_id)?
+        } else {
+            repositories::apply_sync_upsert(
+                conn,
+                spec,
+                &record.key,
+                record.updated_at,
+                &record.data,
+                &record.device_id,
+            )?
+        };
+        if applied {
+            report.applied += 1;
+        } else {
+            report.skipped += 1;
+        }
+    }
+    if let Some(cursor) = pulled.cursor {
+        repositories::set_sync_meta(conn, PULL_CURSOR_KEY, Some(&cursor))?;
+    }
+    report.finished_at = now_secs();
+    repositories::set_sync_meta(conn, LAST_SYNC_KEY, Some(&report.finished_at.to_string()))?;
+    tx.commit()?;
+
+    log::info!(
+        "[sync] {}: pushed={} pulled={} applied={} skipped={}",
+        report.backend,
+        report.pushed,
+        report.pulled,
+        report.applied,
+        report.skipped
+    );
+    Ok(report)
+}
+
+#[cfg(test)]
+pub(super) mod tests {
+    use super::*;
+    use crate::models::ReadingProgress;
+    use crate::repositories::test_support::{book, memory_db, volume};
+    use crate::sync::LocalFolderBackend;
+
+    /// Banco em memória com o mesmo livro/volume (b1/v1) em todos os dispositivos.
+    pub(in crate::sync) fn open_db() -> rusqlite::Connection {
+        let conn = memory_db();
+        repositories::insert_book(&conn, &book("b1", "Book")).unwrap();
+        repositories::insert_volume(&conn, &volume("v1", "b1", "Vol 1")).unwrap();
+        conn
+    }
+
+    pub(in crate::sync) fn progress(page_index: i32, updated_at: i64) -> ReadingProgress {
+        ReadingProgress {
+            book_id: "b1".to_string(),
+            volume_id: "v1".to_string(),
+            current_chapter_id: None,
+            page_index,
+            scroll_offset: 0.0,
+            updated_at,
+            total_pages: None,
+        }
+    }
+
+    /// Dois dispositivos numa pasta compartilhada. A tem o progresso mais novo
+    /// e a estante "s1" com b1; B, um progresso antigo. Já sincronizaram A e depois B.
+    struct Devices {
+        dir: std::path::PathBuf,
+        backend: LocalFolderBackend,
+        a: rusqlite::Connection,
+        b: rusqlite::Connection,
+    }
+
+    impl Drop for Devices {
+        fn drop(&mut self) {
+            let _ = std::fs::remove_dir_all(&self.dir);
+        }
+    }
+
+    fn synced_devices() -> Devices {
+        let dir = std::env::temp_dir().join(format!("readito-sync-{}", uuid::Uuid::new_v4()));
+        let backend = LocalFolderBackend::new(&dir);
+        let a = open_db();
+        let b = open_db();
+
+        repositories::upsert_progress(&a, &progress(5, 200)).unwrap();
+        repositories::create_shelf(&a, "s1", "Favorites").unwrap();
+        repositories::add_book_to_shelf(&a, "b1", "s1").unwrap();
+        a.execute("UPDATE shelves SET updated_at = 10", []).unwrap();
+        repositories::upsert_progress(&b, &progress(3, 100)).unwrap();
+
+        run_sync(&a, &backend).unwrap();
+        run_sync(&b, &backend).unwrap();
+        Devices { dir, backend, a, b }
+    }
+
+    #[test]
+    fn newer_records_reach_the_other_device() {
+        let d = synced_devices();
+        // B recebeu o progresso mais novo de A e a estante com o vínculo
+        let p = repositories::get_progress(&d.b, "b1", "v1")
+            .unwrap()
+            .unwrap();
+        assert_eq!(p.page_index, 5);
+        assert_eq!(
+            repositories::get_books_in_shelf(&d.b, "s1").unwrap(),
+            vec!["b1"]
+        );
+    }
+
+    #[test]
+    fn older_records_do_not_overwrite_newer_ones() {
+        let d = synced_devices();
+        run_sync(&d.a, &d.backend).unwrap();
+        let p = repositories::get_progress(&d.a, "b1", "v1")
+            .unwrap()
+            .unwrap();
+        assert_eq!(p.page_index, 5);
+    }
+
+    #[test]
+    fn deletions_propagate_as_tombstones() {
+        let d = synced_devices();
+        d.b.execute("DELETE FROM shelves WHERE id = 's1'", [])
+            .unwrap();
+        run_sync(&d.b, &d.backend).unwrap();
+        run_sync(&d.a, &d.backend).unwrap();
+        assert!(repositories::list_shelves(&d.a).unwrap().is_empty());
+    }
+
+    #[test]
+    fn applied_remote_changes_are_not_pushed_back() {
+        let d = synced_devices();
+        let report = run_sync(&d.b, &d.backend).unwrap();
+        assert_eq!(report.pushed, 0);
+    }
+}
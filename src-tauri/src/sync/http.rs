@@ -0,0 +1,176 @@
+//! Backend HTTP genérico.
+//! POST {base}/v1/devices/{device_id}/records  body: {"records": [...]}
+//! GET  {base}/v1/records?device_id={id}&cursor={c} -> {"records": [...], "cursor": "..."}
+//! O servidor devolve apenas registros de outros dispositivos.
+
+use std::time::Duration;
+
+use reqwest::blocking::Client;
+use serde::{Deserialize, Serialize};
+
+use super::backend::{PullResult, SyncBackend};
+use super::record::SyncRecord;
+
+const TIMEOUT: Duration = Duration::from_secs(15);
+
+#[derive(Serialize)]
+struct PushBody<'a> {
+    records: &'a [SyncRecord],
+}
+
+#[derive(Deserialize)]
+struct PullBody {
+    #[serde(default)]
+    records: Vec<SyncRecord>,
+    cursor: Option<String>,
+}
+
+pub struct HttpBackend {
+    base_url: String,
+    token: Option<String>,
+    client: Client,
+}
+
+impl HttpBackend {
+    pub fn new(base_url: &str, token: Option<String>) -> crate::Result<Self> {
+        let client = Client::builder()
+            .timeout(TIMEOUT)
+            .user_agent("Readito/1.0")
+            .build()
+            .map_err(|e| crate::Error::Io(e.to_string()))?;
+        Ok(Self {
+            base_url: base_url.trim_end_matches('/').to_string(),
+            token: token.filter(|t| !t.is_empty()),
+            client,
+        })
+    }
+
+    fn authorize(
+        &self,
+        req: reqwest::blocking::RequestBuilder,
+    ) -> reqwest::blocking::RequestBuilder {
+        match &self.token {
+            Some(t) => req.bearer_auth(t),
+            None => req,
+        }
+    }
+}
+
+impl SyncBackend for HttpBackend {
+    fn name(&self) -> &'static str {
+        "http"
+    }
+
+    fn push(&self, device_id: &str, records: &[SyncRecord]) -> crate::Result<()> {
+        let url = format!(
+            "{}/v1/devices/{}/records",
+            self.base_url,
+            urlencoding::encode(device_id)
+        );
+        let resp = self
+            .authorize(self.client.post(&url))
+            .json(&PushBody { records })
+            .send()
+            .map_err(|e| crate::Error::Io(e.to_string()))?;
+        if !resp.status().is_success() {
+            return Err(crate::Error::Io(format!("HTTP {}", resp.status())));
+        }
+        Ok(())
+    }
+
+    fn pull(&self, device_id: &str, cursor: Option<&str>) -> crate::Result<PullResult> {
+        let mut url = format!(
+            "{}/v1/records?device_id={}",
+            self.base_url,
+            urlencoding::encode(device_id)
+        );
+        if let Some(c) = cursor {
+            url.push_str(&format!("&cursor={}", urlencoding::encode(c)));
+        }
+        let resp = self
+            .authorize(self.client.get(&url))
+            .send()
+            .map_err(|e| crate::Error::Io(e.to_string()))?;
+        if !resp.status().is_success() {
+            return Err(crate::Error::Io(format!("HTTP {}", resp.status())));
+        }
+        let body: PullBody = resp.json().map_err(|e| crate::Error::Io(e.to_string()))?;
+        Ok(PullResult {
+            records: body.records,
+            cursor: body.cursor,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sync::test_server::{self, Response};
+
+    /// Implementa o protocolo acima em memória (token "secret").
+    fn spawn_mock_server() -> String {
+        let mut store: Vec<SyncRecord> = Vec::new();
+        test_server::spawn(move |req| {
+            if req.header("authorization") != Some("Bearer secret") {
+                return Response::new(401);
+            }
+            if req.method == "POST" {
+                let v: serde_json::Value = serde_json::from_slice(&req.body).unwrap();
+                let records: Vec<SyncRecord> =
+                    serde_json::from_value(v["records"].clone()).unwrap();
+                store.extend(records);
+                return Response::new(200).with_body("{}");
+            }
+            let device = req.query_param("device_id").unwrap_or_default();
+            let from: usize = req
+                .query_param("cursor")
+                .and_then(|c| c.parse().ok())
+                .unwrap_or(0);
+            let records: Vec<&SyncRecord> = store[from.min(store.len())..]
+                .iter()
+                .filter(|r| r.device_id != device)
+                .collect();
+            let body = serde_json::json!({
+                "records": records,
+                "cursor": store.len().to_string(),
+            });
+            Response::new(200)
+                .with_header("Content-Type", "application/json")
+                .with_body(body.to_string())
+        })
+    }
+
+    fn record(device_id: &str, page: i64) -> SyncRecord {
+        let mut data = serde_json::Map::new();
+        data.insert("page_index".to_string(), page.into());
+        SyncRecord {
+            table: "reading_progress".to_string(),
+            key: "b1|v1".to_string(),
+            updated_at: 100,
+            device_id: device_id.to_string(),
+            deleted: false,
+            data,
+        }
+    }
+
+    #[test]
+    fn push_and_pull_through_mock_server() {
+        let base = spawn_mock_server();
+        let a = HttpBackend::new(&base, Some("secret".to_string())).unwrap();
+        let b = HttpBackend::new(&base, Some("secret".to_string())).unwrap();
+
+        a.push("dev-a", &[record("dev-a", 7)]).unwrap();
+
+        // O próprio dispositivo não recebe de volta o que enviou
+        assert!(a.pull("dev-a", None).unwrap().records.is_empty());
+
+        let pulled = b.pull("dev-b", None).unwrap();
+        assert_eq!(pulled.records, vec![record("dev-a", 7)]);
+
+        let again = b.pull("dev-b", pulled.cursor.as_deref()).unwrap();
+        assert!(again.records.is_empty());
+
+        let unauthorized = HttpBackend::new(&base, None).unwrap();
+        assert!(unauthorized.pull("dev-b", None).is_err());
+    }
+}
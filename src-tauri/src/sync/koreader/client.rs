@@ -0,0 +1,117 @@
+//! Cliente do protocolo kosync (koreader-sync-server e compatíveis).
+//! Autenticação por cabeçalhos `x-auth-user` / `x-auth-key` (MD5 da senha).
+
+use std::time::Duration;
+
+use reqwest::blocking::{Client, RequestBuilder};
+use reqwest::{Method, StatusCode};
+use serde_json::json;
+
+use crate::models::KoreaderProgress;
+
+const TIMEOUT: Duration = Duration::from_secs(15);
+const ACCEPT: &str = "application/vnd.koreader.v1+json";
+
+pub struct KoreaderClient {
+    base_url: String,
+    username: String,
+    userkey: String,
+    client: Client,
+}
+
+impl KoreaderClient {
+    pub fn new(base_url: &str, username: &str, userkey: &str) -> crate::Result<Self> {
+        let client = Client::builder()
+            .timeout(TIMEOUT)
+            .user_agent("Readito/1.0")
+            .build()
+            .map_err(|e| crate::Error::Io(e.to_string()))?;
+        Ok(Self {
+            base_url: base_url.trim().trim_end_matches('/').to_string(),
+            username: username.to_string(),
+            userkey: userkey.to_string(),
+            client,
+        })
+    }
+
+    fn request(&self, method: Method, path: &str) -> RequestBuilder {
+        self.client
+            .request(method, format!("{}{}", self.base_url, path))
+            .header(reqwest::header::ACCEPT, ACCEPT)
+            .header("x-auth-user", &self.username)
+            .header("x-auth-key", &self.userkey)
+    }
+
+    fn check(resp: reqwest::blocking::Response) -> crate::Result<reqwest::blocking::Response> {
+        match resp.status() {
+            s if s.is_success() => Ok(resp),
+            StatusCode::UNAUTHORIZED => Err(crate::Error::Validation(
+                "invalid KOReader sync credentials".to_string(),
+            )),
+            s => Err(crate::Error::Io(format!("KOReader sync: HTTP {}", s))),
+        }
+    }
+
+    /// Cria a conta no servidor (`POST /users/create`).
+    pub fn register(&self) -> crate::Result<()> {
+        let resp = self
+            .client
+            .post(format!("{}/users/create", self.base_url))
+            .header(reqwest::header::ACCEPT, ACCEPT)
+            .json(&json!({ "username": self.username, "password": self.userkey }))
+            .send()
+            .map_err(|e| crate::Error::Io(e.to_string()))?;
+        if resp.status() == StatusCode::PAYMENT_REQUIRED {
+            // kosync responde 402 para usuário existente
+            return Err(crate::Error::Validation(
+                "KOReader sync username is already registered".to_string(),
+            ));
+        }
+        Self::check(resp)?;
+        Ok(())
+    }
+
+    /// Valida as credenciais (`GET /users/auth`).
+    pub fn authorize(&self) -> crate::Result<()> {
+        let resp = self
+            .request(Method::GET, "/users/auth")
+            .send()
+            .map_err(|e| crate::Error::Io(e.to_string()))?;
+        Self::check(resp)?;
+        Ok(())
+    }
+
+    /// Última posição do documento. None se o servidor não tiver nenhuma (`{}`).
+    pub fn get_progress(&self, document: &str) -> crate::Result<Option<KoreaderProgress>> {
+        let path = format!("/syncs/progress/{}", urlencoding::encode(document));
+        let resp = self
+            .request(Method::GET, &path)
+            .send()
+            .map_err(|e| crate::Error::Io(e.to_string()))?;
+        let body: serde_json::Value = Self::check(resp)?
+            .json()
+            .map_err(|e| crate::Error::Io(e.to_string()))?;
+        if body.get("progress").is_none() {
+            return Ok(None);
+        }
+        let mut progress: KoreaderProgress =
+            serde_json::from_value(body).map_err(|e| crate::Error::Io(e.to_string()))?;
+        if progress.document.is_empty() {
+            progress.document = document.to_string();
+        }
+        Ok(Some(progress))
+    }
+
+    /// Envia a posição (`PUT /syncs/progress`) e devolve o timestamp do servidor.
+    pub fn update_progress(&self, progress: &KoreaderProgress) -> crate::Result<Option<i64>> {
+        let resp = self
+            .request(Method::PUT, "/syncs/progress")
+            .json(progress)
+            .send()
+            .map_err(|e| crate::Error::Io(e.to_string()))?;
+        let body: serde_json::Value = Self::check(resp)?
+            .json()
+            .map_err(|e| crate::Error::Io(e.to_string()))?;
+        Ok(body.get("timestamp").and_then(|t| t.as_i64()))
+    }
+}
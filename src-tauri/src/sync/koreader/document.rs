@@ -0,0 +1,195 @@
+//! Identificação de documentos e conversão de posições entre Readito e KOReader.
+//! PDF/CBZ usam número de página nos dois lados; em EPUB o KOReader usa xpointer
+//! (`/body/DocFragment[N]...`, N = item do spine) e o Readito, página do epub.js.
+
+use std::fs::File;
+use std::io::{Read, Seek, SeekFrom};
+use std::path::Path;
+use std::sync::LazyLock;
+
+use md5::{Digest, Md5};
+use regex::Regex;
+use zip::ZipArchive;
+
+static FRAGMENT_REGEX: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"DocFragment\[(\d+)\]").expect("fragment regex"));
+/// Atributo de uma tag XML: nome e valor entre aspas duplas ou simples.
+static ATTR_REGEX: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r#"([\w:.-]+)\s*=\s*(?:"([^"]*)"|'([^']*)')"#).expect("attribute regex")
+});
+static ROOTFILE_REGEX: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"<(?:\w+:)?rootfile\b[^>]*>").expect("rootfile regex"));
+static ITEM_REGEX: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"<(?:\w+:)?item\b[^>]*>").expect("item regex"));
+static ITEMREF_REGEX: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"<(?:\w+:)?itemref\b[^>]*>").expect("itemref regex"));
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DocumentKind {
+    /// Páginas fixas (PDF, CBZ): `progress` é o número da página.
+    Paged,
+    /// Texto refluível (EPUB): `progress` é um xpointer.
+    Reflowable,
+}
+
+pub fn document_kind(path: &str) -> DocumentKind {
+    if path.to_lowercase().ends_with(".epub") {
+        DocumentKind::Reflowable
+    } else {
+        DocumentKind::Paged
+    }
+}
+
+/// MD5 em hexadecimal minúsculo (chave de documento e senha do kosync).
+pub fn md5_hex(data: &[u8]) -> String {
+    format!("{:x}", Md5::digest(data))
+}
+
+/// Lê até encher `buf` ou chegar ao fim do arquivo.
+fn read_up_to(file: &mut File, buf: &mut [u8]) -> std::io::Result<usize> {
+    let mut filled = 0;
+    while filled < buf.len() {
+        match file.read(&mut buf[filled..])? {
+            0 => break,
+            n => filled += n,
+        }
+    }
+    Ok(filled)
+}
+
+/// MD5 parcial do KOReader (`util.partialMD5`): blocos de 1 KiB nos offsets
+/// 0 e 1024·4^i (i = 0..10), parando no fim do arquivo.
+pub fn partial_md5(path: &Path) -> crate::Result<String> {
+    let mut file = File::open(path).map_err(|e| crate::Error::Io(e.to_string()))?;
+    let mut md5 = Md5::new();
+    let mut buf = [0u8; 1024];
+    for i in -1i32..=10 {
+        let offset = if i < 0 { 0 } else { 1024u64 << (2 * i) };
+        file.seek(SeekFrom::Start(offset))
+            .map_err(|e| crate::Error::Io(e.to_string()))?;
+        let n = read_up_to(&mut file, &mut buf).map_err(|e| crate::Error::Io(e.to_string()))?;
+        if n == 0 {
+            break;
+        }
+        md5.update(&buf[..n]);
+    }
+    Ok(format!("{:x}", md5.finalize()))
+}
+
+/// Chave do documento conforme o método configurado no KOReader.
+pub fn document_key(path: &Path, method: &str) -> crate::Result<String> {
+    match method {
+        "filename" => {
+            let name = path
+                .file_name()
+                .map(|n| n.to_string_lossy().to_string())
+                .unwrap_or_default();
+            Ok(md5_hex(name.as_bytes()))
+        }
+        _ => partial_md5(path),
+    }
+}
+
+/// Índice (1-based) do DocFragment de um xpointer.
+pub fn xpointer_fragment(xpointer: &str) -> Option<usize> {
+    FRAGMENT_REGEX.captures(xpointer)?[1].parse().ok()
+}
+
+/// Itens do spine de um EPUB com o tamanho de cada um, usado como peso
+/// para aproximar percentual <-> item (o KOReader mede pela altura renderizada).
+#[derive(Debug, Clone)]
+pub struct EpubSpine {
+    weights: Vec<u64>,
+}
+
+fn zip_text(archive: &mut ZipArchive<File>, name: &str) -> crate::Result<String> {
+    let mut entry = archive
+        .by_name(name)
+        .map_err(|e| crate::Error::Io(format!("{}: {}", name, e)))?;
+    let mut out = String::new();
+    entry
+        .read_to_string(&mut out)
+        .map_err(|e| crate::Error::Io(e.to_string()))?;
+    Ok(out)
+}
+
+fn attr(tag: &str, name: &str) -> Option<String> {
+    let cap = ATTR_REGEX.captures_iter(tag).find(|c| &c[1] == name)?;
+    cap.get(2).or(cap.get(3)).map(|m| m.as_str().to_string())
+}
+
+impl EpubSpine {
+    pub fn from_weights(weights: Vec<u64>) -> Self {
+        Self { weights }
+    }
+
+    pub fn from_file(path: &Path) -> crate::Result<Self> {
+        let file = File::open(path).map_err(|e| crate::Error::Io(e.to_string()))?;
+        let mut archive = ZipArchive::new(file).map_err(|e| crate::Error::Io(e.to_string()))?;
+
+        let container = zip_text(&mut archive, "META-INF/container.xml")?;
+        let opf_path = ROOTFILE_REGEX
+            .find(&container)
+            .and_then(|m| attr(m.as_str(), "full-path"))
+            .ok_or_else(|| crate::Error::Io("EPUB sem rootfile".to_string()))?;
+        let opf = zip_text(&mut archive, &opf_path)?;
+        let opf_dir = match opf_path.rfind('/') {
+            Some(i) => &opf_path[..=i],
+            None => "",
+        };
+
+        let mut hrefs = std::collections::HashMap::new();
+        for m in ITEM_REGEX.find_iter(&opf) {
+            if let (Some(id), Some(href)) = (attr(m.as_str(), "id"), attr(m.as_str(), "href")) {
+                hrefs.insert(id, href);
+            }
+        }
+
+        let mut weights = Vec::new();
+        for m in ITEMREF_REGEX.find_iter(&opf) {
+            let Some(href) = attr(m.as_str(), "idref").and_then(|id| hrefs.get(&id).cloned())
+            else {
+                continue;
+            };
+            let href = urlencoding::decode(&href)
+                .map(|h| h.into_owned())
+                .unwrap_or(href);
+            let size = archive
+                .by_name(&format!("{}{}", opf_dir, href))
+                .map(|e| e.size())
+                .unwrap_or(0);
+            weights.push(size.max(1));
+        }
+        if weights.is_empty() {
+            return Err(crate::Error::Io("EPUB sem spine".to_string()));
+        }
+        Ok(Self { weights })
+    }
+
+    /// xpointer do início do item do spine que contém `percentage` (0..1).
+    pub fn xpointer_at(&self, percentage: f64) -> String {
+        let total: u64 = self.weights.iter().sum();
+        let target = percentage.clamp(0.0, 1.0) * total as f64;
+        let mut end = 0u64;
+        let mut index = self.weights.len();
+        for (i, w) in self.weights.iter().enumerate() {
+            end += w;
+            if (end as f64) > target {
+                index = i + 1;
+                break;
+            }
+        }
+        format!("/body/DocFragment[{}].0", index)
+    }
+
+    /// Percentual do início do DocFragment indicado pelo xpointer.
+    pub fn percentage_of(&self, xpointer: &str) -> Option<f64> {
+        let index = xpointer_fragment(xpointer)?;
+        if index == 0 || index > self.weights.len() {
+            return None;
+        }
+        let total: u64 = self.weights.iter().sum();
+        let before: u64 = self.weights[..index - 1].iter().sum();
+        Some(before as f64 / total as f64)
+    }
+}
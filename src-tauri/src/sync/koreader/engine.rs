@@ -0,0 +1,182 @@
+//! Troca de posições com o servidor kosync, volume a volume.
+//! Vence a posição mais recente: `timestamp` do servidor x `updated_at` local.
+
+use std::path::Path;
+
+use super::client::KoreaderClient;
+use super::document::{document_key, document_kind, DocumentKind, EpubSpine};
+use crate::models::{KoreaderProgress, KoreaderSyncReport, ReadingProgress};
+use crate::repositories::{self, KoreaderVolume};
+
+/// Nome do dispositivo exibido pelo KOReader ("sincronizado de ...").
+const DEVICE_NAME: &str = "Readito";
+
+fn now_secs() -> i64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs() as i64)
+        .unwrap_or(0)
+}
+
+/// Chave kosync do volume, usando o cache enquanto o arquivo não mudar.
+fn volume_document(
+    conn: &rusqlite::Connection,
+    volume: &KoreaderVolume,
+    method: &str,
+) -> crate::Result<Option<String>> {
+    let path = Path::new(&volume.path);
+    let Ok(meta) = std::fs::metadata(path) else {
+        return Ok(None);
+    };
+    let size = meta.len() as i64;
+    let mtime = meta
+        .modified()
+        .ok()
+        .and_then(|m| m.duration_since(std::time::UNIX_EPOCH).ok())
+        .map(|d| d.as_secs() as i64)
+        .unwrap_or(0);
+    if let Some(doc) = repositories::get_cached_document(
+        conn,
+        &volume.volume_id,
+        &volume.path,
+        size,
+        mtime,
+        method,
+    )? {
+        return Ok(Some(doc));
+    }
+    let doc = document_key(path, method)?;
+    repositories::save_cached_document(conn, volume, size, mtime, method, &doc)?;
+    Ok(Some(doc))
+}
+
+/// Posição local no formato kosync (progress, percentage).
+/// None em EPUB sem total de páginas: não há como chegar a um percentual.
+pub fn to_koreader(
+    local: &ReadingProgress,
+    kind: DocumentKind,
+    spine: impl FnOnce() -> crate::Result<EpubSpine>,
+) -> crate::Result<Option<(String, f64)>> {
+    let page = local.page_index.max(1);
+    let total = local.total_pages.filter(|t| *t > 0);
+    let percentage = total.map(|t| (page as f64 / t as f64).min(1.0));
+    match (kind, total) {
+        (DocumentKind::Paged, _) => Ok(Some((page.to_string(), percentage.unwrap_or(0.0)))),
+        (DocumentKind::Reflowable, Some(total)) => {
+            // xpointer do item onde a página começa (página 1 = início do livro)
+            let start = (page - 1) as f64 / total as f64;
+            Ok(Some((
+                spine()?.xpointer_at(start),
+                percentage.unwrap_or(0.0),
+            )))
+        }
+        (DocumentKind::Reflowable, None) => Ok(None),
+    }
+}
+
+/// Página local (1-based) para uma posição do KOReader. Sem percentual,
+/// usa o início do DocFragment do xpointer.
+pub fn from_koreader(
+    remote: &KoreaderProgress,
+    kind: DocumentKind,
+    total_pages: Option<i32>,
+    spine: impl FnOnce() -> crate::Result<EpubSpine>,
+) -> Option<i32> {
+    if kind == DocumentKind::Paged {
+        if let Ok(page) = remote.progress.trim().parse::<i32>() {
+            return Some(page.max(1));
+        }
+    }
+    let total = total_pages.filter(|t| *t > 0)?;
+    let percentage = if remote.percentage > 0.0 {
+        remote.percentage
+    } else {
+        spine()
+            .ok()
+            .and_then(|s| s.percentage_of(&remote.progress))
+            .unwrap_or(0.0)
+    };
+    let page = (percentage.clamp(0.0, 1.0) * total as f64).round() as i32;
+    Some(page.clamp(1, total))
+}
+
+/// Sincroniza todos os volumes de arquivo único com o servidor.
+pub fn sync_progress(
+    conn: &rusqlite::Connection,
+    client: &KoreaderClient,
+    method: &str,
+) -> crate::Result<KoreaderSyncReport> {
+    let device_id = repositories::get_device_id(conn)?;
+    let mut report = KoreaderSyncReport::default();
+
+    for volume in repositories::list_koreader_volumes(conn)? {
+        let Some(document) = volume_document(conn, &volume, method)? else {
+            report.skipped += 1;
+            continue;
+        };
+        let kind = document_kind(&volume.path);
+        let local = repositories::get_progress(conn, &volume.book_id, &volume.volume_id)?;
+        let remote = client.get_progress(&document)?;
+
+        let local_at = local.as_ref().map(|p| p.updated_at).unwrap_or(0);
+        let remote_at = remote.as_ref().and_then(|r| r.timestamp).unwrap_or(0);
+
+        match (&local, &remote) {
+            (_, Some(r)) if r.device_id != device_id && remote_at > local_at => {
+                let total_pages = local.as_ref().and_then(|p| p.total_pages);
+                let path = Path::new(&volume.path);
+                let Some(page_index) =
+                    from_koreader(r, kind, total_pages, || EpubSpine::from_file(path))
+                else {
+                    report.skipped += 1;
+                    continue;
+                };
+                repositories::upsert_progress(
+                    conn,
+                    &ReadingProgress {
+                        book_id: volume.book_id.clone(),
+                        volume_id: volume.volume_id.clone(),
+                        current_chapter_id: local
+                            .as_ref()
+                            .and_then(|p| p.current_chapter_id.clone()),
+                        page_index,
+                        scroll_offset: 0.0,
+                        // Mesmo instante do servidor: a próxima rodada não reenvia
+                        updated_at: remote_at,
+                        total_pages,
+                    },
+                )?;
+                report.pulled += 1;
+            }
+            (Some(l), _) if remote.is_none() || local_at > remote_at => {
+                let path = Path::new(&volume.path);
+                let Some((progress, percentage)) =
+                    to_koreader(l, kind, || EpubSpine::from_file(path))?
+                else {
+                    report.skipped += 1;
+                    continue;
+                };
+                client.update_progress(&KoreaderProgress {
+                    document,
+                    progress,
+                    percentage,
+                    device: DEVICE_NAME.to_string(),
+                    device_id: device_id.clone(),
+                    timestamp: None,
+                })?;
+                report.pushed += 1;
+            }
+            _ => {}
+        }
+    }
+
+    report.finished_at = now_secs();
+    repositories::set_koreader_last_sync(conn, report.finished_at)?;
+    log::info!(
+        "[koreader] pushed={} pulled={} skipped={}",
+        report.pushed,
+        report.pulled,
+        report.skipped
+    );
+    Ok(report)
+}
@@ -0,0 +1,347 @@
+//! Sincronização de posição com o KOReader (protocolo kosync): cliente para um
+//! servidor externo (ex.: sync.koreader.rocks) e servidor embutido opcional.
+//! Documentos são identificados pelo MD5 do arquivo, como no KOReader.
+
+mod client;
+mod document;
+mod engine;
+mod server;
+
+pub use client::KoreaderClient;
+pub use document::{md5_hex, partial_md5};
+pub use engine::sync_progress;
+pub use server::{KoreaderServer, ServerAccess};
+
+use crate::models::KoreaderConfig;
+
+/// URL efetiva: a configurada ou, sem ela, o servidor embutido local.
+pub fn effective_url(config: &KoreaderConfig) -> Option<String> {
+    match config.server_url.as_deref().map(str::trim) {
+        Some(url) if !url.is_empty() => Some(url.to_string()),
+        _ if config.server_enabled => Some(format!("http://127.0.0.1:{}", config.server_port)),
+        _ => None,
+    }
+}
+
+/// Cliente a partir da configuração. None se faltar servidor ou credenciais.
+pub fn client_from_config(config: &KoreaderConfig) -> crate::Result<Option<KoreaderClient>> {
+    let (Some(url), Some(username), Some(userkey)) = (
+        effective_url(config),
+        config.username.as_deref().filter(|u| !u.is_empty()),
+        config.userkey.as_deref().filter(|k| !k.is_empty()),
+    ) else {
+        return Ok(None);
+    };
+    Ok(Some(KoreaderClient::new(&url, username, userkey)?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::document::{document_kind, partial_md5, DocumentKind, EpubSpine};
+    use super::engine::{from_koreader, to_koreader};
+    use super::*;
+    use crate::models::{Book, Chapter, KoreaderProgress, ReadingProgress};
+    use crate::repositories;
+    use crate::repositories::test_support::{book, memory_db, volume};
+    use std::io::Write;
+    use std::path::PathBuf;
+
+    fn temp_dir() -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("readito-koreader-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn start_server(dir: &std::path::Path) -> (KoreaderServer, String) {
+        let server =
+            KoreaderServer::start(dir.join("server.db"), 0, ServerAccess::default()).unwrap();
+        let url = format!("http://127.0.0.1:{}", server.port());
+        (server, url)
+    }
+
+    fn progress(page_index: i32, total_pages: Option<i32>) -> ReadingProgress {
+        ReadingProgress {
+            book_id: "b1".to_string(),
+            volume_id: "v1".to_string(),
+            current_chapter_id: None,
+            page_index,
+            scroll_offset: 0.0,
+            updated_at: 100,
+            total_pages,
+        }
+    }
+
+    fn remote(progress: &str, percentage: f64) -> KoreaderProgress {
+        KoreaderProgress {
+            document: "doc".to_string(),
+            progress: progress.to_string(),
+            percentage,
+            device: "Kobo".to_string(),
+            device_id: "kobo-1".to_string(),
+            timestamp: None,
+        }
+    }
+
+    #[test]
+    fn partial_md5_samples_koreader_offsets() {
+        assert_eq!(md5_hex(b"abc"), "900150983cd24fb0d6963f7d28e17f72");
+        let dir = temp_dir();
+        let small = dir.join("small.pdf");
+        std::fs::write(&small, b"%PDF-1.4 tiny").unwrap();
+        assert_eq!(partial_md5(&small).unwrap(), md5_hex(b"%PDF-1.4 tiny"));
+
+        // Blocos em 0, 1024 e 4096 (parcial); 16384 já passa do fim
+        let data: Vec<u8> = (0..5000u32).map(|i| (i % 251) as u8).collect();
+        let big = dir.join("big.pdf");
+        std::fs::write(&big, &data).unwrap();
+        let mut sampled = data[..2048].to_vec();
+        sampled.extend_from_slice(&data[4096..]);
+        assert_eq!(partial_md5(&big).unwrap(), md5_hex(&sampled));
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn maps_positions_between_readito_and_koreader() {
+        let no_spine = || -> crate::Result<EpubSpine> { panic!("spine not needed") };
+        let spine = || Ok(EpubSpine::from_weights(vec![100, 300, 600]));
+
+        // PDF/CBZ: página nos dois sentidos
+        let (p, pct) = to_koreader(&progress(25, Some(100)), DocumentKind::Paged, no_spine)
+            .unwrap()
+            .unwrap();
+        assert_eq!((p.as_str(), pct), ("25", 0.25));
+        assert_eq!(
+            from_koreader(&remote("42", 0.4), DocumentKind::Paged, None, no_spine),
+            Some(42)
+        );
+
+        // EPUB: percentual <-> xpointer pelo peso dos itens do spine
+        let (xp, pct) = to_koreader(&progress(51, Some(100)), DocumentKind::Reflowable, spine)
+            .unwrap()
+            .unwrap();
+        assert_eq!((xp.as_str(), pct), ("/body/DocFragment[3].0", 0.51));
+        assert!(
+            to_koreader(&progress(51, None), DocumentKind::Reflowable, spine)
+                .unwrap()
+                .is_none()
+        );
+        let xp = "/body/DocFragment[2]/body/div/p[4]/text().17";
+        assert_eq!(
+            from_koreader(
+                &remote(xp, 0.3),
+                DocumentKind::Reflowable,
+                Some(200),
+                no_spine
+            ),
+            Some(60)
+        );
+        // Sem percentual: início do DocFragment (100 / 1000)
+        assert_eq!(
+            from_koreader(&remote(xp, 0.0), DocumentKind::Reflowable, Some(200), spine),
+            Some(20)
+        );
+        assert_eq!(
+            from_koreader(&remote(xp, 0.3), DocumentKind::Reflowable, None, spine),
+            None
+        );
+    }
+
+    #[test]
+    fn reads_epub_spine_weights() {
+        let dir = temp_dir();
+        let path = dir.join("book.epub");
+        let mut zip = zip::ZipWriter::new(std::fs::File::create(&path).unwrap());
+        let options = zip::write::SimpleFileOptions::default();
+        let files: [(&str, String); 5] = [
+            (
+                "META-INF/container.xml",
+                r#"<container><rootfiles><rootfile full-path="OEBPS/content.opf"/></rootfiles></container>"#.to_string(),
+            ),
+            (
+                "OEBPS/content.opf",
+                r#"<package><manifest>
+                    <item id="c1" href="ch1.xhtml" media-type="application/xhtml+xml"/>
+                    <item href="ch%202.xhtml" id="c2" media-type="application/xhtml+xml"/>
+                    <item id="css" href="style.css"/>
+                  </manifest><spine><itemref idref="c1"/><itemref idref="c2"/></spine></package>"#
+                    .to_string(),
+            ),
+            ("OEBPS/ch1.xhtml", "a".repeat(250)),
+            ("OEBPS/ch 2.xhtml", "b".repeat(750)),
+            ("OEBPS/style.css", "c".repeat(5000)),
+        ];
+        for (name, content) in files {
+            zip.start_file(name, options).unwrap();
+            zip.write_all(content.as_bytes()).unwrap();
+        }
+        zip.finish().unwrap();
+
+        assert_eq!(
+            document_kind(path.to_str().unwrap()),
+            DocumentKind::Reflowable
+        );
+        let spine = EpubSpine::from_file(&path).unwrap();
+        assert_eq!(spine.xpointer_at(0.2), "/body/DocFragment[1].0");
+        assert_eq!(spine.xpointer_at(0.25), "/body/DocFragment[2].0");
+        assert_eq!(spine.xpointer_at(1.0), "/body/DocFragment[2].0");
+        assert_eq!(spine.percentage_of("/body/DocFragment[2].0"), Some(0.25));
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn embedded_server_registers_and_authorizes() {
+        let dir = temp_dir();
+        let (_server, url) = start_server(&dir);
+        let key = md5_hex(b"secret");
+        let client = KoreaderClient::new(&url, "alice", &key).unwrap();
+
+        client.register().unwrap();
+        assert!(matches!(
+            client.register(),
+            Err(crate::Error::Validation(_))
+        ));
+        client.authorize().unwrap();
+        let intruder = KoreaderClient::new(&url, "alice", &md5_hex(b"wrong")).unwrap();
+        assert!(matches!(
+            intruder.authorize(),
+            Err(crate::Error::Validation(_))
+        ));
+
+        drop(_server);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn embedded_server_stores_progress() {
+        let dir = temp_dir();
+        let (_server, url) = start_server(&dir);
+        let client = KoreaderClient::new(&url, "alice", &md5_hex(b"secret")).unwrap();
+        client.register().unwrap();
+
+        assert_eq!(client.get_progress("doc").unwrap(), None);
+        let timestamp = client.update_progress(&remote("12", 0.12)).unwrap();
+        assert!(timestamp.is_some());
+        let stored = client.get_progress("doc").unwrap().unwrap();
+        assert_eq!(stored.progress, "12");
+        assert_eq!(stored.device_id, "kobo-1");
+        assert_eq!(stored.timestamp, timestamp);
+
+        drop(_server);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn embedded_server_registration_needs_the_option() {
+        let dir = temp_dir();
+        let (_server, _) = start_server(&dir);
+
+        // Cadastro de outro dispositivo só com a opção ligada
+        let conn = rusqlite::Connection::open(dir.join("server.db")).unwrap();
+        let body = br#"{"username":"bob","password":"x"}"#;
+        let headers = std::collections::HashMap::new();
+        let (status, _) =
+            server::handle_request(&conn, "POST", "/users/create", &headers, body, false).unwrap();
+        assert_eq!(status, 402);
+        let (status, _) =
+            server::handle_request(&conn, "POST", "/users/create", &headers, body, true).unwrap();
+        assert_eq!(status, 201);
+
+        drop(_server);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    /// Servidor local, dois clientes da mesma conta (Readito e um Kobo) e uma
+    /// biblioteca com um PDF de verdade em `dir`, com progresso na página 5/50.
+    struct Device {
+        _server: KoreaderServer,
+        readito: KoreaderClient,
+        kobo: KoreaderClient,
+        conn: rusqlite::Connection,
+        document: String,
+    }
+
+    fn device(dir: &std::path::Path) -> Device {
+        let (server, url) = start_server(dir);
+        let key = md5_hex(b"secret");
+        let readito = KoreaderClient::new(&url, "alice", &key).unwrap();
+        readito.register().unwrap();
+        let kobo = KoreaderClient::new(&url, "alice", &key).unwrap();
+
+        let file = dir.join("Manual.pdf");
+        std::fs::write(&file, b"%PDF-1.4 some pages").unwrap();
+        let file_path = file.to_string_lossy().to_string();
+        let conn = memory_db();
+        repositories::insert_book(
+            &conn,
+            &Book {
+                path: file_path.clone(),
+                book_type: "file".to_string(),
+                ..book("b1", "Manual")
+            },
+        )
+        .unwrap();
+        repositories::insert_volume(&conn, &volume("v1", "b1", "Volume 1")).unwrap();
+        repositories::insert_chapter(
+            &conn,
+            &Chapter {
+                id: "c1".to_string(),
+                volume_id: "v1".to_string(),
+                name: "Manual".to_string(),
+                path: file_path,
+                position: 0,
+            },
+        )
+        .unwrap();
+        repositories::upsert_progress(&conn, &progress(5, Some(50))).unwrap();
+        Device {
+            _server: server,
+            readito,
+            kobo,
+            conn,
+            document: partial_md5(&file).unwrap(),
+        }
+    }
+
+    #[test]
+    fn pushes_local_position_to_koreader() {
+        let dir = temp_dir();
+        let d = device(&dir);
+
+        let report = sync_progress(&d.conn, &d.readito, "binary").unwrap();
+        assert_eq!((report.pushed, report.pulled), (1, 0));
+        let sent = d.kobo.get_progress(&d.document).unwrap().unwrap();
+        assert_eq!((sent.progress.as_str(), sent.percentage), ("5", 0.1));
+
+        drop(d);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn pulls_newer_position_from_koreader() {
+        let dir = temp_dir();
+        let d = device(&dir);
+        sync_progress(&d.conn, &d.readito, "binary").unwrap();
+
+        d.kobo
+            .update_progress(&KoreaderProgress {
+                document: d.document.clone(),
+                ..remote("42", 0.84)
+            })
+            .unwrap();
+        let report = sync_progress(&d.conn, &d.readito, "binary").unwrap();
+        assert_eq!((report.pushed, report.pulled), (0, 1));
+        let local = repositories::get_progress(&d.conn, "b1", "v1")
+            .unwrap()
+            .unwrap();
+        assert_eq!((local.page_index, local.total_pages), (42, Some(50)));
+
+        // Nada mudou: nenhuma troca
+        let report = sync_progress(&d.conn, &d.readito, "binary").unwrap();
+        assert_eq!((report.pushed, report.pulled), (0, 0));
+
+        drop(d);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
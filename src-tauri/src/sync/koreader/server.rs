@@ -0,0 +1,300 @@
+//! Servidor kosync embutido, compatível com o koreader-sync-server, para que
+//! dispositivos KOReader na mesma rede sincronizem direto com o Readito.
+//! Dados nas tabelas koreader_users / koreader_progress do próprio banco.
+//! Por padrão só escuta em 127.0.0.1 e só aceita cadastro vindo da própria
+//! máquina; expor na rede e abrir o cadastro são opções explícitas.
+
+use std::collections::HashMap;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::{Ipv4Addr, TcpListener, TcpStream};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::thread::JoinHandle;
+use std::time::Duration;
+
+use serde_json::{json, Value};
+
+use crate::models::KoreaderProgress;
+use crate::repositories;
+
+/// Intervalo de verificação do sinal de parada no loop de accept.
+const ACCEPT_POLL: Duration = Duration::from_millis(200);
+const READ_TIMEOUT: Duration = Duration::from_secs(10);
+const MAX_BODY: usize = 64 * 1024;
+/// Conexões atendidas ao mesmo tempo; além disso, novas são fechadas na hora.
+const MAX_CONNECTIONS: usize = 8;
+
+/// Quem pode falar com o servidor embutido.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct ServerAccess {
+    /// Escuta em todas as interfaces; desligado, só em 127.0.0.1.
+    pub lan: bool,
+    /// Aceita `/users/create` de outros dispositivos. Da própria máquina, sempre.
+    pub registration: bool,
+}
+
+pub struct KoreaderServer {
+    port: u16,
+    stop: Arc<AtomicBool>,
+    handle: Option<JoinHandle<()>>,
+}
+
+impl KoreaderServer {
+    /// Escuta na porta indicada (0 = porta livre), na rede ou só localmente
+    /// conforme `access`.
+    pub fn start(db_path: PathBuf, port: u16, access: ServerAccess) -> crate::Result<Self> {
+        // Garante o schema uma vez; as requisições abrem conexões simples
+        let conn = rusqlite::Connection::open(&db_path)?;
+        conn.busy_timeout(crate::db::BUSY_TIMEOUT)?;
+        crate::db::run_migrations(&conn)?;
+        drop(conn);
+
+        let host = if access.lan {
+            Ipv4Addr::UNSPECIFIED
+        } else {
+            Ipv4Addr::LOCALHOST
+        };
+        let listener = TcpListener::bind((host, port))
+            .map_err(|e| crate::Error::Io(format!("KOReader server: {}", e)))?;
+        let port = listener
+            .local_addr()
+            .map_err(|e| crate::Error::Io(e.to_string()))?
+            .port();
+        listener
+            .set_nonblocking(true)
+            .map_err(|e| crate::Error::Io(e.to_string()))?;
+
+        let stop = Arc::new(AtomicBool::new(false));
+        let stop_flag = stop.clone();
+        let active = Arc::new(AtomicUsize::new(0));
+        let handle = std::thread::spawn(move || {
+            while !stop_flag.load(Ordering::Relaxed) {
+                match listener.accept() {
+                    Ok((stream, addr)) => {
+                        // Só esta thread incrementa, então ler e somar não disputa
+                        if active.load(Ordering::Acquire) >= MAX_CONNECTIONS {
+                            log::warn!("[koreader:server] Conexões demais; recusando {}", addr);
+                            continue;
+                        }
+                        let slot = ConnectionSlot::take(&active);
+                        let db_path = db_path.clone();
+                        std::thread::spawn(move || {
+                            let _slot = slot;
+                            if let Err(e) = serve_connection(stream, &db_path, access) {
+                                log::warn!("[koreader:server] {:?}", e);
+                            }
+                        });
+                    }
+                    Err(e) if e.kind() == std::io::ErrorKind::WouldBlock => {
+                        std::thread::sleep(ACCEPT_POLL)
+                    }
+                    Err(e) => log::warn!("[koreader:server] accept: {}", e),
+                }
+            }
+        });
+        log::info!("[koreader:server] Escutando em {}:{}", host, port);
+        Ok(Self {
+            port,
+            stop,
+            handle: Some(handle),
+        })
+    }
+
+    pub fn port(&self) -> u16 {
+        self.port
+    }
+}
+
+impl Drop for KoreaderServer {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+        if let Some(handle) = self.handle.take() {
+            let _ = handle.join();
+        }
+    }
+}
+
+/// Vaga de conexão ocupada; devolvida no drop, mesmo se a thread entrar em pânico.
+struct ConnectionSlot(Arc<AtomicUsize>);
+
+impl ConnectionSlot {
+    fn take(active: &Arc<AtomicUsize>) -> Self {
+        active.fetch_add(1, Ordering::AcqRel);
+        Self(active.clone())
+    }
+}
+
+impl Drop for ConnectionSlot {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::AcqRel);
+    }
+}
+
+fn serve_connection(
+    stream: TcpStream,
+    db_path: &PathBuf,
+    access: ServerAccess,
+) -> crate::Result<()> {
+    let io = |e: std::io::Error| crate::Error::Io(e.to_string());
+    let local = stream.peer_addr().map_err(io)?.ip().is_loopback();
+    stream.set_nonblocking(false).map_err(io)?;
+    stream.set_read_timeout(Some(READ_TIMEOUT)).map_err(io)?;
+    let mut reader = BufReader::new(stream.try_clone().map_err(io)?);
+
+    let mut request_line = String::new();
+    reader.read_line(&mut request_line).map_err(io)?;
+    let mut headers = HashMap::new();
+    loop {
+        let mut line = String::new();
+        if reader.read_line(&mut line).map_err(io)? == 0 {
+            break;
+        }
+        let line = line.trim_end();
+        if line.is_empty() {
+            break;
+        }
+        if let Some((k, v)) = line.split_once(':') {
+            headers.insert(k.trim().to_ascii_lowercase(), v.trim().to_string());
+        }
+    }
+    let content_length: usize = headers
+        .get("content-length")
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(0);
+
+    let (status, body) = if content_length > MAX_BODY {
+        (413, json!({ "message": "Payload too large" }))
+    } else {
+        let mut body = vec![0; content_length];
+        reader.read_exact(&mut body).map_err(io)?;
+        let mut parts = request_line.split_whitespace();
+        let method = parts.next().unwrap_or("");
+        let path = parts.next().unwrap_or("");
+        let conn = rusqlite::Connection::open(db_path)?;
+        conn.busy_timeout(crate::db::BUSY_TIMEOUT)?;
+        let allow_registration = access.registration || local;
+        match handle_request(&conn, method, path, &headers, &body, allow_registration) {
+            Ok(r) => r,
+            Err(e) => {
+                log::warn!("[koreader:server] {} {}: {:?}", method, path, e);
+                (500, json!({ "message": "Internal error" }))
+            }
+        }
+    };
+
+    let payload = body.to_string();
+    let mut stream = stream;
+    write!(
+        stream,
+        "HTTP/1.1 {} {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status,
+        reason(status),
+        payload.len(),
+        payload
+    )
+    .map_err(io)?;
+    Ok(())
+}
+
+fn reason(status: u16) -> &'static str {
+    match status {
+        200 => "OK",
+        201 => "Created",
+        401 => "Unauthorized",
+        402 => "Payment Required",
+        403 => "Forbidden",
+        404 => "Not Found",
+        413 => "Payload Too Large",
+        _ => "Internal Server Error",
+    }
+}
+
+/// Erros no formato do koreader-sync-server.
+fn error(status: u16, code: i64, message: &str) -> (u16, Value) {
+    (status, json!({ "code": code, "message": message }))
+}
+
+/// Usuário autenticado pelos cabeçalhos x-auth-user / x-auth-key.
+fn authenticate(
+    conn: &rusqlite::Connection,
+    headers: &HashMap<String, String>,
+) -> crate::Result<Option<String>> {
+    let (Some(user), Some(key)) = (headers.get("x-auth-user"), headers.get("x-auth-key")) else {
+        return Ok(None);
+    };
+    let stored = repositories::get_koreader_userkey(conn, user)?;
+    Ok(stored
+        .is_some_and(|stored| keys_match(&stored, key))
+        .then(|| user.clone()))
+}
+
+/// Compara sem parar no primeiro byte diferente, para o tempo de resposta
+/// não revelar quanto da chave confere.
+fn keys_match(a: &str, b: &str) -> bool {
+    a.len() == b.len()
+        && a.bytes()
+            .zip(b.bytes())
+            .fold(0u8, |diff, (x, y)| diff | (x ^ y))
+            == 0
+}
+
+/// Roteia uma requisição kosync. Separado do socket para facilitar testes.
+pub fn handle_request(
+    conn: &rusqlite::Connection,
+    method: &str,
+    path: &str,
+    headers: &HashMap<String, String>,
+    body: &[u8],
+    allow_registration: bool,
+) -> crate::Result<(u16, Value)> {
+    let path = path.split('?').next().unwrap_or("");
+    if method == "GET" && path == "/healthcheck" {
+        return Ok((200, json!({ "state": "OK" })));
+    }
+    if method == "POST" && path == "/users/create" {
+        if !allow_registration {
+            return Ok(error(402, 2005, "User registration is disabled."));
+        }
+        let v: Value = serde_json::from_slice(body).unwrap_or(Value::Null);
+        let username = v["username"].as_str().unwrap_or("").trim();
+        let password = v["password"].as_str().unwrap_or("");
+        if username.is_empty() || password.is_empty() {
+            return Ok(error(403, 2003, "Invalid request"));
+        }
+        if !repositories::create_koreader_user(conn, username, password)? {
+            return Ok(error(402, 2002, "Username is already registered."));
+        }
+        return Ok((201, json!({ "username": username })));
+    }
+
+    let Some(user) = authenticate(conn, headers)? else {
+        return Ok(error(401, 2001, "Unauthorized"));
+    };
+    match (method, path) {
+        ("GET", "/users/auth") => Ok((200, json!({ "authorized": "OK" }))),
+        ("PUT", "/syncs/progress") => {
+            let Ok(progress) = serde_json::from_slice::<KoreaderProgress>(body) else {
+                return Ok(error(403, 2003, "Invalid request"));
+            };
+            if progress.document.is_empty() {
+                return Ok(error(403, 2004, "Field 'document' not provided."));
+            }
+            let timestamp = repositories::upsert_koreader_progress(conn, &user, &progress)?;
+            Ok((
+                200,
+                json!({ "document": progress.document, "timestamp": timestamp }),
+            ))
+        }
+        ("GET", p) if p.starts_with("/syncs/progress/") => {
+            let document = urlencoding::decode(&p["/syncs/progress/".len()..])
+                .map(|d| d.into_owned())
+                .unwrap_or_default();
+            match repositories::get_koreader_progress(conn, &user, &document)? {
+                Some(progress) => Ok((200, serde_json::to_value(progress).unwrap_or_default())),
+                None => Ok((200, json!({}))),
+            }
+        }
+        _ => Ok(error(404, 2000, "Not found")),
+    }
+}
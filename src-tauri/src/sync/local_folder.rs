@@ -0,0 +1,81 @@
+//! Backend em pasta local (ex.: diretório sincronizado por Syncthing ou Nextcloud).
+//! Cada dispositivo grava apenas o próprio arquivo `<device_id>.json`,
+//! então não há escrita concorrente no mesmo arquivo entre máquinas.
+
+use std::path::{Path, PathBuf};
+
+use super::backend::{PullResult, SyncBackend};
+use super::record::{merge_latest, DeviceFile, SyncRecord};
+
+pub struct LocalFolderBackend {
+    dir: PathBuf,
+}
+
+impl LocalFolderBackend {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    fn device_path(&self, device_id: &str) -> PathBuf {
+        self.dir.join(format!("{}.json", device_id))
+    }
+}
+
+fn read_device_file(path: &Path) -> crate::Result<DeviceFile> {
+    let raw = std::fs::read_to_string(path).map_err(|e| crate::Error::Io(e.to_string()))?;
+    serde_json::from_str(&raw).map_err(|e| crate::Error::Io(format!("{}: {}", path.display(), e)))
+}
+
+impl SyncBackend for LocalFolderBackend {
+    fn name(&self) -> &'static str {
+        "local_folder"
+    }
+
+    fn push(&self, device_id: &str, records: &[SyncRecord]) -> crate::Result<()> {
+        std::fs::create_dir_all(&self.dir).map_err(|e| crate::Error::Io(e.to_string()))?;
+        let path = self.device_path(device_id);
+        let mut all = if path.exists() {
+            read_device_file(&path)?.records
+        } else {
+            Vec::new()
+        };
+        all.extend(records.iter().cloned());
+        let file = DeviceFile {
+            device_id: device_id.to_string(),
+            records: merge_latest(all),
+        };
+        let json = serde_json::to_string(&file).map_err(|e| crate::Error::Io(e.to_string()))?;
+        // Grava em arquivo temporário e renomeia, para o cliente de sync nunca ver arquivo parcial
+        let tmp = self.dir.join(format!(".{}.json.tmp", device_id));
+        std::fs::write(&tmp, json).map_err(|e| crate::Error::Io(e.to_string()))?;
+        std::fs::rename(&tmp, &path).map_err(|e| crate::Error::Io(e.to_string()))?;
+        Ok(())
+    }
+
+    fn pull(&self, device_id: &str, _cursor: Option<&str>) -> crate::Result<PullResult> {
+        let entries = match std::fs::read_dir(&self.dir) {
+            Ok(e) => e,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(PullResult::default()),
+            Err(e) => return Err(crate::Error::Io(e.to_string())),
+        };
+        // Arquivo próprio e as cópias de conflito dele ("<id>.sync-conflict-....json");
+        // o ponto evita pular outro dispositivo cujo id começa com o nosso
+        let own_prefix = format!("{}.", device_id);
+        let mut records = Vec::new();
+        for entry in entries.flatten() {
+            let path = entry.path();
+            let name = entry.file_name().to_string_lossy().to_string();
+            if !name.ends_with(".json") || name.starts_with('.') || name.starts_with(&own_prefix) {
+                continue;
+            }
+            match read_device_file(&path) {
+                Ok(file) => records.extend(file.records),
+                Err(e) => log::warn!("[sync:local_folder] Ignorando {}: {:?}", name, e),
+            }
+        }
+        Ok(PullResult {
+            records,
+            cursor: None,
+        })
+    }
+}
@@ -0,0 +1,22 @@
+//! Sincronização opcional entre dispositivos (Last Write Wins).
+//! O engine lê o change log local, envia/recebe registros por um `SyncBackend`
+//! e aplica as mudanças remotas mais novas.
+
+mod backend;
+mod engine;
+mod http;
+pub mod koreader;
+mod local_folder;
+mod record;
+#[cfg(test)]
+mod test_server;
+mod webdav;
+
+pub mod scheduler;
+
+pub use backend::{backend_from_config, PullResult, SyncBackend};
+pub use engine::{run_sync, SyncReport};
+pub use http::HttpBackend;
+pub use local_folder::LocalFolderBackend;
+pub use record::SyncRecord;
+pub use webdav::WebDavBackend;
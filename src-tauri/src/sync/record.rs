@@ -0,0 +1,86 @@
+//! Registro trocado entre dispositivos: estado completo de uma linha (ou sua exclusão).
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SyncRecord {
+    /// Tabela de origem (ver `repositories::SYNC_TABLE_SPECS`).
+    pub table: String,
+    /// Chave da linha; chaves compostas unidas com `|`.
+    pub key: String,
+    /// updated_at da linha ou deleted_at do tombstone (segundos).
+    pub updated_at: i64,
+    /// Dispositivo que fez a mudança.
+    pub device_id: String,
+    #[serde(default)]
+    pub deleted: bool,
+    #[serde(default)]
+    pub data: serde_json::Map<String, serde_json::Value>,
+}
+
+impl SyncRecord {
+    /// LWW: vence o maior updated_at; empate decidido pelo maior device_id,
+    /// para que todos os dispositivos cheguem ao mesmo resultado.
+    pub fn wins_over(&self, updated_at: i64, device_id: &str) -> bool {
+        (self.updated_at, self.device_id.as_str()) > (updated_at, device_id)
+    }
+
+    /// Identidade da linha (tabela + chave).
+    pub fn row_id(&self) -> (&str, &str) {
+        (self.table.as_str(), self.key.as_str())
+    }
+}
+
+/// Arquivo de um dispositivo nos backends baseados em arquivos (pasta local, WebDAV).
+#[derive(Serialize, Deserialize)]
+pub(super) struct DeviceFile {
+    pub device_id: String,
+    pub records: Vec<SyncRecord>,
+}
+
+/// Mantém apenas o registro vencedor (LWW) por linha.
+pub fn merge_latest(records: impl IntoIterator<Item = SyncRecord>) -> Vec<SyncRecord> {
+    let mut latest: std::collections::HashMap<(String, String), SyncRecord> =
+        std::collections::HashMap::new();
+    for r in records {
+        let id = (r.table.clone(), r.key.clone());
+        match latest.get(&id) {
+            Some(current) if !r.wins_over(current.updated_at, &current.device_id) => {}
+            _ => {
+                latest.insert(id, r);
+            }
+        }
+    }
+    let mut out: Vec<SyncRecord> = latest.into_values().collect();
+    out.sort_by(|a, b| (a.updated_at, &a.table, &a.key).cmp(&(b.updated_at, &b.table, &b.key)));
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(updated_at: i64, device_id: &str) -> SyncRecord {
+        SyncRecord {
+            table: "shelves".to_string(),
+            key: "s1".to_string(),
+            updated_at,
+            device_id: device_id.to_string(),
+            deleted: false,
+            data: serde_json::Map::new(),
+        }
+    }
+
+    #[test]
+    fn last_write_wins_with_device_tie_break() {
+        assert!(record(20, "a").wins_over(10, "z"));
+        assert!(!record(10, "z").wins_over(20, "a"));
+        // Empate: maior device_id vence, em qualquer ordem de chegada
+        assert!(record(10, "b").wins_over(10, "a"));
+        assert!(!record(10, "a").wins_over(10, "b"));
+        let merged = merge_latest(vec![record(10, "b"), record(10, "a")]);
+        assert_eq!(merged, vec![record(10, "b")]);
+        let merged = merge_latest(vec![record(10, "a"), record(10, "b")]);
+        assert_eq!(merged, vec![record(10, "b")]);
+    }
+}
@@ -0,0 +1,109 @@
+//! Sincronização agendada (thread em background) e execução sob demanda,
+//! incluindo o KOReader e o ciclo de vida do servidor kosync embutido.
+
+use std::sync::Mutex;
+use std::time::Duration;
+
+use tauri::{AppHandle, Emitter};
+
+use super::koreader::{self, KoreaderServer, ServerAccess};
+use super::{backend_from_config, run_sync, SyncReport};
+use crate::db;
+use crate::models::KoreaderSyncReport;
+use crate::repositories;
+
+/// Intervalo entre verificações do agendador.
+const CHECK_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Evita rodadas simultâneas (agendada + manual).
+static SYNC_LOCK: Mutex<()> = Mutex::new(());
+static KOREADER_LOCK: Mutex<()> = Mutex::new(());
+
+/// Servidor kosync em execução (None = desligado).
+static KOREADER_SERVER: Mutex<Option<KoreaderServer>> = Mutex::new(None);
+
+fn now_secs() -> i64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs() as i64)
+        .unwrap_or(0)
+}
+
+/// Executa uma rodada com o backend configurado e emite `sync-completed`.
+pub fn sync_now(app: &AppHandle) -> crate::Result<SyncReport> {
+    let _guard = SYNC_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let conn = db::open(app)?;
+    let config = repositories::get_sync_config(&conn)?;
+    let backend = backend_from_config(&config)?
+        .ok_or_else(|| crate::Error::Validation("sync is not configured".to_string()))?;
+    let report = run_sync(&conn, backend.as_ref())?;
+    let _ = app.emit("sync-completed", &report);
+    Ok(report)
+}
+
+/// Troca posições com o servidor KOReader configurado e emite `koreader-sync-completed`.
+pub fn koreader_sync_now(app: &AppHandle) -> crate::Result<KoreaderSyncReport> {
+    let _guard = KOREADER_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let conn = db::open(app)?;
+    let config = repositories::get_koreader_config(&conn)?;
+    let client = koreader::client_from_config(&config)?
+        .ok_or_else(|| crate::Error::Validation("KOReader sync is not configured".to_string()))?;
+    let report = koreader::sync_progress(&conn, &client, &config.match_method)?;
+    let _ = app.emit("koreader-sync-completed", &report);
+    Ok(report)
+}
+
+/// (Re)inicia o servidor kosync embutido conforme a configuração atual.
+pub fn restart_koreader_server(app: &AppHandle) -> crate::Result<()> {
+    let config = repositories::get_koreader_config(&db::open(app)?)?;
+    let mut server = KOREADER_SERVER.lock().unwrap_or_else(|e| e.into_inner());
+    // Para o atual antes de abrir a porta de novo
+    server.take();
+    if config.server_enabled {
+        *server = Some(KoreaderServer::start(
+            db::app_db_path(app)?,
+            config.server_port,
+            ServerAccess {
+                lan: config.server_lan,
+                registration: config.server_registration,
+            },
+        )?);
+    }
+    Ok(())
+}
+
+/// Inicia a thread que dispara a sincronização quando o intervalo configurado vence.
+pub fn start(app: AppHandle) {
+    if let Err(e) = restart_koreader_server(&app) {
+        log::warn!("[koreader] Servidor embutido não iniciado: {:?}", e);
+    }
+    std::thread::spawn(move || loop {
+        std::thread::sleep(CHECK_INTERVAL);
+        let due = db::open(&app)
+            .and_then(|conn| repositories::get_sync_config(&conn))
+            .map(|cfg| {
+                cfg.interval_secs > 0
+                    && backend_from_config(&cfg).ok().flatten().is_some()
+                    && now_secs() - cfg.last_sync_at.unwrap_or(0) >= cfg.interval_secs
+            })
+            .unwrap_or(false);
+        if due {
+            if let Err(e) = sync_now(&app) {
+                log::warn!("[sync] Falha na sincronização agendada: {:?}", e);
+            }
+        }
+        let koreader_due = db::open(&app)
+            .and_then(|conn| repositories::get_koreader_config(&conn))
+            .map(|cfg| {
+                cfg.interval_secs > 0
+                    && koreader::client_from_config(&cfg).ok().flatten().is_some()
+                    && now_secs() - cfg.last_sync_at.unwrap_or(0) >= cfg.interval_secs
+            })
+            .unwrap_or(false);
+        if koreader_due {
+            if let Err(e) = koreader_sync_now(&app) {
+                log::warn!("[koreader] Falha na sincronização agendada: {:?}", e);
+            }
+        }
+    });
+}
@@ -0,0 +1,517 @@
+//! Backend WebDAV (Nextcloud, ownCloud, Apache mod_dav, rclone serve webdav...).
+//! Mesmo layout da pasta local: cada dispositivo grava `<device_id>.json` na coleção.
+//! Gravações usam If-Match com o ETag lido (If-None-Match: * na criação); se outro
+//! processo alterou o arquivo no meio (412), ele é relido e mesclado de novo.
+//! O cursor de pull guarda o ETag de cada arquivo já lido, para baixar só o que mudou.
+
+use std::collections::BTreeMap;
+use std::sync::LazyLock;
+use std::time::Duration;
+
+use regex::Regex;
+use reqwest::blocking::{Client, RequestBuilder};
+use reqwest::{Method, StatusCode};
+
+use super::backend::{PullResult, SyncBackend};
+use super::record::{merge_latest, DeviceFile, SyncRecord};
+
+const TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Tentativas de gravação antes de desistir por conflito de ETag.
+const MAX_PUT_ATTEMPTS: usize = 3;
+
+const PROPFIND_BODY: &str = r#"<?xml version="1.0" encoding="utf-8"?>
+<d:propfind xmlns:d="DAV:"><d:prop><d:getetag/></d:prop></d:propfind>"#;
+
+/// Condição enviada no PUT.
+enum Precondition {
+    /// Arquivo ainda não existe.
+    Absent,
+    /// Arquivo existe com este ETag.
+    Matches(String),
+    /// Servidor não informou ETag: grava sem condição.
+    None,
+}
+
+enum PutOutcome {
+    Written,
+    /// 412: o arquivo mudou desde a leitura.
+    Conflict,
+    /// 404/409: a coleção ainda não existe.
+    MissingCollection,
+}
+
+pub struct WebDavBackend {
+    /// URL da coleção, sempre terminada em `/`.
+    base_url: String,
+    username: Option<String>,
+    password: Option<String>,
+    client: Client,
+}
+
+impl WebDavBackend {
+    pub fn new(
+        base_url: &str,
+        username: Option<String>,
+        password: Option<String>,
+    ) -> crate::Result<Self> {
+        let base_url = base_url.trim();
+        if !base_url.starts_with("http://") && !base_url.starts_with("https://") {
+            return Err(crate::Error::Validation(
+                "webdav target must be an http(s) URL".to_string(),
+            ));
+        }
+        let client = Client::builder()
+            .timeout(TIMEOUT)
+            .user_agent("Readito/1.0")
+            .build()
+            .map_err(|e| crate::Error::Io(e.to_string()))?;
+        Ok(Self {
+            base_url: format!("{}/", base_url.trim_end_matches('/')),
+            username: username.filter(|u| !u.is_empty()),
+            password: password.filter(|p| !p.is_empty()),
+            client,
+        })
+    }
+
+    fn request(&self, method: Method, url: &str) -> RequestBuilder {
+        let req = self.client.request(method, url);
+        match &self.username {
+            Some(user) => req.basic_auth(user, self.password.as_deref()),
+            None => req,
+        }
+    }
+
+    fn file_url(&self, name: &str) -> String {
+        format!("{}{}", self.base_url, urlencoding::encode(name))
+    }
+
+    /// Lê um arquivo de dispositivo e seu ETag. None se não existir.
+    fn get_file(&self, name: &str) -> crate::Result<Option<(DeviceFile, Option<String>)>> {
+        let resp = self
+            .request(Method::GET, &self.file_url(name))
+            .send()
+            .map_err(|e| crate::Error::Io(e.to_string()))?;
+        if resp.status() == StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+        if !resp.status().is_success() {
+            return Err(crate::Error::Io(format!(
+                "WebDAV GET {}: {}",
+                name,
+                resp.status()
+            )));
+        }
+        let etag = resp
+            .headers()
+            .get(reqwest::header::ETAG)
+            .and_then(|v| v.to_str().ok())
+            .map(str::to_string);
+        let file: DeviceFile = resp
+            .json()
+            .map_err(|e| crate::Error::Io(format!("{}: {}", name, e)))?;
+        Ok(Some((file, etag)))
+    }
+
+    fn put_file(
+        &self,
+        name: &str,
+        file: &DeviceFile,
+        precondition: &Precondition,
+    ) -> crate::Result<PutOutcome> {
+        let json = serde_json::to_vec(file).map_err(|e| crate::Error::Io(e.to_string()))?;
+        let mut req = self
+            .request(Method::PUT, &self.file_url(name))
+            .header(reqwest::header::CONTENT_TYPE, "application/json")
+            .body(json);
+        req = match precondition {
+            Precondition::Absent => req.header(reqwest::header::IF_NONE_MATCH, "*"),
+            Precondition::Matches(etag) => req.header(reqwest::header::IF_MATCH, etag.as_str()),
+            Precondition::None => req,
+        };
+        let resp = req.send().map_err(|e| crate::Error::Io(e.to_string()))?;
+        match resp.status() {
+            s if s.is_success() => Ok(PutOutcome::Written),
+            StatusCode::PRECONDITION_FAILED => Ok(PutOutcome::Conflict),
+            StatusCode::NOT_FOUND | StatusCode::CONFLICT => Ok(PutOutcome::MissingCollection),
+            s => Err(crate::Error::Io(format!("WebDAV PUT {}: {}", name, s))),
+        }
+    }
+
+    /// Cria a coleção (MKCOL). 405 = já existe.
+    fn ensure_collection(&self) -> crate::Result<()> {
+        let method = Method::from_bytes(b"MKCOL").expect("valid method");
+        let resp = self
+            .request(method, &self.base_url)
+            .send()
+            .map_err(|e| crate::Error::Io(e.to_string()))?;
+        if resp.status().is_success() || resp.status() == StatusCode::METHOD_NOT_ALLOWED {
+            Ok(())
+        } else {
+            Err(crate::Error::Io(format!("WebDAV MKCOL: {}", resp.status())))
+        }
+    }
+
+    /// Lista os arquivos da coleção com seus ETags (PROPFIND Depth: 1).
+    fn list_files(&self) -> crate::Result<Vec<(String, Option<String>)>> {
+        let method = Method::from_bytes(b"PROPFIND").expect("valid method");
+        let resp = self
+            .request(method, &self.base_url)
+            .header("Depth", "1")
+            .header(reqwest::header::CONTENT_TYPE, "application/xml")
+            .body(PROPFIND_BODY)
+            .send()
+            .map_err(|e| crate::Error::Io(e.to_string()))?;
+        if resp.status() == StatusCode::NOT_FOUND {
+            return Ok(Vec::new());
+        }
+        if !resp.status().is_success() {
+            return Err(crate::Error::Io(format!(
+                "WebDAV PROPFIND: {}",
+                resp.status()
+            )));
+        }
+        let body = resp.text().map_err(|e| crate::Error::Io(e.to_string()))?;
+        Ok(parse_multistatus(&body))
+    }
+}
+
+static RESPONSE_REGEX: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?s)<(?:[\w-]+:)?response\b[^>]*>(.*?)</(?:[\w-]+:)?response>")
+        .expect("response regex")
+});
+static HREF_REGEX: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?s)<(?:[\w-]+:)?href\b[^>]*>(.*?)</(?:[\w-]+:)?href>").expect("href regex")
+});
+static ETAG_REGEX: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?s)<(?:[\w-]+:)?getetag\b[^>]*>(.*?)</(?:[\w-]+:)?getetag>")
+        .expect("getetag regex")
+});
+
+/// Extrai (nome do arquivo, ETag) de cada `<response>` de um multistatus.
+/// Aceita qualquer prefixo de namespace (d:, D:, sem prefixo).
+fn parse_multistatus(xml: &str) -> Vec<(String, Option<String>)> {
+    let mut out = Vec::new();
+    for cap in RESPONSE_REGEX.captures_iter(xml) {
+        let block = &cap[1];
+        let Some(href) = HREF_REGEX
+            .captures(block)
+            .map(|c| xml_unescape(c[1].trim()))
+        else {
+            continue;
+        };
+        // Coleção termina em `/`; arquivos têm o nome no último segmento
+        if href.ends_with('/') {
+            continue;
+        }
+        let segment = href.rsplit('/').next().unwrap_or("");
+        let name = urlencoding::decode(segment)
+            .map(|s| s.into_owned())
+            .unwrap_or_else(|_| segment.to_string());
+        let etag = ETAG_REGEX
+            .captures(block)
+            .map(|c| xml_unescape(c[1].trim()))
+            .filter(|e| !e.is_empty());
+        out.push((name, etag));
+    }
+    out
+}
+
+fn xml_unescape(s: &str) -> String {
+    s.replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&amp;", "&")
+}
+
+impl SyncBackend for WebDavBackend {
+    fn name(&self) -> &'static str {
+        "webdav"
+    }
+
+    fn push(&self, device_id: &str, records: &[SyncRecord]) -> crate::Result<()> {
+        let name = format!("{}.json", device_id);
+        for _ in 0..MAX_PUT_ATTEMPTS {
+            let (mut all, precondition) = match self.get_file(&name)? {
+                Some((file, Some(etag))) => (file.records, Precondition::Matches(etag)),
+                Some((file, None)) => (file.records, Precondition::None),
+                None => (Vec::new(), Precondition::Absent),
+            };
+            all.extend(records.iter().cloned());
+            let file = DeviceFile {
+                device_id: device_id.to_string(),
+                records: merge_latest(all),
+            };
+            match self.put_file(&name, &file, &precondition)? {
+                PutOutcome::Written => return Ok(()),
+                PutOutcome::Conflict => {
+                    log::warn!("[sync:webdav] {} mudou durante a gravação, relendo", name);
+                }
+                PutOutcome::MissingCollection => self.ensure_collection()?,
+            }
+        }
+        Err(crate::Error::Io(format!(
+            "WebDAV: conflito persistente ao gravar {}",
+            name
+        )))
+    }
+
+    fn pull(&self, device_id: &str, cursor: Option<&str>) -> crate::Result<PullResult> {
+        let seen: BTreeMap<String, String> = cursor
+            .and_then(|c| serde_json::from_str(c).ok())
+            .unwrap_or_default();
+        let own = format!("{}.json", device_id);
+        let mut next = BTreeMap::new();
+        let mut records = Vec::new();
+        for (name, listed_etag) in self.list_files()? {
+            if !name.ends_with(".json") || name.starts_with('.') || name == own {
+                continue;
+            }
+            if let Some(etag) = &listed_etag {
+                if seen.get(&name) == Some(etag) {
+                    next.insert(name, etag.clone());
+                    continue;
+                }
+            }
+            match self.get_file(&name) {
+                Ok(Some((file, etag))) => {
+                    records.extend(file.records);
+                    if let Some(etag) = etag.or(listed_etag) {
+                        next.insert(name, etag);
+                    }
+                }
+                Ok(None) => {}
+                Err(e) => log::warn!("[sync:webdav] Ignorando {}: {:?}", name, e),
+            }
+        }
+        let cursor = serde_json::to_string(&next).map_err(|e| crate::Error::Io(e.to_string()))?;
+        Ok(PullResult {
+            records,
+            cursor: Some(cursor),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sync::test_server::{self, Request, Response};
+    use std::collections::HashMap;
+    use std::sync::{Arc, Mutex};
+
+    const BASE_PATH: &str = "/dav/readito/";
+
+    #[derive(Default)]
+    struct DavState {
+        collection: bool,
+        /// nome -> (conteúdo, versão)
+        files: HashMap<String, (Vec<u8>, u32)>,
+        /// Simula outro processo gravando o arquivo entre o GET e o PUT.
+        race_on_put: Option<String>,
+    }
+
+    fn etag(version: u32) -> String {
+        format!("\"v{}\"", version)
+    }
+
+    /// Servidor WebDAV local em memória: MKCOL, PROPFIND, GET e PUT condicional,
+    /// com autenticação básica (alice/secret).
+    fn spawn_dav_server(state: Arc<Mutex<DavState>>) -> String {
+        let base = test_server::spawn(move |req: &Request| {
+            // "alice:secret" em base64
+            if req.header("authorization") != Some("Basic YWxpY2U6c2VjcmV0") {
+                return Response::new(401);
+            }
+            let mut state = state.lock().unwrap();
+            if req.path == BASE_PATH || req.path == BASE_PATH.trim_end_matches('/') {
+                return match req.method.as_str() {
+                    "MKCOL" if state.collection => Response::new(405),
+                    "MKCOL" => {
+                        state.collection = true;
+                        Response::new(201)
+                    }
+                    "PROPFIND" if !state.collection => Response::new(404),
+                    "PROPFIND" => {
+                        assert_eq!(req.header("depth"), Some("1"));
+                        let mut xml = format!(
+                            "<?xml version=\"1.0\"?><D:multistatus xmlns:D=\"DAV:\">\
+                             <D:response><D:href>{}</D:href><D:propstat><D:prop>\
+                             <D:resourcetype><D:collection/></D:resourcetype>\
+                             </D:prop></D:propstat></D:response>",
+                            BASE_PATH
+                        );
+                        for (name, (_, version)) in &state.files {
+                            xml.push_str(&format!(
+                                "<D:response><D:href>{}{}</D:href><D:propstat><D:prop>\
+                                 <D:getetag>{}</D:getetag></D:prop></D:propstat></D:response>",
+                                BASE_PATH,
+                                urlencoding::encode(name),
+                                etag(*version).replace('"', "&quot;")
+                            ));
+                        }
+                        xml.push_str("</D:multistatus>");
+                        Response::new(207).with_body(xml)
+                    }
+                    _ => Response::new(405),
+                };
+            }
+            let Some(name) = req.path.strip_prefix(BASE_PATH) else {
+                return Response::new(404);
+            };
+            let name = urlencoding::decode(name).unwrap().into_owned();
+            match req.method.as_str() {
+                "GET" => match state.files.get(&name) {
+                    Some((body, version)) => Response::new(200)
+                        .with_header("ETag", &etag(*version))
+                        .with_body(body.clone()),
+                    None => Response::new(404),
+                },
+                "PUT" => {
+                    if !state.collection {
+                        return Response::new(409);
+                    }
+                    if state.race_on_put.as_deref() == Some(name.as_str()) {
+                        state.race_on_put = None;
+                        let entry = state.files.get_mut(&name).unwrap();
+                        let mut file: serde_json::Value = serde_json::from_slice(&entry.0).unwrap();
+                        let other = serde_json::to_value(record("shelves", "s2", 50, 1)).unwrap();
+                        file["records"].as_array_mut().unwrap().push(other);
+                        *entry = (serde_json::to_vec(&file).unwrap(), entry.1 + 1);
+                    }
+                    let current = state.files.get(&name).map(|(_, v)| etag(*v));
+                    let ok = match (req.header("if-match"), req.header("if-none-match")) {
+                        (Some(expected), _) => current.as_deref() == Some(expected),
+                        (_, Some("*")) => current.is_none(),
+                        _ => true,
+                    };
+                    if !ok {
+                        return Response::new(412);
+                    }
+                    let version = state.files.get(&name).map(|(_, v)| v + 1).unwrap_or(1);
+                    state.files.insert(name, (req.body.clone(), version));
+                    Response::new(201)
+                }
+                _ => Response::new(405),
+            }
+        });
+        format!("{}{}", base, BASE_PATH)
+    }
+
+    fn record(table: &str, key: &str, updated_at: i64, page: i64) -> SyncRecord {
+        let mut data = serde_json::Map::new();
+        data.insert("page_index".to_string(), page.into());
+        SyncRecord {
+            table: table.to_string(),
+            key: key.to_string(),
+            updated_at,
+            device_id: "dev-a".to_string(),
+            deleted: false,
+            data,
+        }
+    }
+
+    fn backend(url: &str) -> WebDavBackend {
+        WebDavBackend::new(url, Some("alice".to_string()), Some("secret".to_string())).unwrap()
+    }
+
+    #[test]
+    fn pushes_device_file_and_pulls_only_changed_files() {
+        let state = Arc::new(Mutex::new(DavState::default()));
+        let url = spawn_dav_server(state.clone());
+        let a = backend(&url);
+        let b = backend(&url);
+
+        // Coleção inexistente: pull vazio, push cria com MKCOL
+        assert!(b.pull("dev-b", None).unwrap().records.is_empty());
+        a.push("dev-a", &[record("reading_progress", "b1|v1", 100, 3)])
+            .unwrap();
+        assert!(state.lock().unwrap().collection);
+
+        let first = b.pull("dev-b", None).unwrap();
+        assert_eq!(first.records.len(), 1);
+        // O próprio arquivo não é lido de volta
+        assert!(a.pull("dev-a", None).unwrap().records.is_empty());
+        // Só o nome exato: "dev-a" não esconde o arquivo de "dev"
+        assert_eq!(backend(&url).pull("dev", None).unwrap().records.len(), 1);
+
+        // ETag igual: nada é baixado de novo
+        let again = b.pull("dev-b", first.cursor.as_deref()).unwrap();
+        assert!(again.records.is_empty());
+
+        // Novo push mescla com o arquivo existente (LWW por linha)
+        a.push("dev-a", &[record("reading_progress", "b1|v1", 200, 9)])
+            .unwrap();
+        let changed = b.pull("dev-b", again.cursor.as_deref()).unwrap();
+        assert_eq!(
+            changed.records,
+            vec![record("reading_progress", "b1|v1", 200, 9)]
+        );
+
+        let wrong = WebDavBackend::new(&url, Some("alice".to_string()), None).unwrap();
+        assert!(wrong.pull("dev-b", None).is_err());
+    }
+
+    #[test]
+    fn retries_on_etag_conflict_without_losing_records() {
+        let state = Arc::new(Mutex::new(DavState::default()));
+        let url = spawn_dav_server(state.clone());
+        let a = backend(&url);
+
+        a.push("dev-a", &[record("shelves", "s1", 10, 0)]).unwrap();
+        state.lock().unwrap().race_on_put = Some("dev-a.json".to_string());
+        a.push("dev-a", &[record("reading_progress", "b1|v1", 100, 4)])
+            .unwrap();
+
+        let (body, version) = state.lock().unwrap().files["dev-a.json"].clone();
+        // Versão 1 (criação), 2 (escrita concorrente), 3 (regravação após o 412)
+        assert_eq!(version, 3);
+        let file: DeviceFile = serde_json::from_slice(&body).unwrap();
+        let mut keys: Vec<&str> = file.records.iter().map(|r| r.key.as_str()).collect();
+        keys.sort();
+        assert_eq!(keys, vec!["b1|v1", "s1", "s2"]);
+    }
+
+    #[test]
+    fn syncs_reading_progress_between_devices() {
+        use crate::repositories;
+        use crate::sync::engine::tests::{open_db, progress};
+        use crate::sync::run_sync;
+
+        let url = spawn_dav_server(Arc::new(Mutex::new(DavState::default())));
+        let dav = backend(&url);
+        let office = open_db();
+        let home = open_db();
+
+        repositories::upsert_progress(&office, &progress(12, 300)).unwrap();
+        repositories::upsert_progress(&home, &progress(4, 200)).unwrap();
+
+        run_sync(&office, &dav).unwrap();
+        run_sync(&home, &dav).unwrap();
+        run_sync(&office, &dav).unwrap();
+
+        for conn in [&office, &home] {
+            let p = repositories::get_progress(conn, "b1", "v1")
+                .unwrap()
+                .unwrap();
+            assert_eq!((p.page_index, p.updated_at), (12, 300));
+        }
+    }
+
+    #[test]
+    fn parses_multistatus_with_any_prefix() {
+        let xml = r#"<multistatus xmlns="DAV:">
+            <response><href>/remote.php/dav/files/a/readito/</href></response>
+            <response><href>https://host/remote.php/dav/files/a/readito/dev%20a.json</href>
+              <propstat><prop><getetag>&quot;abc&quot;</getetag></prop></propstat></response>
+            <response><href>/remote.php/dav/files/a/readito/dev-b.json</href></response>
+        </multistatus>"#;
+        assert_eq!(
+            parse_multistatus(xml),
+            vec![
+                ("dev a.json".to_string(), Some("\"abc\"".to_string())),
+                ("dev-b.json".to_string(), None),
+            ]
+        );
+    }
+}
@@ -14,8 +14,7 @@ import {
 } from "./services/dbService";
 import {
   scanFile,
-  loadLibrary,
-  hasBookByPath,
+  bookExists,
   persistBook,
 } from "./services/libraryService";
 import type { LibraryBook } from "./types/library";
@@ -119,8 +118,7 @@ function App() {
         if (!path) return;
         try {
           const book = scanFile(path);
-          const books = await loadLibrary();
-          if (!hasBookByPath(books, book.path)) {
+          if (!(await bookExists(book.path))) {
             await persistBook(book);
           }
           const vol = book.volumes[0];
@@ -2,7 +2,13 @@ import React, { useCallback, useEffect, useRef, useState } from 'react';
 import { useTranslation } from 'react-i18next';
 import { useReaderStore } from '../../store/readerStore';
 import { useReaderAdapterContext } from '../../reader/ReaderAdapterContext';
-import { getProgress, saveProgress } from '../../services/dbService';
+import {
+  endReadingSession,
+  getProgress,
+  readingSessionHeartbeat,
+  saveProgress,
+  startReadingSession,
+} from '../../services/dbService';
 import { showContextMenu, type ContextMenuEntry } from '../../utils/contextMenu';
 import { Sidebar } from './Sidebar.tsx';
 import { TopBar } from './TopBar.tsx';
@@ -12,6 +18,8 @@ import { SettingsPanel } from '../settings/SettingsPanel.tsx';
 import type { ProfilePreset } from '../../types/reader';
 
 const PROGRESS_SAVE_DEBOUNCE_MS = 1200;
+/** Intervalo do heartbeat da sessão de leitura (o backend fecha após 5 min sem heartbeat). */
+const SESSION_HEARTBEAT_MS = 60_000;
 
 interface ReaderLayoutProps {
   content: { paths: string[]; title: string; bookId: string; volumeId: string };
@@ -24,6 +32,8 @@ export const ReaderLayout: React.FC<ReaderLayoutProps> = ({ content, onBack, onB
   const { i18n } = useTranslation();
   const { loadPaths } = useReaderAdapterContext();
   const saveTimeoutRef = useRef<ReturnType<typeof setTimeout> | null>(null);
+  const sessionIdRef = useRef<string | null>(null);
+  const lastActivityRef = useRef(Date.now());
 
   const pathsKey = content.paths.join(',');
   useEffect(
@@ -58,7 +68,7 @@ export const ReaderLayout: React.FC<ReaderLayoutProps> = ({ content, onBack, onB
     if (saveTimeoutRef.current) clearTimeout(saveTimeoutRef.current);
     saveTimeoutRef.current = setTimeout(() => {
       saveTimeoutRef.current = null;
-      const page = useReaderStore.getState().currentPage;
+      const { currentPage: page, totalPages } = useReaderStore.getState();
       saveProgress({
         book_id: bookId,
         volume_id: volumeId,
@@ -66,13 +76,14 @@ export const ReaderLayout: React.FC<ReaderLayoutProps> = ({ content, onBack, onB
         page_index: page,
         scroll_offset: 0,
         updated_at: 0,
+        total_pages: totalPages > 0 ? totalPages : null,
       }).catch((e) => console.error('[ReaderLayout] saveProgress:', e));
     }, PROGRESS_SAVE_DEBOUNCE_MS);
     return () => {
       if (saveTimeoutRef.current) {
         clearTimeout(saveTimeoutRef.current);
         saveTimeoutRef.current = null;
-        const page = useReaderStore.getState().currentPage;
+        const { currentPage: page, totalPages } = useReaderStore.getState();
         saveProgress({
           book_id: bookId,
           volume_id: volumeId,
@@ -80,11 +91,58 @@ export const ReaderLayout: React.FC<ReaderLayoutProps> = ({ content, onBack, onB
           page_index: page,
           scroll_offset: 0,
           updated_at: 0,
+          total_pages: totalPages > 0 ? totalPages : null,
         }).catch((e) => console.error('[ReaderLayout] saveProgress (on unmount):', e));
       }
     };
   }, [content.bookId, content.volumeId, currentPage, status]);
 
+  useEffect(() => {
+    lastActivityRef.current = Date.now();
+  }, [currentPage]);
+
+  // Sessão de leitura: heartbeat só com atividade no último intervalo, para que
+  // o tempo parado com o livro aberto não conte.
+  useEffect(() => {
+    if (!content.bookId || !content.volumeId || status !== 'ready') return;
+    let cancelled = false;
+    const markActive = () => {
+      lastActivityRef.current = Date.now();
+    };
+    const activityEvents = ['keydown', 'pointerdown', 'wheel'] as const;
+    activityEvents.forEach((e) => window.addEventListener(e, markActive, { passive: true }));
+    startReadingSession(content.bookId, content.volumeId, useReaderStore.getState().currentPage)
+      .then((s) => {
+        if (cancelled) {
+          endReadingSession(s.id, s.end_page).catch(() => {});
+        } else {
+          sessionIdRef.current = s.id;
+        }
+      })
+      .catch((e) => console.error('[ReaderLayout] startReadingSession:', e));
+    const timer = setInterval(() => {
+      const id = sessionIdRef.current;
+      if (!id || Date.now() - lastActivityRef.current > SESSION_HEARTBEAT_MS) return;
+      readingSessionHeartbeat(id, useReaderStore.getState().currentPage)
+        .then((s) => {
+          sessionIdRef.current = s.id;
+        })
+        .catch((e) => console.error('[ReaderLayout] readingSessionHeartbeat:', e));
+    }, SESSION_HEARTBEAT_MS);
+    return () => {
+      cancelled = true;
+      activityEvents.forEach((e) => window.removeEventListener(e, markActive));
+      clearInterval(timer);
+      const id = sessionIdRef.current;
+      sessionIdRef.current = null;
+      if (id) {
+        endReadingSession(id, useReaderStore.getState().currentPage).catch((e) =>
+          console.error('[ReaderLayout] endReadingSession:', e)
+        );
+      }
+    };
+  }, [content.bookId, content.volumeId, status]);
+
   const [isFullscreen, setIsFullscreen] = React.useState(false);
   const [showGoToPageModal, setShowGoToPageModal] = React.useState(false);
   const [goToPageInput, setGoToPageInput] = useState('');
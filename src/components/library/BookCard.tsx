@@ -24,7 +24,7 @@ function formatDate(timestamp: number): string {
 export const BookCard: React.FC<BookCardProps> = ({ book, onClick, progressPercent, onContextMenu }) => {
   const { t } = useTranslation();
   const [coverError, setCoverError] = useState(false);
-  const volumeCount = book.volumes.length;
+  const volumeCount = book.volumeCount ?? book.volumes.length;
   const volumeLabel =
     volumeCount === 1
       ? t("library.volumes_count", { count: volumeCount })
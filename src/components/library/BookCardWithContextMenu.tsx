@@ -1,7 +1,8 @@
 import React, { useCallback } from "react";
 import { BookCard } from "./BookCard";
 import { useBookCardContextMenu } from "../../hooks/useBookCardContextMenu";
-import { saveProgress } from "../../services/dbService";
+import { markVolumes, saveProgress } from "../../services/dbService";
+import { ensureBookLoaded } from "../../services/libraryService";
 import type { LibraryBook } from "../../types/library";
 import type { Shelf } from "../../services/dbService";
 
@@ -31,7 +32,9 @@ export const BookCardWithContextMenu: React.FC<BookCardWithContextMenuProps> = (
   const hasProgress = (progressPercent ?? 0) > 0;
 
   const onMarkCompleted = useCallback(async () => {
-    for (const vol of book.volumes) {
+    const full = await ensureBookLoaded(book);
+    if (!full) return;
+    for (const vol of full.volumes) {
       await saveProgress({
         book_id: book.id,
         volume_id: vol.id,
@@ -41,11 +44,14 @@ export const BookCardWithContextMenu: React.FC<BookCardWithContextMenuProps> = (
         updated_at: Date.now(),
       });
     }
+    await markVolumes(full.volumes.map((v) => v.id), "completed");
     onProgressChanged?.();
   }, [book, onProgressChanged]);
 
   const onResetProgress = useCallback(async () => {
-    for (const vol of book.volumes) {
+    const full = await ensureBookLoaded(book);
+    if (!full) return;
+    for (const vol of full.volumes) {
       await saveProgress({
         book_id: book.id,
         volume_id: vol.id,
@@ -55,6 +61,7 @@ export const BookCardWithContextMenu: React.FC<BookCardWithContextMenuProps> = (
         updated_at: Date.now(),
       });
     }
+    await markVolumes(full.volumes.map((v) => v.id), "unread");
     onProgressChanged?.();
   }, [book, onProgressChanged]);
 
@@ -1,4 +1,4 @@
-import React, { useCallback, useState, useEffect, useRef } from "react";
+import React, { useCallback, useMemo, useState, useEffect, useRef } from "react";
 import { useTranslation } from "react-i18next";
 import { convertFileSrc } from "@tauri-apps/api/core";
 import { revealItemInDir } from "@tauri-apps/plugin-opener";
@@ -7,11 +7,12 @@ import { useShelves } from "../../hooks/useShelves";
 import { showContextMenu, type ContextMenuEntry } from "../../utils/contextMenu";
 import {
   applyMetadataCandidate,
+  markVolumes,
   saveProgress,
   searchMetadata,
   type RankedCandidateDto,
 } from "../../services/dbService";
-import { loadLibrary, mapBookWithVolumesToLibraryBook } from "../../services/libraryService";
+import { loadBook, mapBookWithVolumesToLibraryBook } from "../../services/libraryService";
 import type { LibraryBook, Volume, Chapter } from "../../types/library";
 
 interface BookDetailViewProps {
@@ -36,7 +37,9 @@ export const BookDetailView: React.FC<BookDetailViewProps> = ({
   onAutoSearchMetadataDone,
 }) => {
   const { t } = useTranslation();
-  const { shelves, bookShelfIds, loadBookShelfIds, addToShelf, removeFromShelf } = useShelves();
+  const { shelves: allShelves, bookShelfIds, loadBookShelfIds, addToShelf, removeFromShelf } = useShelves();
+  // Estantes inteligentes são preenchidas pelas regras
+  const shelves = useMemo(() => allShelves.filter((shelf) => shelf.kind !== "smart"), [allShelves]);
   const [selectedVolume, setSelectedVolume] = useState<Volume | null>(null);
   const [showShelfMenu, setShowShelfMenu] = useState(false);
   const [showRemoveConfirm, setShowRemoveConfirm] = useState(false);
@@ -103,6 +106,7 @@ export const BookDetailView: React.FC<BookDetailViewProps> = ({
         updated_at: Date.now(),
       });
     }
+    await markVolumes(book.volumes.map((v) => v.id), "completed");
   };
 
   const handleResetProgress = async () => {
@@ -116,6 +120,7 @@ export const BookDetailView: React.FC<BookDetailViewProps> = ({
         updated_at: Date.now(),
       });
     }
+    await markVolumes(book.volumes.map((v) => v.id), "unread");
   };
 
   const handleSearchMetadata = async () => {
@@ -125,8 +130,7 @@ export const BookDetailView: React.FC<BookDetailViewProps> = ({
     try {
       const result = await searchMetadata(book.id);
       if (result.applied) {
-        const books = await loadLibrary();
-        const updated = books.find((b) => b.id === book.id);
+        const updated = await loadBook(book.id);
         if (updated) onBookUpdated?.(updated);
         setMetadataMessage(
           result.confirmed
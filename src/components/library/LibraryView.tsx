@@ -7,8 +7,8 @@ import { showContextMenu, type ContextMenuEntry } from "../../utils/contextMenu"
 import { AddBookModal } from "./AddBookModal";
 import { useLibrary } from "../../hooks/useLibrary";
 import { useShelves } from "../../hooks/useShelves";
-import { getBookFormat } from "../../services/libraryService";
-import * as db from "../../services/dbService";
+import { ensureBookLoaded, type RecentProgressItem } from "../../services/libraryService";
+import type { BookQuery } from "../../types/db";
 import type { LibraryBook } from "../../types/library";
 
 type SortOrder = "addedAt" | "title" | "progress";
@@ -16,6 +16,14 @@ type FilterFormat = "all" | "images" | "pdf" | "epub" | "archive";
 type FilterStatus = "all" | "not_started" | "reading" | "completed";
 type FilterShelf = string | null; // shelf id or null for all
 
+const SORT_QUERY: Record<SortOrder, Pick<BookQuery, "sort_by" | "sort_dir">> = {
+  addedAt: { sort_by: "added_at", sort_dir: "desc" },
+  title: { sort_by: "title", sort_dir: "asc" },
+  progress: { sort_by: "progress", sort_dir: "desc" },
+};
+/** Espera após a digitação antes de consultar o banco. */
+const SEARCH_DEBOUNCE_MS = 250;
+
 interface LibraryViewProps {
   onSelectBook: (book: LibraryBook, options?: { autoSearchMetadata?: boolean }) => void;
   onEditBook?: (book: LibraryBook) => void;
@@ -33,9 +41,21 @@ export const LibraryView: React.FC<LibraryViewProps> = ({
 }) => {
   const { t } = useTranslation();
   const { shelves, addToShelf } = useShelves();
-  const [shelfFilterBookIds, setShelfFilterBookIds] = useState<string[]>([]);
+  const [modalOpen, setModalOpen] = useState(false);
+  const [addAndSearchMetadata, setAddAndSearchMetadata] = useState(true);
+  const [searchQuery, setSearchQuery] = useState("");
+  const [debouncedSearch, setDebouncedSearch] = useState("");
+  const [sortOrder, setSortOrder] = useState<SortOrder>("addedAt");
+  const [filterFormat, setFilterFormat] = useState<FilterFormat>("all");
+  const [filterStatus, setFilterStatus] = useState<FilterStatus>("all");
+  const [filterShelf, setFilterShelf] = useState<FilterShelf>(null);
+  const isFiltered =
+    debouncedSearch.trim() !== "" || filterFormat !== "all" || filterStatus !== "all" || filterShelf !== null;
   const {
     books,
+    total,
+    hasMore,
+    loadMore,
     recentProgress,
     progressMap,
     loaded,
@@ -45,20 +65,73 @@ export const LibraryView: React.FC<LibraryViewProps> = ({
     clearError,
     isImporting,
     refresh,
-  } = useLibrary();
+  } = useLibrary({
+    ...SORT_QUERY[sortOrder],
+    search: debouncedSearch.trim() || null,
+    format: filterFormat === "all" ? null : filterFormat,
+    progress: filterStatus === "all" ? null : filterStatus,
+    shelf_id: filterShelf,
+  });
+  const libraryEmpty = total === 0 && !isFiltered;
 
-  const [modalOpen, setModalOpen] = useState(false);
-  const [addAndSearchMetadata, setAddAndSearchMetadata] = useState(true);
-  const [searchQuery, setSearchQuery] = useState("");
-  const [sortOrder, setSortOrder] = useState<SortOrder>("addedAt");
-  const [filterFormat, setFilterFormat] = useState<FilterFormat>("all");
-  const [filterStatus, setFilterStatus] = useState<FilterStatus>("all");
-  const [filterShelf, setFilterShelf] = useState<FilterShelf>(null);
   const [showFilters, setShowFilters] = useState(false);
   const [showShelfDropdown, setShowShelfDropdown] = useState(false);
   const [failedCovers, setFailedCovers] = useState<Set<string>>(new Set());
   const searchInputRef = useRef<HTMLInputElement>(null);
   const shelfDropdownRef = useRef<HTMLDivElement>(null);
+  const loadMoreRef = useRef<HTMLDivElement>(null);
+
+  useEffect(() => {
+    const timer = setTimeout(() => setDebouncedSearch(searchQuery), SEARCH_DEBOUNCE_MS);
+    return () => clearTimeout(timer);
+  }, [searchQuery]);
+
+  // Próxima página quando o fim da grade aparece
+  useEffect(() => {
+    const sentinel = loadMoreRef.current;
+    if (!sentinel || !hasMore) return;
+    const observer = new IntersectionObserver(
+      (entries) => {
+        if (entries.some((e) => e.isIntersecting)) loadMore();
+      },
+      { rootMargin: "400px" }
+    );
+    observer.observe(sentinel);
+    return () => observer.disconnect();
+  }, [hasMore, loadMore]);
+
+  /** Resumos da listagem não têm volumes: carrega a árvore ao abrir. */
+  const openBook = useCallback(
+    async (book: LibraryBook) => {
+      const full = await ensureBookLoaded(book);
+      if (full) onSelectBook(full);
+    },
+    [onSelectBook]
+  );
+
+  const editBook = useCallback(
+    async (book: LibraryBook) => {
+      const full = await ensureBookLoaded(book);
+      if (full) onEditBook?.(full);
+    },
+    [onEditBook]
+  );
+
+  const readBook = useCallback(
+    async (book: LibraryBook, volumeId?: string) => {
+      const full = await ensureBookLoaded(book);
+      const vol = volumeId ? full?.volumes.find((v) => v.id === volumeId) : full?.volumes[0];
+      if (!full || !vol) return;
+      const title = volumeId ? `${full.title} — ${vol.name}` : full.title;
+      onRead?.(
+        vol.chapters.map((c) => c.path),
+        title,
+        full.id,
+        vol.id
+      );
+    },
+    [onRead]
+  );
 
   useEffect(() => {
     const handler = (e: MouseEvent) => {
@@ -81,14 +154,6 @@ export const LibraryView: React.FC<LibraryViewProps> = ({
     return () => window.removeEventListener("keydown", handler);
   }, []);
 
-  useEffect(() => {
-    if (!filterShelf) {
-      setShelfFilterBookIds([]);
-      return;
-    }
-    db.getBooksInShelf(filterShelf).then(setShelfFilterBookIds);
-  }, [filterShelf]);
-
   const handleSelectFolder = useCallback(async () => {
     const book = await addFromFolder();
     if (book) {
@@ -122,7 +187,7 @@ export const LibraryView: React.FC<LibraryViewProps> = ({
         { id: "add_folder", text: t("context.add_folder"), action: () => handleSelectFolder() },
         { id: "add_file", text: t("context.add_file"), action: () => handleSelectFile() },
       ];
-      if (books.length === 0 && onOpenSettings) {
+      if (libraryEmpty && onOpenSettings) {
         items.push({
           id: "create_shelf",
           text: t("library.create_shelf"),
@@ -134,7 +199,7 @@ export const LibraryView: React.FC<LibraryViewProps> = ({
       }
       showContextMenu(items, e.clientX, e.clientY).catch(() => {});
     },
-    [handleSelectFolder, handleSelectFile, onOpenSettings, books.length, t]
+    [handleSelectFolder, handleSelectFile, onOpenSettings, libraryEmpty, t]
   );
 
   const handleHeaderContextMenu = useCallback(
@@ -157,25 +222,19 @@ export const LibraryView: React.FC<LibraryViewProps> = ({
   );
 
   const handleContinueReadingContextMenu = useCallback(
-    (e: React.MouseEvent, item: (typeof recentProgress)[0]) => {
+    (e: React.MouseEvent, item: RecentProgressItem) => {
       e.preventDefault();
       e.stopPropagation();
       const items: ContextMenuEntry[] = [
         {
           id: "continue",
           text: t("context.continue_reading"),
-          action: () =>
-            onRead?.(
-              item.volume.chapters.map((c) => c.path),
-              `${item.book.title} — ${item.volume.name}`,
-              item.book.id,
-              item.volume.id
-            ),
+          action: () => readBook(item.book, item.volume.id),
         },
         {
           id: "open",
           text: t("context.open"),
-          action: () => onSelectBook(item.book),
+          action: () => openBook(item.book),
         },
       ];
       if (onRemoveBook) {
@@ -187,7 +246,7 @@ export const LibraryView: React.FC<LibraryViewProps> = ({
       }
       showContextMenu(items, e.clientX, e.clientY).catch(() => {});
     },
-    [onRead, onSelectBook, onRemoveBook, t]
+    [readBook, openBook, onRemoveBook, t]
   );
 
   return (
@@ -203,7 +262,7 @@ export const LibraryView: React.FC<LibraryViewProps> = ({
           <h1 className="font-heading text-2xl font-semibold text-stone-900 dark:text-stone-100 tracking-tight shrink-0">
             {t("library.title")}
           </h1>
-          {books.length > 0 && (
+          {!libraryEmpty && (
             <div className="flex-1 min-w-0 relative">
               <Search className="absolute left-3 top-1/2 -translate-y-1/2 w-4 h-4 text-stone-400 pointer-events-none" strokeWidth={1.75} />
               <input
@@ -257,14 +316,7 @@ export const LibraryView: React.FC<LibraryViewProps> = ({
                 <button
                   key={`${item.book.id}-${item.volume.id}`}
                   type="button"
-                  onClick={() =>
-                    onRead?.(
-                      item.volume.chapters.map((c) => c.path),
-                      `${item.book.title} — ${item.volume.name}`,
-                      item.book.id,
-                      item.volume.id
-                    )
-                  }
+                  onClick={() => readBook(item.book, item.volume.id)}
                   onContextMenu={(e) => handleContinueReadingContextMenu(e, item)}
                   className="flex-shrink-0 w-28 text-left rounded-xl overflow-hidden bg-white dark:bg-stone-900/80 border border-stone-200 dark:border-stone-800 hover:border-brand/40 dark:hover:border-brand/40 transition-all"
                 >
@@ -285,7 +337,7 @@ export const LibraryView: React.FC<LibraryViewProps> = ({
                       {item.book.title}
                     </p>
                     <p className="text-[10px] text-stone-500 dark:text-stone-400">
-                      {item.pageIndex} / {item.volume.chapters.length}
+                      {item.pageIndex} / {item.volume.chapterCount}
                     </p>
                   </div>
                 </button>
@@ -297,7 +349,7 @@ export const LibraryView: React.FC<LibraryViewProps> = ({
           <div className="flex flex-col items-center justify-center min-h-[60vh] text-stone-500 dark:text-stone-400" data-testid="library-loading">
             {t("library.loading")}
           </div>
-        ) : books.length === 0 ? (
+        ) : libraryEmpty ? (
           <div className="flex flex-col items-center justify-center min-h-[60vh] text-center px-4" data-testid="library-empty">
             <div className="w-28 h-28 rounded-3xl bg-stone-200/80 dark:bg-stone-800/80 flex items-center justify-center mb-6 shadow-inner">
               <LibraryIcon className="w-14 h-14 text-stone-400 dark:text-stone-500" strokeWidth={1.25} />
@@ -319,143 +371,108 @@ export const LibraryView: React.FC<LibraryViewProps> = ({
           </div>
         ) : (
           <>
-            {books.length > 0 && (
-              <div className="flex flex-wrap items-center gap-3 mb-6">
-                <div className="flex items-center gap-2 px-4 py-2 rounded-xl bg-stone-100 dark:bg-stone-800/80">
-                  <ArrowDownUp className="w-4 h-4 text-stone-500 dark:text-stone-400" strokeWidth={1.75} />
-                  <select
-                    value={sortOrder}
-                    onChange={(e) => setSortOrder(e.target.value as SortOrder)}
-                    className="bg-transparent border-0 text-sm font-medium text-stone-700 dark:text-stone-200 focus:outline-none cursor-pointer pr-6"
-                  >
-                    <option value="addedAt">{t("library.sort_added")}</option>
-                    <option value="title">{t("library.sort_title")}</option>
-                    <option value="progress">{t("library.sort_progress")}</option>
-                  </select>
-                </div>
-                <button
-                  type="button"
-                  onClick={() => setShowFilters((s) => !s)}
-                  className={`flex items-center gap-2 px-4 py-2 rounded-xl text-sm font-medium transition-colors ${showFilters ? "bg-brand/15 dark:bg-brand/25 text-brand ring-1 ring-brand/30" : "bg-stone-100 dark:bg-stone-800/80 text-stone-600 dark:text-stone-400 hover:text-stone-900 dark:hover:text-stone-100"}`}
+            <div className="flex flex-wrap items-center gap-3 mb-6">
+              <div className="flex items-center gap-2 px-4 py-2 rounded-xl bg-stone-100 dark:bg-stone-800/80">
+                <ArrowDownUp className="w-4 h-4 text-stone-500 dark:text-stone-400" strokeWidth={1.75} />
+                <select
+                  value={sortOrder}
+                  onChange={(e) => setSortOrder(e.target.value as SortOrder)}
+                  className="bg-transparent border-0 text-sm font-medium text-stone-700 dark:text-stone-200 focus:outline-none cursor-pointer pr-6"
                 >
-                  <Filter className="w-4 h-4" strokeWidth={1.75} />
-                  {t("library.filter_all")}
-                </button>
-                {showFilters && (
-                  <div className="flex flex-wrap items-center gap-2">
-                    <div className="flex items-center gap-2 px-4 py-2 rounded-xl bg-stone-100 dark:bg-stone-800/80">
-                      <select
-                        value={filterFormat}
-                        onChange={(e) => setFilterFormat(e.target.value as FilterFormat)}
-                        className="bg-transparent border-0 text-sm font-medium text-stone-700 dark:text-stone-200 focus:outline-none cursor-pointer pr-5"
-                      >
-                        <option value="all">{t("library.filter_all")}</option>
-                        <option value="images">{t("library.filter_format_images")}</option>
-                        <option value="pdf">{t("library.filter_format_pdf")}</option>
-                        <option value="epub">{t("library.filter_format_epub")}</option>
-                        <option value="archive">{t("library.filter_format_archive")}</option>
-                      </select>
-                    </div>
-                    <div className="flex items-center gap-2 px-4 py-2 rounded-xl bg-stone-100 dark:bg-stone-800/80">
-                      <select
-                        value={filterStatus}
-                        onChange={(e) => setFilterStatus(e.target.value as FilterStatus)}
-                        className="bg-transparent border-0 text-sm font-medium text-stone-700 dark:text-stone-200 focus:outline-none cursor-pointer pr-5"
-                      >
-                        <option value="all">{t("library.filter_status_all")}</option>
-                        <option value="not_started">{t("library.filter_status_not_started")}</option>
-                        <option value="reading">{t("library.filter_status_reading")}</option>
-                        <option value="completed">{t("library.filter_status_completed")}</option>
-                      </select>
-                    </div>
-                    <div className="relative" ref={shelfDropdownRef}>
-                      <button
-                        type="button"
-                        onClick={(e) => {
-                          e.stopPropagation();
-                          setShowShelfDropdown((s) => !s);
-                        }}
-                        className={`flex items-center gap-2 px-4 py-2 rounded-xl text-sm font-medium transition-colors min-w-[140px] justify-between ${filterShelf ? "bg-brand/15 dark:bg-brand/25 text-brand ring-1 ring-brand/30" : "bg-stone-100 dark:bg-stone-800/80 text-stone-600 dark:text-stone-400 hover:text-stone-900 dark:hover:text-stone-100"}`}
-                      >
-                        <Bookmark className="w-4 h-4 shrink-0" strokeWidth={1.75} />
-                        <span className="truncate">
-                          {filterShelf ? shelves.find((s) => s.id === filterShelf)?.name ?? t("library.shelves") : t("library.shelves")}
-                        </span>
-                        <ChevronDown className={`w-4 h-4 shrink-0 transition-transform ${showShelfDropdown ? "rotate-180" : ""}`} strokeWidth={2} />
-                      </button>
-                      {showShelfDropdown && (
-                        <div className="absolute left-0 top-full mt-1.5 py-1.5 min-w-[180px] rounded-xl bg-white dark:bg-stone-900 border border-stone-200 dark:border-stone-700 shadow-xl z-30">
-                          <button
-                            type="button"
-                            onClick={() => { setFilterShelf(null); setShowShelfDropdown(false); }}
-                            className={`w-full px-4 py-2.5 text-left text-sm font-medium transition-colors ${!filterShelf ? "bg-brand/10 dark:bg-brand/20 text-brand" : "text-stone-700 dark:text-stone-300 hover:bg-stone-100 dark:hover:bg-stone-800"}`}
-                          >
-                            {t("library.filter_all")}
-                          </button>
-                          {shelves.length === 0 ? (
-                            <p className="px-4 py-3 text-xs text-stone-500 dark:text-stone-400">{t("library.no_shelves", "Nenhuma estante")}</p>
-                          ) : (
-                            shelves.map((s) => (
-                              <button
-                                key={s.id}
-                                type="button"
-                                onClick={() => { setFilterShelf(s.id); setShowShelfDropdown(false); }}
-                                className={`w-full px-4 py-2.5 text-left text-sm font-medium transition-colors flex items-center gap-2 ${filterShelf === s.id ? "bg-brand/10 dark:bg-brand/20 text-brand" : "text-stone-700 dark:text-stone-300 hover:bg-stone-100 dark:hover:bg-stone-800"}`}
-                              >
-                                <Bookmark className="w-4 h-4 shrink-0" strokeWidth={1.75} />
-                                {s.name}
-                              </button>
-                            ))
-                          )}
-                        </div>
-                      )}
-                    </div>
-                  </div>
-                )}
+                  <option value="addedAt">{t("library.sort_added")}</option>
+                  <option value="title">{t("library.sort_title")}</option>
+                  <option value="progress">{t("library.sort_progress")}</option>
+                </select>
               </div>
-            )}
+              <button
+                type="button"
+                onClick={() => setShowFilters((s) => !s)}
+                className={`flex items-center gap-2 px-4 py-2 rounded-xl text-sm font-medium transition-colors ${showFilters ? "bg-brand/15 dark:bg-brand/25 text-brand ring-1 ring-brand/30" : "bg-stone-100 dark:bg-stone-800/80 text-stone-600 dark:text-stone-400 hover:text-stone-900 dark:hover:text-stone-100"}`}
+              >
+                <Filter className="w-4 h-4" strokeWidth={1.75} />
+                {t("library.filter_all")}
+              </button>
+              {showFilters && (
+                <div className="flex flex-wrap items-center gap-2">
+                  <div className="flex items-center gap-2 px-4 py-2 rounded-xl bg-stone-100 dark:bg-stone-800/80">
+                    <select
+                      value={filterFormat}
+                      onChange={(e) => setFilterFormat(e.target.value as FilterFormat)}
+                      className="bg-transparent border-0 text-sm font-medium text-stone-700 dark:text-stone-200 focus:outline-none cursor-pointer pr-5"
+                    >
+                      <option value="all">{t("library.filter_all")}</option>
+                      <option value="images">{t("library.filter_format_images")}</option>
+                      <option value="pdf">{t("library.filter_format_pdf")}</option>
+                      <option value="epub">{t("library.filter_format_epub")}</option>
+                      <option value="archive">{t("library.filter_format_archive")}</option>
+                    </select>
+                  </div>
+                  <div className="flex items-center gap-2 px-4 py-2 rounded-xl bg-stone-100 dark:bg-stone-800/80">
+                    <select
+                      value={filterStatus}
+                      onChange={(e) => setFilterStatus(e.target.value as FilterStatus)}
+                      className="bg-transparent border-0 text-sm font-medium text-stone-700 dark:text-stone-200 focus:outline-none cursor-pointer pr-5"
+                    >
+                      <option value="all">{t("library.filter_status_all")}</option>
+                      <option value="not_started">{t("library.filter_status_not_started")}</option>
+                      <option value="reading">{t("library.filter_status_reading")}</option>
+                      <option value="completed">{t("library.filter_status_completed")}</option>
+                    </select>
+                  </div>
+                  <div className="relative" ref={shelfDropdownRef}>
+                    <button
+                      type="button"
+                      onClick={(e) => {
+                        e.stopPropagation();
+                        setShowShelfDropdown((s) => !s);
+                      }}
+                      className={`flex items-center gap-2 px-4 py-2 rounded-xl text-sm font-medium transition-colors min-w-[140px] justify-between ${filterShelf ? "bg-brand/15 dark:bg-brand/25 text-brand ring-1 ring-brand/30" : "bg-stone-100 dark:bg-stone-800/80 text-stone-600 dark:text-stone-400 hover:text-stone-900 dark:hover:text-stone-100"}`}
+                    >
+                      <Bookmark className="w-4 h-4 shrink-0" strokeWidth={1.75} />
+                      <span className="truncate">
+                        {filterShelf ? shelves.find((s) => s.id === filterShelf)?.name ?? t("library.shelves") : t("library.shelves")}
+                      </span>
+                      <ChevronDown className={`w-4 h-4 shrink-0 transition-transform ${showShelfDropdown ? "rotate-180" : ""}`} strokeWidth={2} />
+                    </button>
+                    {showShelfDropdown && (
+                      <div className="absolute left-0 top-full mt-1.5 py-1.5 min-w-[180px] rounded-xl bg-white dark:bg-stone-900 border border-stone-200 dark:border-stone-700 shadow-xl z-30">
+                        <button
+                          type="button"
+                          onClick={() => { setFilterShelf(null); setShowShelfDropdown(false); }}
+                          className={`w-full px-4 py-2.5 text-left text-sm font-medium transition-colors ${!filterShelf ? "bg-brand/10 dark:bg-brand/20 text-brand" : "text-stone-700 dark:text-stone-300 hover:bg-stone-100 dark:hover:bg-stone-800"}`}
+                        >
+                          {t("library.filter_all")}
+                        </button>
+                        {shelves.length === 0 ? (
+                          <p className="px-4 py-3 text-xs text-stone-500 dark:text-stone-400">{t("library.no_shelves", "Nenhuma estante")}</p>
+                        ) : (
+                          shelves.map((s) => (
+                            <button
+                              key={s.id}
+                              type="button"
+                              onClick={() => { setFilterShelf(s.id); setShowShelfDropdown(false); }}
+                              className={`w-full px-4 py-2.5 text-left text-sm font-medium transition-colors flex items-center gap-2 ${filterShelf === s.id ? "bg-brand/10 dark:bg-brand/20 text-brand" : "text-stone-700 dark:text-stone-300 hover:bg-stone-100 dark:hover:bg-stone-800"}`}
+                            >
+                              <Bookmark className="w-4 h-4 shrink-0" strokeWidth={1.75} />
+                              {s.name}
+                            </button>
+                          ))
+                        )}
+                      </div>
+                    )}
+                  </div>
+                </div>
+              )}
+            </div>
             <div className="grid grid-cols-2 sm:grid-cols-3 md:grid-cols-4 lg:grid-cols-5 xl:grid-cols-6 gap-6" data-testid="library-grid">
-            {books
-              .filter((book) => {
-                if (searchQuery.trim() && !book.title.toLowerCase().includes(searchQuery.toLowerCase())) return false;
-                if (filterFormat !== "all" && getBookFormat(book) !== filterFormat) return false;
-                const prog = progressMap.get(book.id);
-                const status = prog?.status ?? "not_started";
-                if (filterStatus !== "all" && status !== filterStatus) return false;
-                if (filterShelf && !shelfFilterBookIds.includes(book.id)) return false;
-                return true;
-              })
-              .sort((a, b) => {
-                if (sortOrder === "title") return a.title.localeCompare(b.title);
-                if (sortOrder === "progress") {
-                  const pa = progressMap.get(a.id)?.progressPercent ?? 0;
-                  const pb = progressMap.get(b.id)?.progressPercent ?? 0;
-                  return pb - pa;
-                }
-                return b.addedAt - a.addedAt;
-              })
-              .map((book) => (
+            {books.map((book) => (
               <BookCardWithContextMenu
                 key={`${book.id}-${book.coverPath ?? ""}`}
                 book={book}
                 shelves={shelves}
-                onOpen={() => onSelectBook(book)}
-                onRead={
-                  onRead
-                    ? () => {
-                        const vol = book.volumes[0];
-                        if (vol) {
-                          onRead(
-                            vol.chapters.map((c) => c.path),
-                            book.title,
-                            book.id,
-                            vol.id
-                          );
-                        }
-                      }
-                    : undefined
-                }
-                onEdit={onEditBook ? () => onEditBook(book) : undefined}
+                onOpen={() => openBook(book)}
+                onRead={onRead ? () => readBook(book) : undefined}
+                onEdit={onEditBook ? () => editBook(book) : undefined}
                 onRemove={
                   onRemoveBook
                     ? () => onRemoveBook(book.id)
@@ -463,10 +480,11 @@ export const LibraryView: React.FC<LibraryViewProps> = ({
                 }
                 onProgressChanged={refresh}
                 addToShelf={addToShelf}
-                progressPercent={progressMap.get(book.id)?.progressPercent}
+                progressPercent={progressMap.get(book.id)}
               />
             ))}
           </div>
+          {hasMore && <div ref={loadMoreRef} className="h-px" aria-hidden />}
           </>
         )}
       </main>
@@ -6,8 +6,7 @@ import {
   listCustomThemes,
   deleteCustomTheme,
 } from "../../services/dbService";
-import { loadLibrary, computeBooksWithProgress } from "../../services/libraryService";
-import { getAllProgress } from "../../services/dbService";
+import { getAllProgress, queryBooks } from "../../services/dbService";
 import { getVersion } from "@tauri-apps/api/app";
 import { ArrowLeft, BarChart3, Bookmark, Monitor, Moon, Sun, Globe, Keyboard, Info, ExternalLink, Palette, Plus, Pencil, Trash2 } from "lucide-react";
 import { useShelves } from "../../hooks/useShelves";
@@ -60,25 +59,21 @@ export const GlobalSettingsView: React.FC<GlobalSettingsViewProps> = ({ onBack }
 
   useEffect(() => {
     (async () => {
-      const [books, allProgress] = await Promise.all([
-        loadLibrary(),
+      // Só os totais: limit 1 evita trazer os resumos
+      const [all, reading, completed, allProgress] = await Promise.all([
+        queryBooks({ limit: 1 }),
+        queryBooks({ progress: "reading", limit: 1 }),
+        queryBooks({ progress: "completed", limit: 1 }),
         getAllProgress(),
       ]);
-      const progressMap = computeBooksWithProgress(books, allProgress);
-      let reading = 0;
-      let completed = 0;
       let pagesRead = 0;
-      for (const [, info] of progressMap) {
-        if (info.status === "reading") reading++;
-        if (info.status === "completed") completed++;
-      }
       for (const p of allProgress) {
         pagesRead += p.page_index;
       }
       setStats({
-        totalBooks: books.length,
-        reading,
-        completed,
+        totalBooks: all.total,
+        reading: reading.total,
+        completed: completed.total,
         pagesRead,
       });
     })();
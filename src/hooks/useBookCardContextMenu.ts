@@ -61,11 +61,13 @@ export function useBookCardContextMenu(
         },
       });
 
-      if (callbacks.addToShelf && shelves.length > 0) {
+      // Estantes inteligentes são preenchidas pelas regras
+      const manualShelves = shelves.filter((shelf) => shelf.kind !== "smart");
+      if (callbacks.addToShelf && manualShelves.length > 0) {
         items.push({
           id: "add_to_shelf",
           text: t("context.add_to_shelf"),
-          items: shelves.map((shelf) => ({
+          items: manualShelves.map((shelf) => ({
             id: `shelf-${shelf.id}`,
             text: shelf.name,
             action: () => callbacks.addToShelf!(book.id, shelf.id),
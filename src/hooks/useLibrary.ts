@@ -2,72 +2,97 @@
  * Hook useLibrary — Estado da biblioteca, adicionar/remover livros (persistência via SQLite).
  */
 
-import { useState, useCallback, useEffect } from "react";
+import { useState, useCallback, useEffect, useRef } from "react";
 import { open } from "@tauri-apps/plugin-dialog";
 import {
   scanFolder,
   scanFile,
-  loadLibrary,
+  queryLibrary,
   loadRecentProgress,
   persistBook,
   removeBookFromBackend,
-  hasBookByPath,
-  computeBooksWithProgress,
+  bookExists,
 } from "../services/libraryService";
-import * as db from "../services/dbService";
+import type { BookQuery } from "../types/db";
 import type { LibraryBook } from "../types/library";
 
 const RECENT_LIMIT = 5;
+/** Livros por página da listagem (scroll infinito). */
+const PAGE_SIZE = 60;
 
-export function useLibrary() {
+/** `query` sem offset/limit: filtros e ordenação; a paginação é do hook. */
+export function useLibrary(query: BookQuery = {}) {
   const [books, setBooks] = useState<LibraryBook[]>([]);
+  const [progressMap, setProgressMap] = useState<Map<string, number>>(new Map());
+  const [total, setTotal] = useState(0);
   const [recentProgress, setRecentProgress] = useState<
     Awaited<ReturnType<typeof loadRecentProgress>>
   >([]);
-  const [allProgress, setAllProgress] = useState<{ book_id: string; volume_id: string; page_index: number }[]>([]);
   const [loaded, setLoaded] = useState(false);
+  const [loadingMore, setLoadingMore] = useState(false);
   const [error, setError] = useState<string | null>(null);
   const [isImporting, setIsImporting] = useState(false);
 
+  // Objeto novo a cada render: compara pelo conteúdo
+  const queryKey = JSON.stringify(query);
+  // Descarta respostas de consultas anteriores (filtro mudou no meio)
+  const generation = useRef(0);
+
   const refresh = useCallback(async () => {
-    const [list, recent, progress] = await Promise.all([
-      loadLibrary(),
+    const current = ++generation.current;
+    const [page, recent] = await Promise.all([
+      queryLibrary({ ...JSON.parse(queryKey), offset: 0, limit: PAGE_SIZE }),
       loadRecentProgress(RECENT_LIMIT),
-      db.getAllProgress(),
     ]);
-    setBooks(list);
+    if (current !== generation.current) return;
+    setBooks(page.books);
+    setProgressMap(page.progress);
+    setTotal(page.total);
     setRecentProgress(recent);
-    setAllProgress(progress);
     setLoaded(true);
-  }, []);
+  }, [queryKey]);
 
   useEffect(() => {
     refresh();
   }, [refresh]);
 
+  const hasMore = books.length < total;
+
+  const loadMore = useCallback(async () => {
+    if (!hasMore || loadingMore) return;
+    const current = generation.current;
+    setLoadingMore(true);
+    try {
+      const page = await queryLibrary({
+        ...JSON.parse(queryKey),
+        offset: books.length,
+        limit: PAGE_SIZE,
+      });
+      if (current !== generation.current) return;
+      setBooks((prev) => [...prev, ...page.books]);
+      setProgressMap((prev) => new Map([...prev, ...page.progress]));
+      setTotal(page.total);
+    } finally {
+      setLoadingMore(false);
+    }
+  }, [queryKey, books.length, hasMore, loadingMore]);
+
   const addBook = useCallback(async (book: LibraryBook) => {
-    if (hasBookByPath(books, book.path)) return;
+    if (await bookExists(book.path)) return;
     try {
       await persistBook(book);
-      const next = [...books, book].sort((a, b) => b.addedAt - a.addedAt);
-      setBooks(next);
-      Promise.all([
-        loadRecentProgress(RECENT_LIMIT),
-        db.getAllProgress(),
-      ]).then(([recent, progress]) => {
-        setRecentProgress(recent);
-        setAllProgress(progress);
-      });
+      await refresh();
     } catch (e) {
       console.error("[useLibrary] persistBook:", e);
       setError("import_error");
     }
-  }, [books]);
+  }, [refresh]);
 
   const removeBook = useCallback(async (id: string) => {
     try {
       await removeBookFromBackend(id);
       setBooks((prev) => prev.filter((b) => b.id !== id));
+      setTotal((prev) => Math.max(0, prev - 1));
     } catch (e) {
       console.error("[useLibrary] removeBook:", e);
     }
@@ -88,7 +113,7 @@ export function useLibrary() {
 
       const book = await scanFolder(dirPath);
 
-      if (hasBookByPath(books, book.path)) {
+      if (await bookExists(book.path)) {
         setError("duplicate");
         return null;
       }
@@ -108,7 +133,7 @@ export function useLibrary() {
     } finally {
       setIsImporting(false);
     }
-  }, [books, addBook]);
+  }, [addBook]);
 
   const addFromFile = useCallback(async (): Promise<LibraryBook | null> => {
     setError(null);
@@ -131,7 +156,7 @@ export function useLibrary() {
 
       const book = scanFile(filePath);
 
-      if (hasBookByPath(books, book.path)) {
+      if (await bookExists(book.path)) {
         setError("duplicate");
         return null;
       }
@@ -145,14 +170,15 @@ export function useLibrary() {
     } finally {
       setIsImporting(false);
     }
-  }, [books, addBook]);
+  }, [addBook]);
 
   const clearError = useCallback(() => setError(null), []);
 
-  const progressMap = computeBooksWithProgress(books, allProgress);
-
   return {
     books,
+    total,
+    hasMore,
+    loadMore,
     recentProgress,
     progressMap,
     loaded,
@@ -4,6 +4,7 @@
 
 import { useState, useCallback, useEffect } from "react";
 import * as db from "../services/dbService";
+import type { SmartShelfRules } from "../types/db";
 
 const DEFAULT_SHELVES = [
   { id: "reading", name: "Lendo" },
@@ -51,6 +52,22 @@ export function useShelves() {
     []
   );
 
+  const insertInShelf = useCallback(
+    async (bookId: string, shelfId: string, index: number) => {
+      await db.insertBookInShelf(bookId, shelfId, index);
+      const ids = await db.getBookShelfIds(bookId);
+      setBookShelfIds((prev) => new Map(prev).set(bookId, ids));
+    },
+    []
+  );
+
+  const moveInShelf = useCallback(
+    async (bookId: string, shelfId: string, index: number) => {
+      await db.moveBookInShelf(bookId, shelfId, index);
+    },
+    []
+  );
+
   const removeFromShelf = useCallback(
     async (bookId: string, shelfId: string) => {
       await db.removeBookFromShelf(bookId, shelfId);
@@ -65,13 +82,65 @@ export function useShelves() {
     await refresh();
   }, [refresh]);
 
+  const createSmartShelf = useCallback(
+    async (id: string, name: string, rules: SmartShelfRules) => {
+      await db.createSmartShelf(id, name, rules);
+      await refresh();
+    },
+    [refresh]
+  );
+
+  const updateSmartShelfRules = useCallback(
+    async (id: string, rules: SmartShelfRules) => {
+      await db.updateSmartShelfRules(id, rules);
+      await refresh();
+    },
+    [refresh]
+  );
+
+  const renameShelf = useCallback(async (id: string, name: string) => {
+    await db.renameShelf(id, name);
+    await refresh();
+  }, [refresh]);
+
+  const setShelfStyle = useCallback(
+    async (id: string, icon: string | null, color: string | null) => {
+      await db.setShelfStyle(id, icon, color);
+      await refresh();
+    },
+    [refresh]
+  );
+
+  const deleteShelf = useCallback(async (id: string) => {
+    await db.deleteShelf(id);
+    setBookShelfIds((prev) => {
+      const next = new Map<string, string[]>();
+      prev.forEach((ids, bookId) => next.set(bookId, ids.filter((s) => s !== id)));
+      return next;
+    });
+    await refresh();
+  }, [refresh]);
+
+  const reorderShelves = useCallback(async (ids: string[]) => {
+    await db.reorderShelves(ids);
+    await refresh();
+  }, [refresh]);
+
   return {
     shelves,
     bookShelfIds,
     loadBookShelfIds,
     addToShelf,
+    insertInShelf,
+    moveInShelf,
     removeFromShelf,
     createShelf,
+    createSmartShelf,
+    updateSmartShelfRules,
+    renameShelf,
+    setShelfStyle,
+    deleteShelf,
+    reorderShelves,
     refresh,
   };
 }
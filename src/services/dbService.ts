@@ -6,13 +6,56 @@
 import { invoke } from "@tauri-apps/api/core";
 import type {
   AddBookPayload,
+  Annotation,
+  AnnotationEntry,
+  AnnotationQuery,
+  BackupImportReport,
+  BookPage,
+  BookReadState,
+  BookReadingTime,
+  BookReview,
+  BookQuery,
   BookSettings,
+  BookTag,
   BookWithVolumes,
+  Bookmark,
+  ChangeFeed,
+  ChapterProgress,
+  ContinueReadingItem,
+  CreateAnnotationPayload,
+  CreateBookmarkPayload,
+  DailyReading,
+  ExportAnnotationsPayload,
   CustomTheme,
+  DbChapter,
+  DbVolume,
+  EnqueuePayload,
   GlobalSettings,
+  HistoryPage,
+  HistoryQuery,
+  KoreaderConfig,
+  KoreaderSyncReport,
+  MetadataChange,
+  MetadataConfig,
+  QueueEntry,
+  QueueItem,
+  ReadStatus,
   ReadingProgress,
+  ReadingSession,
+  ReadingStats,
   SaveGlobalSettingsPayload,
+  SaveKoreaderConfigPayload,
+  SaveSyncConfigPayload,
+  SeriesCompletion,
+  ShelfKind,
+  SmartShelfRules,
+  SyncConfig,
+  SyncReport,
+  Tag,
+  Tombstone,
+  UpdateAnnotationPayload,
   UpdateBookPayload,
+  VolumeEta,
 } from "../types/db";
 
 export async function addBook(payload: AddBookPayload): Promise<void> {
@@ -23,6 +66,16 @@ export async function getBooks(): Promise<BookWithVolumes[]> {
   return invoke<BookWithVolumes[]>("get_books");
 }
 
+/** Listagem paginada (resumos, sem volumes/capítulos). */
+export async function queryBooks(query: BookQuery = {}): Promise<BookPage> {
+  return invoke<BookPage>("query_books", { query });
+}
+
+/** Livro com volumes e capítulos — carregar ao abrir. */
+export async function getBook(bookId: string): Promise<BookWithVolumes> {
+  return invoke<BookWithVolumes>("get_book", { bookId });
+}
+
 export async function deleteBook(bookId: string): Promise<void> {
   await invoke("delete_book", { bookId });
 }
@@ -46,6 +99,72 @@ export async function getRecentProgress(limit: number): Promise<ReadingProgress[
   return invoke<ReadingProgress[]>("get_recent_progress", { limit });
 }
 
+/** "Continuar lendo": volumes em andamento com o livro, numa chamada só. */
+export async function getContinueReading(limit: number): Promise<ContinueReadingItem[]> {
+  return invoke<ContinueReadingItem[]>("get_continue_reading", { limit });
+}
+
+export async function getChapterProgress(bookId: string): Promise<ChapterProgress[]> {
+  return invoke<ChapterProgress[]>("get_chapter_progress", { bookId });
+}
+
+/** Marca capítulos como lidos (ou não lidos); retorna quantos mudaram. */
+export async function setChaptersRead(chapterIds: string[], read: boolean): Promise<number> {
+  return invoke<number>("set_chapters_read", { chapterIds, read });
+}
+
+/** Marca como lidos todos os capítulos da série antes do indicado. */
+export async function markPreviousChaptersRead(chapterId: string): Promise<number> {
+  return invoke<number>("mark_previous_chapters_read", { chapterId });
+}
+
+export async function getNextUnreadChapter(bookId: string): Promise<DbChapter | null> {
+  return invoke<DbChapter | null>("get_next_unread_chapter", { bookId });
+}
+
+export async function getSeriesCompletion(bookId: string): Promise<SeriesCompletion> {
+  return invoke<SeriesCompletion>("get_series_completion", { bookId });
+}
+
+export async function listQueue(): Promise<QueueItem[]> {
+  return invoke<QueueItem[]>("list_queue");
+}
+
+export async function enqueue(payload: EnqueuePayload): Promise<QueueEntry> {
+  return invoke<QueueEntry>("enqueue", { payload });
+}
+
+/** Nova ordem da fila; entradas não listadas vão para o fim. */
+export async function reorderQueue(ids: string[]): Promise<void> {
+  return invoke("reorder_queue", { ids });
+}
+
+export async function dequeue(id: string): Promise<void> {
+  return invoke("dequeue", { id });
+}
+
+/** Remove e retorna o primeiro da fila. */
+export async function popQueue(): Promise<QueueEntry | null> {
+  return invoke<QueueEntry | null>("pop_queue");
+}
+
+export async function suggestNextVolume(bookId: string): Promise<DbVolume | null> {
+  return invoke<DbVolume | null>("suggest_next_volume", { bookId });
+}
+
+/** Linha do tempo de leitura, da entrada mais recente para a mais antiga. */
+export async function getHistory(query: HistoryQuery = {}): Promise<HistoryPage> {
+  return invoke<HistoryPage>("get_history", { query });
+}
+
+export async function deleteHistoryEntry(id: string): Promise<void> {
+  return invoke("delete_history_entry", { id });
+}
+
+export async function deleteHistoryEntries(ids: string[]): Promise<number> {
+  return invoke<number>("delete_history_entries", { ids });
+}
+
 export async function getAllProgress(): Promise<ReadingProgress[]> {
   return invoke<ReadingProgress[]>("get_all_progress");
 }
@@ -53,6 +172,16 @@ export async function getAllProgress(): Promise<ReadingProgress[]> {
 export interface Shelf {
   id: string;
   name: string;
+  /** Ordem na barra lateral (0 = primeira). */
+  position: number;
+  icon: string | null;
+  /** Hex (#rgb ou #rrggbb). */
+  color: string | null;
+  kind: ShelfKind;
+  /** Só em estantes inteligentes. */
+  rules: SmartShelfRules | null;
+  /** Livros na estante (inteligentes: avaliado pelas regras). */
+  book_count: number;
 }
 
 export async function listShelves(): Promise<Shelf[]> {
@@ -63,6 +192,44 @@ export async function createShelf(id: string, name: string): Promise<void> {
   await invoke("create_shelf", { id, name });
 }
 
+export async function createSmartShelf(
+  id: string,
+  name: string,
+  rules: SmartShelfRules
+): Promise<void> {
+  await invoke("create_smart_shelf", { id, name, rules });
+}
+
+export async function updateSmartShelfRules(
+  id: string,
+  rules: SmartShelfRules
+): Promise<void> {
+  await invoke("update_smart_shelf_rules", { id, rules });
+}
+
+export async function renameShelf(id: string, name: string): Promise<void> {
+  await invoke("rename_shelf", { id, name });
+}
+
+/** Ícone e cor opcionais; null remove. */
+export async function setShelfStyle(
+  id: string,
+  icon: string | null,
+  color: string | null
+): Promise<void> {
+  await invoke("set_shelf_style", { id, icon, color });
+}
+
+/** Remove a estante; os livros continuam na biblioteca. */
+export async function deleteShelf(id: string): Promise<void> {
+  await invoke("delete_shelf", { id });
+}
+
+/** Nova ordem das estantes; as não listadas vão para o fim. */
+export async function reorderShelves(ids: string[]): Promise<void> {
+  await invoke("reorder_shelves", { ids });
+}
+
 export async function addBookToShelf(
   bookId: string,
   shelfId: string
@@ -70,6 +237,23 @@ export async function addBookToShelf(
   await invoke("add_book_to_shelf", { bookId, shelfId });
 }
 
+/** Adiciona na posição `index` (0 = início); se já estiver na estante, move. */
+export async function insertBookInShelf(
+  bookId: string,
+  shelfId: string,
+  index: number
+): Promise<void> {
+  await invoke("insert_book_in_shelf", { bookId, shelfId, index });
+}
+
+export async function moveBookInShelf(
+  bookId: string,
+  shelfId: string,
+  index: number
+): Promise<void> {
+  await invoke("move_book_in_shelf", { bookId, shelfId, index });
+}
+
 export async function removeBookFromShelf(
   bookId: string,
   shelfId: string
@@ -81,10 +265,58 @@ export async function getBookShelfIds(bookId: string): Promise<string[]> {
   return invoke<string[]>("get_book_shelf_ids", { bookId });
 }
 
+/** Ids na ordem da estante (manual; inteligentes por título). */
 export async function getBooksInShelf(shelfId: string): Promise<string[]> {
   return invoke<string[]>("get_books_in_shelf", { shelfId });
 }
 
+export async function getBookReview(bookId: string): Promise<BookReview | null> {
+  return invoke<BookReview | null>("get_book_review", { bookId });
+}
+
+/** Nota em meias estrelas (0.5–5); nota e resenha vazias removem a avaliação. */
+export async function saveBookReview(
+  bookId: string,
+  rating: number | null,
+  review: string | null
+): Promise<BookReview | null> {
+  return invoke<BookReview | null>("save_book_review", { bookId, rating, review });
+}
+
+export async function listTags(): Promise<Tag[]> {
+  return invoke<Tag[]>("list_tags");
+}
+
+/** Cria a tag (ou retorna a existente com o mesmo nome); retorna o id. */
+export async function createTag(name: string): Promise<string> {
+  return invoke<string>("create_tag", { name });
+}
+
+export async function renameTag(id: string, name: string): Promise<void> {
+  await invoke("rename_tag", { id, name });
+}
+
+/** Junta as tags de origem em `targetId`; as de origem são apagadas. */
+export async function mergeTags(sourceIds: string[], targetId: string): Promise<void> {
+  await invoke("merge_tags", { sourceIds, targetId });
+}
+
+export async function deleteTag(id: string): Promise<void> {
+  await invoke("delete_tag", { id });
+}
+
+export async function getBookTags(bookId: string): Promise<BookTag[]> {
+  return invoke<BookTag[]>("get_book_tags", { bookId });
+}
+
+export async function addTagToBook(bookId: string, tagId: string): Promise<void> {
+  await invoke("add_tag_to_book", { bookId, tagId });
+}
+
+export async function removeTagFromBook(bookId: string, tagId: string): Promise<void> {
+  await invoke("remove_tag_from_book", { bookId, tagId });
+}
+
 export async function saveBookSettings(settings: BookSettings): Promise<void> {
   await invoke("save_book_settings", { settings });
 }
@@ -135,6 +367,193 @@ export async function deleteCustomTheme(id: string): Promise<void> {
   await invoke("delete_custom_theme", { id });
 }
 
+export async function getDeviceId(): Promise<string> {
+  return invoke<string>("get_device_id");
+}
+
+/** Feed de mudanças a partir de um cursor (seq). */
+export async function getChangesSince(
+  cursor: number,
+  limit?: number
+): Promise<ChangeFeed> {
+  return invoke<ChangeFeed>("get_changes_since", { cursor, limit });
+}
+
+export async function getTombstones(since?: number): Promise<Tombstone[]> {
+  return invoke<Tombstone[]>("get_tombstones", { since });
+}
+
+export async function getSyncConfig(): Promise<SyncConfig> {
+  return invoke<SyncConfig>("get_sync_config");
+}
+
+export async function saveSyncConfig(payload: SaveSyncConfigPayload): Promise<void> {
+  await invoke("save_sync_config", { payload });
+}
+
+/** Executa uma sincronização completa (push + pull + merge). */
+export async function syncNow(): Promise<SyncReport> {
+  return invoke<SyncReport>("sync_now");
+}
+
+export async function getKoreaderConfig(): Promise<KoreaderConfig> {
+  return invoke<KoreaderConfig>("get_koreader_config");
+}
+
+export async function saveKoreaderConfig(
+  payload: SaveKoreaderConfigPayload
+): Promise<KoreaderConfig> {
+  return invoke<KoreaderConfig>("save_koreader_config", { payload });
+}
+
+/** Cria a conta configurada no servidor kosync. */
+export async function koreaderRegister(): Promise<void> {
+  await invoke("koreader_register");
+}
+
+/** Verifica servidor e credenciais do KOReader. */
+export async function koreaderAuthorize(): Promise<void> {
+  await invoke("koreader_authorize");
+}
+
+export async function koreaderSyncNow(): Promise<KoreaderSyncReport> {
+  return invoke<KoreaderSyncReport>("koreader_sync_now");
+}
+
+export async function createBookmark(payload: CreateBookmarkPayload): Promise<Bookmark> {
+  return invoke<Bookmark>("create_bookmark", { payload });
+}
+
+/** Marcadores do livro; com `volumeId`, só os do volume. */
+export async function listBookmarks(bookId: string, volumeId?: string): Promise<Bookmark[]> {
+  return invoke<Bookmark[]>("list_bookmarks", { bookId, volumeId: volumeId ?? null });
+}
+
+export async function renameBookmark(id: string, label: string): Promise<void> {
+  await invoke("rename_bookmark", { id, label });
+}
+
+export async function deleteBookmark(id: string): Promise<void> {
+  await invoke("delete_bookmark", { id });
+}
+
+/** Move o progresso do volume para o marcador e o devolve. */
+export async function jumpToBookmark(id: string): Promise<Bookmark> {
+  return invoke<Bookmark>("jump_to_bookmark", { id });
+}
+
+export async function createAnnotation(payload: CreateAnnotationPayload): Promise<Annotation> {
+  return invoke<Annotation>("create_annotation", { payload });
+}
+
+export async function getAnnotation(id: string): Promise<Annotation> {
+  return invoke<Annotation>("get_annotation", { id });
+}
+
+export async function updateAnnotation(payload: UpdateAnnotationPayload): Promise<Annotation> {
+  return invoke<Annotation>("update_annotation", { payload });
+}
+
+export async function deleteAnnotation(id: string): Promise<void> {
+  await invoke("delete_annotation", { id });
+}
+
+/** Anotações do livro (ou só do volume), na ordem de leitura. */
+export async function listAnnotations(bookId: string, volumeId?: string): Promise<Annotation[]> {
+  return invoke<Annotation[]>("list_annotations", { bookId, volumeId: volumeId ?? null });
+}
+
+/** Anotações da biblioteca (ou de um livro) com título e capítulo. */
+export async function queryAnnotations(query?: AnnotationQuery): Promise<AnnotationEntry[]> {
+  return invoke<AnnotationEntry[]>("query_annotations", { query: query ?? null });
+}
+
+/** Grava as anotações no arquivo escolhido; retorna quantas foram exportadas. */
+export async function exportAnnotations(payload: ExportAnnotationsPayload): Promise<number> {
+  return invoke<number>("export_annotations", { payload });
+}
+
+export async function startReadingSession(
+  bookId: string,
+  volumeId: string,
+  pageIndex: number
+): Promise<ReadingSession> {
+  return invoke<ReadingSession>("start_reading_session", { bookId, volumeId, pageIndex });
+}
+
+/** Registra atividade. Após ociosidade devolve uma sessão nova: use sempre o id retornado. */
+export async function readingSessionHeartbeat(
+  sessionId: string,
+  pageIndex: number
+): Promise<ReadingSession> {
+  return invoke<ReadingSession>("reading_session_heartbeat", { sessionId, pageIndex });
+}
+
+export async function endReadingSession(sessionId: string, pageIndex: number): Promise<void> {
+  await invoke("end_reading_session", { sessionId, pageIndex });
+}
+
+/** `from`/`to` em segundos; omitidos = sem limite. */
+export async function getReadingTimeByBook(from?: number, to?: number): Promise<BookReadingTime[]> {
+  return invoke<BookReadingTime[]>("get_reading_time_by_book", {
+    from: from ?? null,
+    to: to ?? null,
+  });
+}
+
+export async function getReadingTimeByDay(
+  from?: number,
+  to?: number,
+  bookId?: string
+): Promise<DailyReading[]> {
+  return invoke<DailyReading[]>("get_reading_time_by_day", {
+    from: from ?? null,
+    to: to ?? null,
+    bookId: bookId ?? null,
+  });
+}
+
+export async function getPagesPerHour(bookId?: string): Promise<number | null> {
+  return invoke<number | null>("get_pages_per_hour", { bookId: bookId ?? null });
+}
+
+/** Estatísticas do intervalo (`from`/`to` em segundos; omitidos = tudo). */
+export async function getReadingStats(from?: number, to?: number): Promise<ReadingStats> {
+  return invoke<ReadingStats>("get_reading_stats", { from: from ?? null, to: to ?? null });
+}
+
+export async function getBookReadState(bookId: string): Promise<BookReadState> {
+  return invoke<BookReadState>("get_book_read_state", { bookId });
+}
+
+/** `completed` e `unread` valem também para todos os volumes do livro. */
+export async function setBookStatus(bookId: string, status: ReadStatus): Promise<BookReadState> {
+  return invoke<BookReadState>("set_book_status", { bookId, status });
+}
+
+export async function setVolumeStatus(volumeId: string, status: ReadStatus): Promise<void> {
+  return invoke("set_volume_status", { volumeId, status });
+}
+
+/** Marca vários volumes de uma vez; retorna quantos mudaram de status. */
+export async function markVolumes(volumeIds: string[], status: ReadStatus): Promise<number> {
+  return invoke<number>("mark_volumes", { volumeIds, status });
+}
+
+export async function getVolumeEta(volumeId: string): Promise<VolumeEta> {
+  return invoke<VolumeEta>("get_volume_eta", { volumeId });
+}
+
+/** Exporta a biblioteca (livros, estantes, progresso, configurações, marcadores) em JSON. */
+export async function exportLibrary(path: string): Promise<void> {
+  await invoke("export_library", { path });
+}
+
+/** Mescla um backup JSON; registros locais mais recentes são mantidos. */
+export async function importLibrary(path: string): Promise<BackupImportReport> {
+  return invoke<BackupImportReport>("import_library", { path });
+}
+
 /** Arquivo passado pela associação do sistema (duplo clique). */
 export async function getPendingFileToOpen(): Promise<string | null> {
   const result = await invoke<string | null>("get_pending_file_to_open");
@@ -152,6 +571,13 @@ export interface MetadataCandidateDto {
   cover_url: string | null;
   year: number | null;
   language: string | null;
+  publisher: string | null;
+  isbns: string[];
+  page_count: number | null;
+  /** Gêneros/assuntos da fonte (viram tags automáticas). */
+  genres: string[];
+  /** Nota da comunidade na fonte (0–100), quando houver. */
+  community_score: number | null;
 }
 
 export interface RankedCandidateDto {
@@ -171,8 +597,12 @@ export interface SearchMetadataResult {
   candidates: RankedCandidateDto[];
 }
 
-export async function searchMetadata(bookId: string): Promise<SearchMetadataResult> {
-  return invoke<SearchMetadataResult>("search_metadata", { bookId });
+/** `bypassCache`: consulta as fontes mesmo com resultado válido no cache. */
+export async function searchMetadata(
+  bookId: string,
+  bypassCache = false
+): Promise<SearchMetadataResult> {
+  return invoke<SearchMetadataResult>("search_metadata", { bookId, bypassCache });
 }
 
 export async function applyMetadataCandidate(
@@ -191,6 +621,100 @@ export async function applyMetadataCandidate(
     cover_url: candidate.cover_url ?? null,
     year: candidate.year ?? null,
     language: candidate.language ?? null,
+    publisher: candidate.publisher ?? null,
+    isbns: candidate.isbns ?? [],
+    page_count: candidate.page_count ?? null,
+    genres: candidate.genres ?? [],
+    community_score: candidate.community_score ?? null,
   };
   return invoke<BookWithVolumes>("apply_metadata_candidate", { bookId, candidate: payload });
 }
+
+/** Histórico de alterações de metadados do livro (mais recentes primeiro). */
+export async function getMetadataHistory(bookId: string): Promise<MetadataChange[]> {
+  return invoke<MetadataChange[]>("get_metadata_history", { bookId });
+}
+
+/** Devolve o campo ao valor anterior à alteração; retorna o livro atualizado. */
+export async function revertMetadata(bookId: string, changeId: string): Promise<BookWithVolumes> {
+  return invoke<BookWithVolumes>("revert_metadata", { bookId, changeId });
+}
+
+export async function getMetadataConfig(): Promise<MetadataConfig> {
+  return invoke<MetadataConfig>("get_metadata_config");
+}
+
+export async function saveMetadataConfig(config: MetadataConfig): Promise<void> {
+  await invoke("save_metadata_config", { config });
+}
+
+/** Esvazia o cache de buscas de uma fonte (ou de todas); retorna quantas entradas saíram. */
+export async function clearMetadataCache(source?: string): Promise<number> {
+  return invoke<number>("clear_metadata_cache", { source: source ?? null });
+}
+
+export type MetadataJobStatus = "running" | "paused" | "completed" | "cancelled";
+export type MetadataJobItemStatus =
+  | "pending"
+  | "applied"
+  | "review"
+  | "no_match"
+  | "failed"
+  | "dismissed";
+
+/** Lote de enriquecimento; também é o payload do evento `metadata-job-progress`. */
+export interface MetadataJob {
+  id: string;
+  status: MetadataJobStatus;
+  total: number;
+  pending: number;
+  applied: number;
+  review: number;
+  no_match: number;
+  failed: number;
+  created_at: number;
+  updated_at: number;
+}
+
+/** Livro do lote; `candidates` só vem preenchido em revisão. */
+export interface MetadataJobItem {
+  job_id: string;
+  book_id: string;
+  title: string;
+  status: MetadataJobItemStatus;
+  score: number | null;
+  candidates: RankedCandidateDto[];
+  error: string | null;
+  updated_at: number;
+}
+
+/** Enriquece os livros sem metadados confirmados; retoma o lote ativo se houver. */
+export async function startMetadataJob(): Promise<MetadataJob> {
+  return invoke<MetadataJob>("start_metadata_job");
+}
+
+export async function pauseMetadataJob(): Promise<void> {
+  await invoke("pause_metadata_job");
+}
+
+export async function cancelMetadataJob(): Promise<void> {
+  await invoke("cancel_metadata_job");
+}
+
+/** Lote mais recente (ativo ou não). */
+export async function getMetadataJob(): Promise<MetadataJob | null> {
+  return invoke<MetadataJob | null>("get_metadata_job");
+}
+
+/** Livros do lote; com `status: "review"`, a fila de revisão. */
+export async function getMetadataJobItems(
+  jobId: string,
+  status?: MetadataJobItemStatus
+): Promise<MetadataJobItem[]> {
+  return invoke<MetadataJobItem[]>("get_metadata_job_items", { jobId, status: status ?? null });
+}
+
+/** Tira o livro da fila de revisão sem aplicar candidato (aplicar um já o tira). */
+export async function dismissMetadataReview(bookId: string): Promise<void> {
+  await invoke("dismiss_metadata_review", { bookId });
+}
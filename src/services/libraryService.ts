@@ -5,14 +5,17 @@
 import { readDir } from "@tauri-apps/plugin-fs";
 import { join } from "@tauri-apps/api/path";
 import * as db from "./dbService";
-import type { BookWithVolumes } from "../types/db";
+import type { BookPage, BookQuery, BookSummary, BookWithVolumes, DbBook } from "../types/db";
 import type { LibraryBook, Volume, Chapter } from "../types/library";
 
 const MEDIA_EXT = ["jpg", "jpeg", "png", "webp", "pdf", "epub", "cbz", "zip", "rar"];
 const IMAGE_EXT = ["jpg", "jpeg", "png", "webp"];
 
 function getCoverPathFromBook(volumes: { chapters: { path: string }[] }[]): string | undefined {
-  const firstChapterPath = volumes[0]?.chapters[0]?.path;
+  return getCoverPathFromChapter(volumes[0]?.chapters[0]?.path);
+}
+
+function getCoverPathFromChapter(firstChapterPath: string | null | undefined): string | undefined {
   if (!firstChapterPath) return undefined;
   const ext = firstChapterPath.split(".").pop()?.toLowerCase();
   return ext && IMAGE_EXT.includes(ext) ? firstChapterPath : undefined;
@@ -146,6 +149,21 @@ export function scanFile(filePath: string): LibraryBook {
   };
 }
 
+function mapDbBook(book: DbBook, volumes: Volume[], derivedCover: string | undefined): LibraryBook {
+  const coverPath = book.cover_path ?? derivedCover;
+  return {
+    id: book.id,
+    title: book.title,
+    path: book.path,
+    type: book.type as "folder" | "file",
+    addedAt: book.added_at,
+    volumes,
+    ...(coverPath && { coverPath }),
+    ...(book.author && { author: book.author }),
+    ...(book.description && { description: book.description }),
+  };
+}
+
 /** Converte resposta do backend (BookWithVolumes) para LibraryBook. */
 export function mapBookWithVolumesToLibraryBook(b: BookWithVolumes): LibraryBook {
   const volumes = b.volumes.map((v) => ({
@@ -153,32 +171,54 @@ export function mapBookWithVolumesToLibraryBook(b: BookWithVolumes): LibraryBook
     name: v.volume.name,
     chapters: v.chapters.map((c) => ({ id: c.id, name: c.name, path: c.path })),
   }));
-  const derivedCover = getCoverPathFromBook(volumes);
-  const coverPath = b.book.cover_path ?? derivedCover;
+  return mapDbBook(b.book, volumes, getCoverPathFromBook(volumes));
+}
+
+/** Converte um resumo da listagem; os volumes ficam vazios até abrir o livro. */
+export function mapBookSummaryToLibraryBook(s: BookSummary): LibraryBook {
   return {
-    id: b.book.id,
-    title: b.book.title,
-    path: b.book.path,
-    type: b.book.type as "folder" | "file",
-    addedAt: b.book.added_at,
-    volumes,
-    ...(coverPath && { coverPath }),
-    ...(b.book.author && { author: b.book.author }),
-    ...(b.book.description && { description: b.book.description }),
+    ...mapDbBook(s.book, [], getCoverPathFromChapter(s.first_chapter_path)),
+    volumeCount: s.volume_count,
   };
 }
 
-/** Carrega a biblioteca do banco local (SQLite via Tauri). */
-export async function loadLibrary(): Promise<LibraryBook[]> {
+export interface LibraryPage {
+  books: LibraryBook[];
+  /** Progresso (0-100) de cada livro da página */
+  progress: Map<string, number>;
+  total: number;
+}
+
+/** Uma página da biblioteca, filtrada e ordenada no banco. */
+export async function queryLibrary(query: BookQuery): Promise<LibraryPage> {
   try {
-    const list = await db.getBooks();
-    return list.map(mapBookWithVolumesToLibraryBook).sort((a, b) => b.addedAt - a.addedAt);
+    const page: BookPage = await db.queryBooks(query);
+    return {
+      books: page.items.map(mapBookSummaryToLibraryBook),
+      progress: new Map(page.items.map((s) => [s.book.id, s.progress_percent])),
+      total: page.total,
+    };
   } catch (e) {
-    console.error("[libraryService] loadLibrary:", e);
-    return [];
+    console.error("[libraryService] queryLibrary:", e);
+    return { books: [], progress: new Map(), total: 0 };
+  }
+}
+
+/** Livro completo (volumes e capítulos), para abrir ou ler. */
+export async function loadBook(bookId: string): Promise<LibraryBook | null> {
+  try {
+    return mapBookWithVolumesToLibraryBook(await db.getBook(bookId));
+  } catch (e) {
+    console.error("[libraryService] loadBook:", e);
+    return null;
   }
 }
 
+/** Garante a árvore de volumes (resumos da listagem chegam sem ela). */
+export async function ensureBookLoaded(book: LibraryBook): Promise<LibraryBook | null> {
+  return book.volumes.length > 0 ? book : loadBook(book.id);
+}
+
 /** Persiste um livro no banco (chamado ao adicionar). */
 export async function persistBook(book: LibraryBook): Promise<void> {
   await db.addBook({
@@ -210,96 +250,33 @@ export async function removeBookFromBackend(bookId: string): Promise<void> {
   await db.deleteBook(bookId);
 }
 
-export function hasBookByPath(books: LibraryBook[], path: string): boolean {
-  const norm = normalizePath(path);
-  return books.some((b) => normalizePath(b.path) === norm);
+/** O id do livro é o caminho normalizado; basta procurar por ele. */
+export async function bookExists(path: string): Promise<boolean> {
+  try {
+    await db.getBook(normalizePath(path));
+    return true;
+  } catch {
+    return false;
+  }
 }
 
 export interface RecentProgressItem {
   book: LibraryBook;
-  volume: { id: string; name: string; chapters: { path: string }[] };
+  volume: { id: string; name: string; chapterCount: number };
   pageIndex: number;
   updatedAt: number;
 }
 
-/** Carrega itens "Continuar lendo" (progresso recente + dados do livro). */
-export type BookProgressStatus = "not_started" | "reading" | "completed";
-
-export function computeBooksWithProgress(
-  books: LibraryBook[],
-  allProgress: { book_id: string; volume_id: string; page_index: number }[]
-): Map<string, { status: BookProgressStatus; progressPercent: number }> {
-  const map = new Map<string, { status: BookProgressStatus; progressPercent: number }>();
-  for (const book of books) {
-    const volumeProgresses = allProgress.filter((p) => p.book_id === book.id);
-    if (volumeProgresses.length === 0) {
-      map.set(book.id, { status: "not_started", progressPercent: 0 });
-      continue;
-    }
-    let totalPages = 0;
-    let readPages = 0;
-    let anyInProgress = false;
-    let allCompleted = true;
-    for (const v of book.volumes) {
-      const total = v.chapters.length;
-      totalPages += total;
-      const p = volumeProgresses.find((x) => x.volume_id === v.id);
-      if (p) {
-        readPages += Math.min(p.page_index, total);
-        if (p.page_index < total) {
-          anyInProgress = true;
-          allCompleted = false;
-        }
-      } else {
-        allCompleted = false;
-      }
-    }
-    const progressPercent = totalPages > 0 ? (readPages / totalPages) * 100 : 0;
-    const status: BookProgressStatus = allCompleted
-      ? "completed"
-      : anyInProgress
-        ? "reading"
-        : "not_started";
-    map.set(book.id, { status, progressPercent });
-  }
-  return map;
-}
-
-export function getBookFormat(book: LibraryBook): "images" | "pdf" | "epub" | "archive" | "other" {
-  const path = book.volumes[0]?.chapters[0]?.path ?? "";
-  const ext = path.split(".").pop()?.toLowerCase() ?? "";
-  if (["jpg", "jpeg", "png", "webp", "gif", "bmp", "avif"].includes(ext)) return "images";
-  if (ext === "pdf") return "pdf";
-  if (ext === "epub") return "epub";
-  if (["cbz", "zip", "rar"].includes(ext)) return "archive";
-  return "other";
-}
-
+/** Carrega itens "Continuar lendo" (volumes em andamento + dados do livro). */
 export async function loadRecentProgress(limit: number): Promise<RecentProgressItem[]> {
   try {
-    const [progressList, bookList] = await Promise.all([
-      db.getRecentProgress(limit),
-      db.getBooks(),
-    ]);
-    const bookMap = new Map(
-      bookList.map((b) => [b.book.id, mapBookWithVolumesToLibraryBook(b)])
-    );
-    const result: RecentProgressItem[] = [];
-    for (const p of progressList) {
-      const book = bookMap.get(p.book_id);
-      if (!book) continue;
-      const volume = book.volumes.find((v) => v.id === p.volume_id);
-      if (!volume) continue;
-      const totalPages = volume.chapters.length;
-      if (p.page_index >= totalPages) continue; // já concluído
-      result.push({
-        book,
-        volume,
-        pageIndex: p.page_index,
-        updatedAt: p.updated_at,
-      });
-    }
-    return result;
+    const items = await db.getContinueReading(limit);
+    return items.map((item) => ({
+      book: mapDbBook(item.book, [], getCoverPathFromChapter(item.first_chapter_path)),
+      volume: { id: item.volume_id, name: item.volume_name, chapterCount: item.chapter_count },
+      pageIndex: item.page_index,
+      updatedAt: item.updated_at,
+    }));
   } catch (e) {
     console.error("[libraryService] loadRecentProgress:", e);
     return [];
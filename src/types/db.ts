@@ -13,6 +13,34 @@ export interface DbBook {
   author?: string | null;
   description?: string | null;
   cover_path?: string | null;
+  publisher?: string | null;
+  year?: number | null;
+  language?: string | null;
+  isbns?: string[];
+  alt_titles?: string[];
+  series?: string | null;
+  /** Número na série (pode ser fracionário, ex.: 7.5). */
+  series_number?: number | null;
+  page_count?: number | null;
+  media_type?: "book" | "anime" | "manga" | null;
+  genres?: string[];
+  /** Id do item em cada fonte de metadados (open_library, anilist...). */
+  external_ids?: Record<string, string>;
+}
+
+/** Metadados além de título/autor/descrição/capa. */
+export interface BookMetadata {
+  publisher: string | null;
+  year: number | null;
+  language: string | null;
+  isbns: string[];
+  alt_titles: string[];
+  series: string | null;
+  series_number: number | null;
+  page_count: number | null;
+  media_type: "book" | "anime" | "manga" | null;
+  genres: string[];
+  external_ids: Record<string, string>;
 }
 
 export interface UpdateBookPayload {
@@ -21,6 +49,8 @@ export interface UpdateBookPayload {
   author?: string | null;
   description?: string | null;
   cover_path?: string | null;
+  /** Ausente = não mexer; campos alterados ficam protegidos da busca automática. */
+  metadata?: BookMetadata | null;
 }
 
 export interface DbVolume {
@@ -47,6 +77,86 @@ export interface VolumeWithChaptersOut {
   chapters: DbChapter[];
 }
 
+export type ReadStatus = "unread" | "reading" | "completed" | "on_hold" | "dropped";
+
+export interface ReadState {
+  status: ReadStatus;
+  started_at: number | null;
+  finished_at: number | null;
+  reread_count: number;
+}
+
+export interface VolumeReadState {
+  volume_id: string;
+  state: ReadState;
+}
+
+export interface BookReadState {
+  book_id: string;
+  state: ReadState;
+  volumes: VolumeReadState[];
+}
+
+export interface BookSummary {
+  book: DbBook;
+  volume_count: number;
+  chapter_count: number;
+  read_pages: number;
+  progress_percent: number;
+  first_chapter_path: string | null;
+  last_read_at: number | null;
+  read_status: ReadStatus;
+  /** Nota pessoal (0.5–5). */
+  rating: number | null;
+}
+
+export type BookSortField =
+  | "added_at"
+  | "title"
+  | "author"
+  | "last_read"
+  | "progress"
+  | "rating";
+
+export type BookFormat = "images" | "pdf" | "epub" | "archive";
+
+export interface BookQuery {
+  search?: string | null;
+  book_type?: "folder" | "file" | null;
+  /** Formato do primeiro capítulo. */
+  format?: BookFormat | null;
+  shelf_id?: string | null;
+  /** O livro precisa ter todas as tags. */
+  tag_ids?: string[];
+  progress?: "not_started" | "reading" | "completed" | null;
+  read_status?: ReadStatus | null;
+  /** Só livros com nota >= este valor. */
+  min_rating?: number | null;
+  sort_by?: BookSortField;
+  sort_dir?: "asc" | "desc";
+  offset?: number;
+  /** 0 = sem limite */
+  limit?: number;
+}
+
+export interface BookPage {
+  items: BookSummary[];
+  total: number;
+  offset: number;
+  limit: number;
+}
+
+/** Volume em andamento para "Continuar lendo" (sem a lista de capítulos). */
+export interface ContinueReadingItem {
+  book: DbBook;
+  volume_id: string;
+  volume_name: string;
+  chapter_count: number;
+  page_index: number;
+  updated_at: number;
+  first_chapter_path: string | null;
+}
+
 export interface AddBookPayload {
   book: {
     id: string;
@@ -77,6 +187,94 @@ export interface ReadingProgress {
   page_index: number;
   scroll_offset: number;
   updated_at: number;
+  /** Total de páginas do volume no leitor (usado para percentual/KOReader). */
+  total_pages?: number | null;
+}
+
+/** Estado de um capítulo; sem registro = não lido. */
+export interface ChapterProgress {
+  chapter_id: string;
+  book_id: string;
+  volume_id: string;
+  read: boolean;
+  last_page: number | null;
+  read_at: number | null;
+  updated_at: number;
+}
+
+export interface VolumeCompletion {
+  volume_id: string;
+  chapters_read: number;
+  chapters_total: number;
+  completed: boolean;
+}
+
+export interface SeriesCompletion {
+  book_id: string;
+  chapters_read: number;
+  chapters_total: number;
+  /** 0–100 */
+  percent: number;
+  completed: boolean;
+  volumes: VolumeCompletion[];
+}
+
+export interface QueueEntry {
+  id: string;
+  book_id: string;
+  /** null = livro/série inteiro */
+  volume_id: string | null;
+  position: number;
+  added_at: number;
+}
+
+export interface QueueItem {
+  entry: QueueEntry;
+  book_title: string;
+  cover_path: string | null;
+  volume_name: string | null;
+}
+
+/** Sem `position`, vai para o fim (ou fica onde está, se já estiver na fila). */
+export interface EnqueuePayload {
+  book_id: string;
+  volume_id?: string | null;
+  position?: number | null;
+}
+
+export interface HistoryEntry {
+  id: string;
+  book_id: string;
+  volume_id: string;
+  chapter_id: string | null;
+  page_index: number;
+  started_at: number;
+  updated_at: number;
+}
+
+export interface HistoryItem {
+  entry: HistoryEntry;
+  book_title: string;
+  cover_path: string | null;
+  volume_name: string | null;
+  chapter_name: string | null;
+}
+
+/** `from`/`to` em segundos; entradas que tocam o intervalo entram no resultado. */
+export interface HistoryQuery {
+  book_id?: string | null;
+  from?: number | null;
+  to?: number | null;
+  offset?: number;
+  /** 0 = sem limite */
+  limit?: number;
+}
+
+export interface HistoryPage {
+  items: HistoryItem[];
+  total: number;
+  offset: number;
+  limit: number;
 }
 
 export interface BookSettings {
@@ -109,3 +307,366 @@ export interface CustomTheme {
   css: string;
   updated_at: number;
 }
+
+export interface ChangeEntry {
+  seq: number;
+  table_name: string;
+  /** Chaves compostas unidas com "|" */
+  row_key: string;
+  op: "upsert" | "delete";
+  changed_at: number;
+  device_id: string;
+}
+
+export interface ChangeFeed {
+  changes: ChangeEntry[];
+  cursor: number;
+  has_more: boolean;
+}
+
+export interface Tombstone {
+  table_name: string;
+  row_key: string;
+  deleted_at: number;
+  device_id: string;
+}
+
+/** Backend de sincronização: "local_folder" | "http" | "webdav" (null = desativado). */
+export interface SyncConfig {
+  backend: string | null;
+  target: string | null;
+  /** Usuário WebDAV; `token` é a senha nesse caso. */
+  username: string | null;
+  token: string | null;
+  interval_secs: number;
+  last_sync_at: number | null;
+}
+
+export interface SaveSyncConfigPayload {
+  backend: string | null;
+  target: string | null;
+  username?: string | null;
+  token: string | null;
+  interval_secs?: number;
+}
+
+export interface SyncReport {
+  backend: string;
+  pushed: number;
+  pulled: number;
+  applied: number;
+  skipped: number;
+  finished_at: number;
+}
+
+/** Sincronização com o KOReader (protocolo kosync). */
+export interface KoreaderConfig {
+  server_url: string | null;
+  username: string | null;
+  /** "binary" (MD5 parcial do arquivo) | "filename" */
+  match_method: string;
+  interval_secs: number;
+  last_sync_at: number | null;
+  server_enabled: boolean;
+  server_port: number;
+  /** Servidor embutido visível na rede; desligado, só em 127.0.0.1. */
+  server_lan: boolean;
+  /** Permite criar contas a partir de outros dispositivos. */
+  server_registration: boolean;
+}
+
+/** Campos omitidos mantêm o valor atual; `password: ""` remove a senha. */
+export interface SaveKoreaderConfigPayload {
+  server_url?: string | null;
+  username?: string | null;
+  password?: string | null;
+  match_method?: string;
+  interval_secs?: number;
+  server_enabled?: boolean;
+  server_port?: number;
+  server_lan?: boolean;
+  server_registration?: boolean;
+}
+
+export interface KoreaderSyncReport {
+  pushed: number;
+  pulled: number;
+  skipped: number;
+  finished_at: number;
+}
+
+/** Marcador nomeado: página (leitores paginados) ou CFI (EPUB). */
+export interface Bookmark {
+  id: string;
+  book_id: string;
+  volume_id: string;
+  chapter_id: string | null;
+  page_index: number | null;
+  cfi: string | null;
+  label: string;
+  /** Data URL da miniatura da página. */
+  thumbnail: string | null;
+  created_at: number;
+  updated_at: number;
+}
+
+/** Exige `page_index` ou `cfi`. Sem `label`, o backend usa "Página N". */
+export interface CreateBookmarkPayload {
+  book_id: string;
+  volume_id: string;
+  chapter_id?: string | null;
+  page_index?: number | null;
+  cfi?: string | null;
+  label?: string | null;
+  thumbnail?: string | null;
+}
+
+/** Registros criados/atualizados ao importar um backup da biblioteca. */
+export interface BackupImportReport {
+  books: number;
+  shelves: number;
+  progress: number;
+  settings: number;
+  reviews: number;
+  bookmarks: number;
+  annotations: number;
+  skipped: number;
+}
+
+/** Quadrilátero da seleção no PDF: [x1, y1, x2, y2, x3, y3, x4, y4]. */
+export type Quad = [number, number, number, number, number, number, number, number];
+
+/** Destaque com nota opcional: intervalo CFI (EPUB) ou página + quads (PDF). */
+export interface Annotation {
+  id: string;
+  book_id: string;
+  volume_id: string;
+  chapter_id: string | null;
+  book_hash: string | null;
+  cfi_range: string | null;
+  page_index: number | null;
+  quads: Quad[];
+  text: string;
+  color: string;
+  note: string | null;
+  created_at: number;
+  updated_at: number;
+}
+
+export interface AnnotationEntry {
+  annotation: Annotation;
+  book_title: string;
+  book_author: string | null;
+  chapter_name: string | null;
+}
+
+/** Sem `book_id`, busca na biblioteca toda. `search` cobre texto e nota. */
+export interface AnnotationQuery {
+  book_id?: string | null;
+  volume_id?: string | null;
+  color?: string | null;
+  search?: string | null;
+  offset?: number;
+  /** 0 = sem limite */
+  limit?: number;
+}
+
+/** Exige `cfi_range` ou `page_index`. */
+export interface CreateAnnotationPayload {
+  book_id: string;
+  volume_id: string;
+  chapter_id?: string | null;
+  cfi_range?: string | null;
+  page_index?: number | null;
+  quads?: Quad[];
+  text?: string;
+  color?: string | null;
+  note?: string | null;
+}
+
+/** Campos omitidos mantêm o valor atual; `note: ""` remove a nota. */
+export interface UpdateAnnotationPayload {
+  id: string;
+  color?: string | null;
+  note?: string | null;
+}
+
+export type AnnotationExportFormat = "markdown" | "json" | "readwise_csv";
+
+/**
+ * Modelos do Markdown: `{{nome}}` e `{{#nome}}...{{/nome}}` (só se não vazio).
+ * book: title, author, count · chapter: chapter ·
+ * annotation: text, quote, note, color, location, date.
+ */
+export interface AnnotationTemplates {
+  book?: string | null;
+  chapter?: string | null;
+  annotation?: string | null;
+}
+
+/** Sem `book_id`, exporta a biblioteca toda. */
+export interface ExportAnnotationsPayload {
+  path: string;
+  format: AnnotationExportFormat;
+  book_id?: string | null;
+  templates?: AnnotationTemplates;
+}
+
+/** Sessão contínua de leitura; `ended_at` é o último heartbeat. */
+export interface ReadingSession {
+  id: string;
+  book_id: string;
+  volume_id: string;
+  started_at: number;
+  ended_at: number;
+  start_page: number;
+  end_page: number;
+  pages_turned: number;
+  closed: boolean;
+}
+
+export interface BookReadingTime {
+  book_id: string;
+  title: string;
+  seconds: number;
+  sessions: number;
+  pages: number;
+  last_read_at: number;
+}
+
+/** Totais de um dia local (`YYYY-MM-DD`). */
+export interface DailyReading {
+  day: string;
+  seconds: number;
+  pages: number;
+}
+
+export interface VolumeEta {
+  volume_id: string;
+  remaining_pages: number;
+  pages_per_hour: number | null;
+  eta_secs: number | null;
+}
+
+/** Semana iniciada na segunda-feira local (`YYYY-MM-DD`). */
+export interface WeeklyReading {
+  week_start: string;
+  seconds: number;
+  pages: number;
+}
+
+export interface MonthlyFinished {
+  /** `YYYY-MM` */
+  month: string;
+  books: number;
+}
+
+/** Tempo somado por série; livros sem série aparecem sozinhos, pelo título. */
+export interface SeriesReadingTime {
+  series: string;
+  books: number;
+  seconds: number;
+  sessions: number;
+  pages: number;
+  last_read_at: number;
+}
+
+export interface ReadingStats {
+  from: number | null;
+  to: number | null;
+  total_seconds: number;
+  total_pages: number;
+  sessions: number;
+  days_read: number;
+  daily: DailyReading[];
+  weekly: WeeklyReading[];
+  /** Dias seguidos com leitura até hoje (ou ontem), independente do intervalo. */
+  current_streak: number;
+  longest_streak: number;
+  finished_per_month: MonthlyFinished[];
+  top_series: SeriesReadingTime[];
+  pages_per_hour: number | null;
+  avg_session_secs: number | null;
+  avg_daily_secs: number | null;
+}
+
+export type ShelfKind = "manual" | "smart";
+
+export type TextOp = "contains" | "not_contains" | "equals" | "starts_with";
+export type NumberOp = "lt" | "lte" | "gt" | "gte" | "eq";
+
+/** Condição de estante inteligente, discriminada por `field`. */
+export type SmartRule =
+  | { field: "title"; op: TextOp; value: string }
+  | { field: "author"; op: TextOp; value: string }
+  | { field: "read_status"; value: ReadStatus }
+  | { field: "tag"; tag_id: string }
+  | { field: "added_within_days"; days: number }
+  | { field: "book_type"; value: "folder" | "file" }
+  | { field: "media_type"; value: "book" | "anime" | "manga" }
+  /** Progresso em % (0–100). */
+  | { field: "progress"; op: NumberOp; value: number }
+  | { field: "missing_cover" };
+
+export interface SmartShelfRules {
+  /** all = todas as regras (E); any = qualquer uma (OU). */
+  match: "all" | "any";
+  rules: SmartRule[];
+}
+
+export interface Tag {
+  id: string;
+  name: string;
+  book_count: number;
+}
+
+export interface BookTag {
+  tag_id: string;
+  name: string;
+  /** true = veio dos gêneros da fonte de metadados. */
+  auto: boolean;
+}
+
+/** Avaliação pessoal: nota em meias estrelas (0.5–5) e resenha privada. */
+export interface BookReview {
+  book_id: string;
+  rating: number | null;
+  review: string | null;
+  updated_at: number;
+}
+
+/**
+ * Alteração de um campo de metadados. `origin`: "manual", "auto:<fonte>",
+ * "candidate:<fonte>" ou "revert"; alterações gravadas juntas dividem o batch_id.
+ */
+export interface MetadataChange {
+  id: string;
+  book_id: string;
+  batch_id: string;
+  field: string;
+  old_value: unknown;
+  new_value: unknown;
+  origin: string;
+  changed_at: number;
+}
+
+export type MetadataSourceName = "open_library" | "loc" | "anilist" | "jikan" | "kitsu";
+
+/** Conteúdo predominante da biblioteca; define as fontes padrão. */
+export type LibraryKind = "mixed" | "books" | "manga";
+
+export interface MetadataSourceSettings {
+  name: MetadataSourceName;
+  enabled: boolean;
+  /** Peso na ordenação dos candidatos da fonte (0 a 2; 1 = neutro). Não afeta o threshold. */
+  weight: number;
+}
+
+/** Configuração da busca de metadados (guardada nas configurações globais). */
+export interface MetadataConfig {
+  /** Validade do cache de cada fonte; 0 = sem cache. */
+  cache_ttl_secs: number;
+  library_kind: LibraryKind;
+  /** Todas as fontes, em ordem de prioridade. Salvar lista vazia volta ao padrão de `library_kind`. */
+  sources: MetadataSourceSettings[];
+}
@@ -21,7 +21,10 @@ export interface LibraryBook {
   title: string;
   path: string;
   type: "folder" | "file";
+  /** Vazio nos resumos da listagem; carregado ao abrir o livro */
   volumes: Volume[];
+  /** Quantidade de volumes (resumos, quando `volumes` não foi carregado) */
+  volumeCount?: number;
   addedAt: number;
   /** Capa customizada ou derivada do primeiro capítulo */
   coverPath?: string;